@@ -0,0 +1,36 @@
+#![no_main]
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use shakmaty::{
+    Chess, Move, Position,
+    san::{San, SanPlus},
+};
+
+fn play(u: &mut Unstructured<'_>) -> Result<()> {
+    let mut pos = Chess::arbitrary(u)?;
+
+    while !u.is_empty() {
+        let moves = pos.legal_moves();
+        let Ok(&m) = u.choose(&moves) else {
+            break;
+        };
+
+        let san = San::from_move(&pos, m);
+        assert_eq!(san.to_move(&pos), Ok(m), "{san} in {pos:?}");
+        let san_plus = SanPlus::from_move(pos.clone(), m);
+        let roundtripped = SanPlus::from_ascii(san_plus.to_string().as_bytes()).expect("roundtrip");
+        assert_eq!(san_plus, roundtripped);
+
+        // Arbitrary moves are usually illegal, but must never panic.
+        let _ = Move::arbitrary(u).map(|m| pos.is_legal(m));
+
+        pos.play_unchecked(m);
+    }
+
+    Ok(())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = play(&mut Unstructured::new(data));
+});
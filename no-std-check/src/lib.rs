@@ -0,0 +1,129 @@
+//! Exercises the core APIs of shakmaty without `std` and without `alloc`.
+//!
+//! Checked in CI for a target without an allocator:
+//!
+//! ```sh
+//! cargo check --package no-std-check --target thumbv6m-none-eabi
+//! ```
+
+#![no_std]
+
+use core::fmt::{self, Write as _};
+
+use shakmaty::{
+    CastlingMode, Chess, EnPassantMode, Position,
+    fen::Fen,
+    perft,
+    san::{San, SanPlus},
+    uci::UciMove,
+    variant::{Variant, VariantPosition},
+    zobrist::{Zobrist64, ZobristHash},
+};
+
+/// Fixed capacity buffer for formatting without allocation.
+pub struct Buffer {
+    bytes: [u8; 128],
+    len: usize,
+}
+
+impl Buffer {
+    pub const fn new() -> Buffer {
+        Buffer {
+            bytes: [0; 128],
+            len: 0,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl Default for Buffer {
+    fn default() -> Buffer {
+        Buffer::new()
+    }
+}
+
+impl fmt::Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len.checked_add(s.len()).ok_or(fmt::Error)?;
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Parses a FEN, counts leaf nodes, and writes the FEN back.
+pub fn perft_fen(fen: &[u8], depth: u32, out: &mut Buffer) -> Option<u64> {
+    let pos: Chess = Fen::from_ascii(fen)
+        .ok()?
+        .into_position(CastlingMode::Chess960)
+        .ok()?;
+    write!(out, "{}", Fen::from_position(&pos, EnPassantMode::Legal)).ok()?;
+    Some(perft(&pos, depth))
+}
+
+/// Plays moves given in SAN or UCI and writes them back in SAN.
+pub fn play_moves(moves: &[&[u8]], out: &mut Buffer) -> Option<Zobrist64> {
+    let mut pos = Chess::default();
+    for ascii in moves {
+        let m = match San::from_ascii(ascii) {
+            Ok(san) => san.to_move(&pos).ok()?,
+            Err(_) => UciMove::from_ascii(ascii).ok()?.to_move(&pos).ok()?,
+        };
+        write!(
+            out,
+            "{} ",
+            SanPlus::from_move_and_play_unchecked(&mut pos, m)
+        )
+        .ok()?;
+    }
+    Some(pos.zobrist_hash(EnPassantMode::Legal))
+}
+
+/// Counts legal moves in the starting position of every variant.
+pub fn variant_moves() -> usize {
+    Variant::ALL
+        .iter()
+        .map(|&variant| VariantPosition::new(variant).legal_moves().len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perft_fen() {
+        let mut out = Buffer::new();
+        assert_eq!(
+            perft_fen(
+                b"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                2,
+                &mut out
+            ),
+            Some(400)
+        );
+        assert_eq!(
+            out.as_bytes(),
+            b"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        );
+        assert_eq!(perft_fen(b"invalid", 1, &mut out), None);
+    }
+
+    #[test]
+    fn test_play_moves() {
+        let mut out = Buffer::new();
+        assert!(play_moves(&[b"f3", b"e7e5", b"g4", b"Qh4"], &mut out).is_some());
+        assert_eq!(out.as_bytes(), b"f3 e5 g4 Qh4# ");
+    }
+
+    #[test]
+    fn test_variant_moves() {
+        assert!(variant_moves() > 0);
+    }
+}
@@ -0,0 +1,675 @@
+//! An owned in-memory representation of games.
+//!
+//! [`GameBuilder`] is a [`Visitor`] that collects games into [`Game`] trees.
+//! Trees of multiple games can be merged with [`Game::merge()`], which
+//! deduplicates common move prefixes and accumulates [`NodeStats`]. This is
+//! the basis for building repertoires and opening explorers.
+//!
+//! # Round trips
+//!
+//! Reading a game with [`GameBuilder`] and writing it back with
+//! [`Writer`](crate::writer::Writer) preserves tags (including unknown tags,
+//! their order, and escape sequences), the exact bytes of `{ comments }`,
+//! NAGs, and the structure of variations. Differences are limited to:
+//!
+//! * Whitespace, line breaks, and move numbers.
+//! * Notation of moves, NAGs and results (e.g., `0-0` becomes `O-O`).
+//! * Comments after a move are written directly after the move, before
+//!   any variations.
+//! * Rest-of-line `; comments`, `%` escapes, empty variations, and tokens
+//!   that the [`Reader`](crate::Reader) does not report are dropped.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::io;
+//! use pgn_reader::{
+//!     Reader,
+//!     game::{GameBuilder, NodeId},
+//! };
+//!
+//! let pgn = b"1. e4 e5 2. Nf3 1-0
+//!
+//!             1. e4 c5 0-1
+//!
+//!             1. e4 e5 2. Nf3 Nc6 1/2-1/2";
+//!
+//! let mut reader = Reader::new(io::Cursor::new(&pgn));
+//!
+//! let mut repertoire = reader.read_game(&mut GameBuilder)?.expect("game");
+//! for game in reader.read_games(&mut GameBuilder) {
+//!     repertoire.merge(game?);
+//! }
+//!
+//! assert_eq!(repertoire.root().stats.games, 3);
+//!
+//! let e4 = repertoire.main_child(NodeId::ROOT).expect("1. e4");
+//! assert_eq!(repertoire.node(e4).stats.games, 3);
+//! assert_eq!(repertoire.children(e4).count(), 2); // 1... e5 and 1... c5
+//!
+//! let e5 = repertoire.node(repertoire.main_child(e4).expect("1... e5"));
+//! assert_eq!(e5.stats.games, 2);
+//! assert_eq!(e5.stats.white, 1);
+//! assert_eq!(e5.stats.draws, 1);
+//! # Ok::<_, io::Error>(())
+//! ```
+
+use std::{iter::FusedIterator, ops::ControlFlow};
+
+use shakmaty::{Color, KnownOutcome, Outcome, san::SanPlus};
+
+use crate::{Nag, RawComment, RawTag, Skip, Visitor};
+
+/// Number of games that reached a [`Node`], grouped by result.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NodeStats {
+    /// Total number of games, including games with unknown outcome.
+    pub games: u64,
+    /// Games won by White.
+    pub white: u64,
+    /// Drawn games.
+    pub draws: u64,
+    /// Games won by Black.
+    pub black: u64,
+}
+
+impl NodeStats {
+    /// Statistics of a single game with the given outcome.
+    pub const fn from_outcome(outcome: Outcome) -> NodeStats {
+        NodeStats {
+            games: 1,
+            white: matches!(
+                outcome,
+                Outcome::Known(KnownOutcome::Decisive {
+                    winner: Color::White
+                })
+            ) as u64,
+            draws: matches!(outcome, Outcome::Known(KnownOutcome::Draw)) as u64,
+            black: matches!(
+                outcome,
+                Outcome::Known(KnownOutcome::Decisive {
+                    winner: Color::Black
+                })
+            ) as u64,
+        }
+    }
+
+    /// Adds the counts of `other` to these statistics.
+    pub const fn add(&mut self, other: NodeStats) {
+        self.games += other.games;
+        self.white += other.white;
+        self.draws += other.draws;
+        self.black += other.black;
+    }
+}
+
+/// Identifies a [`Node`] in the arena of a [`Game`].
+///
+/// Identifiers are only meaningful for the game that created them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// The root node, which exists in every game.
+    pub const ROOT: NodeId = NodeId(0);
+
+    const fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A node in a game tree.
+///
+/// The root node of a [`Game`] has no move. All other nodes are reached by
+/// playing [`Node::san_plus`] from the parent node. Use the methods of
+/// [`Game`] to navigate the tree.
+#[derive(Clone, Debug, Default)]
+pub struct Node {
+    /// The move leading to this node, or `None` for the root node.
+    pub san_plus: Option<SanPlus>,
+    /// Comments preceding the move at the start of a variation, excluding
+    /// the braces.
+    pub starting_comments: Vec<Vec<u8>>,
+    /// Numeric annotation glyphs attached to the move.
+    pub nags: Vec<Nag>,
+    /// Comments following the move (or preceding the first move, in case of
+    /// the root node), excluding the braces.
+    pub comments: Vec<Vec<u8>>,
+    /// Statistics of games that reached this node.
+    pub stats: NodeStats,
+    parent: Option<NodeId>,
+    first_child: Option<NodeId>,
+    last_child: Option<NodeId>,
+    next_sibling: Option<NodeId>,
+}
+
+impl Node {
+    /// Creates a node for the given move, without annotations, statistics,
+    /// or children.
+    pub fn new(san_plus: SanPlus) -> Node {
+        Node {
+            san_plus: Some(san_plus),
+            ..Node::default()
+        }
+    }
+
+    fn merge_annotations(&mut self, other: Node) {
+        self.stats.add(other.stats);
+        for comment in other.starting_comments {
+            if !self.starting_comments.contains(&comment) {
+                self.starting_comments.push(comment);
+            }
+        }
+        for nag in other.nags {
+            if !self.nags.contains(&nag) {
+                self.nags.push(nag);
+            }
+        }
+        for comment in other.comments {
+            if !self.comments.contains(&comment) {
+                self.comments.push(comment);
+            }
+        }
+    }
+}
+
+/// Compares the move, annotations and statistics, but not the position in
+/// the tree.
+impl PartialEq for Node {
+    fn eq(&self, other: &Node) -> bool {
+        self.san_plus == other.san_plus
+            && self.starting_comments == other.starting_comments
+            && self.nags == other.nags
+            && self.comments == other.comments
+            && self.stats == other.stats
+    }
+}
+
+impl Eq for Node {}
+
+/// A game with its tags and tree of moves.
+///
+/// All nodes are stored in a single arena owned by the game, and refer to
+/// each other by [`NodeId`]. Building a game therefore allocates a handful
+/// of buffers, rather than one heap object per move. Annotations allocate
+/// only if present.
+///
+/// [`PartialEq`] and [`Eq`] compare the trees structurally, regardless of
+/// the order in which nodes were added to the arena.
+#[derive(Clone, Debug)]
+pub struct Game {
+    /// Tag pairs in the order they appeared. Values are kept exactly as
+    /// in the PGN, i.e., with backslash escapes. Use [`RawTag`] to decode
+    /// them.
+    pub tags: Vec<(Vec<u8>, Vec<u8>)>,
+    nodes: Vec<Node>,
+    /// The game termination marker.
+    pub outcome: Outcome,
+}
+
+impl Default for Game {
+    fn default() -> Game {
+        Game {
+            tags: Vec::new(),
+            nodes: vec![Node::default()],
+            outcome: Outcome::Unknown,
+        }
+    }
+}
+
+impl Game {
+    /// Creates an empty game without tags and moves.
+    pub fn new() -> Game {
+        Game::default()
+    }
+
+    /// Gets the value of the first tag with the given name.
+    pub fn tag(&self, name: &[u8]) -> Option<RawTag<'_>> {
+        self.tags
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| RawTag(value))
+    }
+
+    /// Gets the root node.
+    pub fn root(&self) -> &Node {
+        self.node(NodeId::ROOT)
+    }
+
+    /// Gets the root node for modification.
+    pub fn root_mut(&mut self) -> &mut Node {
+        self.node_mut(NodeId::ROOT)
+    }
+
+    /// Gets a node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` does not belong to this game.
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.index()]
+    }
+
+    /// Gets a node for modification.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` does not belong to this game.
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        &mut self.nodes[id.index()]
+    }
+
+    /// Number of nodes, including the root node.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Gets the parent of a node, or `None` for the root node.
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).parent
+    }
+
+    /// Gets the mainline continuation of a node, if any.
+    pub fn main_child(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).first_child
+    }
+
+    /// Iterates over the continuations of a node. The first child is the
+    /// mainline, followed by alternatives.
+    pub fn children(&self, id: NodeId) -> Children<'_> {
+        Children {
+            game: self,
+            next: self.node(id).first_child,
+        }
+    }
+
+    /// Finds the child reached by the given move. Check and checkmate
+    /// suffixes are not compared.
+    pub fn child(&self, id: NodeId, san_plus: SanPlus) -> Option<NodeId> {
+        self.children(id).find(|&child| {
+            self.node(child)
+                .san_plus
+                .is_some_and(|c| c.san == san_plus.san)
+        })
+    }
+
+    /// Adds `node` as the last continuation of `parent`, and returns its
+    /// identifier.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parent` does not belong to this game.
+    pub fn add_child(&mut self, parent: NodeId, node: Node) -> NodeId {
+        let id = NodeId(u32::try_from(self.nodes.len()).expect("node count fits into u32"));
+        let previous = self.node(parent).last_child;
+        self.nodes.push(Node {
+            parent: Some(parent),
+            first_child: None,
+            last_child: None,
+            next_sibling: None,
+            ..node
+        });
+        match previous {
+            Some(previous) => self.node_mut(previous).next_sibling = Some(id),
+            None => self.node_mut(parent).first_child = Some(id),
+        }
+        self.node_mut(parent).last_child = Some(id);
+        id
+    }
+
+    fn child_or_insert(&mut self, id: NodeId, san_plus: SanPlus) -> NodeId {
+        match self.child(id, san_plus) {
+            Some(child) => child,
+            None => self.add_child(id, Node::new(san_plus)),
+        }
+    }
+
+    /// Iterates over the mainline moves.
+    pub fn mainline(&self) -> impl Iterator<Item = &Node> {
+        std::iter::successors(self.main_child(NodeId::ROOT), |&id| self.main_child(id))
+            .map(|id| self.node(id))
+    }
+
+    /// Adds a sequence of moves, as if it were a game with the given outcome.
+    ///
+    /// Prefixes that are already present in the tree are shared, and the
+    /// statistics of every node along the line are updated.
+    pub fn add_line<I>(&mut self, line: I, outcome: Outcome)
+    where
+        I: IntoIterator<Item = SanPlus>,
+    {
+        let stats = NodeStats::from_outcome(outcome);
+        let mut id = NodeId::ROOT;
+        self.node_mut(id).stats.add(stats);
+        for san_plus in line {
+            id = self.child_or_insert(id, san_plus);
+            self.node_mut(id).stats.add(stats);
+        }
+    }
+
+    /// Merges the move tree of `other` into this game.
+    ///
+    /// Common move prefixes are deduplicated and statistics are added up.
+    /// New annotations are appended. Children that are not yet present are
+    /// appended as new alternatives, so that the existing mainline is kept.
+    ///
+    /// Note that this is purely based on the move sequences. Transpositions
+    /// are not detected. The tags and outcome of `self` are kept.
+    pub fn merge(&mut self, mut other: Game) {
+        let mut stack = vec![(NodeId::ROOT, NodeId::ROOT)];
+        while let Some((into, from)) = stack.pop() {
+            let node = std::mem::take(other.node_mut(from));
+            let mut child = node.first_child;
+            self.node_mut(into).merge_annotations(node);
+            while let Some(from_child) = child {
+                let from_node = other.node(from_child);
+                child = from_node.next_sibling;
+                match from_node.san_plus {
+                    Some(san_plus) => {
+                        stack.push((self.child_or_insert(into, san_plus), from_child));
+                    }
+                    None => debug_assert!(false, "only the root node has no move"),
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for Game {
+    fn eq(&self, other: &Game) -> bool {
+        if self.tags != other.tags
+            || self.outcome != other.outcome
+            || self.node_count() != other.node_count()
+        {
+            return false;
+        }
+
+        let mut stack = vec![(NodeId::ROOT, NodeId::ROOT)];
+        while let Some((a, b)) = stack.pop() {
+            if self.node(a) != other.node(b) {
+                return false;
+            }
+            let (mut a_children, mut b_children) = (self.children(a), other.children(b));
+            loop {
+                match (a_children.next(), b_children.next()) {
+                    (Some(a), Some(b)) => stack.push((a, b)),
+                    (None, None) => break,
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Eq for Game {}
+
+/// Iterator over the continuations of a node. See [`Game::children()`].
+#[derive(Clone, Debug)]
+pub struct Children<'a> {
+    game: &'a Game,
+    next: Option<NodeId>,
+}
+
+impl Iterator for Children<'_> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        self.next = self.game.node(id).next_sibling;
+        Some(id)
+    }
+}
+
+impl FusedIterator for Children<'_> {}
+
+/// A [`Visitor`] that builds a [`Game`].
+///
+/// The moves are not validated. The root node and each node of the mainline
+/// are counted as a single game with the parsed outcome
+/// (see [`NodeStats`]), so that building and merging many games yields
+/// statistics of the whole collection.
+#[derive(Debug, Default, Clone)]
+pub struct GameBuilder;
+
+/// Intermediate state of [`GameBuilder`].
+#[derive(Debug, Clone)]
+pub struct GameBuilderMovetext {
+    game: Game,
+    current: NodeId,
+    stack: Vec<NodeId>,
+    variation_start: bool,
+    starting_comments: Vec<Vec<u8>>,
+}
+
+impl Visitor for GameBuilder {
+    type Tags = Vec<(Vec<u8>, Vec<u8>)>;
+    type Movetext = GameBuilderMovetext;
+    type Output = Game;
+
+    fn begin_tags(&mut self) -> ControlFlow<Self::Output, Self::Tags> {
+        ControlFlow::Continue(Vec::new())
+    }
+
+    fn tag(
+        &mut self,
+        tags: &mut Self::Tags,
+        name: &[u8],
+        value: RawTag<'_>,
+    ) -> ControlFlow<Self::Output> {
+        tags.push((name.to_owned(), value.as_bytes().to_owned()));
+        ControlFlow::Continue(())
+    }
+
+    fn begin_movetext(&mut self, tags: Self::Tags) -> ControlFlow<Self::Output, Self::Movetext> {
+        ControlFlow::Continue(GameBuilderMovetext {
+            game: Game {
+                tags,
+                ..Game::default()
+            },
+            current: NodeId::ROOT,
+            stack: Vec::new(),
+            variation_start: false,
+            starting_comments: Vec::new(),
+        })
+    }
+
+    fn san(
+        &mut self,
+        movetext: &mut Self::Movetext,
+        san_plus: SanPlus,
+    ) -> ControlFlow<Self::Output> {
+        movetext.current = movetext.game.add_child(
+            movetext.current,
+            Node {
+                starting_comments: std::mem::take(&mut movetext.starting_comments),
+                ..Node::new(san_plus)
+            },
+        );
+        movetext.variation_start = false;
+        ControlFlow::Continue(())
+    }
+
+    fn nag(&mut self, movetext: &mut Self::Movetext, nag: Nag) -> ControlFlow<Self::Output> {
+        movetext.game.node_mut(movetext.current).nags.push(nag);
+        ControlFlow::Continue(())
+    }
+
+    fn comment(
+        &mut self,
+        movetext: &mut Self::Movetext,
+        comment: RawComment<'_>,
+    ) -> ControlFlow<Self::Output> {
+        let comments = if movetext.variation_start {
+            &mut movetext.starting_comments
+        } else {
+            &mut movetext.game.node_mut(movetext.current).comments
+        };
+        comments.push(comment.as_bytes().to_owned());
+        ControlFlow::Continue(())
+    }
+
+    fn begin_variation(
+        &mut self,
+        movetext: &mut Self::Movetext,
+    ) -> ControlFlow<Self::Output, Skip> {
+        // A variation is an alternative to the last move, so continue
+        // from its parent.
+        let parent = movetext
+            .game
+            .parent(movetext.current)
+            .unwrap_or(NodeId::ROOT);
+        movetext
+            .stack
+            .push(std::mem::replace(&mut movetext.current, parent));
+        movetext.variation_start = true;
+        ControlFlow::Continue(Skip(false))
+    }
+
+    fn end_variation(&mut self, movetext: &mut Self::Movetext) -> ControlFlow<Self::Output> {
+        if let Some(id) = movetext.stack.pop() {
+            movetext.current = id;
+        }
+        // Comments in otherwise empty variations are not preserved.
+        movetext.variation_start = false;
+        movetext.starting_comments.clear();
+        ControlFlow::Continue(())
+    }
+
+    fn outcome(
+        &mut self,
+        movetext: &mut Self::Movetext,
+        outcome: Outcome,
+    ) -> ControlFlow<Self::Output> {
+        movetext.game.outcome = outcome;
+        ControlFlow::Continue(())
+    }
+
+    fn end_game(&mut self, movetext: Self::Movetext) -> Self::Output {
+        let mut game = movetext.game;
+        let stats = NodeStats::from_outcome(game.outcome);
+        let mut id = Some(NodeId::ROOT);
+        while let Some(node) = id {
+            game.node_mut(node).stats = stats;
+            id = game.main_child(node);
+        }
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::Reader;
+
+    fn read_game(pgn: &[u8]) -> Game {
+        Reader::new(io::Cursor::new(pgn))
+            .read_game(&mut GameBuilder)
+            .expect("io")
+            .expect("game")
+    }
+
+    fn sans(game: &Game, id: NodeId) -> Vec<String> {
+        game.children(id)
+            .map(|child| game.node(child).san_plus.expect("move").to_string())
+            .collect()
+    }
+
+    fn nth(game: &Game, id: NodeId, n: usize) -> NodeId {
+        game.children(id).nth(n).expect("child")
+    }
+
+    #[test]
+    fn test_build_variations() {
+        let game = read_game(b"1. e4 (1. d4 d5 ({start} 1... Nf6)) e5 {comment} 2. Nf3 $1 *");
+
+        assert_eq!(sans(&game, NodeId::ROOT), ["e4", "d4"]);
+        let d4 = nth(&game, NodeId::ROOT, 1);
+        assert_eq!(sans(&game, d4), ["d5", "Nf6"]);
+        assert_eq!(game.node(d4).stats, NodeStats::default());
+        assert_eq!(
+            game.node(nth(&game, d4, 1)).starting_comments,
+            [b"start".to_vec()]
+        );
+        assert!(game.node(d4).comments.is_empty());
+        assert_eq!(game.parent(d4), Some(NodeId::ROOT));
+
+        let e5 = nth(&game, nth(&game, NodeId::ROOT, 0), 0);
+        assert_eq!(game.node(e5).comments, [b"comment".to_vec()]);
+        assert_eq!(game.node(nth(&game, e5, 0)).nags, [Nag::GOOD_MOVE]);
+        assert_eq!(game.mainline().count(), 3);
+        assert_eq!(game.node_count(), 7);
+        assert_eq!(game.outcome, Outcome::Unknown);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut game = read_game(b"1. e4 e5 2. Nf3 Nc6 1-0");
+        game.merge(read_game(b"1. e4 e5 2. Bc4 (2. Nf3 Nf6) 0-1"));
+        game.merge(read_game(b"1. d4 1/2-1/2"));
+
+        assert_eq!(
+            game.root().stats,
+            NodeStats {
+                games: 3,
+                white: 1,
+                draws: 1,
+                black: 1,
+            }
+        );
+        assert_eq!(sans(&game, NodeId::ROOT), ["e4", "d4"]);
+
+        let e5 = nth(&game, nth(&game, NodeId::ROOT, 0), 0);
+        assert_eq!(game.node(e5).stats.games, 2);
+        assert_eq!(sans(&game, e5), ["Nf3", "Bc4"]);
+        let nf3 = nth(&game, e5, 0);
+        assert_eq!(sans(&game, nf3), ["Nc6", "Nf6"]);
+        assert_eq!(game.node(nf3).stats.games, 1);
+        assert_eq!(game.node(nth(&game, nf3, 1)).stats.games, 0);
+    }
+
+    #[test]
+    fn test_add_line() {
+        let mut game = Game::new();
+        let line = |s: &str| {
+            s.split(' ')
+                .map(|san| san.parse::<SanPlus>().expect("valid san"))
+                .collect::<Vec<_>>()
+        };
+        game.add_line(line("e4 e5 Nf3"), Outcome::Unknown);
+        game.add_line(line("e4 c5"), Outcome::Known(KnownOutcome::Draw));
+
+        assert_eq!(game.root().stats.games, 2);
+        let e4 = nth(&game, NodeId::ROOT, 0);
+        assert_eq!(game.node(e4).stats.draws, 1);
+        assert_eq!(sans(&game, e4), ["e5", "c5"]);
+        assert_eq!(game.mainline().count(), 3);
+    }
+
+    #[test]
+    fn test_eq_ignores_arena_order() {
+        let line = |s: &str| {
+            s.split(' ')
+                .map(|san| san.parse::<SanPlus>().expect("valid san"))
+                .collect::<Vec<_>>()
+        };
+        let mut a = Game::new();
+        a.add_line(line("e4 e5"), Outcome::Unknown);
+        a.add_line(line("d4 d5"), Outcome::Unknown);
+        a.add_line(line("e4 c5"), Outcome::Unknown);
+
+        let mut b = Game::new();
+        b.add_line(line("e4 e5"), Outcome::Unknown);
+        b.add_line(line("e4 c5"), Outcome::Unknown);
+        b.add_line(line("d4 d5"), Outcome::Unknown);
+        assert_eq!(a, b);
+
+        let mut c = Game::new();
+        c.add_line(line("d4 d5"), Outcome::Unknown);
+        c.add_line(line("e4 e5"), Outcome::Unknown);
+        c.add_line(line("e4 c5"), Outcome::Unknown);
+        assert_ne!(a, c);
+    }
+}
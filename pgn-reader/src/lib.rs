@@ -25,6 +25,10 @@
 //!    - [`Visitor::outcome()`]
 //! 3. [`Visitor::end_game()`]
 //!
+//! For convenience, [`game::GameBuilder`] is a visitor that collects games
+//! into owned [`game::Game`] trees, which can be written back with a
+//! configurable [`writer::Writer`].
+//!
 //! # Examples
 //!
 //! A visitor that counts the number of syntactically valid moves in mainline
@@ -156,10 +160,12 @@
 
 mod buffer;
 pub mod comment;
+pub mod game;
 pub mod nag;
 pub mod reader;
 mod tag;
 mod visitor;
+pub mod writer;
 
 pub use comment::RawComment;
 pub use nag::Nag;
@@ -86,6 +86,29 @@ impl Nag {
         buf.push(b'0' + (self.0 % 10));
     }
 
+    /// Returns the traditional symbol for the move assessments
+    /// [`Nag::GOOD_MOVE`] to [`Nag::DUBIOUS_MOVE`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pgn_reader::Nag;
+    ///
+    /// assert_eq!(Nag::BLUNDER.symbol(), Some("??"));
+    /// assert_eq!(Nag(7).symbol(), None);
+    /// ```
+    pub const fn symbol(self) -> Option<&'static str> {
+        Some(match self {
+            Nag::GOOD_MOVE => "!",
+            Nag::MISTAKE => "?",
+            Nag::BRILLIANT_MOVE => "!!",
+            Nag::BLUNDER => "??",
+            Nag::SPECULATIVE_MOVE => "!?",
+            Nag::DUBIOUS_MOVE => "?!",
+            _ => return None,
+        })
+    }
+
     /// A good move (`!`).
     pub const GOOD_MOVE: Nag = Nag(1);
 
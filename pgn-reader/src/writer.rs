@@ -0,0 +1,459 @@
+//! Write [`Game`] trees as PGN.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::io;
+//! use pgn_reader::{
+//!     Reader,
+//!     game::GameBuilder,
+//!     writer::{LineEnding, NagStyle, Writer},
+//! };
+//!
+//! let pgn = b"[Event \"?\"]\n\n1. e4 $1 e5 (1... c5 {Sicilian}) 2. Nf3 *";
+//! let game = Reader::new(io::Cursor::new(&pgn))
+//!     .read_game(&mut GameBuilder)?
+//!     .expect("game");
+//!
+//! let mut writer = Writer::build(Vec::new())
+//!     .set_nag_style(NagStyle::Symbols)
+//!     .set_line_ending(LineEnding::CrLf)
+//!     .finish();
+//! writer.write_game(&game)?;
+//!
+//! assert_eq!(
+//!     writer.into_inner(),
+//!     b"[Event \"?\"]\r\n\r\n1. e4! e5 (1... c5 {Sicilian}) 2. Nf3 *\r\n\r\n"
+//! );
+//! # Ok::<_, io::Error>(())
+//! ```
+
+use std::{
+    io::{self, Write},
+    mem,
+};
+
+use shakmaty::{Color, fen::Fen};
+
+use crate::game::{Game, Node, NodeId};
+
+/// Line terminator to use when writing.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// `\n`.
+    #[default]
+    Lf,
+    /// `\r\n`.
+    CrLf,
+}
+
+impl LineEnding {
+    /// The line terminator as bytes.
+    pub const fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// Placement of `{ comments }` in the movetext.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CommentPlacement {
+    /// Comments flow inline with the moves.
+    #[default]
+    Inline,
+    /// Each comment is written on its own line.
+    OwnLine,
+}
+
+/// Notation for numeric annotation glyphs.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NagStyle {
+    /// Always write NAGs like `$1`, as required by the PGN export format.
+    #[default]
+    Numeric,
+    /// Write a traditional move assessment `!`, `?`, `!!`, `??`, `!?`, or
+    /// `?!` as a suffix of the move, like `e4!?`, if it is the first NAG of
+    /// the move. All other NAGs are written like `$n`, so that they can be
+    /// read back unambiguously.
+    Symbols,
+}
+
+/// Build a [`Writer`] with custom settings.
+#[derive(Debug, Clone)]
+pub struct WriterBuilder<W> {
+    writer: W,
+    max_line_length: Option<usize>,
+    comment_placement: CommentPlacement,
+    nag_style: NagStyle,
+    line_ending: LineEnding,
+}
+
+impl<W: Write> WriterBuilder<W> {
+    /// Create a [`WriterBuilder`] with default settings based on the PGN
+    /// export format.
+    pub fn new(writer: W) -> Self {
+        WriterBuilder {
+            writer,
+            max_line_length: Some(79),
+            comment_placement: CommentPlacement::Inline,
+            nag_style: NagStyle::Numeric,
+            line_ending: LineEnding::Lf,
+        }
+    }
+
+    /// Configure the maximum length of lines in the movetext, or `None` to
+    /// write the entire movetext on a single line.
+    ///
+    /// Tokens are never broken up, so lines may still exceed the limit if
+    /// a single token (like a long comment) does not fit.
+    ///
+    /// Defaults to `Some(79)`. Some databases require `Some(255)`.
+    pub fn set_max_line_length(mut self, max_line_length: Option<usize>) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Configure the placement of comments.
+    ///
+    /// Defaults to [`CommentPlacement::Inline`].
+    pub fn set_comment_placement(mut self, comment_placement: CommentPlacement) -> Self {
+        self.comment_placement = comment_placement;
+        self
+    }
+
+    /// Configure the notation for numeric annotation glyphs.
+    ///
+    /// Defaults to [`NagStyle::Numeric`].
+    pub fn set_nag_style(mut self, nag_style: NagStyle) -> Self {
+        self.nag_style = nag_style;
+        self
+    }
+
+    /// Configure the line terminator.
+    ///
+    /// Defaults to [`LineEnding::Lf`].
+    pub fn set_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Finalize and create a [`Writer`].
+    pub fn finish(self) -> Writer<W> {
+        Writer {
+            writer: self.writer,
+            max_line_length: self.max_line_length,
+            comment_placement: self.comment_placement,
+            nag_style: self.nag_style,
+            line_ending: self.line_ending,
+            buffer: Vec::new(),
+            scratch: Vec::new(),
+            column: 0,
+            open_paren: false,
+        }
+    }
+}
+
+/// Writes games as PGN.
+///
+/// Each game is formatted into an internal buffer and then written with a
+/// single call to the underlying writer.
+#[derive(Debug, Clone)]
+pub struct Writer<W> {
+    writer: W,
+    max_line_length: Option<usize>,
+    comment_placement: CommentPlacement,
+    nag_style: NagStyle,
+    line_ending: LineEnding,
+    buffer: Vec<u8>,
+    scratch: Vec<u8>,
+    column: usize,
+    open_paren: bool,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a writer with default settings based on the PGN export format.
+    pub fn new(writer: W) -> Writer<W> {
+        WriterBuilder::new(writer).finish()
+    }
+
+    /// Build a writer with custom settings.
+    pub fn build(writer: W) -> WriterBuilder<W> {
+        WriterBuilder::new(writer)
+    }
+
+    /// Write a game, followed by an empty line.
+    ///
+    /// If the game has a `FEN` tag, move numbers are derived from the
+    /// starting position.
+    ///
+    /// # Errors
+    ///
+    /// I/O error from the underlying writer.
+    pub fn write_game(&mut self, game: &Game) -> io::Result<()> {
+        self.buffer.clear();
+        self.column = 0;
+        self.open_paren = false;
+
+        for (name, value) in &game.tags {
+            self.buffer.push(b'[');
+            self.buffer.extend_from_slice(name);
+            self.buffer.extend_from_slice(b" \"");
+            self.buffer.extend_from_slice(value);
+            self.buffer.extend_from_slice(b"\"]");
+            self.buffer.extend_from_slice(self.line_ending.as_bytes());
+        }
+        if !game.tags.is_empty() {
+            self.buffer.extend_from_slice(self.line_ending.as_bytes());
+        }
+
+        self.write_comments(&game.root().comments);
+        self.write_line(game, NodeId::ROOT, initial_ply(game), true);
+        self.token(game.outcome.as_str().as_bytes());
+        self.end_line();
+        self.buffer.extend_from_slice(self.line_ending.as_bytes());
+
+        self.writer.write_all(&self.buffer)
+    }
+
+    /// Get the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_line(&mut self, game: &Game, parent: NodeId, mut ply: u64, mut force_number: bool) {
+        let mut node = parent;
+        while let Some(main) = game.main_child(node) {
+            force_number = self.write_node(game.node(main), ply, force_number);
+
+            for alternative in game.children(node).skip(1) {
+                self.open_variation();
+                let force = self.write_node(game.node(alternative), ply, true);
+                self.write_line(game, alternative, ply + 1, force);
+                self.close_variation();
+                force_number = true;
+            }
+
+            node = main;
+            ply += 1;
+        }
+    }
+
+    /// Writes the move of a node including its annotations. Returns whether
+    /// the following move needs an explicit move number.
+    fn write_node(&mut self, node: &Node, ply: u64, force_number: bool) -> bool {
+        let Some(san_plus) = node.san_plus else {
+            return false;
+        };
+
+        self.write_comments(&node.starting_comments);
+        let force_number = force_number || !node.starting_comments.is_empty();
+
+        let mut scratch = mem::take(&mut self.scratch);
+
+        if ply % 2 == 0 || force_number {
+            scratch.clear();
+            append_number(&mut scratch, ply / 2 + 1);
+            scratch.extend_from_slice(if ply % 2 == 0 { b"." } else { b"..." });
+            self.token(&scratch);
+        }
+
+        scratch.clear();
+        san_plus.append_ascii_to(&mut scratch);
+        let mut nags = node.nags.iter().copied().peekable();
+        if self.nag_style == NagStyle::Symbols {
+            if let Some(symbol) = nags.peek().and_then(|nag| nag.symbol()) {
+                scratch.extend_from_slice(symbol.as_bytes());
+                nags.next();
+            }
+        }
+        self.token(&scratch);
+
+        for nag in nags {
+            scratch.clear();
+            nag.append_ascii_to(&mut scratch);
+            self.token(&scratch);
+        }
+
+        self.scratch = scratch;
+
+        self.write_comments(&node.comments);
+        !node.comments.is_empty()
+    }
+
+    fn write_comments(&mut self, comments: &[Vec<u8>]) {
+        let mut token = mem::take(&mut self.scratch);
+        for comment in comments {
+            token.clear();
+            token.push(b'{');
+            token.extend_from_slice(comment);
+            token.push(b'}');
+            match self.comment_placement {
+                CommentPlacement::Inline => self.token(&token),
+                CommentPlacement::OwnLine => {
+                    self.end_line();
+                    self.token(&token);
+                    self.end_line();
+                }
+            }
+        }
+        self.scratch = token;
+    }
+
+    fn open_variation(&mut self) {
+        self.open_paren = true;
+    }
+
+    fn close_variation(&mut self) {
+        self.append(b")");
+    }
+
+    /// Writes a token (with a pending opening parenthesis), separated by a
+    /// space or a line break.
+    fn token(&mut self, token: &[u8]) {
+        let open_paren = mem::take(&mut self.open_paren);
+        if self.column > 0 {
+            let first_line = memchr::memchr(b'\n', token).unwrap_or(token.len());
+            if self
+                .max_line_length
+                .is_some_and(|max| self.column + 1 + usize::from(open_paren) + first_line > max)
+            {
+                self.end_line();
+            } else {
+                self.buffer.push(b' ');
+                self.column += 1;
+            }
+        }
+        if open_paren {
+            self.append(b"(");
+        }
+        self.append(token);
+    }
+
+    /// Appends bytes directly to the current token.
+    fn append(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+        self.column = match memchr::memrchr(b'\n', bytes) {
+            Some(newline) => bytes.len() - newline - 1,
+            None => self.column + bytes.len(),
+        };
+    }
+
+    fn end_line(&mut self) {
+        if self.column > 0 {
+            self.buffer.extend_from_slice(self.line_ending.as_bytes());
+            self.column = 0;
+        }
+    }
+}
+
+fn append_number(buf: &mut Vec<u8>, mut n: u64) {
+    let start = buf.len();
+    loop {
+        buf.push(b'0' + (n % 10) as u8);
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    buf[start..].reverse();
+}
+
+fn initial_ply(game: &Game) -> u64 {
+    game.tag(b"FEN")
+        .and_then(|fen| Fen::from_ascii(&fen.decode()).ok())
+        .map_or(0, |fen| {
+            let setup = fen.as_setup();
+            (u64::from(setup.fullmoves.get()) - 1)
+                .saturating_mul(2)
+                .saturating_add(u64::from(setup.turn == Color::Black))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Reader, game::GameBuilder};
+
+    fn read_game(pgn: &[u8]) -> Game {
+        Reader::new(io::Cursor::new(pgn))
+            .read_game(&mut GameBuilder)
+            .expect("io")
+            .expect("game")
+    }
+
+    fn write_game(game: &Game, builder: WriterBuilder<Vec<u8>>) -> String {
+        let mut writer = builder.finish();
+        writer.write_game(game).expect("write to vec");
+        String::from_utf8(writer.into_inner()).expect("utf-8")
+    }
+
+    #[test]
+    fn test_move_numbers() {
+        let game = read_game(
+            b"[FEN \"4k3/8/8/8/8/8/8/4K2R b K - 0 12\"]\n\n12... Kd7 {c} 13. O-O (13. Rh7+ Ke6) Kc6 1-0",
+        );
+        assert_eq!(
+            write_game(&game, Writer::build(Vec::new())),
+            "[FEN \"4k3/8/8/8/8/8/8/4K2R b K - 0 12\"]\n\n12... Kd7 {c} 13. O-O (13. Rh7+ Ke6) 13... Kc6 1-0\n\n"
+        );
+    }
+
+    #[test]
+    fn test_extreme_fullmoves() {
+        let game = read_game(b"[FEN \"4k3/8/8/8/8/8/8/4K3 b - - 0 4294967295\"]\n\nKd7 *");
+        assert!(write_game(&game, Writer::build(Vec::new())).ends_with("4294967295... Kd7 *\n\n"));
+    }
+
+    #[test]
+    fn test_line_length() {
+        let game = read_game(b"1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O Be7 *");
+        assert_eq!(
+            write_game(
+                &game,
+                Writer::build(Vec::new()).set_max_line_length(Some(20))
+            ),
+            "1. e4 e5 2. Nf3 Nc6\n3. Bb5 a6 4. Ba4 Nf6\n5. O-O Be7 *\n\n"
+        );
+        assert_eq!(
+            write_game(&game, Writer::build(Vec::new()).set_max_line_length(None)),
+            "1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O Be7 *\n\n"
+        );
+    }
+
+    #[test]
+    fn test_comments_and_nags() {
+        let game = read_game(b"{start} 1. e4 ?! {first} {second} e5 $12 *");
+        assert_eq!(
+            write_game(
+                &game,
+                Writer::build(Vec::new())
+                    .set_comment_placement(CommentPlacement::OwnLine)
+                    .set_line_ending(LineEnding::CrLf)
+            ),
+            "{start}\r\n1. e4 $6\r\n{first}\r\n{second}\r\n1... e5 $12 *\r\n\r\n"
+        );
+        assert_eq!(
+            write_game(
+                &game,
+                Writer::build(Vec::new()).set_nag_style(NagStyle::Symbols)
+            ),
+            "{start} 1. e4?! {first} {second} 1... e5 $12 *\n\n"
+        );
+    }
+
+    #[test]
+    fn test_symbol_nags_roundtrip() {
+        let game = read_game(b"1. e4 $1 $6 $14 {comment} e5 $4 $2 (1... c5 $5 $7) *");
+        let pgn = write_game(
+            &game,
+            Writer::build(Vec::new()).set_nag_style(NagStyle::Symbols),
+        );
+        assert_eq!(
+            pgn,
+            "1. e4! $6 $14 {comment} 1... e5?? $2 (1... c5!? $7) *\n\n"
+        );
+        assert_eq!(read_game(pgn.as_bytes()), game);
+    }
+}
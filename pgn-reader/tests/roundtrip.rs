@@ -0,0 +1,48 @@
+use std::{fs, io};
+
+use pgn_reader::{
+    Reader,
+    game::{Game, GameBuilder},
+    writer::{LineEnding, Writer, WriterBuilder},
+};
+
+fn read_games(pgn: &[u8]) -> Vec<Game> {
+    Reader::new(io::Cursor::new(pgn))
+        .read_games(&mut GameBuilder)
+        .collect::<io::Result<_>>()
+        .expect("valid pgn")
+}
+
+fn write_games(games: &[Game], builder: WriterBuilder<Vec<u8>>) -> Vec<u8> {
+    let mut writer = builder.finish();
+    for game in games {
+        writer.write_game(game).expect("write to vec");
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_roundtrip_corpus() {
+    let pgn = fs::read("tests/roundtrip.pgn").expect("corpus");
+    let games = read_games(&pgn);
+    assert_eq!(games.len(), 4);
+
+    // The corpus is already in export format, so writing it back must
+    // reproduce it byte for byte.
+    let written = write_games(&games, Writer::build(Vec::new()));
+    assert_eq!(
+        String::from_utf8_lossy(&written),
+        String::from_utf8_lossy(&pgn)
+    );
+
+    // Different whitespace yields the same games.
+    for builder in [
+        Writer::build(Vec::new()).set_max_line_length(None),
+        Writer::build(Vec::new()).set_max_line_length(Some(255)),
+        Writer::build(Vec::new())
+            .set_max_line_length(Some(10))
+            .set_line_ending(LineEnding::CrLf),
+    ] {
+        assert_eq!(read_games(&write_games(&games, builder)), games);
+    }
+}
@@ -1,11 +1,25 @@
 //! Traits to provide a custom filesystem implementation.
 
+#[cfg(any(unix, windows, all(feature = "mmap", target_pointer_width = "64")))]
+use std::fs;
+#[cfg(any(feature = "http", feature = "zstd"))]
+use std::sync::Arc;
 use std::{
-    fs, io,
+    io,
     path::{Path, PathBuf},
 };
 
+#[cfg(any(feature = "http", feature = "zstd"))]
+use rustc_hash::FxHashMap;
+
 /// An abstract filesystem.
+///
+/// Paths are opaque to the tablebase. They are only inspected to determine
+/// the table name from the final component (e.g., `KQvKP.rtbw`), and
+/// otherwise passed back to the implementation. So implementations need not
+/// be backed by [`std::fs`], and can, for example, map paths to URLs or keys
+/// of a blob store. This also allows providing tables on targets without
+/// a filesystem, like `wasm32-unknown-unknown`.
 pub trait Filesystem: Sync + Send {
     /// Determines the size in bytes of the given file.
     ///
@@ -21,10 +35,20 @@ pub trait Filesystem: Sync + Send {
     /// Returns a list of files in the given directory. May filter for table
     /// files.
     ///
+    /// Only required for
+    /// [`Tablebase::add_directory()`](crate::Tablebase::add_directory).
+    /// Implementations that cannot list directories can rely on the default
+    /// implementation, and tables can be added individually using
+    /// [`Tablebase::add_file()`](crate::Tablebase::add_file).
+    ///
     /// # Errors
     ///
-    /// See [`std::fs::read_dir()`].
-    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    /// See [`std::fs::read_dir()`]. The default implementation errors with
+    /// [`std::io::ErrorKind::Unsupported`].
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let _ = path;
+        Err(io::ErrorKind::Unsupported.into())
+    }
 
     /// Opens the given file, returning a handle for random read requests.
     ///
@@ -191,6 +215,9 @@ mod mmap {
     /// A filesystem implementation using memory maps to read table files.
     pub struct MmapFilesystem {
         advise_random: bool,
+        advise_willneed: bool,
+        huge_pages: bool,
+        populate: bool,
         _unsafe_priv: (),
     }
 
@@ -198,6 +225,9 @@ mod mmap {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             f.debug_struct("MmapFilesystem")
                 .field("advise_random", &self.advise_random)
+                .field("advise_willneed", &self.advise_willneed)
+                .field("huge_pages", &self.huge_pages)
+                .field("populate", &self.populate)
                 .finish_non_exhaustive()
         }
     }
@@ -219,6 +249,9 @@ mod mmap {
         pub unsafe fn new() -> MmapFilesystem {
             MmapFilesystem {
                 advise_random: false,
+                advise_willneed: false,
+                huge_pages: false,
+                populate: false,
                 _unsafe_priv: (),
             }
         }
@@ -232,6 +265,43 @@ mod mmap {
             self.advise_random = advise_random;
             self
         }
+
+        /// Determines whether the `MADV_WILLNEED` hint is used when creating
+        /// memory maps, so that the operating system starts reading the
+        /// table in the background.
+        ///
+        /// Useful when the entire table fits into memory. Defaults to `false`.
+        #[must_use]
+        pub fn with_advise_willneed(mut self, advise_willneed: bool) -> Self {
+            self.advise_willneed = advise_willneed;
+            self
+        }
+
+        /// Determines whether the `MADV_HUGEPAGE` hint is used when creating
+        /// memory maps on Linux. Ignored on other platforms.
+        ///
+        /// Transparent huge pages for file-backed memory maps require
+        /// kernel support (e.g., `CONFIG_READ_ONLY_THP_FOR_FS`) and
+        /// are otherwise ignored.
+        ///
+        /// Defaults to `false`.
+        #[must_use]
+        pub fn with_huge_pages(mut self, huge_pages: bool) -> Self {
+            self.huge_pages = huge_pages;
+            self
+        }
+
+        /// Determines whether memory maps are pre-touched when tables are
+        /// opened (using `MAP_POPULATE` on Linux), so that later probes do
+        /// not incur page faults.
+        ///
+        /// Opening tables will read the entire table from disk.
+        /// Defaults to `false`.
+        #[must_use]
+        pub fn with_populate(mut self, populate: bool) -> Self {
+            self.populate = populate;
+            self
+        }
     }
 
     impl Filesystem for MmapFilesystem {
@@ -246,14 +316,29 @@ mod mmap {
         fn open(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
             let file = fs::File::open(path)?;
 
+            let mut options = MmapOptions::new();
+            if self.populate {
+                options.populate();
+            }
+
             // Safety: Contract forwarded to MmapFilesystem::new().
-            let mmap = unsafe { MmapOptions::new().map(&file)? };
+            let mmap = unsafe { options.map(&file)? };
 
             #[cfg(unix)]
             if self.advise_random {
                 mmap.advise(memmap2::Advice::Random)?;
             }
 
+            #[cfg(unix)]
+            if self.advise_willneed {
+                mmap.advise(memmap2::Advice::WillNeed)?;
+            }
+
+            #[cfg(target_os = "linux")]
+            if self.huge_pages {
+                mmap.advise(memmap2::Advice::HugePage)?;
+            }
+
             Ok(Box::new(MmapRandomAccessFile { mmap }))
         }
     }
@@ -279,6 +364,583 @@ mod mmap {
 #[cfg(all(feature = "mmap", target_pointer_width = "64"))]
 pub use mmap::MmapFilesystem;
 
+/// Least recently used cache of blocks.
+#[cfg(any(feature = "http", feature = "zstd"))]
+#[derive(Default)]
+struct BlockCache {
+    blocks: FxHashMap<u64, (Arc<[u8]>, u64)>,
+    tick: u64,
+}
+
+#[cfg(any(feature = "http", feature = "zstd"))]
+impl BlockCache {
+    fn get(&mut self, index: u64) -> Option<Arc<[u8]>> {
+        self.tick += 1;
+        let (block, last_used) = self.blocks.get_mut(&index)?;
+        *last_used = self.tick;
+        Some(Arc::clone(block))
+    }
+
+    fn insert(&mut self, index: u64, block: Arc<[u8]>, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        while self.blocks.len() >= capacity {
+            let Some(lru) = self
+                .blocks
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(index, _)| *index)
+            else {
+                break;
+            };
+            self.blocks.remove(&lru);
+        }
+        self.tick += 1;
+        self.blocks.insert(index, (block, self.tick));
+    }
+}
+
+#[cfg(feature = "http")]
+mod http {
+    use std::{cmp::min, fmt, sync::Mutex};
+
+    use ureq::{Agent, http::StatusCode};
+
+    use super::*;
+
+    /// A filesystem implementation that reads table files from a remote
+    /// mirror via HTTP range requests.
+    ///
+    /// Paths are interpreted relative to the base URL of the mirror. Recently
+    /// read blocks are kept in a local cache for each opened file.
+    ///
+    /// Directory listings are parsed from the links of an HTML index page, as
+    /// served by typical autoindex modules. Alternatively, add tables
+    /// individually using [`Tablebase::add_file()`](crate::Tablebase::add_file).
+    pub struct HttpFilesystem {
+        agent: Agent,
+        base_url: String,
+        block_size: u64,
+        cache_blocks: usize,
+    }
+
+    impl fmt::Debug for HttpFilesystem {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("HttpFilesystem")
+                .field("base_url", &self.base_url)
+                .field("block_size", &self.block_size)
+                .field("cache_blocks", &self.cache_blocks)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl HttpFilesystem {
+        /// Creates a new instance of a filesystem implementation that
+        /// reads table files from the given base URL, e.g.,
+        /// `https://tablebase.lichess.ovh/tables/standard`.
+        pub fn new(base_url: impl Into<String>) -> HttpFilesystem {
+            HttpFilesystem::with_agent(Agent::new_with_defaults(), base_url)
+        }
+
+        /// Creates a new instance using a custom configured HTTP agent.
+        pub fn with_agent(agent: Agent, base_url: impl Into<String>) -> HttpFilesystem {
+            let mut base_url = base_url.into();
+            while base_url.ends_with('/') {
+                base_url.pop();
+            }
+            HttpFilesystem {
+                agent,
+                base_url,
+                block_size: 16 * 1024,
+                cache_blocks: 256,
+            }
+        }
+
+        /// Sets the number of bytes requested at once. Reads are aligned to
+        /// blocks of this size.
+        ///
+        /// Defaults to 16 KiB.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `block_size` is `0`.
+        #[must_use]
+        pub fn with_block_size(mut self, block_size: u64) -> Self {
+            assert!(block_size > 0, "block size must be positive");
+            self.block_size = block_size;
+            self
+        }
+
+        /// Sets the maximum number of blocks cached for each opened file.
+        ///
+        /// Defaults to `256`.
+        #[must_use]
+        pub fn with_cache_blocks(mut self, cache_blocks: usize) -> Self {
+            self.cache_blocks = cache_blocks;
+            self
+        }
+
+        fn url(&self, path: &Path) -> io::Result<String> {
+            let mut url = self.base_url.clone();
+            for component in path.components() {
+                match component {
+                    std::path::Component::Normal(part) => {
+                        url.push('/');
+                        url.push_str(part.to_str().ok_or(io::ErrorKind::InvalidInput)?);
+                    }
+                    std::path::Component::RootDir | std::path::Component::CurDir => (),
+                    _ => return Err(io::ErrorKind::InvalidInput.into()),
+                }
+            }
+            Ok(url)
+        }
+    }
+
+    impl Filesystem for HttpFilesystem {
+        fn regular_file_size(&self, path: &Path) -> io::Result<u64> {
+            let response = self
+                .agent
+                .head(&self.url(path)?)
+                .call()
+                .map_err(ureq::Error::into_io)?;
+            response
+                .headers()
+                .get("content-length")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing content length"))
+        }
+
+        fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+            let mut url = self.url(path)?;
+            url.push('/');
+            let listing = self
+                .agent
+                .get(&url)
+                .call()
+                .map_err(ureq::Error::into_io)?
+                .body_mut()
+                .read_to_string()
+                .map_err(ureq::Error::into_io)?;
+            Ok(parse_listing(&listing)
+                .map(|name| path.join(name))
+                .collect())
+        }
+
+        fn open(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
+            Ok(Box::new(HttpRandomAccessFile {
+                agent: self.agent.clone(),
+                size: self.regular_file_size(path)?,
+                url: self.url(path)?,
+                block_size: self.block_size,
+                cache_blocks: self.cache_blocks,
+                cache: Mutex::new(BlockCache::default()),
+            }))
+        }
+    }
+
+    fn parse_listing(html: &str) -> impl Iterator<Item = &str> {
+        html.split("href=\"").skip(1).filter_map(|tail| {
+            let name = &tail[..tail.find('"')?];
+            (!name.is_empty() && !name.contains(['/', '?', '#', '%'])).then_some(name)
+        })
+    }
+
+    struct HttpRandomAccessFile {
+        agent: Agent,
+        url: String,
+        size: u64,
+        block_size: u64,
+        cache_blocks: usize,
+        cache: Mutex<BlockCache>,
+    }
+
+    impl HttpRandomAccessFile {
+        fn block(&self, index: u64) -> io::Result<Arc<[u8]>> {
+            if let Some(block) = self.cache.lock().expect("cache lock").get(index) {
+                return Ok(block);
+            }
+
+            let start = index * self.block_size;
+            let end = min(start + self.block_size, self.size);
+            let mut response = self
+                .agent
+                .get(&self.url)
+                .header("Range", &format!("bytes={}-{}", start, end - 1))
+                .call()
+                .map_err(ureq::Error::into_io)?;
+            if response.status() != StatusCode::PARTIAL_CONTENT {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "server does not support range requests",
+                ));
+            }
+            let block: Arc<[u8]> = response
+                .body_mut()
+                .read_to_vec()
+                .map_err(ureq::Error::into_io)?
+                .into();
+            if block.len() as u64 != end - start {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            self.cache.lock().expect("cache lock").insert(
+                index,
+                Arc::clone(&block),
+                self.cache_blocks,
+            );
+            Ok(block)
+        }
+    }
+
+    impl RandomAccessFile for HttpRandomAccessFile {
+        fn read_at(&self, buf: &mut [u8], offset: u64, _hint: ReadHint) -> io::Result<usize> {
+            if offset >= self.size || buf.is_empty() {
+                return Ok(0);
+            }
+            let block = self.block(offset / self.block_size)?;
+            let block = &block[(offset % self.block_size) as usize..];
+            let n = min(buf.len(), block.len());
+            buf[..n].copy_from_slice(&block[..n]);
+            Ok(n)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_listing() {
+            let html = r#"<a href="../">../</a>
+                <a href="KQvK.rtbw">KQvK.rtbw</a>
+                <a href="KQvK.rtbz">KQvK.rtbz</a>
+                <a href="?C=M;O=A">Last modified</a>"#;
+            assert_eq!(
+                parse_listing(html).collect::<Vec<_>>(),
+                ["KQvK.rtbw", "KQvK.rtbz"]
+            );
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+pub use http::HttpFilesystem;
+
+#[cfg(feature = "zstd")]
+mod zstd {
+    use std::{ffi::OsString, fmt, sync::Mutex};
+
+    use ruzstd::decoding::FrameDecoder;
+
+    use super::*;
+
+    const SKIPPABLE_MAGIC: u32 = 0x184d_2a5e;
+    const SEEKABLE_MAGIC: u32 = 0x8f92_eab1;
+    const FOOTER_SIZE: u64 = 9;
+
+    /// A filesystem adapter that reads table files from
+    /// [Zstandard seekable format](https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md)
+    /// containers provided by another filesystem.
+    ///
+    /// A table like `KQvK.rtbw` is read from `KQvK.rtbw.zst`, decompressing
+    /// individual frames as needed. Recently used frames are kept in a cache
+    /// for each opened file. Smaller frames allow faster random access, at
+    /// the cost of compression ratio.
+    pub struct ZstdFilesystem {
+        inner: Arc<dyn Filesystem>,
+        cache_frames: usize,
+    }
+
+    impl fmt::Debug for ZstdFilesystem {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("ZstdFilesystem")
+                .field("cache_frames", &self.cache_frames)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl ZstdFilesystem {
+        /// Creates a new adapter, reading compressed table files from
+        /// `inner`.
+        pub fn new(inner: Arc<dyn Filesystem>) -> ZstdFilesystem {
+            ZstdFilesystem {
+                inner,
+                cache_frames: 64,
+            }
+        }
+
+        /// Sets the maximum number of decompressed frames cached for each
+        /// opened file.
+        ///
+        /// Defaults to `64`.
+        #[must_use]
+        pub fn with_cache_frames(mut self, cache_frames: usize) -> Self {
+            self.cache_frames = cache_frames;
+            self
+        }
+
+        fn open_seekable(&self, path: &Path) -> io::Result<(Box<dyn RandomAccessFile>, SeekTable)> {
+            let mut compressed_path = OsString::from(path.as_os_str());
+            compressed_path.push(".zst");
+            let compressed_path = PathBuf::from(compressed_path);
+
+            let size = self.inner.regular_file_size(&compressed_path)?;
+            let raf = self.inner.open(&compressed_path)?;
+            let seek_table = SeekTable::read(&*raf, size)?;
+            Ok((raf, seek_table))
+        }
+    }
+
+    impl Filesystem for ZstdFilesystem {
+        fn regular_file_size(&self, path: &Path) -> io::Result<u64> {
+            self.open_seekable(path)
+                .map(|(_, seek_table)| seek_table.decompressed_size())
+        }
+
+        fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+            Ok(self
+                .inner
+                .read_dir(path)?
+                .into_iter()
+                .filter(|entry| entry.extension().is_some_and(|ext| ext == "zst"))
+                .map(|entry| entry.with_extension(""))
+                .collect())
+        }
+
+        fn open(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
+            let (raf, seek_table) = self.open_seekable(path)?;
+            Ok(Box::new(ZstdRandomAccessFile {
+                raf,
+                seek_table,
+                cache_frames: self.cache_frames,
+                cache: Mutex::new(BlockCache::default()),
+            }))
+        }
+    }
+
+    struct Frame {
+        compressed_offset: u64,
+        decompressed_offset: u64,
+        compressed_size: u32,
+        decompressed_size: u32,
+    }
+
+    struct SeekTable {
+        frames: Vec<Frame>,
+    }
+
+    impl SeekTable {
+        fn read(raf: &dyn RandomAccessFile, size: u64) -> io::Result<SeekTable> {
+            let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+            // Read footer.
+            let footer_offset = size
+                .checked_sub(FOOTER_SIZE)
+                .ok_or_else(|| invalid("zstd seek table footer missing"))?;
+            let mut footer = [0; FOOTER_SIZE as usize];
+            raf.read_exact_at(&mut footer, footer_offset, ReadHint::Header)?;
+            let num_frames = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]);
+            let descriptor = footer[4];
+            if u32::from_le_bytes([footer[5], footer[6], footer[7], footer[8]]) != SEEKABLE_MAGIC {
+                return Err(invalid("not a zstd seekable file"));
+            }
+            if descriptor & 0x7c != 0 {
+                return Err(invalid("reserved bits set in zstd seek table descriptor"));
+            }
+            let entry_size = if descriptor & 0x80 != 0 { 12 } else { 8 };
+
+            // Read skippable frame header.
+            let table_size = u64::from(num_frames) * entry_size;
+            let table_offset = footer_offset
+                .checked_sub(table_size)
+                .ok_or_else(|| invalid("zstd seek table truncated"))?;
+            let header_offset = table_offset
+                .checked_sub(8)
+                .ok_or_else(|| invalid("zstd seek table truncated"))?;
+            let mut header = [0; 8];
+            raf.read_exact_at(&mut header, header_offset, ReadHint::Header)?;
+            if u32::from_le_bytes([header[0], header[1], header[2], header[3]]) != SKIPPABLE_MAGIC
+                || u64::from(u32::from_le_bytes([
+                    header[4], header[5], header[6], header[7],
+                ])) != table_size + FOOTER_SIZE
+            {
+                return Err(invalid("invalid zstd seek table frame"));
+            }
+
+            // Read entries.
+            let mut table = vec![0; table_size as usize];
+            raf.read_exact_at(&mut table, table_offset, ReadHint::Header)?;
+            let mut frames = Vec::with_capacity(num_frames as usize);
+            let mut compressed_offset = 0;
+            let mut decompressed_offset = 0;
+            for entry in table.chunks_exact(entry_size as usize) {
+                let compressed_size = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+                let decompressed_size =
+                    u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]);
+                frames.push(Frame {
+                    compressed_offset,
+                    decompressed_offset,
+                    compressed_size,
+                    decompressed_size,
+                });
+                compressed_offset += u64::from(compressed_size);
+                decompressed_offset += u64::from(decompressed_size);
+            }
+            if compressed_offset > header_offset {
+                return Err(invalid("zstd frames overlap seek table"));
+            }
+
+            Ok(SeekTable { frames })
+        }
+
+        fn decompressed_size(&self) -> u64 {
+            self.frames.last().map_or(0, |frame| {
+                frame.decompressed_offset + u64::from(frame.decompressed_size)
+            })
+        }
+    }
+
+    struct ZstdRandomAccessFile {
+        raf: Box<dyn RandomAccessFile>,
+        seek_table: SeekTable,
+        cache_frames: usize,
+        cache: Mutex<BlockCache>,
+    }
+
+    impl ZstdRandomAccessFile {
+        fn frame(&self, index: usize, hint: ReadHint) -> io::Result<Arc<[u8]>> {
+            if let Some(block) = self.cache.lock().expect("cache lock").get(index as u64) {
+                return Ok(block);
+            }
+
+            let frame = &self.seek_table.frames[index];
+            let mut compressed = vec![0; frame.compressed_size as usize];
+            self.raf
+                .read_exact_at(&mut compressed, frame.compressed_offset, hint)?;
+            let mut decompressed = vec![0; frame.decompressed_size as usize];
+            let n = FrameDecoder::new()
+                .decode_all(&compressed, &mut decompressed)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            if n != decompressed.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "zstd frame size does not match seek table",
+                ));
+            }
+
+            let block: Arc<[u8]> = decompressed.into();
+            self.cache.lock().expect("cache lock").insert(
+                index as u64,
+                Arc::clone(&block),
+                self.cache_frames,
+            );
+            Ok(block)
+        }
+    }
+
+    impl RandomAccessFile for ZstdRandomAccessFile {
+        fn read_at(&self, buf: &mut [u8], offset: u64, hint: ReadHint) -> io::Result<usize> {
+            let frames = &self.seek_table.frames;
+            let index = frames.partition_point(|frame| {
+                frame.decompressed_offset + u64::from(frame.decompressed_size) <= offset
+            });
+            if index >= frames.len() || buf.is_empty() {
+                return Ok(0);
+            }
+            let block = self.frame(index, hint)?;
+            let block = &block[(offset - frames[index].decompressed_offset) as usize..];
+            let n = buf.len().min(block.len());
+            buf[..n].copy_from_slice(&block[..n]);
+            Ok(n)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ruzstd::encoding::{CompressionLevel, compress_to_vec};
+
+        use super::*;
+
+        struct MemoryFilesystem {
+            path: PathBuf,
+            bytes: Arc<[u8]>,
+        }
+
+        struct MemoryRandomAccessFile(Arc<[u8]>);
+
+        impl RandomAccessFile for MemoryRandomAccessFile {
+            fn read_at(&self, buf: &mut [u8], offset: u64, _hint: ReadHint) -> io::Result<usize> {
+                let tail = self.0.get(offset as usize..).unwrap_or_default();
+                let n = buf.len().min(tail.len());
+                buf[..n].copy_from_slice(&tail[..n]);
+                Ok(n)
+            }
+        }
+
+        impl Filesystem for MemoryFilesystem {
+            fn regular_file_size(&self, path: &Path) -> io::Result<u64> {
+                if path == self.path {
+                    Ok(self.bytes.len() as u64)
+                } else {
+                    Err(io::ErrorKind::NotFound.into())
+                }
+            }
+
+            fn read_dir(&self, _path: &Path) -> io::Result<Vec<PathBuf>> {
+                Ok(vec![self.path.clone()])
+            }
+
+            fn open(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
+                self.regular_file_size(path)?;
+                Ok(Box::new(MemoryRandomAccessFile(Arc::clone(&self.bytes))))
+            }
+        }
+
+        #[test]
+        fn test_seekable() {
+            let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+
+            let mut container = Vec::new();
+            let mut seek_table = Vec::new();
+            for chunk in data.chunks(300) {
+                let frame = compress_to_vec(chunk, CompressionLevel::Fastest);
+                seek_table.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+                seek_table.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+                container.extend_from_slice(&frame);
+            }
+            container.extend_from_slice(&SKIPPABLE_MAGIC.to_le_bytes());
+            container.extend_from_slice(&(seek_table.len() as u32 + 9).to_le_bytes());
+            container.extend_from_slice(&seek_table);
+            container.extend_from_slice(&4u32.to_le_bytes());
+            container.push(0);
+            container.extend_from_slice(&SEEKABLE_MAGIC.to_le_bytes());
+
+            let filesystem = ZstdFilesystem::new(Arc::new(MemoryFilesystem {
+                path: PathBuf::from("tables/KQvK.rtbw.zst"),
+                bytes: container.into(),
+            }));
+            assert_eq!(
+                filesystem.read_dir(Path::new("tables")).expect("read dir"),
+                [PathBuf::from("tables/KQvK.rtbw")]
+            );
+            let path = Path::new("tables/KQvK.rtbw");
+            assert_eq!(filesystem.regular_file_size(path).expect("size"), 1000);
+
+            let raf = filesystem.open(path).expect("open");
+            let mut buf = [0; 100];
+            raf.read_exact_at(&mut buf, 250, ReadHint::Data)
+                .expect("read across frames");
+            assert_eq!(&buf[..], &data[250..350]);
+            assert_eq!(raf.read_at(&mut buf, 1000, ReadHint::Data).expect("eof"), 0);
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+pub use zstd::ZstdFilesystem;
+
 #[cfg(any(unix, windows, all(feature = "mmap", target_pointer_width = "64")))]
 fn regular_file_size_impl(path: &Path) -> io::Result<u64> {
     let meta = path.metadata()?;
@@ -0,0 +1,726 @@
+//! Generate small tables, for testing.
+//!
+//! WDL tables for endgames with up to 4 pieces are generated by retrograde
+//! analysis, and written in the Syzygy format, so that they can be probed
+//! like any other table. Positions are indexed like in the written table,
+//! so that the state space is reduced by the same symmetries.
+//!
+//! The generator does not take the 50-move rule into account, so it
+//! generates wrong tables for endgames with cursed wins or blessed losses.
+//! There are no such endgames with up to 4 pieces in standard chess.
+//!
+//! # Example
+//!
+//! ```
+//! use shakmaty::{CastlingMode, Chess, fen::Fen};
+//! use shakmaty_syzygy::{Tablebase, Wdl, generator::GeneratedFilesystem};
+//!
+//! let filesystem = GeneratedFilesystem::new().with_wdl_table::<Chess>(&"KRvK".parse()?);
+//!
+//! let mut tables = Tablebase::with_filesystem(std::sync::Arc::new(filesystem));
+//! tables.add_directory("generated")?;
+//!
+//! let pos: Chess = "8/8/8/8/8/2k5/8/KR6 w - - 0 1"
+//!     .parse::<Fen>()?
+//!     .into_position(CastlingMode::Standard)?;
+//!
+//! assert_eq!(tables.probe_wdl_after_zeroing(&pos)?, Wdl::Win);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+
+use rustc_hash::FxHashMap;
+use shakmaty::{
+    Board, CastlingMode, Color, EnPassantMode, File, FromSetup, Piece, Position, PositionError,
+    Role, Setup, Square,
+};
+
+use crate::{
+    filesystem::{Filesystem, RandomAccessFile},
+    material::Material,
+    table::WdlTable,
+    tablebase::BytesRandomAccessFile,
+    types::{Syzygy, Wdl},
+};
+
+/// Maximum number of pieces supported by the generator.
+pub const MAX_PIECES: usize = 4;
+
+const LOSS: u8 = 0;
+const DRAW: u8 = 2;
+const WIN: u8 = 4;
+const UNKNOWN: u8 = 5;
+const INVALID: u8 = 6;
+
+const BLOCK_SIZE_LOG: usize = 6;
+const SPAN_LOG: usize = 10;
+const MAX_RUN_LOG: usize = 8;
+
+fn value(wdl: Wdl) -> u8 {
+    match wdl {
+        Wdl::Loss => 0,
+        Wdl::BlessedLoss => 1,
+        Wdl::Draw => 2,
+        Wdl::CursedWin => 3,
+        Wdl::Win => 4,
+    }
+}
+
+/// Orders the pieces of `material` as expected by the table encoding:
+/// Leading pawns or unique pieces first, then groups of identical pieces.
+fn pieces(material: &Material) -> Vec<Piece> {
+    let mut unique = Vec::new();
+    let mut groups: Vec<Vec<Piece>> = Vec::new();
+    for color in Color::ALL {
+        for (role, count) in material.by_role(color).zip_role().into_iter().rev() {
+            let piece = role.of(color);
+            if count == 1 {
+                unique.push(piece);
+            } else if count > 1 {
+                groups.push(vec![piece; usize::from(count)]);
+            }
+        }
+    }
+
+    let lead_pawns = Color::ALL
+        .into_iter()
+        .filter(|&color| material.by_role(color).pawn > 0)
+        .min_by_key(|&color| material.by_role(color).pawn);
+
+    let mut pieces: Vec<Piece> = unique
+        .into_iter()
+        .chain(groups.into_iter().flatten())
+        .collect();
+    if let Some(color) = lead_pawns {
+        // Leading pawns, then remaining pawns, then other pieces.
+        pieces.sort_by_key(|piece| (piece.role != Role::Pawn, piece.color != color));
+    } else if material.unique_pieces() < 2 {
+        // Leading group of identical pieces.
+        let min_like_man = material.min_like_man();
+        pieces.sort_by_key(|piece| {
+            usize::from(*material.by_role(piece.color).get(piece.role)) != min_like_man
+        });
+    }
+    pieces
+}
+
+/// Calls `f` with every valid position of the pieces, except for some
+/// positions that are equivalent to others under the symmetries of the
+/// table encoding. The first piece is placed only on files a to d, and
+/// without pawns only in the triangle a1-d1-d4.
+fn for_each_position<S: Position + FromSetup>(pieces: &[Piece], mut f: impl FnMut(S)) {
+    let has_pawns = pieces.iter().any(|piece| piece.role == Role::Pawn);
+    for turn in Color::ALL {
+        for lead in Square::ALL {
+            if lead.file() > File::D || (!has_pawns && lead.rank().to_u32() > lead.file().to_u32())
+            {
+                continue;
+            }
+            for mut idx in 0..1 << (6 * (pieces.len() - 1)) {
+                let mut board = Board::empty();
+                board.set_piece_at(lead, pieces[0]);
+                for &piece in &pieces[1..] {
+                    let sq = Square::new(idx % 64);
+                    idx /= 64;
+                    if board.occupied().contains(sq) {
+                        break;
+                    }
+                    board.set_piece_at(sq, piece);
+                }
+                if board.occupied().count() < pieces.len() {
+                    continue;
+                }
+
+                if let Ok(pos) = S::from_setup(
+                    Setup {
+                        board,
+                        turn,
+                        ..Setup::empty()
+                    },
+                    CastlingMode::Standard,
+                )
+                .or_else(PositionError::ignore_impossible_check)
+                {
+                    f(pos);
+                }
+            }
+        }
+    }
+}
+
+/// Values of all positions with a material, indexed like the subtables
+/// of a WDL table.
+struct Solved<S: Position + Syzygy> {
+    /// Placeholder table, to reuse its encoding of positions.
+    table: WdlTable<S>,
+    /// Start of each subtable, by file and side.
+    offsets: Vec<Vec<usize>>,
+    values: Vec<u8>,
+}
+
+impl<S: Position + Syzygy> Solved<S> {
+    fn index(&self, pos: &S) -> usize {
+        let (file, side, idx) = self.table.encode(pos).expect("encodable position");
+        self.offsets[file][side] + idx as usize
+    }
+
+    fn value(&self, pos: &S) -> u8 {
+        self.values[self.index(pos)]
+    }
+}
+
+/// Retrograde analysis with results for all reachable materials.
+struct Solver<S: Position + Syzygy> {
+    solved: FxHashMap<Material, Rc<Solved<S>>>,
+}
+
+impl<S: Position + FromSetup + Clone + Syzygy> Solver<S> {
+    fn new() -> Solver<S> {
+        Solver {
+            solved: FxHashMap::default(),
+        }
+    }
+
+    /// Looks up the value of a position with different material.
+    fn lookup(&mut self, pos: &S) -> u8 {
+        let material = Material::from_board(pos.board()).normalized();
+        self.solve(&material).value(pos)
+    }
+
+    /// Determines the value of each position with the given material, from
+    /// the point of view of the side to move.
+    fn solve(&mut self, material: &Material) -> Rc<Solved<S>> {
+        if let Some(solved) = self.solved.get(material) {
+            return Rc::clone(solved);
+        }
+
+        let pieces = pieces(material);
+        let table = WdlTable::<S>::new(
+            Box::new(BytesRandomAccessFile {
+                bytes: Arc::new(write_table::<S>(material, &pieces, None)),
+            }),
+            material,
+        )
+        .expect("valid placeholder table");
+        let mut offsets = Vec::new();
+        let mut size = 0;
+        for file in table.subtable_sizes() {
+            offsets.push(
+                file.into_iter()
+                    .map(|len| {
+                        size += len as usize;
+                        size - len as usize
+                    })
+                    .collect(),
+            );
+        }
+        let mut solved = Solved {
+            table,
+            offsets,
+            values: vec![INVALID; size],
+        };
+
+        // Initialize terminal positions, and determine the successors of
+        // all other positions. Positions with a legal en passant square are
+        // not part of the state space, but reachable by double pawn pushes.
+        // They are appended as additional states.
+        let mut graph = Graph {
+            best: vec![LOSS; size],
+            ranges: vec![(0, 0); size],
+            successors: Vec::new(),
+            ep_states: FxHashMap::default(),
+            ep_positions: Vec::new(),
+        };
+        for_each_position::<S>(&pieces, |pos| {
+            let idx = solved.index(&pos);
+            if solved.values[idx] == INVALID {
+                graph.expand(self, &mut solved, idx, &pos);
+            }
+        });
+        let mut idx = size;
+        while idx < solved.values.len() {
+            let pos = graph.ep_positions[idx - size].clone();
+            graph.expand(self, &mut solved, idx, &pos);
+            idx += 1;
+        }
+        let Graph {
+            mut best,
+            ranges,
+            successors,
+            ..
+        } = graph;
+
+        // Iterate until no more positions can be resolved.
+        let values = &mut solved.values;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for idx in 0..values.len() {
+                if values[idx] != UNKNOWN {
+                    continue;
+                }
+
+                let mut unknown = false;
+                let (start, end) = ranges[idx];
+                for &succ in &successors[start..end] {
+                    match values[succ as usize] {
+                        UNKNOWN => unknown = true,
+                        v => best[idx] = best[idx].max(WIN - v),
+                    }
+                }
+
+                if best[idx] == WIN || !unknown {
+                    values[idx] = best[idx];
+                    changed = true;
+                }
+            }
+        }
+
+        // Neither side can force a result in the remaining positions.
+        values.truncate(size);
+        for value in values {
+            if *value == UNKNOWN {
+                *value = DRAW;
+            }
+        }
+
+        let solved = Rc::new(solved);
+        self.solved.insert(material.clone(), Rc::clone(&solved));
+        solved
+    }
+}
+
+/// Successors of the positions in the state space of a material.
+struct Graph<S> {
+    best: Vec<u8>,
+    ranges: Vec<(usize, usize)>,
+    successors: Vec<u32>,
+    ep_states: FxHashMap<(usize, Square), usize>,
+    ep_positions: Vec<S>,
+}
+
+impl<S: Position + FromSetup + Clone + Syzygy> Graph<S> {
+    /// Determines the value of `pos` if it is terminal, or otherwise its
+    /// successors. Successors with different material are resolved
+    /// immediately.
+    fn expand(&mut self, solver: &mut Solver<S>, solved: &mut Solved<S>, idx: usize, pos: &S) {
+        let start = self.successors.len();
+        if let Some(outcome) = pos.outcome().known() {
+            solved.values[idx] = value(Wdl::from_outcome(outcome, pos.turn()));
+        } else {
+            solved.values[idx] = UNKNOWN;
+            for m in pos.legal_moves() {
+                let mut after = pos.clone();
+                after.play_unchecked(m);
+                if let Some(outcome) = after.variant_outcome().known() {
+                    let v = value(Wdl::from_outcome(outcome, after.turn()));
+                    self.best[idx] = self.best[idx].max(WIN - v);
+                } else if m.is_capture() || m.is_promotion() {
+                    let v = solver.lookup(&after);
+                    self.best[idx] = self.best[idx].max(WIN - v);
+                } else {
+                    let succ = solved.index(&after);
+                    let succ = match after.ep_square(EnPassantMode::Legal) {
+                        Some(ep) => *self.ep_states.entry((succ, ep)).or_insert_with(|| {
+                            solved.values.push(INVALID);
+                            self.best.push(LOSS);
+                            self.ranges.push((0, 0));
+                            self.ep_positions.push(after);
+                            solved.values.len() - 1
+                        }),
+                        None => succ,
+                    };
+                    self.successors.push(succ as u32);
+                }
+            }
+        }
+        self.ranges[idx] = (start, self.successors.len());
+    }
+}
+
+/// Generates a WDL table for `material`.
+///
+/// Depending on the material, this may take a few seconds for 3 pieces,
+/// and up to a few minutes and a few hundred megabytes of memory for
+/// 4 pieces, including all endgames reachable by captures and promotions.
+///
+/// # Panics
+///
+/// Panics if `material` has more than [`MAX_PIECES`] pieces, or if
+/// one side has no pieces.
+#[track_caller]
+pub fn generate_wdl<S>(material: &Material) -> Vec<u8>
+where
+    S: Position + FromSetup + Clone + Syzygy,
+{
+    let material = material.normalized();
+    assert!(material.count() <= MAX_PIECES);
+    assert!(material.by_color.iter().all(|side| side.count() > 0));
+    write_wdl::<S>(&mut Solver::new(), &material)
+}
+
+fn write_wdl<S>(solver: &mut Solver<S>, material: &Material) -> Vec<u8>
+where
+    S: Position + FromSetup + Clone + Syzygy,
+{
+    let solved = solver.solve(material);
+
+    let subtables: Vec<Vec<Vec<u8>>> = solved
+        .table
+        .subtable_sizes()
+        .into_iter()
+        .zip(&solved.offsets)
+        .map(|(file, offsets)| {
+            file.into_iter()
+                .zip(offsets)
+                .map(|(size, &offset)| {
+                    solved.values[offset..offset + size as usize]
+                        .iter()
+                        .map(|&value| if value == INVALID { DRAW } else { value })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+
+    write_table::<S>(material, &pieces(material), Some(&subtables))
+}
+
+fn nibble(piece: Piece) -> u8 {
+    let role = match piece.role {
+        Role::Pawn => 1,
+        Role::Knight => 2,
+        Role::Bishop => 3,
+        Role::Rook => 4,
+        Role::Queen => 5,
+        Role::King => 6,
+    };
+    piece.color.fold_wb(role, role | 8)
+}
+
+/// Compressed subtable. Each symbol stands for a run of `2^k` identical
+/// values, and is encoded with a fixed number of bits.
+struct Subtable {
+    header: Vec<u8>,
+    sparse_index: Vec<u8>,
+    block_lengths: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl Subtable {
+    fn new(values: &[u8]) -> Subtable {
+        let mut literals: Vec<u8> = values.to_vec();
+        literals.sort_unstable();
+        literals.dedup();
+
+        if literals.len() <= 1 {
+            return Subtable {
+                header: vec![0x80, literals.first().copied().unwrap_or(DRAW)],
+                sparse_index: Vec::new(),
+                block_lengths: Vec::new(),
+                data: Vec::new(),
+            };
+        }
+
+        // Symbol k * literals.len() + j stands for 2^k times literals[j].
+        let num_symbols = literals.len() * (MAX_RUN_LOG + 1);
+        let symlen = (usize::BITS - (num_symbols - 1).leading_zeros()) as usize;
+        let block_size = 1 << BLOCK_SIZE_LOG;
+        let block_symbols = block_size * 8 / symlen;
+
+        let mut starts = Vec::new();
+        let mut blocks = Vec::new();
+        let mut i = 0;
+        while i < values.len() {
+            starts.push(i);
+            let mut symbols = Vec::with_capacity(block_symbols);
+            while i < values.len() && symbols.len() < block_symbols {
+                let j = literals.binary_search(&values[i]).expect("literal");
+                let run = values[i..]
+                    .iter()
+                    .take(1 << MAX_RUN_LOG)
+                    .take_while(|v| **v == values[i])
+                    .count();
+                let k = run.ilog2() as usize;
+                symbols.push(k * literals.len() + j);
+                i += 1 << k;
+            }
+            blocks.push(symbols);
+        }
+
+        let mut header = vec![0, BLOCK_SIZE_LOG as u8, SPAN_LOG as u8, 0];
+        header.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+        header.extend_from_slice(&[symlen as u8, symlen as u8]);
+        header.extend_from_slice(&0u16.to_le_bytes()); // Lowest symbol
+        header.extend_from_slice(&(num_symbols as u16).to_le_bytes());
+        for k in 0..=MAX_RUN_LOG {
+            for (j, &literal) in literals.iter().enumerate() {
+                if k == 0 {
+                    header.extend_from_slice(&[literal, 0xf0, 0xff]);
+                } else {
+                    let half = (k - 1) * literals.len() + j;
+                    header.extend_from_slice(&[
+                        half as u8,
+                        ((half >> 8) & 0xf) as u8 | ((half & 0xf) << 4) as u8,
+                        (half >> 4) as u8,
+                    ]);
+                }
+            }
+        }
+        if num_symbols % 2 == 1 {
+            header.push(0);
+        }
+
+        let span = 1 << SPAN_LOG;
+        let mut sparse_index = Vec::new();
+        for i in 0..values.len().div_ceil(span) {
+            let mid = i * span + span / 2;
+            let block = starts.partition_point(|start| *start <= mid.min(values.len() - 1)) - 1;
+            sparse_index.extend_from_slice(&(block as u32).to_le_bytes());
+            sparse_index.extend_from_slice(&((mid - starts[block]) as u16).to_le_bytes());
+        }
+
+        let mut block_lengths = Vec::new();
+        let mut data = Vec::new();
+        for (b, symbols) in blocks.iter().enumerate() {
+            let end = starts.get(b + 1).copied().unwrap_or(values.len());
+            block_lengths.extend_from_slice(&((end - starts[b] - 1) as u16).to_le_bytes());
+
+            let mut bytes = vec![0; block_size];
+            for (i, symbol) in symbols.iter().enumerate() {
+                for bit in 0..symlen {
+                    if symbol & (1 << (symlen - 1 - bit)) != 0 {
+                        let pos = i * symlen + bit;
+                        bytes[pos / 8] |= 0x80 >> (pos % 8);
+                    }
+                }
+            }
+            data.extend(bytes);
+        }
+
+        Subtable {
+            header,
+            sparse_index,
+            block_lengths,
+            data,
+        }
+    }
+}
+
+/// Writes a WDL table. Subtables are indexed by file and side. Without
+/// subtables, writes placeholders.
+fn write_table<S: Syzygy>(
+    material: &Material,
+    pieces: &[Piece],
+    subtables: Option<&[Vec<Vec<u8>>]>,
+) -> Vec<u8> {
+    let has_pawns = material.has_pawns();
+    let pp = Color::ALL
+        .into_iter()
+        .all(|color| material.by_role(color).pawn > 0);
+    let num_files = if has_pawns { 4 } else { 1 };
+    let num_sides = if material.is_symmetric() { 1 } else { 2 };
+
+    let mut out = S::TBW.magic.to_vec();
+    out.push(u8::from(!material.is_symmetric()) | (u8::from(has_pawns) << 1));
+
+    for _ in 0..num_files {
+        out.push(0x00); // Leading group first
+        if pp {
+            out.push(0x11); // Remaining pawns second
+        }
+        out.extend(
+            pieces
+                .iter()
+                .map(|&piece| nibble(piece) | (nibble(piece) << 4)),
+        );
+    }
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+
+    let subtables: Vec<Subtable> = (0..num_files)
+        .flat_map(|file| (0..num_sides).map(move |side| (file, side)))
+        .map(|(file, side)| match subtables {
+            Some(subtables) => Subtable::new(&subtables[file][side]),
+            None => Subtable::new(&[]),
+        })
+        .collect();
+
+    for subtable in &subtables {
+        out.extend_from_slice(&subtable.header);
+    }
+    for subtable in &subtables {
+        out.extend_from_slice(&subtable.sparse_index);
+    }
+    for subtable in &subtables {
+        out.extend_from_slice(&subtable.block_lengths);
+    }
+    for subtable in &subtables {
+        out.resize(out.len().next_multiple_of(64), 0);
+        out.extend_from_slice(&subtable.data);
+    }
+
+    // Pad, such that the final block can be read with a few bytes of
+    // lookahead, and the file size is as expected.
+    out.resize(out.len().next_multiple_of(64) + 16, 0);
+    out
+}
+
+/// A [`Filesystem`] serving generated tables.
+///
+/// Tables are available in every directory.
+#[derive(Debug, Default, Clone)]
+pub struct GeneratedFilesystem {
+    tables: FxHashMap<String, Arc<Vec<u8>>>,
+}
+
+impl GeneratedFilesystem {
+    /// Creates a filesystem without any tables.
+    pub fn new() -> GeneratedFilesystem {
+        GeneratedFilesystem::default()
+    }
+
+    /// Generates a WDL table for `material` (see [`generate_wdl()`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `material` is not supported by the generator.
+    #[must_use]
+    #[track_caller]
+    pub fn with_wdl_table<S>(mut self, material: &Material) -> GeneratedFilesystem
+    where
+        S: Position + FromSetup + Clone + Syzygy,
+    {
+        self.tables.insert(
+            format!("{}.{}", material.normalized(), S::TBW.ext),
+            Arc::new(generate_wdl::<S>(material)),
+        );
+        self
+    }
+
+    fn get(&self, path: &Path) -> io::Result<&Arc<Vec<u8>>> {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| self.tables.get(name))
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+}
+
+impl Filesystem for GeneratedFilesystem {
+    fn regular_file_size(&self, path: &Path) -> io::Result<u64> {
+        self.get(path).map(|bytes| bytes.len() as u64)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self.tables.keys().map(|name| path.join(name)).collect())
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
+        let bytes = Arc::clone(self.get(path)?);
+        Ok(Box::new(BytesRandomAccessFile { bytes }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shakmaty::{Chess, fen::Fen};
+
+    use super::*;
+    use crate::Tablebase;
+
+    fn generated_tables(material: &Material) -> (Solver<Chess>, Tablebase<Chess>) {
+        // Also provide tables for all materials reachable by captures and
+        // promotions.
+        let mut required = vec![material.clone()];
+        let mut i = 0;
+        while i < required.len() {
+            for successor in required[i].successors(true) {
+                let successor = successor.normalized();
+                if successor.count() > 2 && !required.contains(&successor) {
+                    required.push(successor);
+                }
+            }
+            i += 1;
+        }
+
+        let mut solver = Solver::new();
+        let mut filesystem = GeneratedFilesystem::new();
+        for material in &required {
+            filesystem.tables.insert(
+                format!("{material}.rtbw"),
+                Arc::new(write_wdl::<Chess>(&mut solver, material)),
+            );
+        }
+        let mut tables = Tablebase::with_filesystem(Arc::new(filesystem));
+        assert_eq!(
+            tables.add_directory("generated").expect("add tables"),
+            required.len()
+        );
+        (solver, tables)
+    }
+
+    /// Compares the generated table with the values of every `step`-th
+    /// position.
+    fn assert_generated(material: &str, step: usize) -> Tablebase<Chess> {
+        let material: Material = material.parse().expect("valid material");
+        let (mut solver, tables) = generated_tables(&material);
+        let solved = solver.solve(&material);
+
+        let mut i = 0;
+        for_each_position::<Chess>(&pieces(&material), |pos| {
+            i += 1;
+            if i % step == 0 {
+                let wdl = tables.probe_wdl_after_zeroing(&pos).expect("probe");
+                assert_eq!(self::value(wdl), solved.value(&pos), "{:?}", pos.board());
+            }
+        });
+
+        tables
+    }
+
+    #[test]
+    fn test_generate_krvk() {
+        assert_generated("KRvK", 1);
+    }
+
+    #[test]
+    fn test_generate_kpvk() {
+        assert_generated("KPvK", 1);
+    }
+
+    #[test]
+    #[ignore = "takes minutes without optimizations"]
+    fn test_generate_krvkn() {
+        let tables = assert_generated("KRvKN", 97);
+
+        for (fen, expected) in [
+            ("8/8/8/8/8/2k5/8/KR4n1 w - - 0 1", Wdl::Win),
+            ("8/8/8/8/8/4K3/2k5/1R4n1 b - - 0 1", Wdl::Draw),
+            ("k7/2K5/8/8/8/8/8/R5n1 b - - 0 1", Wdl::Loss),
+        ] {
+            let pos: Chess = fen
+                .parse::<Fen>()
+                .expect("valid fen")
+                .into_position(CastlingMode::Standard)
+                .expect("legal position");
+            assert_eq!(
+                tables.probe_wdl_after_zeroing(&pos).expect("probe"),
+                expected,
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_generate_too_many_pieces() {
+        generate_wdl::<Chess>(&"KRvKNN".parse().expect("valid material"));
+    }
+}
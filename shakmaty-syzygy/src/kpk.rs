@@ -0,0 +1,241 @@
+//! Bitbase for the King and Pawn versus King endgame.
+//!
+//! The bitbase is a compiled-in table of 24 KiB, so probes do not need any
+//! initialization. It was generated by retrograde analysis, which is
+//! verified by the tests of this module.
+//!
+//! [`Tablebase`](crate::Tablebase) falls back to the bitbase for WDL probes
+//! of KPvK positions, if the corresponding table is not available.
+//!
+//! # Example
+//!
+//! ```
+//! use shakmaty::{Color, Square};
+//! use shakmaty_syzygy::kpk;
+//!
+//! assert!(kpk::probe(Square::E6, Square::E5, Square::E8, Color::Black));
+//! assert!(!kpk::probe(Square::A6, Square::A5, Square::A8, Color::White));
+//! ```
+
+use shakmaty::{Color, File, Rank, Square};
+
+mod bitbase;
+
+use bitbase::BITBASE;
+
+/// Pawn on files A to D, ranks 2 to 7, both sides to move, all king squares.
+const MAX_INDEX: usize = 2 * 24 * 64 * 64;
+
+/// Tests if the side with the pawn wins (assuming optimal play, without
+/// regard to the 50-move rule), given the position of the white king, the
+/// white pawn, the black king, and the side to move.
+///
+/// For positions where the pawn is black, flip all squares vertically and
+/// swap the side to move.
+///
+/// Returns `false` for illegal positions (e.g., the pawn on the first or
+/// eighth rank, or the side not to move in check).
+pub fn probe(white_king: Square, pawn: Square, black_king: Square, turn: Color) -> bool {
+    if pawn.rank() == Rank::First || pawn.rank() == Rank::Eighth {
+        return false;
+    }
+
+    let (white_king, pawn, black_king) = if pawn.file() >= File::E {
+        (
+            white_king.flip_horizontal(),
+            pawn.flip_horizontal(),
+            black_king.flip_horizontal(),
+        )
+    } else {
+        (white_king, pawn, black_king)
+    };
+
+    let idx = index(turn, black_king, white_king, pawn);
+    BITBASE[idx / 64] & (1 << (idx % 64)) != 0
+}
+
+fn index(turn: Color, black_king: Square, white_king: Square, pawn: Square) -> usize {
+    white_king.to_usize()
+        | (black_king.to_usize() << 6)
+        | (turn.fold_wb(0, 1) << 12)
+        | (pawn.file().to_usize() << 13)
+        | ((Rank::Seventh.to_usize() - pawn.rank().to_usize()) << 15)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fmt::Write as _, fs};
+
+    use shakmaty::attacks::{king_attacks, pawn_attacks};
+
+    use super::*;
+
+    const INVALID: u8 = 0;
+    const UNKNOWN: u8 = 1;
+    const DRAW: u8 = 2;
+    const WIN: u8 = 4;
+
+    fn decode(idx: usize) -> (Color, Square, Square, Square) {
+        let white_king = Square::new((idx & 0x3f) as u32);
+        let black_king = Square::new(((idx >> 6) & 0x3f) as u32);
+        let turn = Color::from_white((idx >> 12) & 1 == 0);
+        let pawn = Square::from_coords(
+            File::new(((idx >> 13) & 0x3) as u32),
+            Rank::new((Rank::Seventh.to_usize() - (idx >> 15)) as u32),
+        );
+        (turn, black_king, white_king, pawn)
+    }
+
+    fn initial(idx: usize) -> u8 {
+        let (turn, black_king, white_king, pawn) = decode(idx);
+        let push = pawn.offset(8).expect("pawn not on last rank");
+
+        if white_king.distance(black_king) <= 1
+            || white_king == pawn
+            || black_king == pawn
+            || (turn == Color::White && pawn_attacks(Color::White, pawn).contains(black_king))
+        {
+            // Overlapping pieces, adjacent kings, or black in check with white
+            // to move.
+            INVALID
+        } else if turn == Color::White
+            && pawn.rank() == Rank::Seventh
+            && white_king != push
+            && (black_king.distance(push) > 1 || white_king.distance(push) == 1)
+        {
+            // Promotion without the new queen being captured.
+            WIN
+        } else if turn == Color::Black
+            && ((king_attacks(black_king)
+                & !(king_attacks(white_king) | pawn_attacks(Color::White, pawn)))
+            .is_empty()
+                || (king_attacks(black_king) & !king_attacks(white_king)).contains(pawn))
+        {
+            // Stalemate, or black captures the pawn.
+            DRAW
+        } else {
+            UNKNOWN
+        }
+    }
+
+    fn classify(db: &[u8], idx: usize) -> u8 {
+        let (turn, black_king, white_king, pawn) = decode(idx);
+
+        // The side to move wins if any move leads to a win, and white can only
+        // draw if all moves lead to a draw (and vice versa).
+        let (good, bad) = match turn {
+            Color::White => (WIN, DRAW),
+            Color::Black => (DRAW, WIN),
+        };
+
+        let mut r = INVALID;
+        match turn {
+            Color::White => {
+                for to in king_attacks(white_king) {
+                    r |= db[index(Color::Black, black_king, to, pawn)];
+                }
+
+                if pawn.rank() < Rank::Seventh {
+                    let push = pawn.offset(8).expect("pawn not on last rank");
+                    r |= db[index(Color::Black, black_king, white_king, push)];
+
+                    if pawn.rank() == Rank::Second && push != white_king && push != black_king {
+                        let double_push = push.offset(8).expect("pawn on second rank");
+                        r |= db[index(Color::Black, black_king, white_king, double_push)];
+                    }
+                }
+            }
+            Color::Black => {
+                for to in king_attacks(black_king) {
+                    r |= db[index(Color::White, to, white_king, pawn)];
+                }
+            }
+        }
+
+        if r & good != 0 {
+            good
+        } else if r & UNKNOWN != 0 {
+            UNKNOWN
+        } else {
+            bad
+        }
+    }
+
+    fn generate() -> Vec<u64> {
+        let mut db: Vec<u8> = (0..MAX_INDEX).map(initial).collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for idx in 0..MAX_INDEX {
+                if db[idx] == UNKNOWN {
+                    db[idx] = classify(&db, idx);
+                    changed |= db[idx] != UNKNOWN;
+                }
+            }
+        }
+
+        let mut bitbase = vec![0; MAX_INDEX / 64];
+        for (idx, result) in db.into_iter().enumerate() {
+            if result == WIN {
+                bitbase[idx / 64] |= 1 << (idx % 64);
+            }
+        }
+        bitbase
+    }
+
+    fn render(bitbase: &[u64]) -> String {
+        let mut out = String::from(
+            "// Generated by `cargo test -p shakmaty-syzygy --lib -- kpk --ignored`.\n\
+             // Do not edit.\n\n\
+             use super::MAX_INDEX;\n\n\
+             #[rustfmt::skip]\n\
+             pub(super) static BITBASE: [u64; MAX_INDEX / 64] = [\n",
+        );
+        for words in bitbase.chunks(4) {
+            out.push_str("   ");
+            for word in words {
+                write!(out, " 0x{word:016x},").unwrap();
+            }
+            out.push('\n');
+        }
+        out.push_str("];\n");
+        out
+    }
+
+    #[test]
+    fn test_bitbase() {
+        assert!(
+            generate() == BITBASE,
+            "compiled-in bitbase is outdated, regenerate with write_bitbase"
+        );
+    }
+
+    #[test]
+    #[ignore = "writes src/kpk/bitbase.rs"]
+    fn write_bitbase() {
+        fs::write("src/kpk/bitbase.rs", render(&generate())).expect("write bitbase");
+    }
+
+    #[test]
+    fn test_kpk() {
+        // King in front of the pawn on the sixth rank.
+        assert!(probe(Square::E6, Square::E5, Square::E8, Color::White));
+        assert!(probe(Square::E6, Square::E5, Square::E8, Color::Black));
+
+        // Opposition decides.
+        assert!(!probe(Square::E4, Square::E3, Square::E6, Color::White));
+        assert!(probe(Square::E4, Square::E3, Square::E6, Color::Black));
+
+        // Rook pawn.
+        assert!(!probe(Square::A6, Square::A5, Square::A8, Color::White));
+        assert!(!probe(Square::H6, Square::H5, Square::H8, Color::White));
+
+        // Black captures the pawn.
+        assert!(!probe(Square::A1, Square::D4, Square::E5, Color::Black));
+
+        // Rule of the square.
+        assert!(probe(Square::H1, Square::C4, Square::H4, Color::White));
+        assert!(!probe(Square::H1, Square::C4, Square::H4, Color::Black));
+    }
+}
@@ -0,0 +1,776 @@
+// Generated by `cargo test -p shakmaty-syzygy --lib -- kpk --ignored`.
+// Do not edit.
+
+use super::MAX_INDEX;
+
+#[rustfmt::skip]
+pub(super) static BITBASE: [u64; MAX_INDEX / 64] = [
+    0xfffefffffffffcfc, 0xfffefffffffff8f8, 0xfffefffffffff1f1, 0xfffeffffffffe3e3,
+    0xfffeffffffffc7c7, 0xfffeffffffff8f8f, 0xfffeffffffff1f1f, 0xfffeffffffff3f3f,
+    0xfffefffffffcfcfc, 0xfffefffffff8f8f8, 0xfffefffffff1f1f1, 0xfffeffffffe3e3e3,
+    0xfffeffffffc7c7c7, 0xfffeffffff8f8f8f, 0xfffeffffff1f1f1f, 0xfffeffffff3f3f3f,
+    0xfffefffffcfcfcff, 0xfffefffff8f8f8ff, 0xfffefffff1f1f1ff, 0xfffeffffe3e3e3ff,
+    0xfffeffffc7c7c7ff, 0xfffeffff8f8f8fff, 0xfffeffff1f1f1fff, 0xfffeffff3f3f3fff,
+    0xfffefffcfcfcffff, 0xfffefff8f8f8ffff, 0xfffefff1f1f1ffff, 0xfffeffe3e3e3ffff,
+    0xfffeffc7c7c7ffff, 0xfffeff8f8f8fffff, 0xfffeff1f1f1fffff, 0xfffeff3f3f3fffff,
+    0xfffefcfcfcffffff, 0xfffef8f8f8ffffff, 0xfffef1f1f1ffffff, 0xfffee3e3e3ffffff,
+    0xfffec7c7c7ffffff, 0xfffe8f8f8fffffff, 0xfffe1f1f1fffffff, 0xfffe3f3f3fffffff,
+    0xfffcfcfcffffffff, 0xfff8f8f8ffffffff, 0xfff0f1f1ffffffff, 0xffe2e3e3ffffffff,
+    0xffc6c7c7ffffffff, 0xff8e8f8fffffffff, 0xff1e1f1fffffffff, 0xff3e3f3fffffffff,
+    0x0000000000000000, 0x0000000000000000, 0xf0f0f1ffffffffff, 0xe3e2e3ffffffffff,
+    0xc7c6c7ffffffffff, 0x8f8e8fffffffffff, 0x1f1e1fffffffffff, 0x3f3e3fffffffffff,
+    0x0000000000000000, 0x0000000000000000, 0xf0f0ffffffffffff, 0xe3e2ffffffffffff,
+    0xc7c6ffffffffffff, 0x8f8effffffffffff, 0x1f1effffffffffff, 0x3f3effffffffffff,
+    0xfffefffffffffcfc, 0xfffefffffffff8f8, 0xfffefffffffff1f1, 0xfffeffffffffe3e3,
+    0xfffeffffffffc7c7, 0xfffeffffffff8f8f, 0xfffeffffffff1f1f, 0xfffeffffffff3f3f,
+    0xfffefffffffcfcfc, 0xfffefffffff8f8f8, 0xfffefffffff1f1f1, 0xfffeffffffe3e3e3,
+    0xfffeffffffc7c7c7, 0xfffeffffff8f8f8f, 0xfffeffffff1f1f1f, 0xfffeffffff3f3f3f,
+    0xfffefffffcfcfcff, 0xfffefffff8f8f8ff, 0xfffefffff1f1f1ff, 0xfffeffffe3e3e3ff,
+    0xfffeffffc7c7c7ff, 0xfffeffff8f8f8fff, 0xfffeffff1f1f1fff, 0xfffeffff3f3f3fff,
+    0xfffefffcfcfcffff, 0xfffefff8f8f8ffff, 0xfffefff1f1f1ffff, 0xfffeffe3e3e3ffff,
+    0xfffeffc7c7c7ffff, 0xfffeff8f8f8fffff, 0xfffeff1f1f1fffff, 0xfffeff3f3f3fffff,
+    0xfffefcfcfcffffff, 0xfffef8f8f8ffffff, 0xfffef1f1f1ffffff, 0xfffee3e3e3ffffff,
+    0xfffec7c7c7ffffff, 0xfffe8f8f8fffffff, 0xfffe1f1f1fffffff, 0xfffe3f3f3fffffff,
+    0x0300000000000000, 0x0200000000000000, 0x0600010000000000, 0xfee2e3e3ffffffff,
+    0xffc6c7c7ffffffff, 0xff8e8f8fffffffff, 0xff1e1f1fffffffff, 0xff3e3f3fffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000010000000000, 0xe2e2e3ffffffffff,
+    0xc7c6c7ffffffffff, 0x8f8e8fffffffffff, 0x1f1e1fffffffffff, 0x3f3e3fffffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000070000000000, 0xe2e2ffffffffffff,
+    0xc7c6ffffffffffff, 0x8f8effffffffffff, 0x1f1effffffffffff, 0x3f3effffffffffff,
+    0xfffdfffffffffcfc, 0xfffdfffffffff8f8, 0xfffdfffffffff1f1, 0xfffdffffffffe3e3,
+    0xfffdffffffffc7c7, 0xfffdffffffff8f8f, 0xfffdffffffff1f1f, 0xfffdffffffff3f3f,
+    0xfffdfffffffcfcfc, 0xfffdfffffff8f8f8, 0xfffdfffffff1f1f1, 0xfffdffffffe3e3e3,
+    0xfffdffffffc7c7c7, 0xfffdffffff8f8f8f, 0xfffdffffff1f1f1f, 0xfffdffffff3f3f3f,
+    0xfffdfffffcfcfcff, 0xfffdfffff8f8f8ff, 0xfffdfffff1f1f1ff, 0xfffdffffe3e3e3ff,
+    0xfffdffffc7c7c7ff, 0xfffdffff8f8f8fff, 0xfffdffff1f1f1fff, 0xfffdffff3f3f3fff,
+    0xfffdfffcfcfcffff, 0xfffdfff8f8f8ffff, 0xfffdfff1f1f1ffff, 0xfffdffe3e3e3ffff,
+    0xfffdffc7c7c7ffff, 0xfffdff8f8f8fffff, 0xfffdff1f1f1fffff, 0xfffdff3f3f3fffff,
+    0xfffdfcfcfcffffff, 0xfffdf8f8f8ffffff, 0xfffdf1f1f1ffffff, 0xfffde3e3e3ffffff,
+    0xfffdc7c7c7ffffff, 0xfffd8f8f8fffffff, 0xfffd1f1f1fffffff, 0xfffd3f3f3fffffff,
+    0xfffcfcfcffffffff, 0xfff8f8f8ffffffff, 0xfff1f1f1ffffffff, 0xffe1e3e3ffffffff,
+    0xffc5c7c7ffffffff, 0xff8d8f8fffffffff, 0xff1d1f1fffffffff, 0xff3d3f3fffffffff,
+    0x0c0c0c0000000000, 0x0000000000000000, 0x0101010000000000, 0xe3e1e3ffffffffff,
+    0xc7c5c7ffffffffff, 0x8f8d8fffffffffff, 0x1f1d1fffffffffff, 0x3f3d3fffffffffff,
+    0x0000000000000000, 0x00080a0f00000000, 0x0000000000000000, 0xe3e1ffffffffffff,
+    0xc7c5ffffffffffff, 0x8f8dffffffffffff, 0x1f1dffffffffffff, 0x3f3dffffffffffff,
+    0xfffdfffffffffcfc, 0xfffdfffffffff8f8, 0xfffdfffffffff1f1, 0xfffdffffffffe3e3,
+    0xfffdffffffffc7c7, 0xfffdffffffff8f8f, 0xfffdffffffff1f1f, 0xfffdffffffff3f3f,
+    0xfffdfffffffcfcfc, 0xfffdfffffff8f8f8, 0xfffdfffffff1f1f1, 0xfffdffffffe3e3e3,
+    0xfffdffffffc7c7c7, 0xfffdffffff8f8f8f, 0xfffdffffff1f1f1f, 0xfffdffffff3f3f3f,
+    0xfffdfffffcfcfcff, 0xfffdfffff8f8f8ff, 0xfffdfffff1f1f1ff, 0xfffdffffe3e3e3ff,
+    0xfffdffffc7c7c7ff, 0xfffdffff8f8f8fff, 0xfffdffff1f1f1fff, 0xfffdffff3f3f3fff,
+    0xfffdfffcfcfcffff, 0xfffdfff8f8f8ffff, 0xfffdfff1f1f1ffff, 0xfffdffe3e3e3ffff,
+    0xfffdffc7c7c7ffff, 0xfffdff8f8f8fffff, 0xfffdff1f1f1fffff, 0xfffdff3f3f3fffff,
+    0xfffdfcfcfcffffff, 0xfffdf8f8f8ffffff, 0xfffdf1f1f1ffffff, 0xfffde3e3e3ffffff,
+    0xfffdc7c7c7ffffff, 0xfffd8f8f8fffffff, 0xfffd1f1f1fffffff, 0xfffd3f3f3fffffff,
+    0x0704040000000000, 0x0700000000000000, 0x0701010000000000, 0x0f01030000000000,
+    0xffc5c7c7ffffffff, 0xff8d8f8fffffffff, 0xff1d1f1fffffffff, 0xff3d3f3fffffffff,
+    0x0404000000000000, 0x0000000000000000, 0x0101000000000000, 0x0301030000000000,
+    0xc7c5c7ffffffffff, 0x8f8d8fffffffffff, 0x1f1d1fffffffffff, 0x3f3d3fffffffffff,
+    0x0404020000000000, 0x0000050000000000, 0x0101020000000000, 0x03010f0000000000,
+    0xc7c5ffffffffffff, 0x8f8dffffffffffff, 0x1f1dffffffffffff, 0x3f3dffffffffffff,
+    0xfffbfffffffffcfc, 0xfffbfffffffff8f8, 0xfffbfffffffff1f1, 0xfffbffffffffe3e3,
+    0xfffbffffffffc7c7, 0xfffbffffffff8f8f, 0xfffbffffffff1f1f, 0xfffbffffffff3f3f,
+    0xfffbfffffffcfcfc, 0xfffbfffffff8f8f8, 0xfffbfffffff1f1f1, 0xfffbffffffe3e3e3,
+    0xfffbffffffc7c7c7, 0xfffbffffff8f8f8f, 0xfffbffffff1f1f1f, 0xfffbffffff3f3f3f,
+    0xfffbfffffcfcfcff, 0xfffbfffff8f8f8ff, 0xfffbfffff1f1f1ff, 0xfffbffffe3e3e3ff,
+    0xfffbffffc7c7c7ff, 0xfffbffff8f8f8fff, 0xfffbffff1f1f1fff, 0xfffbffff3f3f3fff,
+    0xfffbfffcfcfcffff, 0xfffbfff8f8f8ffff, 0xfffbfff1f1f1ffff, 0xfffbffe3e3e3ffff,
+    0xfffbffc7c7c7ffff, 0xfffbff8f8f8fffff, 0xfffbff1f1f1fffff, 0xfffbff3f3f3fffff,
+    0xfffbfcfcfcffffff, 0xfffbf8f8f8ffffff, 0xfffbf1f1f1ffffff, 0xfffbe3e3e3ffffff,
+    0xfffbc7c7c7ffffff, 0xfffb8f8f8fffffff, 0xfffb1f1f1fffffff, 0xfffb3f3f3fffffff,
+    0xfff8fcfcffffffff, 0xfff8f8f8ffffffff, 0xfff1f1f1ffffffff, 0xffe3e3e3ffffffff,
+    0xffc3c7c7ffffffff, 0xff8b8f8fffffffff, 0xff1b1f1fffffffff, 0xff3b3f3fffffffff,
+    0xfcf8fcffffffffff, 0x1818180000000000, 0x0000000000000000, 0x0303030000000000,
+    0xc7c3c7ffffffffff, 0x8f8b8fffffffffff, 0x1f1b1fffffffffff, 0x3f3b3fffffffffff,
+    0xfcf8ffffffffffff, 0x0000000000000000, 0x0011151f00000000, 0x0000000000000000,
+    0xc7c3ffffffffffff, 0x8f8bffffffffffff, 0x1f1bffffffffffff, 0x3f3bffffffffffff,
+    0xfffbfffffffffcfc, 0xfffbfffffffff8f8, 0xfffbfffffffff1f1, 0xfffbffffffffe3e3,
+    0xfffbffffffffc7c7, 0xfffbffffffff8f8f, 0xfffbffffffff1f1f, 0xfffbffffffff3f3f,
+    0xfffbfffffffcfcfc, 0xfffbfffffff8f8f8, 0xfffbfffffff1f1f1, 0xfffbffffffe3e3e3,
+    0xfffbffffffc7c7c7, 0xfffbffffff8f8f8f, 0xfffbffffff1f1f1f, 0xfffbffffff3f3f3f,
+    0xfffbfffffcfcfcff, 0xfffbfffff8f8f8ff, 0xfffbfffff1f1f1ff, 0xfffbffffe3e3e3ff,
+    0xfffbffffc7c7c7ff, 0xfffbffff8f8f8fff, 0xfffbffff1f1f1fff, 0xfffbffff3f3f3fff,
+    0xfffbfffcfcfcffff, 0xfffbfff8f8f8ffff, 0xfffbfff1f1f1ffff, 0xfffbffe3e3e3ffff,
+    0xfffbffc7c7c7ffff, 0xfffbff8f8f8fffff, 0xfffbff1f1f1fffff, 0xfffbff3f3f3fffff,
+    0xfffbfcfcfcffffff, 0xfffbf8f8f8ffffff, 0xfffbf1f1f1ffffff, 0xfffbe3e3e3ffffff,
+    0xfffbc7c7c7ffffff, 0xfffb8f8f8fffffff, 0xfffb1f1f1fffffff, 0xfffb3f3f3fffffff,
+    0x1f181c0000000000, 0x0e08080000000000, 0x0e00000000000000, 0x0e02020000000000,
+    0x1f03070000000000, 0xff8b8f8fffffffff, 0xff1b1f1fffffffff, 0xff3b3f3fffffffff,
+    0x1c181c0000000000, 0x0808000000000000, 0x0000000000000000, 0x0202000000000000,
+    0x0703070000000000, 0x8f8b8fffffffffff, 0x1f1b1fffffffffff, 0x3f3b3fffffffffff,
+    0x1c181c0000000000, 0x0808040000000000, 0x00000a0000000000, 0x0202040000000000,
+    0x07031f0000000000, 0x8f8bffffffffffff, 0x1f1bffffffffffff, 0x3f3bffffffffffff,
+    0xfff7fffffffffcfc, 0xfff7fffffffff8f8, 0xfff7fffffffff1f1, 0xfff7ffffffffe3e3,
+    0xfff7ffffffffc7c7, 0xfff7ffffffff8f8f, 0xfff7ffffffff1f1f, 0xfff7ffffffff3f3f,
+    0xfff7fffffffcfcfc, 0xfff7fffffff8f8f8, 0xfff7fffffff1f1f1, 0xfff7ffffffe3e3e3,
+    0xfff7ffffffc7c7c7, 0xfff7ffffff8f8f8f, 0xfff7ffffff1f1f1f, 0xfff7ffffff3f3f3f,
+    0xfff7fffffcfcfcff, 0xfff7fffff8f8f8ff, 0xfff7fffff1f1f1ff, 0xfff7ffffe3e3e3ff,
+    0xfff7ffffc7c7c7ff, 0xfff7ffff8f8f8fff, 0xfff7ffff1f1f1fff, 0xfff7ffff3f3f3fff,
+    0xfff7fffcfcfcffff, 0xfff7fff8f8f8ffff, 0xfff7fff1f1f1ffff, 0xfff7ffe3e3e3ffff,
+    0xfff7ffc7c7c7ffff, 0xfff7ff8f8f8fffff, 0xfff7ff1f1f1fffff, 0xfff7ff3f3f3fffff,
+    0xfff7fcfcfcffffff, 0xfff7f8f8f8ffffff, 0xfff7f1f1f1ffffff, 0xfff7e3e3e3ffffff,
+    0xfff7c7c7c7ffffff, 0xfff78f8f8fffffff, 0xfff71f1f1fffffff, 0xfff73f3f3fffffff,
+    0xfff4fcfcffffffff, 0xfff0f8f8ffffffff, 0xfff1f1f1ffffffff, 0xffe3e3e3ffffffff,
+    0xffc7c7c7ffffffff, 0xff878f8fffffffff, 0xff171f1fffffffff, 0xff373f3fffffffff,
+    0xfcf4fcffffffffff, 0xf8f0f8ffffffffff, 0x3030300000000000, 0x0000000000000000,
+    0x0606060000000000, 0x8f878fffffffffff, 0x1f171fffffffffff, 0x3f373fffffffffff,
+    0xfcf4ffffffffffff, 0xf8f0ffffffffffff, 0x0000000000000000, 0x00222a3e00000000,
+    0x0000000000000000, 0x8f87ffffffffffff, 0x1f17ffffffffffff, 0x3f37ffffffffffff,
+    0xfff7fffffffffcfc, 0xfff7fffffffff8f8, 0xfff7fffffffff1f1, 0xfff7ffffffffe3e3,
+    0xfff7ffffffffc7c7, 0xfff7ffffffff8f8f, 0xfff7ffffffff1f1f, 0xfff7ffffffff3f3f,
+    0xfff7fffffffcfcfc, 0xfff7fffffff8f8f8, 0xfff7fffffff1f1f1, 0xfff7ffffffe3e3e3,
+    0xfff7ffffffc7c7c7, 0xfff7ffffff8f8f8f, 0xfff7ffffff1f1f1f, 0xfff7ffffff3f3f3f,
+    0xfff7fffffcfcfcff, 0xfff7fffff8f8f8ff, 0xfff7fffff1f1f1ff, 0xfff7ffffe3e3e3ff,
+    0xfff7ffffc7c7c7ff, 0xfff7ffff8f8f8fff, 0xfff7ffff1f1f1fff, 0xfff7ffff3f3f3fff,
+    0xfff7fffcfcfcffff, 0xfff7fff8f8f8ffff, 0xfff7fff1f1f1ffff, 0xfff7ffe3e3e3ffff,
+    0xfff7ffc7c7c7ffff, 0xfff7ff8f8f8fffff, 0xfff7ff1f1f1fffff, 0xfff7ff3f3f3fffff,
+    0xfff7fcfcfcffffff, 0xfff7f8f8f8ffffff, 0xfff7f1f1f1ffffff, 0xfff7e3e3e3ffffff,
+    0xfff7c7c7c7ffffff, 0xfff78f8f8fffffff, 0xfff71f1f1fffffff, 0xfff73f3f3fffffff,
+    0xfff4fcfcffffffff, 0x3e30380000000000, 0x1c10100000000000, 0x1c00000000000000,
+    0x1c04040000000000, 0x3e060e0000000000, 0xff171f1fffffffff, 0xff373f3fffffffff,
+    0xfcf4fcffffffffff, 0x3830380000000000, 0x1010000000000000, 0x0000000000000000,
+    0x0404000000000000, 0x0e060e0000000000, 0x1f171fffffffffff, 0x3f373fffffffffff,
+    0xfcf4ffffffffffff, 0x38303e0000000000, 0x1010080000000000, 0x0000140000000000,
+    0x0404080000000000, 0x0e063e0000000000, 0x1f17ffffffffffff, 0x3f37ffffffffffff,
+    0xfffffefffffffcfc, 0xfffffefffffff8f8, 0xfffffefffffff1f1, 0xfffffeffffffe3e3,
+    0xfffffeffffffc7c7, 0xfffffeffffff8f8f, 0xfffffeffffff1f1f, 0xfffffeffffff3f3f,
+    0xfffffefffffcfcfc, 0xfffffefffff8f8f8, 0xfffffefffff1f1f1, 0xfffffeffffe3e3e3,
+    0xfffffeffffc7c7c7, 0xfffffeffff8f8f8f, 0xfffffeffff1f1f1f, 0xfffffeffff3f3f3f,
+    0xfffffefffcfcfcff, 0xfffffefff8f8f8ff, 0xfffffefff1f1f1ff, 0xfffffeffe3e3e3ff,
+    0xfffffeffc7c7c7ff, 0xfffffeff8f8f8fff, 0xfffffeff1f1f1fff, 0xfffffeff3f3f3fff,
+    0xfffffefcfcfcffff, 0xfffffef8f8f8ffff, 0xfffffef1f1f1ffff, 0xfffffee3e3e3ffff,
+    0xfffffec7c7c7ffff, 0xfffffe8f8f8fffff, 0xfffffe1f1f1fffff, 0xfffffe3f3f3fffff,
+    0xfffffcfcfcffffff, 0xfffff8f8f8ffffff, 0xfffff0f1f1ffffff, 0xffffe2e3e3ffffff,
+    0xffffc6c7c7ffffff, 0xffff8e8f8fffffff, 0xffff1e1f1fffffff, 0xffff3e3f3fffffff,
+    0x0000000000000000, 0x0200000000000000, 0x0701000000000000, 0xffe3e2e3ffffffff,
+    0xffc7c6c7ffffffff, 0xff8f8e8fffffffff, 0xff1f1e1fffffffff, 0xff3f3e3fffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0xe3e3e2ffffffffff,
+    0xc7c7c6ffffffffff, 0x8f8f8effffffffff, 0x1f1f1effffffffff, 0x3f3f3effffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000060000000000, 0xe3e3feffffffffff,
+    0xc7c7feffffffffff, 0x8f8ffeffffffffff, 0x1f1ffeffffffffff, 0x3f3ffeffffffffff,
+    0xfffffefffffffcfc, 0xfffffefffffff8f8, 0xfffffefffffff1f1, 0xfffffeffffffe3e3,
+    0xfffffeffffffc7c7, 0xfffffeffffff8f8f, 0xfffffeffffff1f1f, 0xfffffeffffff3f3f,
+    0xfffffefffffcfcfc, 0xfffffefffff8f8f8, 0xfffffefffff1f1f1, 0xfffffeffffe3e3e3,
+    0xfffffeffffc7c7c7, 0xfffffeffff8f8f8f, 0xfffffeffff1f1f1f, 0xfffffeffff3f3f3f,
+    0xfffffefffcfcfcff, 0xfffffefff8f8f8ff, 0xfffffefff1f1f1ff, 0xfffffeffe3e3e3ff,
+    0xfffffeffc7c7c7ff, 0xfffffeff8f8f8fff, 0xfffffeff1f1f1fff, 0xfffffeff3f3f3fff,
+    0xfffffefcfcfcffff, 0xfffffef8f8f8ffff, 0xfffffef1f1f1ffff, 0xfffffee3e3e3ffff,
+    0xfffffec7c7c7ffff, 0xfffffe8f8f8fffff, 0xfffffe1f1f1fffff, 0xfffffe3f3f3fffff,
+    0x0003000000000000, 0x0003000000000000, 0x0207000000000000, 0x070f020200000000,
+    0xffffc6c7c7ffffff, 0xffff8e8f8fffffff, 0xffff1e1f1fffffff, 0xffff3e3f3fffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0200000000000000, 0x0602020000000000,
+    0xffc7c6c7ffffffff, 0xff8f8e8fffffffff, 0xff1f1e1fffffffff, 0xff3f3e3fffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0202020000000000,
+    0xc7c7c6ffffffffff, 0x8f8f8effffffffff, 0x1f1f1effffffffff, 0x3f3f3effffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0202060000000000,
+    0xc7c7feffffffffff, 0x8f8ffeffffffffff, 0x1f1ffeffffffffff, 0x3f3ffeffffffffff,
+    0xfffffdfffffffcfc, 0xfffffdfffffff8f8, 0xfffffdfffffff1f1, 0xfffffdffffffe3e3,
+    0xfffffdffffffc7c7, 0xfffffdffffff8f8f, 0xfffffdffffff1f1f, 0xfffffdffffff3f3f,
+    0xfffffdfffffcfcfc, 0xfffffdfffff8f8f8, 0xfffffdfffff1f1f1, 0xfffffdffffe3e3e3,
+    0xfffffdffffc7c7c7, 0xfffffdffff8f8f8f, 0xfffffdffff1f1f1f, 0xfffffdffff3f3f3f,
+    0xfffffdfffcfcfcff, 0xfffffdfff8f8f8ff, 0xfffffdfff1f1f1ff, 0xfffffdffe3e3e3ff,
+    0xfffffdffc7c7c7ff, 0xfffffdff8f8f8fff, 0xfffffdff1f1f1fff, 0xfffffdff3f3f3fff,
+    0xfffffdfcfcfcffff, 0xfffffdf8f8f8ffff, 0xfffffdf1f1f1ffff, 0xfffffde3e3e3ffff,
+    0xfffffdc7c7c7ffff, 0xfffffd8f8f8fffff, 0xfffffd1f1f1fffff, 0xfffffd3f3f3fffff,
+    0xfffffcfcfcffffff, 0xfffff8f8f8ffffff, 0xfffff1f1f1ffffff, 0xffffe1e3e3ffffff,
+    0xffffc5c7c7ffffff, 0xffff8d8f8fffffff, 0xffff1d1f1fffffff, 0xffff3d3f3fffffff,
+    0x0f0c0c0c00000000, 0x0000000000000000, 0x0701010100000000, 0x0f03010307000000,
+    0xffc7c5c7ffffffff, 0xff8f8d8fffffffff, 0xff1f1d1fffffffff, 0xff3f3d3fffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303011f1f000000,
+    0xc7c7c5ffffffffff, 0x8f8f8dffffffffff, 0x1f1f1dffffffffff, 0x3f3f3dffffffffff,
+    0x040c080f00000000, 0x0000050000000000, 0x0101090f00000000, 0x03031d1f1f000000,
+    0xc7c7fdffffffffff, 0x8f8ffdffffffffff, 0x1f1ffdffffffffff, 0x3f3ffdffffffffff,
+    0xfffffdfffffffcfc, 0xfffffdfffffff8f8, 0xfffffdfffffff1f1, 0xfffffdffffffe3e3,
+    0xfffffdffffffc7c7, 0xfffffdffffff8f8f, 0xfffffdffffff1f1f, 0xfffffdffffff3f3f,
+    0xfffffdfffffcfcfc, 0xfffffdfffff8f8f8, 0xfffffdfffff1f1f1, 0xfffffdffffe3e3e3,
+    0xfffffdffffc7c7c7, 0xfffffdffff8f8f8f, 0xfffffdffff1f1f1f, 0xfffffdffff3f3f3f,
+    0xfffffdfffcfcfcff, 0xfffffdfff8f8f8ff, 0xfffffdfff1f1f1ff, 0xfffffdffe3e3e3ff,
+    0xfffffdffc7c7c7ff, 0xfffffdff8f8f8fff, 0xfffffdff1f1f1fff, 0xfffffdff3f3f3fff,
+    0xfffffdfcfcfcffff, 0xfffffdf8f8f8ffff, 0xfffffdf1f1f1ffff, 0xfffffde3e3e3ffff,
+    0xfffffdc7c7c7ffff, 0xfffffd8f8f8fffff, 0xfffffd1f1f1fffff, 0xfffffd3f3f3fffff,
+    0x0007040400000000, 0x0007000000000000, 0x0007010100000000, 0x070f010300000000,
+    0x0f1f050707000000, 0xffff8d8f8fffffff, 0xffff1d1f1fffffff, 0xffff3d3f3fffffff,
+    0x0004040000000000, 0x0000000000000000, 0x0001010000000000, 0x0703010300000000,
+    0x0f07050707000000, 0xff8f8d8fffffffff, 0xff1f1d1fffffffff, 0xff3f3d3fffffffff,
+    0x0004000000000000, 0x0000000000000000, 0x0001010000000000, 0x0303010f00000000,
+    0x0707051f07000000, 0x8f8f8dffffffffff, 0x1f1f1dffffffffff, 0x3f3f3dffffffffff,
+    0x0000050000000000, 0x0000000000000000, 0x0101050000000000, 0x0303090f00000000,
+    0x07071d1f1f000000, 0x8f8ffdffffffffff, 0x1f1ffdffffffffff, 0x3f3ffdffffffffff,
+    0xfffffbfffffffcfc, 0xfffffbfffffff8f8, 0xfffffbfffffff1f1, 0xfffffbffffffe3e3,
+    0xfffffbffffffc7c7, 0xfffffbffffff8f8f, 0xfffffbffffff1f1f, 0xfffffbffffff3f3f,
+    0xfffffbfffffcfcfc, 0xfffffbfffff8f8f8, 0xfffffbfffff1f1f1, 0xfffffbffffe3e3e3,
+    0xfffffbffffc7c7c7, 0xfffffbffff8f8f8f, 0xfffffbffff1f1f1f, 0xfffffbffff3f3f3f,
+    0xfffffbfffcfcfcff, 0xfffffbfff8f8f8ff, 0xfffffbfff1f1f1ff, 0xfffffbffe3e3e3ff,
+    0xfffffbffc7c7c7ff, 0xfffffbff8f8f8fff, 0xfffffbff1f1f1fff, 0xfffffbff3f3f3fff,
+    0xfffffbfcfcfcffff, 0xfffffbf8f8f8ffff, 0xfffffbf1f1f1ffff, 0xfffffbe3e3e3ffff,
+    0xfffffbc7c7c7ffff, 0xfffffb8f8f8fffff, 0xfffffb1f1f1fffff, 0xfffffb3f3f3fffff,
+    0xfffff8fcfcffffff, 0xfffff8f8f8ffffff, 0xfffff1f1f1ffffff, 0xffffe3e3e3ffffff,
+    0xffffc3c7c7ffffff, 0xffff8b8f8fffffff, 0xffff1b1f1fffffff, 0xffff3b3f3fffffff,
+    0x3f3c383c3e000000, 0x1e18181800000000, 0x0000000000000000, 0x0f03030300000000,
+    0x1f0703070f000000, 0xff8f8b8fffffffff, 0xff1f1b1fffffffff, 0xff3f3b3fffffffff,
+    0x3c3c383f3f000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000,
+    0x0707033f3f000000, 0x8f8f8bffffffffff, 0x1f1f1bffffffffff, 0x3f3f3bffffffffff,
+    0x3c3c3b3f3f000000, 0x1818191f00000000, 0x00000a0000000000, 0x0303131f00000000,
+    0x07073b3f3f000000, 0x8f8ffbffffffffff, 0x1f1ffbffffffffff, 0x3f3ffbffffffffff,
+    0xfffffbfffffffcfc, 0xfffffbfffffff8f8, 0xfffffbfffffff1f1, 0xfffffbffffffe3e3,
+    0xfffffbffffffc7c7, 0xfffffbffffff8f8f, 0xfffffbffffff1f1f, 0xfffffbffffff3f3f,
+    0xfffffbfffffcfcfc, 0xfffffbfffff8f8f8, 0xfffffbfffff1f1f1, 0xfffffbffffe3e3e3,
+    0xfffffbffffc7c7c7, 0xfffffbffff8f8f8f, 0xfffffbffff1f1f1f, 0xfffffbffff3f3f3f,
+    0xfffffbfffcfcfcff, 0xfffffbfff8f8f8ff, 0xfffffbfff1f1f1ff, 0xfffffbffe3e3e3ff,
+    0xfffffbffc7c7c7ff, 0xfffffbff8f8f8fff, 0xfffffbff1f1f1fff, 0xfffffbff3f3f3fff,
+    0xfffffbfcfcfcffff, 0xfffffbf8f8f8ffff, 0xfffffbf1f1f1ffff, 0xfffffbe3e3e3ffff,
+    0xfffffbc7c7c7ffff, 0xfffffb8f8f8fffff, 0xfffffb1f1f1fffff, 0xfffffb3f3f3fffff,
+    0x1e1f181c00000000, 0x000e080800000000, 0x000e000000000000, 0x000e020200000000,
+    0x0f1f030700000000, 0x1f3f0b0f0f000000, 0xffff1b1f1fffffff, 0xffff3b3f3fffffff,
+    0x1e1c181c00000000, 0x0008080000000000, 0x0000000000000000, 0x0002020000000000,
+    0x0f07030700000000, 0x1f0f0b0f0f000000, 0xff1f1b1fffffffff, 0xff3f3b3fffffffff,
+    0x1c1c181f00000000, 0x0008080000000000, 0x0000000000000000, 0x0002020000000000,
+    0x0707031f00000000, 0x0f0f0b3f0f000000, 0x1f1f1bffffffffff, 0x3f3f3bffffffffff,
+    0x1c1c191f00000000, 0x08080a0000000000, 0x0000000000000000, 0x02020a0000000000,
+    0x0707131f00000000, 0x0f0f3b3f3f000000, 0x1f1ffbffffffffff, 0x3f3ffbffffffffff,
+    0xfffff7fffffffcfc, 0xfffff7fffffff8f8, 0xfffff7fffffff1f1, 0xfffff7ffffffe3e3,
+    0xfffff7ffffffc7c7, 0xfffff7ffffff8f8f, 0xfffff7ffffff1f1f, 0xfffff7ffffff3f3f,
+    0xfffff7fffffcfcfc, 0xfffff7fffff8f8f8, 0xfffff7fffff1f1f1, 0xfffff7ffffe3e3e3,
+    0xfffff7ffffc7c7c7, 0xfffff7ffff8f8f8f, 0xfffff7ffff1f1f1f, 0xfffff7ffff3f3f3f,
+    0xfffff7fffcfcfcff, 0xfffff7fff8f8f8ff, 0xfffff7fff1f1f1ff, 0xfffff7ffe3e3e3ff,
+    0xfffff7ffc7c7c7ff, 0xfffff7ff8f8f8fff, 0xfffff7ff1f1f1fff, 0xfffff7ff3f3f3fff,
+    0xfffff7fcfcfcffff, 0xfffff7f8f8f8ffff, 0xfffff7f1f1f1ffff, 0xfffff7e3e3e3ffff,
+    0xfffff7c7c7c7ffff, 0xfffff78f8f8fffff, 0xfffff71f1f1fffff, 0xfffff73f3f3fffff,
+    0xfffff4fcfcffffff, 0xfffff0f8f8ffffff, 0xfffff1f1f1ffffff, 0xffffe3e3e3ffffff,
+    0xffffc7c7c7ffffff, 0xffff878f8fffffff, 0xffff171f1fffffff, 0xffff373f3fffffff,
+    0xfffcf4fcffffffff, 0x7e7870787c000000, 0x3c30303000000000, 0x0000000000000000,
+    0x1e06060600000000, 0x3f0f070f1f000000, 0xff1f171fffffffff, 0xff3f373fffffffff,
+    0xfcfcf4ffffffffff, 0x7878707f7f000000, 0x0000000000000000, 0x0000000000000000,
+    0x0000000000000000, 0x0f0f077f7f000000, 0x1f1f17ffffffffff, 0x3f3f37ffffffffff,
+    0xfcfcf7ffffffffff, 0x7878777f7f000000, 0x3030323e00000000, 0x0000140000000000,
+    0x0606263e00000000, 0x0f0f777f7f000000, 0x1f1ff7ffffffffff, 0x3f3ff7ffffffffff,
+    0xfffff7fffffffcfc, 0xfffff7fffffff8f8, 0xfffff7fffffff1f1, 0xfffff7ffffffe3e3,
+    0xfffff7ffffffc7c7, 0xfffff7ffffff8f8f, 0xfffff7ffffff1f1f, 0xfffff7ffffff3f3f,
+    0xfffff7fffffcfcfc, 0xfffff7fffff8f8f8, 0xfffff7fffff1f1f1, 0xfffff7ffffe3e3e3,
+    0xfffff7ffffc7c7c7, 0xfffff7ffff8f8f8f, 0xfffff7ffff1f1f1f, 0xfffff7ffff3f3f3f,
+    0xfffff7fffcfcfcff, 0xfffff7fff8f8f8ff, 0xfffff7fff1f1f1ff, 0xfffff7ffe3e3e3ff,
+    0xfffff7ffc7c7c7ff, 0xfffff7ff8f8f8fff, 0xfffff7ff1f1f1fff, 0xfffff7ff3f3f3fff,
+    0xfffff7fcfcfcffff, 0xfffff7f8f8f8ffff, 0xfffff7f1f1f1ffff, 0xfffff7e3e3e3ffff,
+    0xfffff7c7c7c7ffff, 0xfffff78f8f8fffff, 0xfffff71f1f1fffff, 0xfffff73f3f3fffff,
+    0x7e7f747c7c000000, 0x3c3e303800000000, 0x001c101000000000, 0x001c000000000000,
+    0x001c040400000000, 0x1e3e060e00000000, 0x3f7f171f1f000000, 0xffff373f3fffffff,
+    0x7e7c747c7c000000, 0x3c38303800000000, 0x0010100000000000, 0x0000000000000000,
+    0x0004040000000000, 0x1e0e060e00000000, 0x3f1f171f1f000000, 0xff3f373fffffffff,
+    0x7c7c747f7c000000, 0x3838303e00000000, 0x0010100000000000, 0x0000000000000000,
+    0x0004040000000000, 0x0e0e063e00000000, 0x1f1f177f1f000000, 0x3f3f37ffffffffff,
+    0x7c7c777f7f000000, 0x3838323e00000000, 0x1010140000000000, 0x0000000000000000,
+    0x0404140000000000, 0x0e0e263e00000000, 0x1f1f777f7f000000, 0x3f3ff7ffffffffff,
+    0xfffffffefffffcfc, 0xfffffffefffff8f8, 0xfffffffefffff1f1, 0xfffffffeffffe3e3,
+    0xfffffffeffffc7c7, 0xfffffffeffff8f8f, 0xfffffffeffff1f1f, 0xfffffffeffff3f3f,
+    0xfffffffefffcfcfc, 0xfffffffefff8f8f8, 0xfffffffefff1f1f1, 0xfffffffeffe3e3e3,
+    0xfffffffeffc7c7c7, 0xfffffffeff8f8f8f, 0xfffffffeff1f1f1f, 0xfffffffeff3f3f3f,
+    0xfffffffefcfcfcff, 0xfffffffef8f8f8ff, 0xfffffffef1f1f1ff, 0xfffffffee3e3e3ff,
+    0xfffffffec7c7c7ff, 0xfffffffe8f8f8fff, 0xfffffffe1f1f1fff, 0xfffffffe3f3f3fff,
+    0xfffffffcfcfcffff, 0xfffffff8f8f8ffff, 0xfffffff0f1f1ffff, 0xffffffe2e3e3ffff,
+    0xffffffc6c7c7ffff, 0xffffff8e8f8fffff, 0xffffff1e1f1fffff, 0xffffff3e3f3fffff,
+    0x0000000000000000, 0x0003000000000000, 0x0707010000000000, 0x0f0f030203000000,
+    0xffffc7c6c7ffffff, 0xffff8f8e8fffffff, 0xffff1f1e1fffffff, 0xffff3f3e3fffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0200000000000000, 0x0703030200000000,
+    0xffc7c7c6ffffffff, 0xff8f8f8effffffff, 0xff1f1f1effffffff, 0xff3f3f3effffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303030000000000,
+    0xc7c7c7feffffffff, 0x8f8f8ffeffffffff, 0x1f1f1ffeffffffff, 0x3f3f3ffeffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303070000000000,
+    0xc7c7fffeffffffff, 0x8f8ffffeffffffff, 0x1f1ffffeffffffff, 0x3f3ffffeffffffff,
+    0xfffffffefffffcfc, 0xfffffffefffff8f8, 0xfffffffefffff1f1, 0xfffffffeffffe3e3,
+    0xfffffffeffffc7c7, 0xfffffffeffff8f8f, 0xfffffffeffff1f1f, 0xfffffffeffff3f3f,
+    0xfffffffefffcfcfc, 0xfffffffefff8f8f8, 0xfffffffefff1f1f1, 0xfffffffeffe3e3e3,
+    0xfffffffeffc7c7c7, 0xfffffffeff8f8f8f, 0xfffffffeff1f1f1f, 0xfffffffeff3f3f3f,
+    0xfffffffefcfcfcff, 0xfffffffef8f8f8ff, 0xfffffffef1f1f1ff, 0xfffffffee3e3e3ff,
+    0xfffffffec7c7c7ff, 0xfffffffe8f8f8fff, 0xfffffffe1f1f1fff, 0xfffffffe3f3f3fff,
+    0x0000030000000000, 0x0000030000000000, 0x0003070000000000, 0x07070f0202000000,
+    0x0f0f1f0607000000, 0xffffff8e8f8fffff, 0xffffff1e1f1fffff, 0xffffff3e3f3fffff,
+    0x0000000000000000, 0x0000000000000000, 0x0002000000000000, 0x0206020200000000,
+    0x070f070600000000, 0xffff8f8e8fffffff, 0xffff1f1e1fffffff, 0xffff3f3e3fffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0202020000000000,
+    0x0707070000000000, 0xff8f8f8effffffff, 0xff1f1f1effffffff, 0xff3f3f3effffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0202000000000000,
+    0x0707070000000000, 0x8f8f8ffeffffffff, 0x1f1f1ffeffffffff, 0x3f3f3ffeffffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0202000000000000,
+    0x0707070000000000, 0x8f8ffffeffffffff, 0x1f1ffffeffffffff, 0x3f3ffffeffffffff,
+    0xfffffffdfffffcfc, 0xfffffffdfffff8f8, 0xfffffffdfffff1f1, 0xfffffffdffffe3e3,
+    0xfffffffdffffc7c7, 0xfffffffdffff8f8f, 0xfffffffdffff1f1f, 0xfffffffdffff3f3f,
+    0xfffffffdfffcfcfc, 0xfffffffdfff8f8f8, 0xfffffffdfff1f1f1, 0xfffffffdffe3e3e3,
+    0xfffffffdffc7c7c7, 0xfffffffdff8f8f8f, 0xfffffffdff1f1f1f, 0xfffffffdff3f3f3f,
+    0xfffffffdfcfcfcff, 0xfffffffdf8f8f8ff, 0xfffffffdf1f1f1ff, 0xfffffffde3e3e3ff,
+    0xfffffffdc7c7c7ff, 0xfffffffd8f8f8fff, 0xfffffffd1f1f1fff, 0xfffffffd3f3f3fff,
+    0xfffffffcfcfcffff, 0xfffffff8f8f8ffff, 0xfffffff1f1f1ffff, 0xffffffe1e3e3ffff,
+    0xffffffc5c7c7ffff, 0xffffff8d8f8fffff, 0xffffff1d1f1fffff, 0xffffff3d3f3fffff,
+    0x000f0c0c0c000000, 0x0000000000000000, 0x0007010101000000, 0x0f0f030103070000,
+    0x1f1f070507070000, 0xffff8f8d8fffffff, 0xffff1f1d1fffffff, 0xffff3f3d3fffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0703030103000000,
+    0x0f0707050f070000, 0xff8f8f8dffffffff, 0xff1f1f1dffffffff, 0xff3f3f3dffffffff,
+    0x0c0c0c0c00000000, 0x0000000000000000, 0x0101010100000000, 0x0303030d03000000,
+    0x0707071d1f070000, 0x8f8f8ffdffffffff, 0x1f1f1ffdffffffff, 0x3f3f3ffdffffffff,
+    0x0c0c0f0d00000000, 0x00080f0d00000000, 0x01010f0d00000000, 0x03031f1d03000000,
+    0x07073f3d1f070000, 0x8f8ffffdffffffff, 0x1f1ffffdffffffff, 0x3f3ffffdffffffff,
+    0xfffffffdfffffcfc, 0xfffffffdfffff8f8, 0xfffffffdfffff1f1, 0xfffffffdffffe3e3,
+    0xfffffffdffffc7c7, 0xfffffffdffff8f8f, 0xfffffffdffff1f1f, 0xfffffffdffff3f3f,
+    0xfffffffdfffcfcfc, 0xfffffffdfff8f8f8, 0xfffffffdfff1f1f1, 0xfffffffdffe3e3e3,
+    0xfffffffdffc7c7c7, 0xfffffffdff8f8f8f, 0xfffffffdff1f1f1f, 0xfffffffdff3f3f3f,
+    0xfffffffdfcfcfcff, 0xfffffffdf8f8f8ff, 0xfffffffdf1f1f1ff, 0xfffffffde3e3e3ff,
+    0xfffffffdc7c7c7ff, 0xfffffffd8f8f8fff, 0xfffffffd1f1f1fff, 0xfffffffd3f3f3fff,
+    0x0000070404000000, 0x0000070000000000, 0x0000070101000000, 0x00070f0103000000,
+    0x0f0f1f0507070000, 0x1f1f3f0d0f070000, 0xffffff1d1f1fffff, 0xffffff3d3f3fffff,
+    0x0000040400000000, 0x0000000000000000, 0x0000010100000000, 0x0007030103000000,
+    0x070f070503000000, 0x0f1f0f0d0f070000, 0xffff1f1d1fffffff, 0xffff3f3d3fffffff,
+    0x0000040000000000, 0x0000000000000000, 0x0000010000000000, 0x0003030100000000,
+    0x0707070503000000, 0x0f0f0f0d0f070000, 0xff1f1f1dffffffff, 0xff3f3f3dffffffff,
+    0x0004040000000000, 0x0000000000000000, 0x0001010000000000, 0x0303030100000000,
+    0x0707070d03000000, 0x0f0f0f1d0f070000, 0x1f1f1ffdffffffff, 0x3f3f3ffdffffffff,
+    0x0404070000000000, 0x0000070000000000, 0x0101070000000000, 0x03030f0100000000,
+    0x07071f0d03000000, 0x0f0f3f1d1f070000, 0x1f1ffffdffffffff, 0x3f3ffffdffffffff,
+    0xfffffffbfffffcfc, 0xfffffffbfffff8f8, 0xfffffffbfffff1f1, 0xfffffffbffffe3e3,
+    0xfffffffbffffc7c7, 0xfffffffbffff8f8f, 0xfffffffbffff1f1f, 0xfffffffbffff3f3f,
+    0xfffffffbfffcfcfc, 0xfffffffbfff8f8f8, 0xfffffffbfff1f1f1, 0xfffffffbffe3e3e3,
+    0xfffffffbffc7c7c7, 0xfffffffbff8f8f8f, 0xfffffffbff1f1f1f, 0xfffffffbff3f3f3f,
+    0xfffffffbfcfcfcff, 0xfffffffbf8f8f8ff, 0xfffffffbf1f1f1ff, 0xfffffffbe3e3e3ff,
+    0xfffffffbc7c7c7ff, 0xfffffffb8f8f8fff, 0xfffffffb1f1f1fff, 0xfffffffb3f3f3fff,
+    0xfffffff8fcfcffff, 0xfffffff8f8f8ffff, 0xfffffff1f1f1ffff, 0xffffffe3e3e3ffff,
+    0xffffffc3c7c7ffff, 0xffffff8b8f8fffff, 0xffffff1b1f1fffff, 0xffffff3b3f3fffff,
+    0x3f3f3c383c3e0000, 0x001e181818000000, 0x0000000000000000, 0x000f030303000000,
+    0x1f1f0703070f0000, 0x3f3f0f0b0f0f0000, 0xffff1f1b1fffffff, 0xffff3f3b3fffffff,
+    0x3e3c3c383c000000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000,
+    0x0f07070307000000, 0x1f0f0f0b1f0f0000, 0xff1f1f1bffffffff, 0xff3f3f3bffffffff,
+    0x3c3c3c3b3c000000, 0x1818181800000000, 0x0000000000000000, 0x0303030300000000,
+    0x0707071b07000000, 0x0f0f0f3b3f0f0000, 0x1f1f1ffbffffffff, 0x3f3f3ffbffffffff,
+    0x3c3c3f3b3c000000, 0x18181f1b00000000, 0x00111f1b00000000, 0x03031f1b00000000,
+    0x07073f3b07000000, 0x0f0f7f7b3f0f0000, 0x1f1ffffbffffffff, 0x3f3ffffbffffffff,
+    0xfffffffbfffffcfc, 0xfffffffbfffff8f8, 0xfffffffbfffff1f1, 0xfffffffbffffe3e3,
+    0xfffffffbffffc7c7, 0xfffffffbffff8f8f, 0xfffffffbffff1f1f, 0xfffffffbffff3f3f,
+    0xfffffffbfffcfcfc, 0xfffffffbfff8f8f8, 0xfffffffbfff1f1f1, 0xfffffffbffe3e3e3,
+    0xfffffffbffc7c7c7, 0xfffffffbff8f8f8f, 0xfffffffbff1f1f1f, 0xfffffffbff3f3f3f,
+    0xfffffffbfcfcfcff, 0xfffffffbf8f8f8ff, 0xfffffffbf1f1f1ff, 0xfffffffbe3e3e3ff,
+    0xfffffffbc7c7c7ff, 0xfffffffb8f8f8fff, 0xfffffffb1f1f1fff, 0xfffffffb3f3f3fff,
+    0x001e1f181c000000, 0x00000e0808000000, 0x00000e0000000000, 0x00000e0202000000,
+    0x000f1f0307000000, 0x1f1f3f0b0f0f0000, 0x3f3f7f1b1f0f0000, 0xffffff3b3f3fffff,
+    0x001e1c181c000000, 0x0000080800000000, 0x0000000000000000, 0x0000020200000000,
+    0x000f070307000000, 0x0f1f0f0b07000000, 0x1f3f1f1b1f0f0000, 0xffff3f3b3fffffff,
+    0x001c1c1800000000, 0x0000080000000000, 0x0000000000000000, 0x0000020000000000,
+    0x0007070300000000, 0x0f0f0f0b07000000, 0x1f1f1f1b1f0f0000, 0xff3f3f3bffffffff,
+    0x1c1c1c1800000000, 0x0008080000000000, 0x0000000000000000, 0x0002020000000000,
+    0x0707070300000000, 0x0f0f0f1b07000000, 0x1f1f1f3b1f0f0000, 0x3f3f3ffbffffffff,
+    0x1c1c1f1800000000, 0x08080e0000000000, 0x00000e0000000000, 0x02020e0000000000,
+    0x07071f0300000000, 0x0f0f3f1b07000000, 0x1f1f7f3b3f0f0000, 0x3f3ffffbffffffff,
+    0xfffffff7fffffcfc, 0xfffffff7fffff8f8, 0xfffffff7fffff1f1, 0xfffffff7ffffe3e3,
+    0xfffffff7ffffc7c7, 0xfffffff7ffff8f8f, 0xfffffff7ffff1f1f, 0xfffffff7ffff3f3f,
+    0xfffffff7fffcfcfc, 0xfffffff7fff8f8f8, 0xfffffff7fff1f1f1, 0xfffffff7ffe3e3e3,
+    0xfffffff7ffc7c7c7, 0xfffffff7ff8f8f8f, 0xfffffff7ff1f1f1f, 0xfffffff7ff3f3f3f,
+    0xfffffff7fcfcfcff, 0xfffffff7f8f8f8ff, 0xfffffff7f1f1f1ff, 0xfffffff7e3e3e3ff,
+    0xfffffff7c7c7c7ff, 0xfffffff78f8f8fff, 0xfffffff71f1f1fff, 0xfffffff73f3f3fff,
+    0xfffffff4fcfcffff, 0xfffffff0f8f8ffff, 0xfffffff1f1f1ffff, 0xffffffe3e3e3ffff,
+    0xffffffc7c7c7ffff, 0xffffff878f8fffff, 0xffffff171f1fffff, 0xffffff373f3fffff,
+    0xfffffcf4fcfc0000, 0x7e7e7870787c0000, 0x003c303030000000, 0x0000000000000000,
+    0x001e060606000000, 0x3f3f0f070f1f0000, 0x7f7f1f171f1f0000, 0xffff3f373fffffff,
+    0xfefcfcf4fefc0000, 0x7c78787078000000, 0x0000000000000000, 0x0000000000000000,
+    0x0000000000000000, 0x1f0f0f070f000000, 0x3f1f1f173f1f0000, 0xff3f3f37ffffffff,
+    0xfcfcfcf7fffc0000, 0x7878787678000000, 0x3030303000000000, 0x0000000000000000,
+    0x0606060600000000, 0x0f0f0f370f000000, 0x1f1f1f777f1f0000, 0x3f3f3ff7ffffffff,
+    0xfcfcfff7fffc0000, 0x78787f7778000000, 0x30303e3600000000, 0x00223e3600000000,
+    0x06063e3600000000, 0x0f0f7f770f000000, 0x1f1ffff77f1f0000, 0x3f3ffff7ffffffff,
+    0xfffffff7fffffcfc, 0xfffffff7fffff8f8, 0xfffffff7fffff1f1, 0xfffffff7ffffe3e3,
+    0xfffffff7ffffc7c7, 0xfffffff7ffff8f8f, 0xfffffff7ffff1f1f, 0xfffffff7ffff3f3f,
+    0xfffffff7fffcfcfc, 0xfffffff7fff8f8f8, 0xfffffff7fff1f1f1, 0xfffffff7ffe3e3e3,
+    0xfffffff7ffc7c7c7, 0xfffffff7ff8f8f8f, 0xfffffff7ff1f1f1f, 0xfffffff7ff3f3f3f,
+    0xfffffff7fcfcfcff, 0xfffffff7f8f8f8ff, 0xfffffff7f1f1f1ff, 0xfffffff7e3e3e3ff,
+    0xfffffff7c7c7c7ff, 0xfffffff78f8f8fff, 0xfffffff71f1f1fff, 0xfffffff73f3f3fff,
+    0x7e7e7f747c7c0000, 0x003c3e3038000000, 0x00001c1010000000, 0x00001c0000000000,
+    0x00001c0404000000, 0x001e3e060e000000, 0x3f3f7f171f1f0000, 0x7f7fff373f1f0000,
+    0x7c7e7c7478000000, 0x003c383038000000, 0x0000101000000000, 0x0000000000000000,
+    0x0000040400000000, 0x001e0e060e000000, 0x1f3f1f170f000000, 0x3f7f3f373f1f0000,
+    0x7c7c7c7478000000, 0x0038383000000000, 0x0000100000000000, 0x0000000000000000,
+    0x0000040000000000, 0x000e0e0600000000, 0x1f1f1f170f000000, 0x3f3f3f373f1f0000,
+    0x7c7c7c7678000000, 0x3838383000000000, 0x0010100000000000, 0x0000000000000000,
+    0x0004040000000000, 0x0e0e0e0600000000, 0x1f1f1f370f000000, 0x3f3f3f773f1f0000,
+    0x7c7c7f7678000000, 0x38383e3000000000, 0x10101c0000000000, 0x00001c0000000000,
+    0x04041c0000000000, 0x0e0e3e0600000000, 0x1f1f7f370f000000, 0x3f3fff777f1f0000,
+    0xfffffffffefffcfc, 0xfffffffffefff8f8, 0xfffffffffefff1f1, 0xfffffffffeffe3e3,
+    0xfffffffffeffc7c7, 0xfffffffffeff8f8f, 0xfffffffffeff1f1f, 0xfffffffffeff3f3f,
+    0xfffffffffefcfcfc, 0xfffffffffef8f8f8, 0xfffffffffef1f1f1, 0xfffffffffee3e3e3,
+    0xfffffffffec7c7c7, 0xfffffffffe8f8f8f, 0xfffffffffe1f1f1f, 0xfffffffffe3f3f3f,
+    0xfffffffffcfcfcff, 0xfffffffff8f8f8ff, 0xfffffffff0f1f1ff, 0xffffffffe2e3e3ff,
+    0xffffffffc6c7c7ff, 0xffffffff8e8f8fff, 0xffffffff1e1f1fff, 0xffffffff3e3f3fff,
+    0x0000000000000000, 0x0000030000000000, 0x0007070100000000, 0x070f0f0302030000,
+    0x0f1f1f0706070000, 0xffffff8f8e8fffff, 0xffffff1f1e1fffff, 0xffffff3f3e3fffff,
+    0x0000000000000000, 0x0000000000000000, 0x0002000000000000, 0x0707030302000000,
+    0x0f0f070706000000, 0xffff8f8f8effffff, 0xffff1f1f1effffff, 0xffff3f3f3effffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0703030300000000,
+    0x0f07070700000000, 0xff8f8f8ffeffffff, 0xff1f1f1ffeffffff, 0xff3f3f3ffeffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303030000000000,
+    0x0707070f00000000, 0x8f8f8ffffeffffff, 0x1f1f1ffffeffffff, 0x3f3f3ffffeffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303070000000000,
+    0x07070f0f00000000, 0x8f8ffffffeffffff, 0x1f1ffffffeffffff, 0x3f3ffffffeffffff,
+    0xfffffffffefffcfc, 0xfffffffffefff8f8, 0xfffffffffefff1f1, 0xfffffffffeffe3e3,
+    0xfffffffffeffc7c7, 0xfffffffffeff8f8f, 0xfffffffffeff1f1f, 0xfffffffffeff3f3f,
+    0xfffffffffefcfcfc, 0xfffffffffef8f8f8, 0xfffffffffef1f1f1, 0xfffffffffee3e3e3,
+    0xfffffffffec7c7c7, 0xfffffffffe8f8f8f, 0xfffffffffe1f1f1f, 0xfffffffffe3f3f3f,
+    0x0000000300000000, 0x0000000300000000, 0x0000030700000000, 0x0007070f02020000,
+    0x070f0f1f06070000, 0x0f1f1f3f0e0f0000, 0xffffffff1e1f1fff, 0xffffffff3e3f3fff,
+    0x0000000000000000, 0x0000000000000000, 0x0000020000000000, 0x0002060202000000,
+    0x07070f0706000000, 0x0f0f1f0f0e000000, 0xffffff1f1e1fffff, 0xffffff3f3e3fffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0002020200000000,
+    0x0707070700000000, 0x0f0f0f0f00000000, 0xffff1f1f1effffff, 0xffff3f3f3effffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0002020000000000,
+    0x0707070000000000, 0x0f0f0f0f00000000, 0xff1f1f1ffeffffff, 0xff3f3f3ffeffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0002000000000000,
+    0x0707070000000000, 0x0f0f0f0f00000000, 0x1f1f1ffffeffffff, 0x3f3f3ffffeffffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0202000000000000,
+    0x0707070000000000, 0x0f0f0f0f00000000, 0x1f1ffffffeffffff, 0x3f3ffffffeffffff,
+    0xfffffffffdfffcfc, 0xfffffffffdfff8f8, 0xfffffffffdfff1f1, 0xfffffffffdffe3e3,
+    0xfffffffffdffc7c7, 0xfffffffffdff8f8f, 0xfffffffffdff1f1f, 0xfffffffffdff3f3f,
+    0xfffffffffdfcfcfc, 0xfffffffffdf8f8f8, 0xfffffffffdf1f1f1, 0xfffffffffde3e3e3,
+    0xfffffffffdc7c7c7, 0xfffffffffd8f8f8f, 0xfffffffffd1f1f1f, 0xfffffffffd3f3f3f,
+    0xfffffffffcfcfcff, 0xfffffffff8f8f8ff, 0xfffffffff1f1f1ff, 0xffffffffe1e3e3ff,
+    0xffffffffc5c7c7ff, 0xffffffff8d8f8fff, 0xffffffff1d1f1fff, 0xffffffff3d3f3fff,
+    0x00000f0c0c0c0000, 0x0000000000000000, 0x0000070101010000, 0x000f0f0301030700,
+    0x0f1f1f0705070700, 0x1f3f3f0f0d0f1f0f, 0xffffff1f1d1fffff, 0xffffff3f3d3fffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0007030301030000,
+    0x0f0f0707050f0700, 0x1f1f0f0f0d1f1f0f, 0xffff1f1f1dffffff, 0xffff3f3f3dffffff,
+    0x000c0c0c0c000000, 0x0000000000000000, 0x0001010101000000, 0x0703030305030000,
+    0x0f0707070d0f0700, 0x1f0f0f0f1d1f1f0f, 0xff1f1f1ffdffffff, 0xff3f3f3ffdffffff,
+    0x0c0c0c0e0d000000, 0x0000000505000000, 0x0101010b0d000000, 0x030303171d030000,
+    0x0707072f3d0f0700, 0x0f0f0f5f7d1f1f0f, 0x1f1f1ffffdffffff, 0x3f3f3ffffdffffff,
+    0x0c0c0f0f0c000000, 0x00080f0f00000000, 0x01010f0f01000000, 0x03031f1f05030000,
+    0x07073f3f0d0f0700, 0x0f0f7f7f1d1f1f0f, 0x1f1ffffffdffffff, 0x3f3ffffffdffffff,
+    0xfffffffffdfffcfc, 0xfffffffffdfff8f8, 0xfffffffffdfff1f1, 0xfffffffffdffe3e3,
+    0xfffffffffdffc7c7, 0xfffffffffdff8f8f, 0xfffffffffdff1f1f, 0xfffffffffdff3f3f,
+    0xfffffffffdfcfcfc, 0xfffffffffdf8f8f8, 0xfffffffffdf1f1f1, 0xfffffffffde3e3e3,
+    0xfffffffffdc7c7c7, 0xfffffffffd8f8f8f, 0xfffffffffd1f1f1f, 0xfffffffffd3f3f3f,
+    0x0000000704040000, 0x0000000700000000, 0x0000000701010000, 0x0000070f01030000,
+    0x000f0f1f05070700, 0x0f1f1f3f0d0f0700, 0x1f3f3f7f1d1f1f0f, 0xffffffff3d3f3fff,
+    0x0000000404000000, 0x0000000000000000, 0x0000000101000000, 0x0000070301030000,
+    0x00070f0705030000, 0x0f0f1f0f0d0f0700, 0x1f1f3f1f1d1f1f0f, 0xffffff3f3d3fffff,
+    0x0000000400000000, 0x0000000000000000, 0x0000000100000000, 0x0000030301000000,
+    0x0007070705030000, 0x0f0f0f0f0d0f0700, 0x1f1f1f1f1d1f1f0f, 0xffff3f3f3dffffff,
+    0x0000040400000000, 0x0000000000000000, 0x0000010100000000, 0x0003030301000000,
+    0x0007070705030000, 0x0f0f0f0f0d0f0700, 0x1f1f1f1f1d1f1f0f, 0xff3f3f3ffdffffff,
+    0x0004040500000000, 0x0000000200000000, 0x0001010500000000, 0x0003030b01000000,
+    0x0707071705030000, 0x0f0f0f2f0d0f0700, 0x1f1f1f5f1d1f1f0f, 0x3f3f3ffffdffffff,
+    0x0404070400000000, 0x0000070000000000, 0x0101070100000000, 0x03030f0301000000,
+    0x07071f0705030000, 0x0f0f3f0f0d0f0700, 0x1f1f7f5f1d1f1f0f, 0x3f3ffffffdffffff,
+    0xfffffffffbfffcfc, 0xfffffffffbfff8f8, 0xfffffffffbfff1f1, 0xfffffffffbffe3e3,
+    0xfffffffffbffc7c7, 0xfffffffffbff8f8f, 0xfffffffffbff1f1f, 0xfffffffffbff3f3f,
+    0xfffffffffbfcfcfc, 0xfffffffffbf8f8f8, 0xfffffffffbf1f1f1, 0xfffffffffbe3e3e3,
+    0xfffffffffbc7c7c7, 0xfffffffffb8f8f8f, 0xfffffffffb1f1f1f, 0xfffffffffb3f3f3f,
+    0xfffffffff8fcfcff, 0xfffffffff8f8f8ff, 0xfffffffff1f1f1ff, 0xffffffffe3e3e3ff,
+    0xffffffffc3c7c7ff, 0xffffffff8b8f8fff, 0xffffffff1b1f1fff, 0xffffffff3b3f3fff,
+    0x003f3f3c383c3e00, 0x00001e1818180000, 0x0000000000000000, 0x00000f0303030000,
+    0x001f1f0703070f00, 0x1f3f3f0f0b0f0f00, 0x3f7f7f1f1b1f3f1f, 0xffffff3f3b3fffff,
+    0x003e3c3c383c0000, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000,
+    0x000f070703070000, 0x1f1f0f0f0b1f0f00, 0x3f3f1f1f1b3f3f1f, 0xffff3f3f3bffffff,
+    0x3e3c3c3c3a3c0000, 0x0018181818000000, 0x0000000000000000, 0x0003030303000000,
+    0x0f0707070b070000, 0x1f0f0f0f1b1f0f00, 0x3f1f1f1f3b3f3f1f, 0xff3f3f3ffbffffff,
+    0x3c3c3c3e3b3c0000, 0x1818181d1b000000, 0x0000000a0a000000, 0x030303171b000000,
+    0x0707072f3b070000, 0x0f0f0f5f7b1f0f00, 0x1f1f1fbffb3f3f1f, 0x3f3f3ffffbffffff,
+    0x3c3c3f3f3a3c0000, 0x18181f1f18000000, 0x00111f1f00000000, 0x03031f1f03000000,
+    0x07073f3f0b070000, 0x0f0f7f7f1b1f0f00, 0x1f1fffff3b3f3f1f, 0x3f3ffffffbffffff,
+    0xfffffffffbfffcfc, 0xfffffffffbfff8f8, 0xfffffffffbfff1f1, 0xfffffffffbffe3e3,
+    0xfffffffffbffc7c7, 0xfffffffffbff8f8f, 0xfffffffffbff1f1f, 0xfffffffffbff3f3f,
+    0xfffffffffbfcfcfc, 0xfffffffffbf8f8f8, 0xfffffffffbf1f1f1, 0xfffffffffbe3e3e3,
+    0xfffffffffbc7c7c7, 0xfffffffffb8f8f8f, 0xfffffffffb1f1f1f, 0xfffffffffb3f3f3f,
+    0x00001e1f181c0000, 0x0000000e08080000, 0x0000000e00000000, 0x0000000e02020000,
+    0x00000f1f03070000, 0x001f1f3f0b0f0f00, 0x1f3f3f7f1b1f0f00, 0x3f7f7fff3b3f3f1f,
+    0x00001e1c181c0000, 0x0000000808000000, 0x0000000000000000, 0x0000000202000000,
+    0x00000f0703070000, 0x000f1f0f0b070000, 0x1f1f3f1f1b1f0f00, 0x3f3f7f3f3b3f3f1f,
+    0x00001c1c18000000, 0x0000000800000000, 0x0000000000000000, 0x0000000200000000,
+    0x0000070703000000, 0x000f0f0f0b070000, 0x1f1f1f1f1b1f0f00, 0x3f3f3f3f3b3f3f1f,
+    0x001c1c1c18000000, 0x0000080800000000, 0x0000000000000000, 0x0000020200000000,
+    0x0007070703000000, 0x000f0f0f0b070000, 0x1f1f1f1f1b1f0f00, 0x3f3f3f3f3b3f3f1f,
+    0x001c1c1d18000000, 0x0008080a00000000, 0x0000000400000000, 0x0002020a00000000,
+    0x0007071703000000, 0x0f0f0f2f0b070000, 0x1f1f1f5f1b1f0f00, 0x3f3f3fbf3b3f3f1f,
+    0x1c1c1f1c18000000, 0x08080e0800000000, 0x00000e0000000000, 0x02020e0200000000,
+    0x07071f0703000000, 0x0f0f3f0f0b070000, 0x1f1f7f1f1b1f0f00, 0x3f3fffbf3b3f3f1f,
+    0xfffffffff7fffcfc, 0xfffffffff7fff8f8, 0xfffffffff7fff1f1, 0xfffffffff7ffe3e3,
+    0xfffffffff7ffc7c7, 0xfffffffff7ff8f8f, 0xfffffffff7ff1f1f, 0xfffffffff7ff3f3f,
+    0xfffffffff7fcfcfc, 0xfffffffff7f8f8f8, 0xfffffffff7f1f1f1, 0xfffffffff7e3e3e3,
+    0xfffffffff7c7c7c7, 0xfffffffff78f8f8f, 0xfffffffff71f1f1f, 0xfffffffff73f3f3f,
+    0xfffffffff4fcfcff, 0xfffffffff0f8f8ff, 0xfffffffff1f1f1ff, 0xffffffffe3e3e3ff,
+    0xffffffffc7c7c7ff, 0xffffffff878f8fff, 0xffffffff171f1fff, 0xffffffff373f3fff,
+    0xfefffffcf4fcfc00, 0x007e7e7870787c00, 0x00003c3030300000, 0x0000000000000000,
+    0x00001e0606060000, 0x003f3f0f070f1f00, 0x3f7f7f1f171f1f00, 0x7fffff3f373f7f3f,
+    0xfefefcfcf4fefc00, 0x007c787870780000, 0x0000000000000000, 0x0000000000000000,
+    0x0000000000000000, 0x001f0f0f070f0000, 0x3f3f1f1f173f1f00, 0x7f7f3f3f377f7f3f,
+    0xfefcfcfcf6fefc00, 0x7c78787874780000, 0x0030303030000000, 0x0000000000000000,
+    0x0006060606000000, 0x1f0f0f0f170f0000, 0x3f1f1f1f373f1f00, 0x7f3f3f3f777f7f3f,
+    0xfcfcfcfef7fefc00, 0x7878787d77780000, 0x3030303a36000000, 0x0000001414000000,
+    0x0606062e36000000, 0x0f0f0f5f770f0000, 0x1f1f1fbff73f1f00, 0x3f3f3f7ff77f7f3f,
+    0xfcfcfffff6fefc00, 0x78787f7f74780000, 0x30303e3e30000000, 0x00223e3e00000000,
+    0x06063e3e06000000, 0x0f0f7f7f170f0000, 0x1f1fffff373f1f00, 0x3f3fffff777f7f3f,
+    0xfffffffff7fffcfc, 0xfffffffff7fff8f8, 0xfffffffff7fff1f1, 0xfffffffff7ffe3e3,
+    0xfffffffff7ffc7c7, 0xfffffffff7ff8f8f, 0xfffffffff7ff1f1f, 0xfffffffff7ff3f3f,
+    0xfffffffff7fcfcfc, 0xfffffffff7f8f8f8, 0xfffffffff7f1f1f1, 0xfffffffff7e3e3e3,
+    0xfffffffff7c7c7c7, 0xfffffffff78f8f8f, 0xfffffffff71f1f1f, 0xfffffffff73f3f3f,
+    0x007e7e7f747c7c00, 0x00003c3e30380000, 0x0000001c10100000, 0x0000001c00000000,
+    0x0000001c04040000, 0x00001e3e060e0000, 0x003f3f7f171f1f00, 0x3f7f7fff373f1f00,
+    0x007c7e7c74780000, 0x00003c3830380000, 0x0000001010000000, 0x0000000000000000,
+    0x0000000404000000, 0x00001e0e060e0000, 0x001f3f1f170f0000, 0x3f3f7f3f373f1f00,
+    0x007c7c7c74780000, 0x0000383830000000, 0x0000001000000000, 0x0000000000000000,
+    0x0000000400000000, 0x00000e0e06000000, 0x001f1f1f170f0000, 0x3f3f3f3f373f1f00,
+    0x007c7c7c74780000, 0x0038383830000000, 0x0000101000000000, 0x0000000000000000,
+    0x0000040400000000, 0x000e0e0e06000000, 0x001f1f1f170f0000, 0x3f3f3f3f373f1f00,
+    0x7c7c7c7d74780000, 0x0038383a30000000, 0x0010101400000000, 0x0000000800000000,
+    0x0004041400000000, 0x000e0e2e06000000, 0x1f1f1f5f170f0000, 0x3f3f3fbf373f1f00,
+    0x7c7c7f7c74780000, 0x38383e3830000000, 0x10101c1000000000, 0x00001c0000000000,
+    0x04041c0400000000, 0x0e0e3e0e06000000, 0x1f1f7f1f170f0000, 0x3f3fff3f373f1f00,
+    0xfffffffffffefcfc, 0xfffffffffffef8f8, 0xfffffffffffef1f1, 0xfffffffffffee3e3,
+    0xfffffffffffec7c7, 0xfffffffffffe8f8f, 0xfffffffffffe1f1f, 0xfffffffffffe3f3f,
+    0xfffffffffffcfcfc, 0xfffffffffff8f8f8, 0xfffffffffff0f1f1, 0xffffffffffe2e3e3,
+    0xffffffffffc6c7c7, 0xffffffffff8e8f8f, 0xffffffffff1e1f1f, 0xffffffffff3e3f3f,
+    0x0000000000000000, 0x0000000300000000, 0x0000070701000000, 0x00070f0f03020300,
+    0x0f0f1f1f07060700, 0x1f1f3f3f0f0e0f00, 0xffffffff1f1e1fff, 0xffffffff3f3e3fff,
+    0x0000000000000000, 0x0000000000000000, 0x0000020000000000, 0x0007070303020000,
+    0x0f0f0f0707060000, 0x1f1f1f0f0f0e0000, 0xffffff1f1f1effff, 0xffffff3f3f3effff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0007030303000000,
+    0x0f0f070707000000, 0x1f1f0f0f0f000000, 0xffff1f1f1ffeffff, 0xffff3f3f3ffeffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0003030300000000,
+    0x0f07070700000000, 0x1f0f0f0f1f000000, 0xff1f1f1ffffeffff, 0xff3f3f3ffffeffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303030000000000,
+    0x0707070f00000000, 0x0f0f0f1f1f000000, 0x1f1f1ffffffeffff, 0x3f3f3ffffffeffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303070000000000,
+    0x07070f0f00000000, 0x0f0f1f1f1f000000, 0x1f1ffffffffeffff, 0x3f3ffffffffeffff,
+    0xfffffffffffefcfc, 0xfffffffffffef8f8, 0xfffffffffffef1f1, 0xfffffffffffee3e3,
+    0xfffffffffffec7c7, 0xfffffffffffe8f8f, 0xfffffffffffe1f1f, 0xfffffffffffe3f3f,
+    0x0000000003000000, 0x0000000003000000, 0x0000000307000000, 0x000007070f020200,
+    0x00070f0f1f060700, 0x0f0f1f1f3f0e0f00, 0x1f1f3f3f7f1e1f00, 0xffffffffff3e3f3f,
+    0x0000000000000000, 0x0000000000000000, 0x0000000200000000, 0x0000020602020000,
+    0x0007070f07060000, 0x0f0f0f1f0f0e0000, 0x1f1f1f3f1f1e0000, 0xffffffff3f3e3fff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000020202000000,
+    0x0007070707000000, 0x0f0f0f0f0f000000, 0x1f1f1f1f1f000000, 0xffffff3f3f3effff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000020200000000,
+    0x0007070700000000, 0x0f0f0f0f00000000, 0x1f1f1f1f1f000000, 0xffff3f3f3ffeffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000020000000000,
+    0x0007070000000000, 0x0f0f0f0f00000000, 0x1f1f1f1f1f000000, 0xff3f3f3ffffeffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0002000000000000,
+    0x0007070000000000, 0x0f0f0f0f00000000, 0x1f1f1f1f1f000000, 0x3f3f3ffffffeffff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0202000000000000,
+    0x0707070000000000, 0x0f0f0f0f00000000, 0x1f1f1f1f1f000000, 0x3f3ffffffffeffff,
+    0xfffffffffffdfcfc, 0xfffffffffffdf8f8, 0xfffffffffffdf1f1, 0xfffffffffffde3e3,
+    0xfffffffffffdc7c7, 0xfffffffffffd8f8f, 0xfffffffffffd1f1f, 0xfffffffffffd3f3f,
+    0xfffffffffffcfcfc, 0xfffffffffff8f8f8, 0xfffffffffff1f1f1, 0xffffffffffe1e3e3,
+    0xffffffffffc5c7c7, 0xffffffffff8d8f8f, 0xffffffffff1d1f1f, 0xffffffffff3d3f3f,
+    0x0000000f0c0c0c00, 0x0000000000000000, 0x0000000701010100, 0x00000f0f03010307,
+    0x000f1f1f07050707, 0x1f1f3f3f0f0d0f1f, 0x3f3f7f7f1f1d1f3f, 0xffffffff3f3d3fff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000070303010300,
+    0x000f0f0707050f07, 0x1f1f1f0f0f0d1f1f, 0x3f3f3f1f1f1d3f3f, 0xffffff3f3f3dffff,
+    0x00000c0c0c0c0000, 0x0000000000000000, 0x0000010101010000, 0x0007030303050300,
+    0x000f0707070d0f07, 0x1f1f0f0f0f1d1f1f, 0x3f3f1f1f1f3d3f3f, 0xffff3f3f3ffdffff,
+    0x000c0c0c0e0d0000, 0x0000000005050000, 0x000101010b0d0000, 0x00030303171d0300,
+    0x0f0707072f3d0f07, 0x1f0f0f0f5f7d1f1f, 0x3f1f1f1fbffd3f3f, 0xff3f3f3ffffdffff,
+    0x0c0c0c0f0f0c0000, 0x0000080f0f000000, 0x0101010f0f010000, 0x0303031f1f050300,
+    0x0707073f3f0d0f07, 0x0f0f0f7f7f1d1f1f, 0x1f1f1fffff3d3f3f, 0x3f3f3ffffffdffff,
+    0x0c1c1f1f1f1d0000, 0x00181f1f1f1d0000, 0x01111f1f1f1d0000, 0x03031f1f1f1d0300,
+    0x07073f3f3f3d0f07, 0x0f0f7f7f7f7d1f1f, 0x1f1ffffffffd3f3f, 0x3f3ffffffffdffff,
+    0xfffffffffffdfcfc, 0xfffffffffffdf8f8, 0xfffffffffffdf1f1, 0xfffffffffffde3e3,
+    0xfffffffffffdc7c7, 0xfffffffffffd8f8f, 0xfffffffffffd1f1f, 0xfffffffffffd3f3f,
+    0x0000000007040400, 0x0000000007000000, 0x0000000007010100, 0x000000070f010300,
+    0x00000f0f1f050707, 0x000f1f1f3f0d0f07, 0x1f1f3f3f7f1d1f1f, 0x3f3f7f7fff3d3f3f,
+    0x0000000004040000, 0x0000000000000000, 0x0000000001010000, 0x0000000703010300,
+    0x0000070f07050300, 0x000f0f1f0f0d0f07, 0x1f1f1f3f1f1d1f1f, 0x3f3f3f7f3f3d3f3f,
+    0x0000000004000000, 0x0000000000000000, 0x0000000001000000, 0x0000000303010000,
+    0x0000070707050300, 0x000f0f0f0f0d0f07, 0x1f1f1f1f1f1d1f1f, 0x3f3f3f3f3f3d3f3f,
+    0x0000000404000000, 0x0000000000000000, 0x0000000101000000, 0x0000030303010000,
+    0x0000070707050300, 0x000f0f0f0f0d0f07, 0x1f1f1f1f1f1d1f1f, 0x3f3f3f3f3f3d3f3f,
+    0x0000040405000000, 0x0000000002000000, 0x0000010105000000, 0x000003030b010000,
+    0x0007070717050300, 0x000f0f0f2f0d0f07, 0x1f1f1f1f5f1d1f1f, 0x3f3f3f3fbf3d3f3f,
+    0x0004040704000000, 0x0000000700000000, 0x0001010701000000, 0x0003030f03010000,
+    0x0007071f07050300, 0x0f0f0f3f0f0d0f07, 0x1f1f1f7f1f1d1f1f, 0x3f3f3fffbf3d3f3f,
+    0x04040f0f0f000000, 0x00000f0f0f000000, 0x01010f0f0f000000, 0x03030f0f0f010000,
+    0x07071f1f1f050300, 0x0f0f3f3f3f0d0f07, 0x1f1f7f7f7f1d1f1f, 0x3f3fffffff3d3f3f,
+    0xfffffffffffbfcfc, 0xfffffffffffbf8f8, 0xfffffffffffbf1f1, 0xfffffffffffbe3e3,
+    0xfffffffffffbc7c7, 0xfffffffffffb8f8f, 0xfffffffffffb1f1f, 0xfffffffffffb3f3f,
+    0xfffffffffff8fcfc, 0xfffffffffff8f8f8, 0xfffffffffff1f1f1, 0xffffffffffe3e3e3,
+    0xffffffffffc3c7c7, 0xffffffffff8b8f8f, 0xffffffffff1b1f1f, 0xffffffffff3b3f3f,
+    0x00003f3f3c383c3e, 0x0000001e18181800, 0x0000000000000000, 0x0000000f03030300,
+    0x00001f1f0703070f, 0x001f3f3f0f0b0f0f, 0x3f3f7f7f1f1b1f3f, 0x7f7fffff3f3b3f7f,
+    0x00003e3c3c383c00, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000,
+    0x00000f0707030700, 0x001f1f0f0f0b1f0f, 0x3f3f3f1f1f1b3f3f, 0x7f7f7f3f3f3b7f7f,
+    0x003e3c3c3c3a3c00, 0x0000181818180000, 0x0000000000000000, 0x0000030303030000,
+    0x000f0707070b0700, 0x001f0f0f0f1b1f0f, 0x3f3f1f1f1f3b3f3f, 0x7f7f3f3f3f7b7f7f,
+    0x003c3c3c3e3b3c00, 0x001818181d1b0000, 0x000000000a0a0000, 0x00030303171b0000,
+    0x000707072f3b0700, 0x1f0f0f0f5f7b1f0f, 0x3f1f1f1fbffb3f3f, 0x7f3f3f3f7ffb7f7f,
+    0x3c3c3c3f3f3a3c00, 0x1818181f1f180000, 0x0000111f1f000000, 0x0303031f1f030000,
+    0x0707073f3f0b0700, 0x0f0f0f7f7f1b1f0f, 0x1f1f1fffff3b3f3f, 0x3f3f3fffff7b7f7f,
+    0x3c3c3f3f3f3b3c00, 0x18383f3f3f3b0000, 0x00313f3f3f3b0000, 0x03233f3f3f3b0000,
+    0x07073f3f3f3b0700, 0x0f0f7f7f7f7b1f0f, 0x1f1ffffffffb3f3f, 0x3f3ffffffffb7f7f,
+    0xfffffffffffbfcfc, 0xfffffffffffbf8f8, 0xfffffffffffbf1f1, 0xfffffffffffbe3e3,
+    0xfffffffffffbc7c7, 0xfffffffffffb8f8f, 0xfffffffffffb1f1f, 0xfffffffffffb3f3f,
+    0x0000001e1f181c00, 0x000000000e080800, 0x000000000e000000, 0x000000000e020200,
+    0x0000000f1f030700, 0x00001f1f3f0b0f0f, 0x001f3f3f7f1b1f0f, 0x3f3f7f7fff3b3f3f,
+    0x0000001e1c181c00, 0x0000000008080000, 0x0000000000000000, 0x0000000002020000,
+    0x0000000f07030700, 0x00000f1f0f0b0700, 0x001f1f3f1f1b1f0f, 0x3f3f3f7f3f3b3f3f,
+    0x0000001c1c180000, 0x0000000008000000, 0x0000000000000000, 0x0000000002000000,
+    0x0000000707030000, 0x00000f0f0f0b0700, 0x001f1f1f1f1b1f0f, 0x3f3f3f3f3f3b3f3f,
+    0x00001c1c1c180000, 0x0000000808000000, 0x0000000000000000, 0x0000000202000000,
+    0x0000070707030000, 0x00000f0f0f0b0700, 0x001f1f1f1f1b1f0f, 0x3f3f3f3f3f3b3f3f,
+    0x00001c1c1d180000, 0x000008080a000000, 0x0000000004000000, 0x000002020a000000,
+    0x0000070717030000, 0x000f0f0f2f0b0700, 0x001f1f1f5f1b1f0f, 0x3f3f3f3fbf3b3f3f,
+    0x001c1c1f1c180000, 0x0008080e08000000, 0x0000000e00000000, 0x0002020e02000000,
+    0x0007071f07030000, 0x000f0f3f0f0b0700, 0x1f1f1f7f1f1b1f0f, 0x3f3f3fff3f3b3f3f,
+    0x1c1c1f1f1f180000, 0x08081f1f1f000000, 0x00001f1f1f000000, 0x02021f1f1f000000,
+    0x07071f1f1f030000, 0x0f0f3f3f3f0b0700, 0x1f1f7f7f7f1b1f0f, 0x3f3fffffff3b3f3f,
+    0xfffffffffff7fcfc, 0xfffffffffff7f8f8, 0xfffffffffff7f1f1, 0xfffffffffff7e3e3,
+    0xfffffffffff7c7c7, 0xfffffffffff78f8f, 0xfffffffffff71f1f, 0xfffffffffff73f3f,
+    0xfffffffffff4fcfc, 0xfffffffffff0f8f8, 0xfffffffffff1f1f1, 0xffffffffffe3e3e3,
+    0xffffffffffc7c7c7, 0xffffffffff878f8f, 0xffffffffff171f1f, 0xffffffffff373f3f,
+    0x00fefffffcf4fcfc, 0x00007e7e7870787c, 0x0000003c30303000, 0x0000000000000000,
+    0x0000001e06060600, 0x00003f3f0f070f1f, 0x003f7f7f1f171f1f, 0x7f7fffff3f373f7f,
+    0x00fefefcfcf4fefc, 0x00007c7878707800, 0x0000000000000000, 0x0000000000000000,
+    0x0000000000000000, 0x00001f0f0f070f00, 0x003f3f1f1f173f1f, 0x7f7f7f3f3f377f7f,
+    0x00fefcfcfcf6fefc, 0x007c787878747800, 0x0000303030300000, 0x0000000000000000,
+    0x0000060606060000, 0x001f0f0f0f170f00, 0x003f1f1f1f373f1f, 0x7f7f3f3f3f777f7f,
+    0xfefcfcfcfef7fefc, 0x007878787d777800, 0x003030303a360000, 0x0000000014140000,
+    0x000606062e360000, 0x000f0f0f5f770f00, 0x3f1f1f1fbff73f1f, 0x7f3f3f3f7ff77f7f,
+    0xfcfcfcfffff6fefc, 0x7878787f7f747800, 0x3030303e3e300000, 0x0000223e3e000000,
+    0x0606063e3e060000, 0x0f0f0f7f7f170f00, 0x1f1f1fffff373f1f, 0x3f3f3fffff777f7f,
+    0xfcfcfffffff7fefc, 0x78787f7f7f777800, 0x30717f7f7f770000, 0x00637f7f7f770000,
+    0x06477f7f7f770000, 0x0f0f7f7f7f770f00, 0x1f1ffffffff73f1f, 0x3f3ffffffff77f7f,
+    0xfffffffffff7fcfc, 0xfffffffffff7f8f8, 0xfffffffffff7f1f1, 0xfffffffffff7e3e3,
+    0xfffffffffff7c7c7, 0xfffffffffff78f8f, 0xfffffffffff71f1f, 0xfffffffffff73f3f,
+    0x00007e7e7f747c7c, 0x0000003c3e303800, 0x000000001c101000, 0x000000001c000000,
+    0x000000001c040400, 0x0000001e3e060e00, 0x00003f3f7f171f1f, 0x003f7f7fff373f1f,
+    0x00007c7e7c747800, 0x0000003c38303800, 0x0000000010100000, 0x0000000000000000,
+    0x0000000004040000, 0x0000001e0e060e00, 0x00001f3f1f170f00, 0x003f3f7f3f373f1f,
+    0x00007c7c7c747800, 0x0000003838300000, 0x0000000010000000, 0x0000000000000000,
+    0x0000000004000000, 0x0000000e0e060000, 0x00001f1f1f170f00, 0x003f3f3f3f373f1f,
+    0x00007c7c7c747800, 0x0000383838300000, 0x0000001010000000, 0x0000000000000000,
+    0x0000000404000000, 0x00000e0e0e060000, 0x00001f1f1f170f00, 0x003f3f3f3f373f1f,
+    0x007c7c7c7d747800, 0x000038383a300000, 0x0000101014000000, 0x0000000008000000,
+    0x0000040414000000, 0x00000e0e2e060000, 0x001f1f1f5f170f00, 0x003f3f3fbf373f1f,
+    0x007c7c7f7c747800, 0x0038383e38300000, 0x0010101c10000000, 0x0000001c00000000,
+    0x0004041c04000000, 0x000e0e3e0e060000, 0x001f1f7f1f170f00, 0x3f3f3fff3f373f1f,
+    0x7c7c7f7f7f747800, 0x38383e3e3e300000, 0x10103e3e3e000000, 0x00003e3e3e000000,
+    0x04043e3e3e000000, 0x0e0e3e3e3e060000, 0x1f1f7f7f7f170f00, 0x3f3fffffff373f1f,
+    0xfffffffffffffcfc, 0xfffffffffffff8f8, 0xfffffffffffff0f1, 0xffffffffffffe2e3,
+    0xffffffffffffc6c7, 0xffffffffffff8e8f, 0xffffffffffff1e1f, 0xffffffffffff3e3f,
+    0x0000000000000000, 0xfffffffffff8f8f8, 0xfffffffffff1f0f1, 0xffffffffffe3e2e3,
+    0xffffffffffc7c6c7, 0xffffffffff8f8e8f, 0xffffffffff1f1e1f, 0xffffffffff3f3e3f,
+    0x0000000000000000, 0x0000000000000000, 0x00000f0f01000000, 0x00071f1f03030200,
+    0x0f0f3f3f07070600, 0x1f1f7f7f0f0f0e00, 0xffffffff1f1f1eff, 0xffffffff3f3f3eff,
+    0x0000000000000000, 0x0000000000000000, 0x0000020000000000, 0x0007070303030000,
+    0x0f0f0f0707070000, 0x1f1f1f0f0f0f0000, 0xffffff1f1f1ffeff, 0xffffff3f3f3ffeff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0007030303000000,
+    0x0f0f070707000000, 0x1f1f0f0f0f000000, 0xffff1f1f1ffffeff, 0xffff3f3f3ffffeff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0003030300000000,
+    0x0f07070700000000, 0x1f0f0f0f1f000000, 0xff1f1f1ffffffeff, 0xff3f3f3ffffffeff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303030000000000,
+    0x0707070f00000000, 0x0f0f0f1f1f000000, 0x1f1f1ffffffffeff, 0x3f3f3ffffffffeff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0303070000000000,
+    0x07070f0f00000000, 0x0f0f1f1f1f000000, 0x1f1ffffffffffeff, 0x3f3ffffffffffeff,
+    0x0000000000030000, 0x0000000000030000, 0xfffffffffffff0f1, 0xffffffffffffe2e3,
+    0xffffffffffffc6c7, 0xffffffffffff8e8f, 0xffffffffffff1e1f, 0xffffffffffff3e3f,
+    0x0000000000000000, 0x0000000000000000, 0x00000f0f0f000000, 0x00000f0f0f020200,
+    0x00071f1f1f070600, 0x0f0f3f3f3f0f0e00, 0x1f1f7f7f7f1f1e00, 0xffffffffff3f3e3f,
+    0x0000000000000000, 0x0000000000000000, 0x0000000600000000, 0x0000020e02020000,
+    0x0007071f07070000, 0x0f0f0f3f0f0f0000, 0x1f1f1f7f1f1f0000, 0xffffffff3f3f3eff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000020202000000,
+    0x0007070707000000, 0x0f0f0f0f0f000000, 0x1f1f1f1f1f000000, 0xffffff3f3f3ffeff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000020200000000,
+    0x0007070700000000, 0x0f0f0f0f00000000, 0x1f1f1f1f1f000000, 0xffff3f3f3ffffeff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0000020000000000,
+    0x0007070000000000, 0x0f0f0f0f00000000, 0x1f1f1f1f1f000000, 0xff3f3f3ffffffeff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0002000000000000,
+    0x0007070000000000, 0x0f0f0f0f00000000, 0x1f1f1f1f1f000000, 0x3f3f3ffffffffeff,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x0202000000000000,
+    0x0707070000000000, 0x0f0f0f0f00000000, 0x1f1f1f1f1f000000, 0x3f3ffffffffffeff,
+    0xfffffffffffffcfc, 0xfffffffffffff8f8, 0xfffffffffffff1f1, 0xffffffffffffe1e3,
+    0xffffffffffffc5c7, 0xffffffffffff8d8f, 0xffffffffffff1d1f, 0xffffffffffff3d3f,
+    0xfffffffffffcfcfc, 0x0000000000000000, 0xfffffffffff1f1f1, 0xffffffffffe3e1e3,
+    0xffffffffffc7c5c7, 0xffffffffff8f8d8f, 0xffffffffff1f1d1f, 0xffffffffff3f3d3f,
+    0x0000000000000000, 0x0000000000000000, 0x0000000000000000, 0x00001f1f03030103,
+    0x000f3f3f0707050f, 0x1f1f7f7f0f0f0d1f, 0x3f3fffff1f1f1d3f, 0xffffffff3f3f3dff,
+    0x0000000c0c0c0c00, 0x0000000000000000, 0x0000000101010100, 0x0000070303030503,
+    0x000f0f0707070d0f, 0x1f1f1f0f0f0f1d1f, 0x3f3f3f1f1f1f3d3f, 0xffffff3f3f3ffdff,
+    0x00000c0c0c0e0d00, 0x0000000000050500, 0x00000101010b0d00, 0x0000030303171d03,
+    0x000f0707072f3d0f, 0x1f1f0f0f0f5f7d1f, 0x3f3f1f1f1fbffd3f, 0xffff3f3f3ffffdff,
+    0x000c0c0c0f0f0c00, 0x000000080f0f0000, 0x000101010f0f0100, 0x000303031f1f0503,
+    0x000707073f3f0d0f, 0x1f0f0f0f7f7f1d1f, 0x3f1f1f1fffff3d3f, 0xff3f3f3ffffffdff,
+    0x0c0c1c1f1f1f1d00, 0x0000181f1f1f1d00, 0x0101111f1f1f1d00, 0x0303031f1f1f1d03,
+    0x0707073f3f3f3d0f, 0x0f0f0f7f7f7f7d1f, 0x1f1f1ffffffffd3f, 0x3f3f3ffffffffdff,
+    0x0c3c3f3f3f3f3d3f, 0x00383f3f3f3f3d3f, 0x01313f3f3f3f3d3f, 0x03233f3f3f3f3d3f,
+    0x07073f3f3f3f3d3f, 0x0f0f7f7f7f7f7d7f, 0x1f1ffffffffffdff, 0x3f3ffffffffffdff,
+    0x0000000000070404, 0x0000000000070000, 0x0000000000070101, 0xffffffffffffe1e3,
+    0xffffffffffffc5c7, 0xffffffffffff8d8f, 0xffffffffffff1d1f, 0xffffffffffff3d3f,
+    0x0000000000040400, 0x0000000000000000, 0x0000000000010100, 0x00001f1f1f030103,
+    0x00001f1f1f070503, 0x000f3f3f3f0f0d0f, 0x1f1f7f7f7f1f1d1f, 0x3f3fffffff3f3d3f,
+    0x0000000000040000, 0x0000000000000000, 0x0000000000010000, 0x0000000f03030100,
+    0x0000071f07070503, 0x000f0f3f0f0f0d0f, 0x1f1f1f7f1f1f1d1f, 0x3f3f3fff3f3f3d3f,
+    0x0000000004040000, 0x0000000000000000, 0x0000000001010000, 0x0000000303030100,
+    0x0000070707070503, 0x000f0f0f0f0f0d0f, 0x1f1f1f1f1f1f1d1f, 0x3f3f3f3f3f3f3d3f,
+    0x0000000404050000, 0x0000000000020000, 0x0000000101050000, 0x00000003030b0100,
+    0x0000070707170503, 0x000f0f0f0f2f0d0f, 0x1f1f1f1f1f5f1d1f, 0x3f3f3f3f3fbf3d3f,
+    0x0000040407040000, 0x0000000007000000, 0x0000010107010000, 0x000003030f030100,
+    0x000007071f070503, 0x000f0f0f3f0f0d0f, 0x1f1f1f1f7f1f1d1f, 0x3f3f3f3fffbf3d3f,
+    0x0004040f0f0f0000, 0x0000000f0f0f0000, 0x0001010f0f0f0000, 0x0003030f0f0f0100,
+    0x0007071f1f1f0503, 0x000f0f3f3f3f0d0f, 0x1f1f1f7f7f7f1d1f, 0x3f3f3fffffff3d3f,
+    0x04041f1f1f1f1d00, 0x00001f1f1f1f1d00, 0x01011f1f1f1f1d00, 0x03031f1f1f1f1d00,
+    0x07071f1f1f1f1d03, 0x0f0f3f3f3f3f3d0f, 0x1f1f7f7f7f7f7d1f, 0x3f3ffffffffffd3f,
+    0xfffffffffffff8fc, 0xfffffffffffff8f8, 0xfffffffffffff1f1, 0xffffffffffffe3e3,
+    0xffffffffffffc3c7, 0xffffffffffff8b8f, 0xffffffffffff1b1f, 0xffffffffffff3b3f,
+    0xfffffffffffcf8fc, 0xfffffffffff8f8f8, 0x0000000000000000, 0xffffffffffe3e3e3,
+    0xffffffffffc7c3c7, 0xffffffffff8f8b8f, 0xffffffffff1f1b1f, 0xffffffffff3f3b3f,
+    0x00003f3f3c3c383c, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000,
+    0x00003f3f07070307, 0x001f7f7f0f0f0b1f, 0x3f3fffff1f1f1b3f, 0x7f7fffff3f3f3b7f,
+    0x00003e3c3c3c3a3c, 0x0000001818181800, 0x0000000000000000, 0x0000000303030300,
+    0x00000f0707070b07, 0x001f1f0f0f0f1b1f, 0x3f3f3f1f1f1f3b3f, 0x7f7f7f3f3f3f7b7f,
+    0x00003c3c3c3e3b3c, 0x00001818181d1b00, 0x00000000000a0a00, 0x0000030303171b00,
+    0x00000707072f3b07, 0x001f0f0f0f5f7b1f, 0x3f3f1f1f1fbffb3f, 0x7f7f3f3f3f7ffb7f,
+    0x003c3c3c3f3f3a3c, 0x001818181f1f1800, 0x000000111f1f0000, 0x000303031f1f0300,
+    0x000707073f3f0b07, 0x000f0f0f7f7f1b1f, 0x3f1f1f1fffff3b3f, 0x7f3f3f3fffff7b7f,
+    0x3c3c3c3f3f3f3b3c, 0x1818383f3f3f3b00, 0x0000313f3f3f3b00, 0x0303233f3f3f3b00,
+    0x0707073f3f3f3b07, 0x0f0f0f7f7f7f7b1f, 0x1f1f1ffffffffb3f, 0x3f3f3ffffffffb7f,
+    0x3c7c7f7f7f7f7b7f, 0x18787f7f7f7f7b7f, 0x00717f7f7f7f7b7f, 0x03637f7f7f7f7b7f,
+    0x07477f7f7f7f7b7f, 0x0f0f7f7f7f7f7b7f, 0x1f1ffffffffffbff, 0x3f3ffffffffffbff,
+    0xfffffffffffff8fc, 0x00000000000e0808, 0x00000000000e0000, 0x00000000000e0202,
+    0xffffffffffffc3c7, 0xffffffffffff8b8f, 0xffffffffffff1b1f, 0xffffffffffff3b3f,
+    0x00003f3f3f3c383c, 0x0000000000080800, 0x0000000000000000, 0x0000000000020200,
+    0x00003f3f3f070307, 0x00003f3f3f0f0b07, 0x001f7f7f7f1f1b1f, 0x3f3fffffff3f3b3f,
+    0x0000001f1c1c1800, 0x0000000000080000, 0x0000000000000000, 0x0000000000020000,
+    0x0000001f07070300, 0x00000f3f0f0f0b07, 0x001f1f7f1f1f1b1f, 0x3f3f3fff3f3f3b3f,
+    0x0000001c1c1c1800, 0x0000000008080000, 0x0000000000000000, 0x0000000002020000,
+    0x0000000707070300, 0x00000f0f0f0f0b07, 0x001f1f1f1f1f1b1f, 0x3f3f3f3f3f3f3b3f,
+    0x0000001c1c1d1800, 0x00000008080a0000, 0x0000000000040000, 0x00000002020a0000,
+    0x0000000707170300, 0x00000f0f0f2f0b07, 0x001f1f1f1f5f1b1f, 0x3f3f3f3f3fbf3b3f,
+    0x00001c1c1f1c1800, 0x000008080e080000, 0x000000000e000000, 0x000002020e020000,
+    0x000007071f070300, 0x00000f0f3f0f0b07, 0x001f1f1f7f1f1b1f, 0x3f3f3f3fff3f3b3f,
+    0x001c1c1f1f1f1800, 0x0008081f1f1f0000, 0x0000001f1f1f0000, 0x0002021f1f1f0000,
+    0x0007071f1f1f0300, 0x000f0f3f3f3f0b07, 0x001f1f7f7f7f1b1f, 0x3f3f3fffffff3b3f,
+    0x1c1c3f3f3f3f3b00, 0x08083f3f3f3f3b00, 0x00003f3f3f3f3b00, 0x02023f3f3f3f3b00,
+    0x07073f3f3f3f3b00, 0x0f0f3f3f3f3f3b07, 0x1f1f7f7f7f7f7b1f, 0x3f3ffffffffffb3f,
+    0xfffffffffffff4fc, 0xfffffffffffff0f8, 0xfffffffffffff1f1, 0xffffffffffffe3e3,
+    0xffffffffffffc7c7, 0xffffffffffff878f, 0xffffffffffff171f, 0xffffffffffff373f,
+    0xfffffffffffcf4fc, 0xfffffffffff8f0f8, 0xfffffffffff1f1f1, 0x0000000000000000,
+    0xffffffffffc7c7c7, 0xffffffffff8f878f, 0xffffffffff1f171f, 0xffffffffff3f373f,
+    0x00fefffffcfcf4fe, 0x00007f7f78787078, 0x0000000000000000, 0x0000000000000000,
+    0x0000000000000000, 0x00007f7f0f0f070f, 0x003fffff1f1f173f, 0x7f7fffff3f3f377f,
+    0x00fefefcfcfcf6fe, 0x00007c7878787478, 0x0000003030303000, 0x0000000000000000,
+    0x0000000606060600, 0x00001f0f0f0f170f, 0x003f3f1f1f1f373f, 0x7f7f7f3f3f3f777f,
+    0x00fefcfcfcfef7fe, 0x00007878787d7778, 0x00003030303a3600, 0x0000000000141400,
+    0x00000606062e3600, 0x00000f0f0f5f770f, 0x003f1f1f1fbff73f, 0x7f7f3f3f3f7ff77f,
+    0x00fcfcfcfffff6fe, 0x007878787f7f7478, 0x003030303e3e3000, 0x000000223e3e0000,
+    0x000606063e3e0600, 0x000f0f0f7f7f170f, 0x001f1f1fffff373f, 0x7f3f3f3fffff777f,
+    0xfcfcfcfffffff7fe, 0x7878787f7f7f7778, 0x3030717f7f7f7700, 0x0000637f7f7f7700,
+    0x0606477f7f7f7700, 0x0f0f0f7f7f7f770f, 0x1f1f1ffffffff73f, 0x3f3f3ffffffff77f,
+    0xfcfcfffffffff7ff, 0x78f8fffffffff7ff, 0x30f1fffffffff7ff, 0x00e3fffffffff7ff,
+    0x06c7fffffffff7ff, 0x0f8ffffffffff7ff, 0x1f1ffffffffff7ff, 0x3f3ffffffffff7ff,
+    0xfffffffffffff4fc, 0xfffffffffffff0f8, 0x00000000001c1010, 0x00000000001c0000,
+    0x00000000001c0404, 0xffffffffffff878f, 0xffffffffffff171f, 0xffffffffffff373f,
+    0x00007f7f7f7c7478, 0x00007f7f7f787078, 0x0000000000101000, 0x0000000000000000,
+    0x0000000000040400, 0x00007f7f7f0f070f, 0x00007f7f7f1f170f, 0x003fffffff3f373f,
+    0x00007c7f7c7c7478, 0x0000003e38383000, 0x0000000000100000, 0x0000000000000000,
+    0x0000000000040000, 0x0000003e0e0e0600, 0x00001f7f1f1f170f, 0x003f3fff3f3f373f,
+    0x00007c7c7c7c7478, 0x0000003838383000, 0x0000000010100000, 0x0000000000000000,
+    0x0000000004040000, 0x0000000e0e0e0600, 0x00001f1f1f1f170f, 0x003f3f3f3f3f373f,
+    0x00007c7c7c7d7478, 0x00000038383a3000, 0x0000001010140000, 0x0000000000080000,
+    0x0000000404140000, 0x0000000e0e2e0600, 0x00001f1f1f5f170f, 0x003f3f3f3fbf373f,
+    0x00007c7c7f7c7478, 0x000038383e383000, 0x000010101c100000, 0x000000001c000000,
+    0x000004041c040000, 0x00000e0e3e0e0600, 0x00001f1f7f1f170f, 0x003f3f3fff3f373f,
+    0x007c7c7f7f7f7478, 0x0038383e3e3e3000, 0x0010103e3e3e0000, 0x0000003e3e3e0000,
+    0x0004043e3e3e0000, 0x000e0e3e3e3e0600, 0x001f1f7f7f7f170f, 0x003f3fffffff373f,
+    0x7c7c7f7f7f7f7778, 0x38387f7f7f7f7700, 0x10107f7f7f7f7700, 0x00007f7f7f7f7700,
+    0x04047f7f7f7f7700, 0x0e0e7f7f7f7f7700, 0x1f1f7f7f7f7f770f, 0x3f3ffffffffff73f,
+];
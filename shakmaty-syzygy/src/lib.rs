@@ -34,6 +34,22 @@
 //!
 //! * `mmap`: Enables support for memory-mapped tablebase files
 //!   via `Tablebase::with_mmap_filesystem()`.
+//! * `checksum`: Enables verifying tables against published MD5 checksums
+//!   via `Tablebase::verify_checksums()`.
+//! * `http`: Enables reading tables from a remote mirror via
+//!   `filesystem::HttpFilesystem`.
+//! * `lichess`: Implies `http`. Enables a client for the Lichess tablebase
+//!   API via `lichess::LichessTablebase`.
+//! * `rayon`: Enables parallel batch probing via
+//!   `Tablebase::par_probe_wdl_many()`.
+//! * `tokio`: Enables methods like `Tablebase::probe_wdl_spawn_blocking()`
+//!   that run blocking probes on the blocking thread pool of the Tokio
+//!   runtime, for convenient use from async code. File access itself stays
+//!   synchronous.
+//! * `generator`: Enables generating small WDL tables for testing via
+//!   `generator::GeneratedFilesystem`.
+//! * `zstd`: Enables reading tables from Zstandard seekable containers
+//!   via `filesystem::ZstdFilesystem`.
 //! * `variant`: Enables support for Antichess and Atomic chess.
 
 #![warn(missing_debug_implementations)]
@@ -43,14 +59,21 @@
 #[macro_use]
 mod errors;
 pub mod filesystem;
+#[cfg(feature = "generator")]
+pub mod generator;
+pub mod kpk;
+#[cfg(feature = "lichess")]
+pub mod lichess;
 mod material;
+mod stats;
 mod table;
 mod tablebase;
 mod types;
 
 pub use crate::{
     errors::{ProbeError, SyzygyError},
-    material::Material,
-    tablebase::Tablebase,
-    types::{AmbiguousWdl, Dtz, MaybeRounded, Metric, Syzygy, TableType, Wdl},
+    material::{Material, ParseMaterialError},
+    stats::Stats,
+    tablebase::{TableInfo, Tablebase},
+    types::{AmbiguousWdl, Dtz, MaybeRounded, Metric, RootMove, Syzygy, TableType, Wdl},
 };
@@ -0,0 +1,365 @@
+//! Client for the [Lichess tablebase API](https://tablebase.lichess.ovh).
+//!
+//! Results are converted to the same types as local probes, so that
+//! applications can fall back to the remote service for positions that are
+//! not covered by local tables.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use shakmaty::{CastlingMode, Chess, fen::Fen};
+//! use shakmaty_syzygy::{AmbiguousWdl, lichess::LichessTablebase};
+//!
+//! let pos: Chess = "4k3/8/8/8/8/8/8/4K2R w K - 0 1"
+//!     .parse::<Fen>()?
+//!     .into_position(CastlingMode::Standard)?;
+//!
+//! let tables = LichessTablebase::new();
+//! let response = tables.probe(&pos)?;
+//! assert_eq!(response.info.wdl, Some(AmbiguousWdl::Win));
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use std::{fmt, io};
+
+use serde::Deserialize;
+use shakmaty::{Chess, EnPassantMode, Position, fen::Fen, san::SanPlus, uci::UciMove};
+use ureq::Agent;
+
+use crate::{AmbiguousWdl, Dtz, MaybeRounded, Syzygy};
+
+/// A chess variant supported by the Lichess tablebase API.
+pub trait LichessVariant: Syzygy + Position {
+    /// Path of the API endpoint, e.g., `standard`.
+    const ENDPOINT: &'static str;
+}
+
+impl LichessVariant for Chess {
+    const ENDPOINT: &'static str = "standard";
+}
+
+#[cfg(feature = "variant")]
+impl LichessVariant for shakmaty::variant::Atomic {
+    const ENDPOINT: &'static str = "atomic";
+}
+
+#[cfg(feature = "variant")]
+impl LichessVariant for shakmaty::variant::Antichess {
+    const ENDPOINT: &'static str = "antichess";
+}
+
+/// Tablebase information about a position or move.
+#[derive(Debug, Clone)]
+pub struct TablebaseInfo {
+    /// WDL<sub>50</sub>, or `None` if the position is not covered (or the
+    /// category reported by the API is not known to this client).
+    pub wdl: Option<AmbiguousWdl>,
+    /// DTZ<sub>50</sub>′′ with rounding.
+    pub dtz: Option<MaybeRounded<Dtz>>,
+    /// Depth to mate in plies, where available.
+    pub dtm: Option<i32>,
+    /// The game is over by checkmate.
+    pub checkmate: bool,
+    /// The game is over by stalemate.
+    pub stalemate: bool,
+    /// The game is over by a variant specific rule, and the side to move
+    /// won.
+    pub variant_win: bool,
+    /// The game is over by a variant specific rule, and the side to move
+    /// lost.
+    pub variant_loss: bool,
+    /// Neither side has sufficient material to win.
+    pub insufficient_material: bool,
+}
+
+/// A legal move with tablebase information about the position after the
+/// move, from the point of view of the opponent.
+#[derive(Debug, Clone)]
+pub struct TablebaseMove {
+    /// The move in UCI notation.
+    pub uci: UciMove,
+    /// The move in SAN notation.
+    pub san: SanPlus,
+    /// Whether the move resets the halfmove clock.
+    pub zeroing: bool,
+    /// Information about the position after the move.
+    pub info: TablebaseInfo,
+}
+
+/// Response of the Lichess tablebase API.
+#[derive(Debug, Clone)]
+pub struct TablebaseResponse {
+    /// Information about the position, from the point of view of the side
+    /// to move.
+    pub info: TablebaseInfo,
+    /// All legal moves, sorted from best to worst.
+    pub moves: Vec<TablebaseMove>,
+}
+
+#[derive(Deserialize)]
+struct RawInfo {
+    category: String,
+    dtz: Option<i32>,
+    precise_dtz: Option<i32>,
+    dtm: Option<i32>,
+    #[serde(default)]
+    checkmate: bool,
+    #[serde(default)]
+    stalemate: bool,
+    #[serde(default)]
+    variant_win: bool,
+    #[serde(default)]
+    variant_loss: bool,
+    #[serde(default)]
+    insufficient_material: bool,
+}
+
+#[derive(Deserialize)]
+struct RawMove {
+    uci: String,
+    san: String,
+    #[serde(default)]
+    zeroing: bool,
+    #[serde(flatten)]
+    info: RawInfo,
+}
+
+#[derive(Deserialize)]
+struct RawResponse {
+    #[serde(flatten)]
+    info: RawInfo,
+    #[serde(default)]
+    moves: Vec<RawMove>,
+}
+
+fn invalid_data<E: fmt::Display>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+impl RawInfo {
+    fn convert(self) -> TablebaseInfo {
+        TablebaseInfo {
+            // Categories that are unknown or new to this client are treated
+            // like positions that are not covered.
+            wdl: match self.category.as_str() {
+                "win" | "syzygy-win" => Some(AmbiguousWdl::Win),
+                "maybe-win" => Some(AmbiguousWdl::MaybeWin),
+                "cursed-win" => Some(AmbiguousWdl::CursedWin),
+                "draw" => Some(AmbiguousWdl::Draw),
+                "blessed-loss" => Some(AmbiguousWdl::BlessedLoss),
+                "maybe-loss" => Some(AmbiguousWdl::MaybeLoss),
+                "loss" | "syzygy-loss" => Some(AmbiguousWdl::Loss),
+                _ => None,
+            },
+            dtz: match (self.precise_dtz, self.dtz) {
+                (Some(dtz), _) => Some(MaybeRounded::Precise(Dtz(dtz))),
+                (None, Some(dtz)) => Some(MaybeRounded::Rounded(Dtz(dtz))),
+                (None, None) => None,
+            },
+            dtm: self.dtm,
+            checkmate: self.checkmate,
+            stalemate: self.stalemate,
+            variant_win: self.variant_win,
+            variant_loss: self.variant_loss,
+            insufficient_material: self.insufficient_material,
+        }
+    }
+}
+
+impl TablebaseResponse {
+    /// Parses a JSON response body.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`io::ErrorKind::InvalidData`] if the body is not a valid
+    /// response.
+    pub fn from_json(body: &str) -> io::Result<TablebaseResponse> {
+        let raw: RawResponse = serde_json::from_str(body).map_err(invalid_data)?;
+        Ok(TablebaseResponse {
+            info: raw.info.convert(),
+            moves: raw
+                .moves
+                .into_iter()
+                .map(|m| {
+                    Ok(TablebaseMove {
+                        uci: m.uci.parse().map_err(invalid_data)?,
+                        san: m.san.parse().map_err(invalid_data)?,
+                        zeroing: m.zeroing,
+                        info: m.info.convert(),
+                    })
+                })
+                .collect::<io::Result<_>>()?,
+        })
+    }
+}
+
+/// A client for the Lichess tablebase API.
+pub struct LichessTablebase {
+    agent: Agent,
+    base_url: String,
+}
+
+impl fmt::Debug for LichessTablebase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LichessTablebase")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for LichessTablebase {
+    fn default() -> LichessTablebase {
+        LichessTablebase::new()
+    }
+}
+
+impl LichessTablebase {
+    /// Creates a client for `https://tablebase.lichess.ovh`.
+    pub fn new() -> LichessTablebase {
+        LichessTablebase::with_agent(Agent::new_with_defaults(), "https://tablebase.lichess.ovh")
+    }
+
+    /// Creates a client for a custom instance, using a custom configured
+    /// HTTP agent.
+    pub fn with_agent(agent: Agent, base_url: impl Into<String>) -> LichessTablebase {
+        let mut base_url = base_url.into();
+        while base_url.ends_with('/') {
+            base_url.pop();
+        }
+        LichessTablebase { agent, base_url }
+    }
+
+    /// Requests information about a position and all its legal moves.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the request fails, or with [`io::ErrorKind::InvalidData`]
+    /// if the response can not be parsed.
+    pub fn probe<S: LichessVariant>(&self, pos: &S) -> io::Result<TablebaseResponse> {
+        let fen = Fen::from_position(pos, EnPassantMode::Legal).to_string();
+        let body = self
+            .agent
+            .get(&format!("{}/{}", self.base_url, S::ENDPOINT))
+            .query("fen", &fen)
+            .call()
+            .map_err(ureq::Error::into_io)?
+            .body_mut()
+            .read_to_string()
+            .map_err(ureq::Error::into_io)?;
+        TablebaseResponse::from_json(&body)
+    }
+
+    /// Requests the WDL<sub>50</sub> of a position. Returns `None` if the
+    /// position is not covered.
+    ///
+    /// # Errors
+    ///
+    /// See [`LichessTablebase::probe()`].
+    pub fn probe_wdl<S: LichessVariant>(&self, pos: &S) -> io::Result<Option<AmbiguousWdl>> {
+        Ok(self.probe(pos)?.info.wdl)
+    }
+
+    /// Requests the DTZ<sub>50</sub>′′ of a position. Returns `None` if the
+    /// position is not covered.
+    ///
+    /// # Errors
+    ///
+    /// See [`LichessTablebase::probe()`].
+    pub fn probe_dtz<S: LichessVariant>(&self, pos: &S) -> io::Result<Option<MaybeRounded<Dtz>>> {
+        Ok(self.probe(pos)?.info.dtz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json() {
+        let body = r#"{
+            "checkmate": false,
+            "stalemate": false,
+            "variant_win": false,
+            "variant_loss": false,
+            "insufficient_material": false,
+            "dtz": 1,
+            "precise_dtz": 1,
+            "dtm": 17,
+            "dtw": null,
+            "dtc": null,
+            "category": "win",
+            "moves": [
+                {
+                    "uci": "h7h8q",
+                    "san": "h8=Q+",
+                    "zeroing": true,
+                    "conversion": false,
+                    "checkmate": false,
+                    "stalemate": false,
+                    "variant_win": false,
+                    "variant_loss": false,
+                    "insufficient_material": false,
+                    "dtz": -2,
+                    "precise_dtz": null,
+                    "dtm": -16,
+                    "category": "loss"
+                }
+            ]
+        }"#;
+        let response = TablebaseResponse::from_json(body).unwrap();
+        assert_eq!(response.info.wdl, Some(AmbiguousWdl::Win));
+        assert!(matches!(
+            response.info.dtz,
+            Some(MaybeRounded::Precise(Dtz(1)))
+        ));
+        assert_eq!(response.info.dtm, Some(17));
+        assert_eq!(response.moves.len(), 1);
+
+        let m = &response.moves[0];
+        assert_eq!(m.uci.to_string(), "h7h8q");
+        assert_eq!(m.san.to_string(), "h8=Q+");
+        assert!(m.zeroing);
+        assert_eq!(m.info.wdl, Some(AmbiguousWdl::Loss));
+        assert!(matches!(m.info.dtz, Some(MaybeRounded::Rounded(Dtz(-2)))));
+    }
+
+    #[test]
+    fn test_from_json_unknown() {
+        let response = TablebaseResponse::from_json(
+            r#"{"category": "unknown", "dtz": null, "precise_dtz": null, "dtm": null, "moves": []}"#,
+        )
+        .unwrap();
+        assert_eq!(response.info.wdl, None);
+        assert!(response.info.dtz.is_none());
+
+        let response = TablebaseResponse::from_json(
+            r#"{"category": "bogus", "dtz": null, "precise_dtz": null, "dtm": null}"#,
+        )
+        .unwrap();
+        assert_eq!(response.info.wdl, None);
+    }
+
+    #[test]
+    fn test_from_json_syzygy_categories() {
+        let body = r#"{
+            "dtz": 3,
+            "precise_dtz": 3,
+            "dtm": null,
+            "category": "syzygy-win",
+            "moves": [
+                {
+                    "uci": "a1a2",
+                    "san": "Ra2",
+                    "zeroing": false,
+                    "dtz": -2,
+                    "precise_dtz": -2,
+                    "dtm": null,
+                    "category": "syzygy-loss"
+                }
+            ]
+        }"#;
+        let response = TablebaseResponse::from_json(body).unwrap();
+        assert_eq!(response.info.wdl, Some(AmbiguousWdl::Win));
+        assert_eq!(response.moves[0].info.wdl, Some(AmbiguousWdl::Loss));
+    }
+}
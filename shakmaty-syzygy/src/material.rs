@@ -1,6 +1,18 @@
-use std::{cmp::Ordering, fmt};
+use std::{cmp::Ordering, error::Error, fmt, str::FromStr};
 
-use shakmaty::{Board, ByColor, ByRole, Piece, Role};
+use shakmaty::{Board, ByColor, ByRole, Color, Piece, Role};
+
+/// Error when parsing an invalid material key.
+#[derive(Clone, Debug)]
+pub struct ParseMaterialError;
+
+impl fmt::Display for ParseMaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid material key")
+    }
+}
+
+impl Error for ParseMaterialError {}
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub(crate) struct MaterialSide {
@@ -14,10 +26,10 @@ impl MaterialSide {
         }
     }
 
-    fn from_str_part(s: &str) -> Result<MaterialSide, ()> {
+    fn from_str_part(s: &str) -> Result<MaterialSide, ParseMaterialError> {
         let mut side = MaterialSide::empty();
         for ch in s.as_bytes() {
-            let role = Role::from_char(char::from(*ch)).ok_or(())?;
+            let role = Role::from_char(char::from(*ch)).ok_or(ParseMaterialError)?;
             *side.by_role.get_mut(role) += 1;
         }
         Ok(side)
@@ -74,7 +86,28 @@ impl fmt::Debug for MaterialSide {
     }
 }
 
-/// A material key.
+/// A material key, like `KRPvKR`.
+///
+/// Lists the pieces of white, followed by the pieces of black.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Board, Color};
+/// use shakmaty_syzygy::Material;
+///
+/// let material: Material = "KRvKRP".parse()?;
+/// assert_eq!(material.count(), 5);
+/// assert_eq!(material.by_role(Color::Black).pawn, 1);
+/// assert_eq!(material.normalized().to_string(), "KRPvKR");
+///
+/// let board = Board::new();
+/// assert_eq!(
+///     Material::from_board(&board).to_string(),
+///     "KQRRBBNNPPPPPPPPvKQRRBBNNPPPPPPPP"
+/// );
+/// # Ok::<_, shakmaty_syzygy::ParseMaterialError>(())
+/// ```
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Material {
     pub(crate) by_color: ByColor<MaterialSide>,
@@ -111,32 +144,33 @@ impl Material {
         material
     }
 
-    pub(crate) fn from_str(s: &str) -> Result<Material, ()> {
-        if s.len() > 64 + 1 {
-            return Err(());
-        }
-
-        let (white, black) = s.split_once('v').ok_or(())?;
-        Ok(Material {
-            by_color: ByColor {
-                white: MaterialSide::from_str_part(white)?,
-                black: MaterialSide::from_str_part(black)?,
-            },
-        })
+    /// Number of pieces of each role for `color`.
+    pub fn by_role(&self, color: Color) -> ByRole<u8> {
+        self.by_color.get(color).by_role
     }
 
-    pub(crate) fn count(&self) -> usize {
+    /// Total number of pieces.
+    pub fn count(&self) -> usize {
         self.by_color.iter().map(|side| side.count()).sum()
     }
 
-    pub(crate) fn is_symmetric(&self) -> bool {
+    /// Tests if both sides have the same pieces.
+    pub fn is_symmetric(&self) -> bool {
         self.by_color.white == self.by_color.black
     }
 
-    pub(crate) fn has_pawns(&self) -> bool {
+    /// Tests if there are any pawns.
+    pub fn has_pawns(&self) -> bool {
         self.by_color.iter().any(|side| side.has_pawns())
     }
 
+    /// Swaps the sides, such that the stronger side is listed first, as in
+    /// the names of table files.
+    #[must_use]
+    pub fn normalized(&self) -> Material {
+        self.to_normalized().0
+    }
+
     pub(crate) fn unique_pieces(&self) -> usize {
         self.by_color.iter().map(|side| side.unique_roles()).sum()
     }
@@ -158,6 +192,45 @@ impl Material {
         }
     }
 
+    /// Material configurations reachable by a single capture or promotion.
+    pub(crate) fn successors(&self, one_king: bool) -> Vec<Material> {
+        let mut successors = Vec::new();
+        for color in Color::ALL {
+            for role in Role::ALL {
+                if *self.by_color.get(color).by_role.get(role) == 0
+                    || (one_king && role == Role::King)
+                {
+                    continue;
+                }
+
+                let mut captured = self.clone();
+                *captured.by_color.get_mut(color).by_role.get_mut(role) -= 1;
+
+                if role == Role::Pawn {
+                    let promotions: &[Role] = if one_king {
+                        &[Role::Queen, Role::Rook, Role::Bishop, Role::Knight]
+                    } else {
+                        &[
+                            Role::Queen,
+                            Role::Rook,
+                            Role::Bishop,
+                            Role::Knight,
+                            Role::King,
+                        ]
+                    };
+                    for &promotion in promotions {
+                        let mut promoted = captured.clone();
+                        *promoted.by_color.get_mut(color).by_role.get_mut(promotion) += 1;
+                        successors.push(promoted);
+                    }
+                }
+
+                successors.push(captured);
+            }
+        }
+        successors
+    }
+
     pub(crate) fn to_normalized(&self) -> NormalizedMaterial {
         NormalizedMaterial(Material {
             by_color: self.by_color.clone().into_normalized(),
@@ -165,6 +238,24 @@ impl Material {
     }
 }
 
+impl FromStr for Material {
+    type Err = ParseMaterialError;
+
+    fn from_str(s: &str) -> Result<Material, ParseMaterialError> {
+        if s.len() > 64 + 1 {
+            return Err(ParseMaterialError);
+        }
+
+        let (white, black) = s.split_once('v').ok_or(ParseMaterialError)?;
+        Ok(Material {
+            by_color: ByColor {
+                white: MaterialSide::from_str_part(white)?,
+                black: MaterialSide::from_str_part(black)?,
+            },
+        })
+    }
+}
+
 impl fmt::Display for Material {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}v{}", self.by_color.white, self.by_color.black)
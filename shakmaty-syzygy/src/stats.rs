@@ -0,0 +1,97 @@
+use std::{
+    io,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::{
+    filesystem::{RandomAccessFile, ReadHint},
+    material::Material,
+    types::Metric,
+};
+
+/// Snapshot of statistics collected while probing.
+///
+/// See [`Tablebase::stats()`](crate::Tablebase::stats).
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    /// Number of lookups in WDL tables.
+    pub wdl_probes: u64,
+    /// Number of lookups in DTZ tables.
+    pub dtz_probes: u64,
+    /// Number of table accesses that found the table already opened.
+    pub table_hits: u64,
+    /// Number of table accesses that had to open the table.
+    pub table_misses: u64,
+    /// Number of read requests to the underlying files.
+    pub reads: u64,
+    /// Number of bytes read from the underlying files.
+    pub bytes_read: u64,
+    /// Number of accesses per table, most frequently accessed tables first.
+    /// Tables that have not been accessed are omitted.
+    pub table_accesses: Vec<(Metric, Material, u64)>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct StatsCounters {
+    wdl_probes: AtomicU64,
+    dtz_probes: AtomicU64,
+    table_hits: AtomicU64,
+    table_misses: AtomicU64,
+    reads: AtomicU64,
+    bytes_read: AtomicU64,
+}
+
+impl StatsCounters {
+    pub fn record_probe(&self, metric: Metric) {
+        match metric {
+            Metric::Wdl => &self.wdl_probes,
+            Metric::Dtz => &self.dtz_probes,
+        }
+        .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_table_lookup(&self, hit: bool) {
+        if hit {
+            &self.table_hits
+        } else {
+            &self.table_misses
+        }
+        .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self, table_accesses: Vec<(Metric, Material, u64)>) -> Stats {
+        Stats {
+            wdl_probes: self.wdl_probes.load(Ordering::Relaxed),
+            dtz_probes: self.dtz_probes.load(Ordering::Relaxed),
+            table_hits: self.table_hits.load(Ordering::Relaxed),
+            table_misses: self.table_misses.load(Ordering::Relaxed),
+            reads: self.reads.load(Ordering::Relaxed),
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            table_accesses,
+        }
+    }
+}
+
+/// Wraps a [`RandomAccessFile`], counting reads.
+pub(crate) struct CountingRandomAccessFile {
+    inner: Box<dyn RandomAccessFile>,
+    stats: Arc<StatsCounters>,
+}
+
+impl CountingRandomAccessFile {
+    pub fn new(inner: Box<dyn RandomAccessFile>, stats: Arc<StatsCounters>) -> Self {
+        CountingRandomAccessFile { inner, stats }
+    }
+}
+
+impl RandomAccessFile for CountingRandomAccessFile {
+    fn read_at(&self, buf: &mut [u8], offset: u64, hint: ReadHint) -> io::Result<usize> {
+        let n = self.inner.read_at(buf, offset, hint)?;
+        self.stats.reads.fetch_add(1, Ordering::Relaxed);
+        self.stats.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
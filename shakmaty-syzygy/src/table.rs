@@ -1,4 +1,4 @@
-use std::{fmt, io, marker::PhantomData};
+use std::{fmt, io, marker::PhantomData, mem};
 
 use arrayvec::ArrayVec;
 use bitflags::bitflags;
@@ -637,6 +637,11 @@ struct PairsData {
 }
 
 impl PairsData {
+    fn heap_size(&self) -> usize {
+        self.base.capacity() * mem::size_of::<u64>()
+            + self.symbols.capacity() * mem::size_of::<Symbol>()
+    }
+
     pub fn parse<S: Syzygy, T: TableTag>(
         raf: &dyn RandomAccessFile,
         mut ptr: u64,
@@ -1405,6 +1410,7 @@ impl<T: TableTag, S: Position + Syzygy> Table<T, S> {
             material.by_color.white.has_pawns() && material.by_color.black.has_pawns();
         let mut next = 1;
         let mut group_sq = side.groups.lens[0];
+        #[allow(clippy::explicit_counter_loop)]
         for lens in side.groups.lens.iter().copied().skip(1) {
             let (prev_squares, group_squares) = squares.split_at_mut(group_sq);
             let group_squares = &mut group_squares[..lens];
@@ -1529,6 +1535,18 @@ impl<T: TableTag, S: Position + Syzygy> Table<T, S> {
             }))
         })
     }
+
+    /// Approximate memory used by the table, not including the
+    /// [`RandomAccessFile`].
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of::<Self>()
+            + self
+                .files
+                .iter()
+                .flat_map(|file| &file.sides)
+                .map(PairsData::heap_size)
+                .sum::<usize>()
+    }
 }
 
 /// A WDL Table.
@@ -1545,6 +1563,39 @@ impl<S: Position + Syzygy> WdlTable<S> {
     pub fn probe_wdl(&self, pos: &S) -> ProbeResult<Wdl> {
         self.table.probe_wdl(pos)
     }
+
+    pub fn memory_usage(&self) -> usize {
+        self.table.memory_usage()
+    }
+
+    /// Number of entries of each subtable, indexed by file and side.
+    #[cfg(feature = "generator")]
+    pub fn subtable_sizes(&self) -> ArrayVec<ArrayVec<u64, 2>, 4> {
+        self.table
+            .files
+            .iter()
+            .map(|file| {
+                file.sides
+                    .iter()
+                    .map(|side| side.groups.factors[side.groups.lens.len()])
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Determines the subtable (file and side) and index of a position.
+    #[cfg(feature = "generator")]
+    pub fn encode(&self, pos: &S) -> ProbeResult<(usize, usize, u64)> {
+        let (side, idx) = self.table.encode(pos)?.expect("wdl tables are two sided");
+        for (i, file) in self.table.files.iter().enumerate() {
+            for (j, candidate) in file.sides.iter().enumerate() {
+                if std::ptr::eq(side, candidate) {
+                    return Ok((i, j, idx));
+                }
+            }
+        }
+        unreachable!("subtable belongs to table")
+    }
 }
 
 /// A DTZ Table.
@@ -1561,4 +1612,8 @@ impl<S: Position + Syzygy> DtzTable<S> {
     pub fn probe_dtz(&self, pos: &S, wdl: DecisiveWdl) -> ProbeResult<Option<MaybeRounded<u32>>> {
         self.table.probe_dtz(pos, wdl)
     }
+
+    pub fn memory_usage(&self) -> usize {
+        self.table.memory_usage()
+    }
 }
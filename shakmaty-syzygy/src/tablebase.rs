@@ -1,25 +1,31 @@
 use std::{
-    cmp::{Reverse, max},
+    cmp::{Reverse, max, min},
     ffi::OsStr,
     fmt, io,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc, PoisonError, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
 };
 
 use arrayvec::ArrayVec;
-use once_cell::sync::OnceCell;
 use rustc_hash::FxHashMap;
-use shakmaty::{Move, Position, Role};
+use shakmaty::{
+    Color, EnPassantMode, Move, Position, Role,
+    zobrist::{Zobrist64, ZobristHash as _},
+};
 use tracing::trace_span;
 
 use crate::{
     AmbiguousWdl,
-    errors::{ProbeResultExt as _, SyzygyError, SyzygyResult},
-    filesystem,
-    filesystem::Filesystem,
+    errors::{ProbeError, ProbeResult, ProbeResultExt as _, SyzygyError, SyzygyResult},
+    filesystem::{Filesystem, RandomAccessFile, ReadHint},
+    kpk,
     material::{Material, NormalizedMaterial},
+    stats::{CountingRandomAccessFile, Stats, StatsCounters},
     table::{DtzTable, WdlTable},
-    types::{DecisiveWdl, Dtz, MaybeRounded, Metric, Syzygy, Wdl},
+    types::{DecisiveWdl, Dtz, MaybeRounded, Metric, RootMove, Syzygy, Wdl},
 };
 
 /// Additional probe information from a brief alpha-beta search.
@@ -34,12 +40,133 @@ enum ProbeState {
     Threat,
 }
 
+/// Where to read a table from.
+enum TableSource {
+    /// Path to open using the configured filesystem.
+    File(PathBuf),
+    /// Table bytes in memory.
+    Bytes(Arc<dyn AsRef<[u8]> + Send + Sync>),
+}
+
+impl fmt::Debug for TableSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableSource::File(path) => f.debug_tuple("File").field(path).finish(),
+            TableSource::Bytes(bytes) => f
+                .debug_struct("Bytes")
+                .field("len", &(**bytes).as_ref().len())
+                .finish(),
+        }
+    }
+}
+
+pub(crate) struct BytesRandomAccessFile {
+    pub(crate) bytes: Arc<dyn AsRef<[u8]> + Send + Sync>,
+}
+
+impl RandomAccessFile for BytesRandomAccessFile {
+    fn read_at(&self, buf: &mut [u8], offset: u64, _hint: ReadHint) -> io::Result<usize> {
+        let bytes = (*self.bytes).as_ref();
+        let Some(tail) = usize::try_from(offset)
+            .ok()
+            .and_then(|offset| bytes.get(offset..))
+        else {
+            return Ok(0);
+        };
+        let n = min(buf.len(), tail.len());
+        buf[..n].copy_from_slice(&tail[..n]);
+        Ok(n)
+    }
+}
+
+fn into_io_error(err: SyzygyError) -> io::Error {
+    let kind = match err {
+        SyzygyError::ProbeFailed { ref error, .. } => match **error {
+            ProbeError::Read { ref error } => error.kind(),
+            _ => io::ErrorKind::InvalidData,
+        },
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, err)
+}
+
+/// An added table, opened lazily.
+#[derive(Debug)]
+struct TableEntry<T> {
+    source: TableSource,
+    size: u64,
+    table: RwLock<Option<Arc<T>>>,
+    accesses: AtomicU64,
+    last_used: AtomicU64,
+}
+
+impl<T> TableEntry<T> {
+    fn new(source: TableSource, size: u64) -> TableEntry<T> {
+        TableEntry {
+            source,
+            size,
+            table: RwLock::new(None),
+            accesses: AtomicU64::new(0),
+            last_used: AtomicU64::new(0),
+        }
+    }
+
+    fn get(&self) -> Option<Arc<T>> {
+        self.table
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn close(&self) {
+        self.table
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+    }
+
+    fn info(&self, metric: Metric, material: &NormalizedMaterial) -> TableInfo {
+        TableInfo {
+            metric,
+            material: material.inner().clone(),
+            pieces: material.inner().count(),
+            path: match self.source {
+                TableSource::File(ref path) => Some(path.clone()),
+                TableSource::Bytes(_) => None,
+            },
+            size: self.size,
+        }
+    }
+}
+
+/// Information about an added table.
+///
+/// See [`Tablebase::tables()`].
+#[derive(Debug, Clone)]
+pub struct TableInfo {
+    /// Whether this is a WDL or DTZ table.
+    pub metric: Metric,
+    /// Material key of the table, with the stronger side first.
+    pub material: Material,
+    /// Number of pieces.
+    pub pieces: usize,
+    /// Path of the table file, or `None` for tables added from memory.
+    pub path: Option<PathBuf>,
+    /// Size of the table file in bytes.
+    pub size: u64,
+}
+
 /// A collection of tables.
 pub struct Tablebase<S: Position + Clone + Syzygy> {
     filesystem: Arc<dyn Filesystem>,
-    wdl: FxHashMap<NormalizedMaterial, (PathBuf, OnceCell<WdlTable<S>>)>,
-    dtz: FxHashMap<NormalizedMaterial, (PathBuf, OnceCell<DtzTable<S>>)>,
+    wdl: FxHashMap<NormalizedMaterial, TableEntry<WdlTable<S>>>,
+    dtz: FxHashMap<NormalizedMaterial, TableEntry<DtzTable<S>>>,
     max_pieces: usize,
+    stats: Option<Arc<StatsCounters>>,
+    trivial_draws: bool,
+    preload: bool,
+    memory_limit: usize,
+    clock: AtomicU64,
 }
 
 impl<S: Position + Clone + Syzygy + fmt::Debug> fmt::Debug for Tablebase<S> {
@@ -48,6 +175,10 @@ impl<S: Position + Clone + Syzygy + fmt::Debug> fmt::Debug for Tablebase<S> {
             .field("wdl", &self.wdl)
             .field("dtz", &self.dtz)
             .field("max_pieces", &self.max_pieces)
+            .field("stats", &self.stats)
+            .field("trivial_draws", &self.trivial_draws)
+            .field("preload", &self.preload)
+            .field("memory_limit", &self.memory_limit)
             .finish_non_exhaustive()
     }
 }
@@ -64,7 +195,7 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
     /// implementation will be used to read table files.
     #[cfg(any(unix, windows))]
     pub fn new() -> Tablebase<S> {
-        Tablebase::with_filesystem(Arc::new(filesystem::OsFilesystem::new()))
+        Tablebase::with_filesystem(Arc::new(crate::filesystem::OsFilesystem::new()))
     }
 
     /// Creates an empty collection of tables. Memory maps will be used
@@ -80,11 +211,15 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
     /// * Externally guarantee absence of I/O errors (or live with the
     ///   consequences). For example, I/O errors will generate
     ///   `SIGSEV`/`SIGBUS` on Linux.
+    ///
+    /// To configure paging behavior (e.g., `MADV_RANDOM`), use
+    /// [`Tablebase::with_filesystem()`] with a configured
+    /// [`MmapFilesystem`](crate::filesystem::MmapFilesystem) instead.
     #[cfg(all(feature = "mmap", target_pointer_width = "64"))]
     pub unsafe fn with_mmap_filesystem() -> Tablebase<S> {
         // Safety: Forwarding contract of memmap2::MmapOptions::map()
         // to caller.
-        Tablebase::with_filesystem(unsafe { Arc::new(filesystem::MmapFilesystem::new()) })
+        Tablebase::with_filesystem(unsafe { Arc::new(crate::filesystem::MmapFilesystem::new()) })
     }
 
     /// Creates an empty collection of tables. A custom filesystem
@@ -95,9 +230,95 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
             wdl: FxHashMap::with_capacity_and_hasher(145, Default::default()),
             dtz: FxHashMap::with_capacity_and_hasher(145, Default::default()),
             max_pieces: 0,
+            stats: None,
+            trivial_draws: false,
+            preload: false,
+            memory_limit: usize::MAX,
+            clock: AtomicU64::new(0),
         }
     }
 
+    /// Determines whether positions with insufficient material to win for
+    /// either side (e.g., KBvK, KNvK, or KBvKB with bishops on the same
+    /// color) are resolved as draws without requiring table files.
+    ///
+    /// Defaults to `false`, so that missing tables are reported
+    /// consistently.
+    #[must_use]
+    pub fn with_trivial_draws(mut self, trivial_draws: bool) -> Self {
+        self.trivial_draws = trivial_draws;
+        self
+    }
+
+    /// Determines whether tables are opened and their headers parsed as soon
+    /// as they are added, rather than lazily on the first probe.
+    ///
+    /// This avoids latency spikes when probing, and reports corrupted tables
+    /// immediately, with [`io::ErrorKind::InvalidData`]. To open tables that
+    /// have already been added, use [`Tablebase::warmup()`].
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub fn with_preload(mut self, preload: bool) -> Self {
+        self.preload = preload;
+        self
+    }
+
+    /// Limits the approximate number of bytes of memory used for metadata
+    /// of opened tables (see [`Tablebase::memory_usage()`]).
+    ///
+    /// Whenever opening a table exceeds the limit, the least recently used
+    /// other tables are closed until the limit is met again. Closed tables
+    /// are reopened lazily when probing. The most recently opened table is
+    /// never closed, even if it alone exceeds the limit. Probes that are
+    /// still in progress keep using a closed table until they finish.
+    ///
+    /// Defaults to `usize::MAX`, so that tables stay open until they are
+    /// removed or closed using [`Tablebase::close_tables()`].
+    #[must_use]
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+
+    /// Determines whether statistics are collected while probing.
+    /// See [`Tablebase::stats()`].
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub fn with_stats(mut self, stats: bool) -> Self {
+        self.stats = stats.then(Arc::default);
+        self
+    }
+
+    /// Returns a snapshot of the statistics collected while probing, or
+    /// `None` if not enabled via [`Tablebase::with_stats()`].
+    ///
+    /// Reads are only counted for tables opened after enabling statistics.
+    pub fn stats(&self) -> Option<Stats> {
+        let counters = self.stats.as_ref()?;
+        let mut table_accesses: Vec<_> = self
+            .wdl
+            .iter()
+            .map(|(material, entry)| (Metric::Wdl, material, &entry.accesses))
+            .chain(
+                self.dtz
+                    .iter()
+                    .map(|(material, entry)| (Metric::Dtz, material, &entry.accesses)),
+            )
+            .map(|(metric, material, accesses)| {
+                (
+                    metric,
+                    material.inner().clone(),
+                    accesses.load(Ordering::Relaxed),
+                )
+            })
+            .filter(|(_, _, accesses)| *accesses > 0)
+            .collect();
+        table_accesses.sort_by_key(|(_, _, accesses)| Reverse(*accesses));
+        Some(counters.snapshot(table_accesses))
+    }
+
     /// Returns the maximum number of pieces over all added tables.
     ///
     /// This number is updated when adding table files and very fast to read.
@@ -110,7 +331,8 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
     ///
     /// Tables are selected by filename, e.g., `KQvKP.rtbz`.
     ///
-    /// The files are not actually opened. This happens lazily when probing.
+    /// The files are not actually opened. This happens lazily when probing,
+    /// unless enabled via [`Tablebase::with_preload()`].
     /// Eventually all files may be opened, so configure resource limits like
     /// `RLIMIT_NOFILE` accordingly.
     ///
@@ -133,6 +355,8 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
     ///   maybe have already been added.
     /// * There is a table in `path` where the file size indicates that
     ///   it must be corrupted. Some tables may have already been added.
+    /// * Preloading is enabled, and opening a table in `path` failed.
+    ///   Some tables may have already been added.
     pub fn add_directory<P: AsRef<Path>>(&mut self, path: P) -> io::Result<usize> {
         let mut num = 0;
 
@@ -149,7 +373,8 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
 
     /// Add a table file.
     ///
-    /// The file is not actually opened. This happens lazily when probing.
+    /// The file is not actually opened. This happens lazily when probing,
+    /// unless enabled via [`Tablebase::with_preload()`].
     ///
     /// Traverses symbolic links.
     ///
@@ -163,16 +388,208 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
     ///   broken symlink, no permission to read metadata, ...).
     /// * `path` is not pointing to a regular file.
     /// * The file size indicates that the table file must be corrupted.
+    /// * Preloading is enabled, and opening the table failed.
     pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
         self.add_file_impl(path.as_ref())
     }
 
+    /// Add a table from bytes in memory, for example a table embedded in the
+    /// binary using [`include_bytes!`], or loaded from a database blob.
+    ///
+    /// `name` is the filename of the table (e.g., `KQvKP.rtbw`), used to
+    /// determine its material composition and type.
+    ///
+    /// The table is not actually parsed. This happens lazily when probing,
+    /// unless enabled via [`Tablebase::with_preload()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an immediate error result when:
+    ///
+    /// * `name` does not indicate that it is a valid table file
+    ///   (e.g., `KQvKP.rtbz`).
+    /// * The size of `bytes` indicates that the table must be corrupted.
+    /// * Preloading is enabled, and parsing the table failed.
+    pub fn add_table_bytes<B>(&mut self, name: &str, bytes: B) -> io::Result<()>
+    where
+        B: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        let (material, is_tbw, pieces) = self.parse_table_name(Path::new(name))?;
+        self.insert_table(
+            material,
+            is_tbw,
+            pieces,
+            bytes.as_ref().len() as u64,
+            TableSource::Bytes(Arc::new(bytes)),
+        )
+    }
+
+    /// Remove all tables that were added from the given directory.
+    ///
+    /// Open file handles and cached data of the removed tables are released.
+    /// To swap table sets in a long-running process that shares the
+    /// tablebase between threads, prepare a new [`Tablebase`] and replace
+    /// the shared instance.
+    ///
+    /// Returns the number of removed table files.
+    pub fn remove_directory<P: AsRef<Path>>(&mut self, path: P) -> usize {
+        let path = path.as_ref();
+        let in_directory = |source: &TableSource| matches!(source, TableSource::File(p) if p.parent() == Some(path));
+        let before = self.wdl.len() + self.dtz.len();
+        self.wdl.retain(|_, entry| !in_directory(&entry.source));
+        self.dtz.retain(|_, entry| !in_directory(&entry.source));
+        self.update_max_pieces();
+        before - self.wdl.len() - self.dtz.len()
+    }
+
+    /// Remove the WDL and DTZ tables for the given material composition.
+    ///
+    /// Open file handles and cached data of the removed tables are released.
+    ///
+    /// Returns the number of removed tables.
+    pub fn remove_material(&mut self, material: &Material) -> usize {
+        let material = material.to_normalized();
+        let removed = usize::from(self.wdl.remove(&material).is_some())
+            + usize::from(self.dtz.remove(&material).is_some());
+        self.update_max_pieces();
+        removed
+    }
+
+    /// Remove all tables.
+    ///
+    /// Open file handles and cached data are released.
+    pub fn clear(&mut self) {
+        self.wdl.clear();
+        self.dtz.clear();
+        self.max_pieces = 0;
+    }
+
+    /// Close all opened tables, without removing them.
+    ///
+    /// Open file handles and parsed table metadata are released. Tables will
+    /// be reopened lazily when probing.
+    pub fn close_tables(&mut self) {
+        for entry in self.wdl.values() {
+            entry.close();
+        }
+        for entry in self.dtz.values() {
+            entry.close();
+        }
+    }
+
+    /// Returns the approximate number of bytes of memory used for metadata
+    /// of opened tables.
+    ///
+    /// Tables are opened lazily when probing, and stay open until they are
+    /// removed, closed using [`Tablebase::close_tables()`], or evicted to
+    /// stay within [`Tablebase::with_memory_limit()`].
+    ///
+    /// The tablebase itself does not cache table data. Any caching is up to
+    /// the [`Filesystem`] implementation (e.g., the page cache of the
+    /// operating system, or the configurable block cache of
+    /// `HttpFilesystem`).
+    pub fn memory_usage(&self) -> usize {
+        self.wdl
+            .values()
+            .filter_map(TableEntry::get)
+            .map(|table| table.memory_usage())
+            .sum::<usize>()
+            + self
+                .dtz
+                .values()
+                .filter_map(TableEntry::get)
+                .map(|table| table.memory_usage())
+                .sum::<usize>()
+    }
+
+    /// Iterates over all added tables, in no particular order.
+    pub fn tables(&self) -> impl Iterator<Item = TableInfo> + '_ {
+        self.wdl
+            .iter()
+            .map(|(material, entry)| entry.info(Metric::Wdl, material))
+            .chain(
+                self.dtz
+                    .iter()
+                    .map(|(material, entry)| entry.info(Metric::Dtz, material)),
+            )
+    }
+
+    /// Opens the WDL and DTZ tables for `material` and parses their
+    /// headers, so that the first probe does not have to.
+    ///
+    /// Tables stay open until they are removed or closed using
+    /// [`Tablebase::close_tables()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SyzygyError::MissingTable`] if neither table has been
+    /// added, or [`SyzygyError::ProbeFailed`] if opening a table failed.
+    pub fn preload(&self, material: &Material) -> SyzygyResult<()> {
+        let material = material.to_normalized();
+        let wdl = self.wdl.get(&material);
+        let dtz = self.dtz.get(&material);
+        if wdl.is_none() && dtz.is_none() {
+            return Err(SyzygyError::MissingTable {
+                metric: Metric::Wdl,
+                material: material.inner().clone(),
+            });
+        }
+        if let Some(entry) = wdl {
+            self.init_wdl(entry, &material)?;
+        }
+        if let Some(entry) = dtz {
+            self.init_dtz(entry, &material)?;
+        }
+        Ok(())
+    }
+
+    /// Opens all added tables and parses their headers, so that probes do
+    /// not have to. Stops at the first table that fails to open.
+    ///
+    /// See [`Tablebase::with_preload()`] to open tables as soon as they are
+    /// added.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SyzygyError::ProbeFailed`] if opening a table failed.
+    pub fn warmup(&self) -> SyzygyResult<()> {
+        for (material, entry) in &self.wdl {
+            self.init_wdl(entry, material)?;
+        }
+        for (material, entry) in &self.dtz {
+            self.init_dtz(entry, material)?;
+        }
+        Ok(())
+    }
+
+    fn update_max_pieces(&mut self) {
+        self.max_pieces = self
+            .wdl
+            .keys()
+            .chain(self.dtz.keys())
+            .map(|material| material.inner().count())
+            .max()
+            .unwrap_or(0);
+    }
+
     fn add_file_impl(&mut self, path: &Path) -> io::Result<()> {
+        let (material, is_tbw, pieces) = self.parse_table_name(path)?;
+        let size = self.filesystem.regular_file_size(path)?;
+        self.insert_table(
+            material,
+            is_tbw,
+            pieces,
+            size,
+            TableSource::File(path.to_path_buf()),
+        )
+    }
+
+    fn parse_table_name(&self, path: &Path) -> io::Result<(Material, bool, usize)> {
         // Validate filename.
         let Some(stem) = path.file_stem().and_then(OsStr::to_str) else {
             return Err(io::Error::from(io::ErrorKind::InvalidInput));
         };
-        let Ok(material) = Material::from_str(stem) else {
+        let Ok(material) = stem.parse::<Material>() else {
             return Err(io::Error::from(io::ErrorKind::InvalidInput));
         };
         let pieces = material.count();
@@ -195,31 +612,242 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
             return Err(io::Error::from(io::ErrorKind::InvalidInput));
         }
 
-        // Check meta data.
-        if self.filesystem.regular_file_size(path)? % 64 != 16 {
+        Ok((material, is_tbw, pieces))
+    }
+
+    fn insert_table(
+        &mut self,
+        material: Material,
+        is_tbw: bool,
+        pieces: usize,
+        size: u64,
+        source: TableSource,
+    ) -> io::Result<()> {
+        // Check size.
+        if size % 64 != 16 {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "unexpected file size",
             ));
         }
 
-        // Add path.
+        // Add source.
         let material = material.to_normalized();
-        let path = path.to_path_buf();
         if is_tbw {
-            self.wdl.insert(material, (path, OnceCell::new()));
+            let entry = TableEntry::new(source, size);
+            if self.preload {
+                self.init_wdl(&entry, &material).map_err(into_io_error)?;
+            }
+            self.wdl.insert(material, entry);
         } else {
-            self.dtz.insert(material, (path, OnceCell::new()));
+            let entry = TableEntry::new(source, size);
+            if self.preload {
+                self.init_dtz(&entry, &material).map_err(into_io_error)?;
+            }
+            self.dtz.insert(material, entry);
         }
         self.max_pieces = max(self.max_pieces, pieces);
         Ok(())
     }
 
-    fn wdl_table(&self, material: &NormalizedMaterial) -> SyzygyResult<&WdlTable<S>> {
-        if let Some((path, table)) = self.wdl.get(material) {
+    /// Verify all added tables against a list of MD5 checksums, in the
+    /// format produced by `md5sum`, e.g., as published alongside table
+    /// downloads:
+    ///
+    /// ```text
+    /// 2c7e1e4e5bcd5b5a8b7c4e0b0d0ecf2a  KQvK.rtbw
+    /// ```
+    ///
+    /// Tables are matched by filename. Tables without a listed checksum
+    /// are skipped. Note that this reads all tables in full.
+    ///
+    /// Returns the tables that failed verification, with
+    /// [`io::ErrorKind::InvalidData`] for checksum mismatches, or the error
+    /// that occurred while reading the table.
+    #[cfg(feature = "checksum")]
+    pub fn verify_checksums(&self, md5sums: &str) -> Vec<(Metric, Material, io::Error)> {
+        use md5::{Digest as _, Md5};
+
+        let mut expected = FxHashMap::default();
+        for line in md5sums.lines() {
+            let Some((hash, name)) = line.trim().split_once(char::is_whitespace) else {
+                continue;
+            };
+            let name = name.trim_start().trim_start_matches('*');
+            if let Ok((material, is_tbw, _)) = self.parse_table_name(Path::new(name)) {
+                let metric = if is_tbw { Metric::Wdl } else { Metric::Dtz };
+                expected.insert(
+                    (metric, material.to_normalized()),
+                    hash.to_ascii_lowercase(),
+                );
+            }
+        }
+
+        let tables = self
+            .wdl
+            .iter()
+            .map(|(material, entry)| (Metric::Wdl, material, &entry.source))
+            .chain(
+                self.dtz
+                    .iter()
+                    .map(|(material, entry)| (Metric::Dtz, material, &entry.source)),
+            );
+
+        let mut failed = Vec::new();
+        for (metric, material, source) in tables {
+            let Some(hash) = expected.get(&(metric, material.clone())) else {
+                continue;
+            };
+
+            let actual = (|| {
+                let size = match source {
+                    TableSource::File(path) => self.filesystem.regular_file_size(path)?,
+                    TableSource::Bytes(bytes) => (**bytes).as_ref().len() as u64,
+                };
+                let raf = self.open(source)?;
+                let mut hasher = Md5::new();
+                let mut buf = vec![0; 64 * 1024];
+                let mut offset = 0;
+                while offset < size {
+                    let n = min(buf.len() as u64, size - offset) as usize;
+                    raf.read_exact_at(&mut buf[..n], offset, ReadHint::Data)?;
+                    hasher.update(&buf[..n]);
+                    offset += n as u64;
+                }
+                Ok(hasher
+                    .finalize()
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<String>())
+            })();
+
+            let err = match actual {
+                Ok(actual) if actual == *hash => continue,
+                Ok(_) => io::Error::new(io::ErrorKind::InvalidData, "checksum mismatch"),
+                Err(err) => err,
+            };
+            failed.push((metric, material.inner().clone(), err));
+        }
+        failed
+    }
+
+    fn open(&self, source: &TableSource) -> io::Result<Box<dyn RandomAccessFile>> {
+        let raf: Box<dyn RandomAccessFile> = match source {
+            TableSource::File(path) => self.filesystem.open(path)?,
+            TableSource::Bytes(bytes) => Box::new(BytesRandomAccessFile {
+                bytes: Arc::clone(bytes),
+            }),
+        };
+        Ok(match self.stats {
+            Some(ref stats) => Box::new(CountingRandomAccessFile::new(raf, Arc::clone(stats))),
+            None => raf,
+        })
+    }
+
+    fn init_wdl(
+        &self,
+        entry: &TableEntry<WdlTable<S>>,
+        material: &NormalizedMaterial,
+    ) -> SyzygyResult<Arc<WdlTable<S>>> {
+        self.init(entry, || {
+            WdlTable::new(self.open(&entry.source)?, material.inner())
+        })
+        .ctx(Metric::Wdl, material)
+    }
+
+    fn init_dtz(
+        &self,
+        entry: &TableEntry<DtzTable<S>>,
+        material: &NormalizedMaterial,
+    ) -> SyzygyResult<Arc<DtzTable<S>>> {
+        self.init(entry, || {
+            DtzTable::new(self.open(&entry.source)?, material.inner())
+        })
+        .ctx(Metric::Dtz, material)
+    }
+
+    fn init<T>(
+        &self,
+        entry: &TableEntry<T>,
+        open: impl FnOnce() -> ProbeResult<T>,
+    ) -> ProbeResult<Arc<T>> {
+        if self.memory_limit != usize::MAX {
+            let now = self.clock.fetch_add(1, Ordering::Relaxed) + 1;
+            entry.last_used.store(now, Ordering::Relaxed);
+        }
+
+        if let Some(table) = entry.get() {
+            return Ok(table);
+        }
+
+        let table = {
+            let mut guard = entry.table.write().unwrap_or_else(PoisonError::into_inner);
+            if let Some(ref table) = *guard {
+                return Ok(Arc::clone(table));
+            }
+            let table = Arc::new(open()?);
+            *guard = Some(Arc::clone(&table));
             table
-                .get_or_try_init(|| WdlTable::new(self.filesystem.open(path)?, material.inner()))
-                .ctx(Metric::Wdl, material)
+        };
+
+        self.evict();
+        Ok(table)
+    }
+
+    fn evict(&self) {
+        if self.memory_limit == usize::MAX {
+            return;
+        }
+
+        let mut open: Vec<_> = self
+            .wdl
+            .iter()
+            .filter_map(|(material, entry)| {
+                let usage = entry.get()?.memory_usage();
+                Some((
+                    entry.last_used.load(Ordering::Relaxed),
+                    usage,
+                    Metric::Wdl,
+                    material,
+                ))
+            })
+            .chain(self.dtz.iter().filter_map(|(material, entry)| {
+                let usage = entry.get()?.memory_usage();
+                Some((
+                    entry.last_used.load(Ordering::Relaxed),
+                    usage,
+                    Metric::Dtz,
+                    material,
+                ))
+            }))
+            .collect();
+        open.sort_unstable_by_key(|&(last_used, ..)| last_used);
+        open.pop(); // Keep the most recently used table
+
+        let mut usage = self.memory_usage();
+        for (_, table_usage, metric, material) in open {
+            if usage <= self.memory_limit {
+                break;
+            }
+            match metric {
+                Metric::Wdl => self.wdl[material].close(),
+                Metric::Dtz => self.dtz[material].close(),
+            }
+            usage = usage.saturating_sub(table_usage);
+        }
+    }
+
+    fn record_lookup<T>(&self, entry: &TableEntry<T>) {
+        if let Some(ref stats) = self.stats {
+            entry.accesses.fetch_add(1, Ordering::Relaxed);
+            stats.record_table_lookup(entry.get().is_some());
+        }
+    }
+
+    fn wdl_table(&self, material: &NormalizedMaterial) -> SyzygyResult<Arc<WdlTable<S>>> {
+        if let Some(entry) = self.wdl.get(material) {
+            self.record_lookup(entry);
+            self.init_wdl(entry, material)
         } else {
             Err(SyzygyError::MissingTable {
                 metric: Metric::Wdl,
@@ -228,11 +856,10 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
         }
     }
 
-    fn dtz_table(&self, material: &NormalizedMaterial) -> SyzygyResult<&DtzTable<S>> {
-        if let Some((path, table)) = self.dtz.get(material) {
-            table
-                .get_or_try_init(|| DtzTable::new(self.filesystem.open(path)?, material.inner()))
-                .ctx(Metric::Dtz, material)
+    fn dtz_table(&self, material: &NormalizedMaterial) -> SyzygyResult<Arc<DtzTable<S>>> {
+        if let Some(entry) = self.dtz.get(material) {
+            self.record_lookup(entry);
+            self.init_dtz(entry, material)
         } else {
             Err(SyzygyError::MissingTable {
                 metric: Metric::Dtz,
@@ -270,6 +897,16 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
         })
     }
 
+    /// Probe tables for the WDL values of many positions, like
+    /// [`Tablebase::probe_wdl()`] for each position.
+    ///
+    /// Opened tables are shared across all probes. Enable the `rayon`
+    /// feature for `Tablebase::par_probe_wdl_many()`, which probes in
+    /// parallel.
+    pub fn probe_wdl_many(&self, positions: &[S]) -> Vec<SyzygyResult<AmbiguousWdl>> {
+        positions.iter().map(|pos| self.probe_wdl(pos)).collect()
+    }
+
     /// Probe tables for the [`Dtz`] value of a position.
     ///
     /// Requires both WDL and DTZ tables.
@@ -282,14 +919,99 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
             .in_scope(|| self.probe(pos).and_then(|entry| entry.dtz()))
     }
 
+    /// Probe tables for the exact [`Dtz`] value of a position.
+    ///
+    /// Like [`Tablebase::probe_dtz()`], but if the value may be affected by
+    /// DTZ rounding (see [`MaybeRounded`]), additional probes along the
+    /// DTZ-optimal lines are performed, until the precise value is known.
+    /// This may require a large number of probes.
+    ///
+    /// # Errors
+    ///
+    /// See [`SyzygyError`] for possible error conditions.
+    pub fn probe_dtz_exact(&self, pos: &S) -> SyzygyResult<Dtz> {
+        trace_span!("probe dtz exact", pieces = pos.board().occupied().count())
+            .in_scope(|| self.probe_dtz_exact_memo(pos, &mut FxHashMap::default()))
+    }
+
+    fn probe_dtz_exact_memo(
+        &self,
+        pos: &S,
+        memo: &mut FxHashMap<Zobrist64, Dtz>,
+    ) -> SyzygyResult<Dtz> {
+        let dtz = match self.probe_dtz(pos)? {
+            MaybeRounded::Precise(dtz) => return Ok(dtz),
+            MaybeRounded::Rounded(dtz) => dtz,
+        };
+
+        let key = pos.zobrist_hash(EnPassantMode::Legal);
+        if let Some(&exact) = memo.get(&key) {
+            return Ok(exact);
+        }
+
+        // The exact value is either dtz or dtz.add_plies(1). When winning,
+        // find a move that achieves dtz. When losing, find a move that
+        // achieves dtz.add_plies(1).
+        let target = if dtz.is_positive() {
+            dtz
+        } else {
+            dtz.add_plies(1)
+        };
+
+        let mut found = false;
+        for m in pos.legal_moves() {
+            let mut after = pos.clone();
+            after.play_unchecked(m);
+
+            let achieved = if m.is_zeroing() {
+                MaybeRounded::Precise(Dtz::before_zeroing(-self.probe_wdl_after_zeroing(&after)?))
+            } else {
+                -self.probe_dtz(&after)?.add_plies(1)
+            };
+
+            found = match achieved {
+                MaybeRounded::Precise(achieved) => achieved == target,
+                MaybeRounded::Rounded(achieved) => {
+                    (achieved == target || achieved.add_plies(1) == target)
+                        && -self.probe_dtz_exact_memo(&after, memo)?.add_plies(1) == target
+                }
+            };
+            if found {
+                break;
+            }
+        }
+
+        let exact = if found {
+            target
+        } else if dtz.is_positive() {
+            dtz.add_plies(1)
+        } else {
+            dtz
+        };
+        memo.insert(key, exact);
+        Ok(exact)
+    }
+
     /// Get the recommended tablebase move.
     ///
+    /// Among the moves with the best [`Wdl`], a DTZ-optimal move is selected:
+    /// Moves that immediately end the game are preferred. When winning,
+    /// zeroing moves are preferred, followed by the move that minimizes DTZ.
+    /// When losing, non-zeroing moves are preferred, followed by the move
+    /// that maximizes DTZ. Returns `None` if there are no legal moves.
+    ///
+    /// The returned [`Dtz`] is the value of the position *after* the move.
+    ///
     /// Following the tablebase mainline *starting from a capture or pawn move*
     /// guarantees achieving the optimal outcome under the 50-move rule.
     ///
     /// Otherwise (i.e., when not immediately following the tablebase mainline
     /// after the capture that crosses into tablebase territory),
     /// [some care needs to be taken due to DTZ rounding](MaybeRounded).
+    /// In particular, the move may fail to convert a
+    /// [`MaybeWin`](AmbiguousWdl::MaybeWin) before the halfmove counter
+    /// runs out. Use [`Tablebase::probe_root()`] to take the halfmove counter
+    /// of `pos` into account.
     ///
     /// Requires both WDL and DTZ tables.
     ///
@@ -372,6 +1094,116 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
             .map(|m| (m.m, m.dtz)))
     }
 
+    /// Probe tables for all legal moves of `pos`.
+    ///
+    /// Each move is annotated with the [`Wdl`] and [`Dtz`] values that
+    /// result from playing it, from the point of view of the side to move
+    /// in `pos`. Zeroing moves are resolved using only WDL tables, and the
+    /// halfmove counter of `pos` is taken into account for the WDL value of
+    /// all other moves.
+    ///
+    /// Moves are sorted best-first: by WDL, then moves that immediately
+    /// end the game, then by DTZ (quickest zeroing when winning, slowest
+    /// zeroing when losing). Ties keep the order of
+    /// [`Position::legal_moves()`].
+    ///
+    /// Requires both WDL and DTZ tables.
+    ///
+    /// # Errors
+    ///
+    /// See [`SyzygyError`] for possible error conditions.
+    pub fn probe_root(&self, pos: &S) -> SyzygyResult<Vec<RootMove>> {
+        let mut root_moves = pos
+            .legal_moves()
+            .into_iter()
+            .map(|m| {
+                let mut after = pos.clone();
+                after.play_unchecked(m);
+                let entry = self.probe(&after)?;
+                let game_over = after.is_checkmate() || after.variant_outcome().is_known();
+
+                Ok(if game_over || m.is_zeroing() {
+                    let wdl = -entry.wdl_after_zeroing();
+                    RootMove {
+                        m,
+                        wdl: AmbiguousWdl::from(wdl),
+                        dtz: MaybeRounded::Precise(Dtz::before_zeroing(wdl)),
+                        game_over,
+                    }
+                } else {
+                    let dtz = -entry.dtz()?.add_plies(1);
+                    RootMove {
+                        m,
+                        wdl: AmbiguousWdl::from_dtz_and_halfmoves(dtz, pos.halfmoves()),
+                        dtz,
+                        game_over,
+                    }
+                })
+            })
+            .collect::<SyzygyResult<Vec<_>>>()?;
+
+        root_moves.sort_by_key(|r| {
+            (
+                Reverse(r.wdl),
+                Reverse(r.game_over),
+                r.dtz.ignore_rounding(),
+            )
+        });
+
+        Ok(root_moves)
+    }
+
+    /// Lists the tables that are required to probe `pos`, but have not
+    /// been added.
+    ///
+    /// Probing WDL requires WDL tables for the material composition of `pos`
+    /// and all material compositions reachable by captures and promotions.
+    /// Probing DTZ additionally requires the DTZ table for the material
+    /// composition of `pos`.
+    ///
+    /// The list is conservative, i.e., some of the tables may not actually
+    /// be needed for the specific position. Use the [`fmt::Display`]
+    /// implementation of [`Material`] to get table names like `KQvKRP`.
+    pub fn missing_tables(&self, pos: &S) -> Vec<(Metric, Material)> {
+        let root = Material::from_board(pos.board()).to_normalized();
+
+        let mut seen = vec![root.clone()];
+        let mut queue = vec![root.clone()];
+        while let Some(material) = queue.pop() {
+            for successor in material.inner().successors(S::ONE_KING) {
+                let successor = successor.to_normalized();
+                if !seen.contains(&successor) {
+                    seen.push(successor.clone());
+                    queue.push(successor);
+                }
+            }
+        }
+
+        let has_table = |material: &NormalizedMaterial| {
+            let material = material.inner();
+            material.by_color.iter().all(|side| side.count() > 0)
+                && !(S::ONE_KING && material.count() <= 2)
+        };
+
+        let mut missing: Vec<_> = seen
+            .into_iter()
+            .filter(|material| has_table(material) && !self.wdl.contains_key(material))
+            .map(|material| (Metric::Wdl, material.inner().clone()))
+            .collect();
+        if has_table(&root) && !self.dtz.contains_key(&root) {
+            missing.push((Metric::Dtz, root.inner().clone()));
+        }
+
+        missing.sort_by_cached_key(|(metric, material)| {
+            (
+                Reverse(material.count()),
+                material.to_string(),
+                *metric == Metric::Wdl,
+            )
+        });
+        missing
+    }
+
     fn probe<'a>(&'a self, pos: &'a S) -> SyzygyResult<WdlEntry<'a, S>> {
         // Probing resolves captures, so sometimes we can obtain results
         // for positions that have more pieces than the maximum amount of
@@ -631,29 +1463,178 @@ impl<S: Position + Clone + Syzygy> Tablebase<S> {
             return Ok(Wdl::Draw);
         }
 
+        // Test for other trivial draws.
+        if self.trivial_draws && pos.is_insufficient_material() {
+            return Ok(Wdl::Draw);
+        }
+
+        // Use the embedded bitbase for KPvK, unless the table is available.
+        if let Some(wdl) = self.probe_kpk(pos) {
+            return Ok(wdl);
+        }
+
         // More pieces than any opened table.
         if pos.board().occupied().count() > self.max_pieces {
             return Err(SyzygyError::TooManyPieces);
         }
 
         // Get raw WDL value from the appropriate table.
+        if let Some(ref stats) = self.stats {
+            stats.record_probe(Metric::Wdl);
+        }
         let material = Material::from_board(pos.board()).to_normalized();
         self.wdl_table(&material)
             .and_then(|table| table.probe_wdl(pos).ctx(Metric::Wdl, &material))
     }
 
+    fn probe_kpk(&self, pos: &S) -> Option<Wdl> {
+        if !S::ONE_KING || S::CONNECTED_KINGS || S::CAPTURES_COMPULSORY {
+            return None;
+        }
+
+        let board = pos.board();
+        if board.occupied().count() != 3 || board.pawns().count() != 1 {
+            return None;
+        }
+
+        let material = Material::from_board(board).to_normalized();
+        if self.wdl.contains_key(&material) {
+            return None;
+        }
+
+        let pawn = board.pawns().first()?;
+        let strong = board.color_at(pawn)?;
+        let white_king = board.king_of(strong)?;
+        let black_king = board.king_of(!strong)?;
+        let (white_king, pawn, black_king) = match strong {
+            Color::White => (white_king, pawn, black_king),
+            Color::Black => (
+                white_king.flip_vertical(),
+                pawn.flip_vertical(),
+                black_king.flip_vertical(),
+            ),
+        };
+
+        let turn = Color::from_white(pos.turn() == strong);
+        Some(if !kpk::probe(white_king, pawn, black_king, turn) {
+            Wdl::Draw
+        } else if turn == Color::White {
+            Wdl::Win
+        } else {
+            Wdl::Loss
+        })
+    }
+
     fn probe_dtz_table(
         &self,
         pos: &S,
         wdl: DecisiveWdl,
     ) -> SyzygyResult<Option<MaybeRounded<u32>>> {
         // Get raw DTZ value from the appropriate table.
+        if let Some(ref stats) = self.stats {
+            stats.record_probe(Metric::Dtz);
+        }
         let material = Material::from_board(pos.board()).to_normalized();
         self.dtz_table(&material)
             .and_then(|table| table.probe_dtz(pos, wdl).ctx(Metric::Dtz, &material))
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<S: Position + Clone + Syzygy + Send + Sync> Tablebase<S> {
+    /// Like [`Tablebase::probe_wdl_many()`], but probes positions in
+    /// parallel on the current Rayon thread pool.
+    ///
+    /// Results are in the same order as `positions`.
+    pub fn par_probe_wdl_many(&self, positions: &[S]) -> Vec<SyzygyResult<AmbiguousWdl>> {
+        use rayon::prelude::*;
+
+        positions
+            .par_iter()
+            .map(|pos| self.probe_wdl(pos))
+            .collect()
+    }
+}
+
+/// # Blocking offload
+///
+/// These methods are a convenience for calling the blocking probes from
+/// async code, not async I/O. The
+/// [`RandomAccessFile`](crate::filesystem::RandomAccessFile) trait stays
+/// synchronous, and each call runs a complete blocking probe on the blocking
+/// thread pool of the current Tokio runtime, occupying one of its threads
+/// until all required reads are done. This keeps slow disk or network reads
+/// off the executor threads, but concurrency is bounded by the size of the
+/// blocking thread pool (see
+/// [`tokio::runtime::Builder::max_blocking_threads()`]).
+///
+/// Panics during a probe are resumed in the calling task.
+#[cfg(feature = "tokio")]
+impl<S: Position + Clone + Syzygy + Send + Sync + 'static> Tablebase<S> {
+    /// Like [`Tablebase::probe_wdl_after_zeroing()`], but runs on the
+    /// blocking thread pool. See [blocking offload](#blocking-offload).
+    ///
+    /// # Errors
+    ///
+    /// See [`SyzygyError`] for possible error conditions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub async fn probe_wdl_after_zeroing_spawn_blocking(
+        self: &Arc<Self>,
+        pos: S,
+    ) -> SyzygyResult<Wdl> {
+        self.spawn_blocking(move |tables| tables.probe_wdl_after_zeroing(&pos))
+            .await
+    }
+
+    /// Like [`Tablebase::probe_wdl()`], but runs on the blocking thread
+    /// pool. See [blocking offload](#blocking-offload).
+    ///
+    /// # Errors
+    ///
+    /// See [`SyzygyError`] for possible error conditions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub async fn probe_wdl_spawn_blocking(self: &Arc<Self>, pos: S) -> SyzygyResult<AmbiguousWdl> {
+        self.spawn_blocking(move |tables| tables.probe_wdl(&pos))
+            .await
+    }
+
+    /// Like [`Tablebase::probe_dtz()`], but runs on the blocking thread
+    /// pool. See [blocking offload](#blocking-offload).
+    ///
+    /// # Errors
+    ///
+    /// See [`SyzygyError`] for possible error conditions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub async fn probe_dtz_spawn_blocking(
+        self: &Arc<Self>,
+        pos: S,
+    ) -> SyzygyResult<MaybeRounded<Dtz>> {
+        self.spawn_blocking(move |tables| tables.probe_dtz(&pos))
+            .await
+    }
+
+    async fn spawn_blocking<T, F>(self: &Arc<Self>, f: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(&Tablebase<S>) -> T + Send + 'static,
+    {
+        let tables = Arc::clone(self);
+        match tokio::task::spawn_blocking(move || f(&tables)).await {
+            Ok(res) => res,
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        }
+    }
+}
+
 /// WDL entry. Prerequisite for probing DTZ tables.
 #[derive(Debug)]
 struct WdlEntry<'a, S: Position + Clone + Syzygy> {
@@ -755,6 +1736,428 @@ mod tests {
         assert_sync(Tablebase::<Chess>::new());
     }
 
+    #[test]
+    fn test_add_table_bytes() {
+        let mut tables = Tablebase::<Chess>::new();
+
+        let err = tables.add_table_bytes("KQvK.txt", vec![0; 80]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let err = tables
+            .add_table_bytes("KQvK.rtbw", vec![0; 64])
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(tables.max_pieces(), 0);
+
+        static BYTES: [u8; 80] = [0; 80];
+        tables
+            .add_table_bytes("KQvK.rtbw", &BYTES[..])
+            .expect("plausible table");
+        assert_eq!(tables.max_pieces(), 3);
+    }
+
+    #[test]
+    fn test_preload() {
+        static BYTES: [u8; 80] = [0; 80];
+
+        let mut tables = Tablebase::<Chess>::new().with_preload(true);
+        let err = tables
+            .add_table_bytes("KQvK.rtbw", &BYTES[..])
+            .expect_err("invalid magic");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(tables.max_pieces(), 0);
+
+        let mut tables = Tablebase::<Chess>::new();
+        tables
+            .add_table_bytes("KQvK.rtbw", &BYTES[..])
+            .expect("plausible table");
+        assert!(matches!(
+            tables.warmup(),
+            Err(SyzygyError::ProbeFailed {
+                metric: Metric::Wdl,
+                ..
+            })
+        ));
+        assert!(matches!(
+            tables.preload(&"KQvK".parse().unwrap()),
+            Err(SyzygyError::ProbeFailed { .. })
+        ));
+        assert!(matches!(
+            tables.preload(&"KRvK".parse().unwrap()),
+            Err(SyzygyError::MissingTable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tables() {
+        static BYTES: [u8; 80] = [0; 80];
+
+        let mut tables = Tablebase::<Chess>::new();
+        for name in ["KQvK.rtbw", "KvKR.rtbz"] {
+            tables
+                .add_table_bytes(name, &BYTES[..])
+                .expect("plausible table");
+        }
+
+        let mut infos: Vec<_> = tables.tables().collect();
+        infos.sort_by_key(|info| info.metric == Metric::Dtz);
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].metric, Metric::Wdl);
+        assert_eq!(infos[0].material.to_string(), "KQvK");
+        assert_eq!(infos[1].metric, Metric::Dtz);
+        assert_eq!(infos[1].material.to_string(), "KRvK");
+        for info in infos {
+            assert_eq!(info.pieces, 3);
+            assert_eq!(info.path, None);
+            assert_eq!(info.size, 80);
+        }
+    }
+
+    #[test]
+    fn test_remove_tables() {
+        static BYTES: [u8; 80] = [0; 80];
+
+        let mut tables = Tablebase::<Chess>::new();
+        for name in ["KQvK.rtbw", "KQvK.rtbz", "KRPvK.rtbw"] {
+            tables
+                .add_table_bytes(name, &BYTES[..])
+                .expect("plausible table");
+        }
+        assert_eq!(tables.max_pieces(), 4);
+
+        let krpvk: Material = "KRPvK".parse().expect("valid material");
+        assert_eq!(tables.remove_material(&krpvk), 1);
+        assert_eq!(tables.remove_material(&krpvk), 0);
+        assert_eq!(tables.max_pieces(), 3);
+
+        assert_eq!(tables.remove_directory("tables/chess"), 0);
+
+        tables.clear();
+        assert_eq!(tables.max_pieces(), 0);
+    }
+
+    #[test]
+    fn test_missing_tables() {
+        static BYTES: [u8; 80] = [0; 80];
+
+        let mut tables = Tablebase::<Chess>::new();
+        tables
+            .add_table_bytes("KPvK.rtbw", &BYTES[..])
+            .expect("plausible table");
+
+        let pos: Chess = "8/8/8/8/8/4k3/4P3/3RK3 w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Chess960)
+            .expect("legal position");
+
+        let missing: Vec<_> = tables
+            .missing_tables(&pos)
+            .into_iter()
+            .map(|(metric, material)| format!("{metric}:{material}"))
+            .collect();
+        assert_eq!(
+            missing,
+            [
+                "wdl:KQRvK",
+                "wdl:KRBvK",
+                "wdl:KRNvK",
+                "dtz:KRPvK",
+                "wdl:KRPvK",
+                "wdl:KRRvK",
+                "wdl:KBvK",
+                "wdl:KNvK",
+                "wdl:KQvK",
+                "wdl:KRvK",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "checksum")]
+    fn test_verify_checksums() {
+        static BYTES: [u8; 80] = [0; 80];
+
+        let mut tables = Tablebase::<Chess>::new();
+        tables
+            .add_table_bytes("KQvK.rtbw", &BYTES[..])
+            .expect("plausible table");
+        tables
+            .add_table_bytes("KRvK.rtbw", &BYTES[..])
+            .expect("plausible table");
+
+        let failed = tables.verify_checksums(
+            "bbf7c6077962a7c28114dbd10be947cd  KQvK.rtbw\n\
+             fc99f82f1e4e7eba1e98a0e4e5b7a58c  KRvK.rtbw\n",
+        );
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].1.to_string(), "KRvK");
+        assert_eq!(failed[0].2.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let mut tables = Tablebase::<Chess>::new();
+        tables
+            .add_directory("tables/chess")
+            .expect("read directory");
+        assert_eq!(tables.memory_usage(), 0);
+
+        let pos: Chess = "8/8/8/8/8/4k3/8/3QK3 w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Chess960)
+            .expect("legal position");
+        assert!(matches!(tables.probe_wdl_after_zeroing(&pos), Ok(Wdl::Win)));
+        assert!(tables.memory_usage() > 0);
+
+        tables.close_tables();
+        assert_eq!(tables.memory_usage(), 0);
+    }
+
+    #[cfg(feature = "generator")]
+    #[test]
+    fn test_memory_limit() {
+        use crate::generator::generate_wdl;
+
+        let kqvk: Material = "KQvK".parse().expect("valid material");
+        let krvk: Material = "KRvK".parse().expect("valid material");
+        let kqvk_pos: Chess = "8/8/8/8/8/4k3/8/3QK3 w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Chess960)
+            .expect("legal position");
+        let krvk_pos: Chess = "8/8/8/8/8/4k3/8/3RK3 w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Chess960)
+            .expect("legal position");
+
+        let mut tables = Tablebase::<Chess>::new();
+        tables
+            .add_table_bytes("KQvK.rtbw", generate_wdl::<Chess>(&kqvk))
+            .expect("plausible table");
+        tables
+            .add_table_bytes("KRvK.rtbw", generate_wdl::<Chess>(&krvk))
+            .expect("plausible table");
+
+        // Measure tables without a limit.
+        tables.preload(&kqvk).expect("open KQvK");
+        let kqvk_usage = tables.memory_usage();
+        tables.close_tables();
+        tables.preload(&krvk).expect("open KRvK");
+        let krvk_usage = tables.memory_usage();
+        tables.close_tables();
+        assert!(kqvk_usage > 0 && krvk_usage > 0);
+        tables.warmup().expect("open all tables");
+        assert_eq!(tables.memory_usage(), kqvk_usage + krvk_usage);
+
+        // Only one table fits at a time.
+        let limit = kqvk_usage + krvk_usage - 1;
+        let mut tables = tables.with_memory_limit(limit);
+        tables.close_tables();
+
+        assert!(matches!(
+            tables.probe_wdl_after_zeroing(&kqvk_pos),
+            Ok(Wdl::Win)
+        ));
+        assert_eq!(tables.memory_usage(), kqvk_usage);
+        assert!(matches!(
+            tables.probe_wdl_after_zeroing(&krvk_pos),
+            Ok(Wdl::Win)
+        ));
+        assert_eq!(tables.memory_usage(), krvk_usage);
+        assert!(matches!(
+            tables.probe_wdl_after_zeroing(&krvk_pos),
+            Ok(Wdl::Win)
+        ));
+        assert_eq!(tables.memory_usage(), krvk_usage);
+        assert!(matches!(
+            tables.probe_wdl_after_zeroing(&kqvk_pos),
+            Ok(Wdl::Win)
+        ));
+        assert_eq!(tables.memory_usage(), kqvk_usage);
+
+        // The most recently used table stays open, even if it alone
+        // exceeds the limit.
+        let mut tables = tables.with_memory_limit(0);
+        tables.close_tables();
+        assert!(matches!(
+            tables.probe_wdl_after_zeroing(&krvk_pos),
+            Ok(Wdl::Win)
+        ));
+        assert_eq!(tables.memory_usage(), krvk_usage);
+        assert!(matches!(
+            tables.probe_wdl_after_zeroing(&kqvk_pos),
+            Ok(Wdl::Win)
+        ));
+        assert_eq!(tables.memory_usage(), kqvk_usage);
+    }
+
+    #[test]
+    fn test_stats() {
+        static BYTES: [u8; 80] = [0; 80];
+
+        let mut tables = Tablebase::<Chess>::new();
+        assert!(tables.stats().is_none());
+
+        tables = tables.with_stats(true);
+        tables
+            .add_table_bytes("KQvK.rtbw", &BYTES[..])
+            .expect("plausible table");
+
+        let pos: Chess = "8/8/8/8/8/4k3/8/3QK3 w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Chess960)
+            .expect("legal position");
+        assert!(tables.probe_wdl_after_zeroing(&pos).is_err()); // Bad magic
+
+        let stats = tables.stats().expect("stats enabled");
+        assert_eq!(stats.wdl_probes, 1);
+        assert_eq!(stats.dtz_probes, 0);
+        assert_eq!(stats.table_hits, 0);
+        assert_eq!(stats.table_misses, 1);
+        assert_eq!(stats.reads, 1);
+        assert_eq!(stats.bytes_read, 4);
+        assert_eq!(stats.table_accesses.len(), 1);
+    }
+
+    #[test]
+    fn test_custom_filesystem() {
+        struct KeyValueFilesystem;
+
+        impl Filesystem for KeyValueFilesystem {
+            fn regular_file_size(&self, path: &Path) -> io::Result<u64> {
+                match path.to_str() {
+                    Some("blobs/KQvK.rtbw") => Ok(80),
+                    _ => Err(io::ErrorKind::NotFound.into()),
+                }
+            }
+
+            fn open(&self, _path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
+                Err(io::ErrorKind::Unsupported.into())
+            }
+        }
+
+        let mut tables = Tablebase::<Chess>::with_filesystem(Arc::new(KeyValueFilesystem));
+        assert_eq!(
+            tables.add_directory("blobs").unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+        tables.add_file("blobs/KQvK.rtbw").expect("plausible table");
+        assert_eq!(tables.max_pieces(), 3);
+    }
+
+    #[test]
+    fn test_probe_wdl_many() {
+        let mut tables = Tablebase::new();
+        tables
+            .add_directory("tables/chess")
+            .expect("read directory");
+
+        let positions: Vec<Chess> = [
+            "8/8/8/8/8/4k3/8/3QK3 w - - 0 1",
+            "8/8/8/8/8/4k3/8/3QK3 b - - 0 1",
+            "8/8/8/8/8/4k3/8/4K3 w - - 0 1",
+        ]
+        .into_iter()
+        .map(|fen| {
+            fen.parse::<Fen>()
+                .expect("valid fen")
+                .into_position(CastlingMode::Chess960)
+                .expect("legal position")
+        })
+        .collect();
+
+        let wdls: Vec<_> = tables
+            .probe_wdl_many(&positions)
+            .into_iter()
+            .map(|wdl| wdl.expect("probe"))
+            .collect();
+        assert_eq!(
+            wdls,
+            [AmbiguousWdl::Win, AmbiguousWdl::Loss, AmbiguousWdl::Draw]
+        );
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(
+            tables
+                .par_probe_wdl_many(&positions)
+                .into_iter()
+                .map(|wdl| wdl.expect("probe"))
+                .collect::<Vec<_>>(),
+            wdls
+        );
+    }
+
+    #[test]
+    fn test_trivial_draws() {
+        let pos: Chess = "8/8/8/8/8/4k3/8/3BK3 w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Chess960)
+            .expect("legal position");
+
+        let tables = Tablebase::<Chess>::new();
+        assert!(matches!(
+            tables.probe_wdl_after_zeroing(&pos),
+            Err(SyzygyError::TooManyPieces)
+        ));
+
+        let tables = tables.with_trivial_draws(true);
+        assert!(matches!(
+            tables.probe_wdl_after_zeroing(&pos),
+            Ok(Wdl::Draw)
+        ));
+        assert!(matches!(
+            tables.probe_dtz(&pos),
+            Ok(MaybeRounded::Precise(Dtz(0)))
+        ));
+    }
+
+    #[test]
+    fn test_kpk_fallback() {
+        let tables = Tablebase::<Chess>::new();
+
+        for (fen, expected) in [
+            ("4k3/8/4K3/4P3/8/8/8/8 w - - 0 1", Wdl::Win),
+            ("4k3/8/4K3/4P3/8/8/8/8 b - - 0 1", Wdl::Loss),
+            ("8/8/8/8/4p3/4k3/8/4K3 b - - 0 1", Wdl::Win),
+            ("k7/8/K7/P7/8/8/8/8 w - - 0 1", Wdl::Draw),
+        ] {
+            let pos: Chess = fen
+                .parse::<Fen>()
+                .expect("valid fen")
+                .into_position(CastlingMode::Chess960)
+                .expect("legal position");
+            assert_eq!(
+                tables.probe_wdl_after_zeroing(&pos).expect("bitbase"),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_probe_dtz_exact() {
+        let mut tables = Tablebase::new();
+        tables
+            .add_directory("tables/chess")
+            .expect("read directory");
+
+        let pos: Chess = "5BrN/8/8/8/8/2k5/8/2K5 b - -"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Chess960)
+            .expect("legal position");
+
+        let exact = tables.probe_dtz_exact(&pos).expect("probe dtz");
+        match tables.probe_dtz(&pos).expect("probe dtz") {
+            MaybeRounded::Precise(dtz) => assert_eq!(exact, dtz),
+            MaybeRounded::Rounded(dtz) => assert!(exact == dtz || exact == dtz.add_plies(1)),
+        }
+    }
+
     #[test]
     fn test_mating_best_move() {
         let mut tables = Tablebase::new();
@@ -783,6 +2186,38 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_mating_probe_root() {
+        let mut tables = Tablebase::new();
+        tables
+            .add_directory("tables/chess")
+            .expect("read directory");
+
+        let pos: Chess = "5BrN/8/8/8/8/2k5/8/2K5 b - -"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Chess960)
+            .expect("legal position");
+
+        let root_moves = tables.probe_root(&pos).expect("probe root");
+        assert_eq!(root_moves.len(), pos.legal_moves().len());
+        assert!(matches!(
+            root_moves[0],
+            RootMove {
+                m: Move::Normal {
+                    role: Role::Rook,
+                    from: Square::G8,
+                    capture: None,
+                    to: Square::G1,
+                    promotion: None,
+                },
+                wdl: AmbiguousWdl::Win,
+                dtz: MaybeRounded::Precise(Dtz(1)),
+                game_over: true,
+            }
+        ));
+    }
+
     #[test]
     fn test_black_escapes_via_underpromotion() {
         let mut tables = Tablebase::new();
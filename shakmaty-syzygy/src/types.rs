@@ -1,7 +1,7 @@
 use std::{fmt, ops::Neg};
 
 use arrayvec::ArrayVec;
-use shakmaty::{Chess, Color, KnownOutcome, Piece};
+use shakmaty::{Chess, Color, KnownOutcome, Move, Piece};
 
 /// File extension and magic header bytes of Syzygy tables.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -13,6 +13,11 @@ pub struct TableType {
 }
 
 /// A chess variant with Syzygy support.
+///
+/// Implemented for the variants with published table files: standard chess,
+/// Atomic chess, and Antichess. No table generator produces files for
+/// other variants like Racing Kings or Horde, so there is no format to
+/// support.
 pub trait Syzygy {
     /// WDL table type.
     const TBW: TableType;
@@ -633,6 +638,34 @@ impl Neg for Dtz {
     }
 }
 
+/// A legal move annotated with tablebase values, as returned by
+/// [`Tablebase::probe_root()`](crate::Tablebase::probe_root).
+///
+/// Values are from the point of view of the side playing the move, i.e.,
+/// they describe the position *before* the move, assuming that `m` is
+/// played.
+#[derive(Debug, Copy, Clone)]
+pub struct RootMove {
+    /// The legal move.
+    pub m: Move,
+    /// WDL value when playing `m`, considering the halfmove counter of the
+    /// root position. The result may be
+    /// [ambiguous due to DTZ rounding](MaybeRounded).
+    pub wdl: AmbiguousWdl,
+    /// DTZ value when playing `m`. Zeroing moves have a DTZ of `1`, `101`,
+    /// `0`, `-101` or `-1`, respectively.
+    pub dtz: MaybeRounded<Dtz>,
+    /// Whether `m` immediately ends the game (e.g., checkmate).
+    pub game_over: bool,
+}
+
+impl RootMove {
+    /// Returns `true` if `m` is zeroing the halfmove counter.
+    pub fn is_zeroing(&self) -> bool {
+        self.m.is_zeroing()
+    }
+}
+
 /// Syzygy tables are available for up to 7 pieces.
 pub const MAX_PIECES: usize = 7;
 
@@ -0,0 +1,69 @@
+//! Wall-clock benchmarks of slider attack lookups, to compare the default
+//! magic bitboard tables with the `compact-attacks` feature:
+//!
+//! ```text
+//! cargo bench -p shakmaty --bench attacks
+//! cargo bench -p shakmaty --bench attacks --features compact-attacks
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use shakmaty::{Bitboard, Chess, Square, attacks, perft};
+
+/// Pseudo-random squares and occupancies, so that lookups are spread over
+/// the whole table, like in a search.
+fn samples() -> Vec<(Square, Bitboard)> {
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+    (0..4096)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let sq = Square::new((state % 64) as u32);
+            let occupied = Bitboard(state & state.rotate_left(17) & state.rotate_left(31));
+            (sq, occupied)
+        })
+        .collect()
+}
+
+fn bench_rook_attacks(c: &mut Criterion) {
+    let samples = samples();
+    c.bench_function("rook_attacks", |b| {
+        b.iter(|| {
+            samples
+                .iter()
+                .fold(Bitboard::EMPTY, |acc, &(sq, occupied)| {
+                    acc ^ attacks::rook_attacks(black_box(sq), black_box(occupied))
+                })
+        })
+    });
+}
+
+fn bench_bishop_attacks(c: &mut Criterion) {
+    let samples = samples();
+    c.bench_function("bishop_attacks", |b| {
+        b.iter(|| {
+            samples
+                .iter()
+                .fold(Bitboard::EMPTY, |acc, &(sq, occupied)| {
+                    acc ^ attacks::bishop_attacks(black_box(sq), black_box(occupied))
+                })
+        })
+    });
+}
+
+fn bench_perft(c: &mut Criterion) {
+    let pos = Chess::default();
+    c.bench_function("perft_4", |b| {
+        b.iter(|| assert_eq!(perft(black_box(&pos), 4), 197_281))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_rook_attacks,
+    bench_bishop_attacks,
+    bench_perft
+);
+criterion_main!(benches);
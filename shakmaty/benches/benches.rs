@@ -2,7 +2,8 @@ use std::hint::black_box;
 
 use iai_callgrind::{library_benchmark, library_benchmark_group, main};
 use shakmaty::{
-    CastlingMode, Chess, EnPassantMode, Move, Position, Role, Setup, Square,
+    Bitboard, Board, ByColor, ByRole, CastlingMode, Chess, EnPassantMode, Move, Position, Role,
+    Setup, Square, attacks,
     fen::Fen,
     packed::{PackedSetup, PackedUciMove},
     perft,
@@ -89,6 +90,32 @@ fn bench_san_roundtrip() {
     }
 }
 
+#[library_benchmark]
+fn bench_san_to_move() -> usize {
+    let pos: Chess = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+        .parse::<Fen>()
+        .expect("valid fen")
+        .into_position(CastlingMode::Standard)
+        .expect("legal position");
+    let sans = [
+        "Nxf7", "Nxg6", "Nd7", "Nc4", "Qxf6", "Qxh3", "Qg3", "Bxa6", "Bh6", "Rb1", "gxh3", "d6",
+        "a4", "Nb5", "O-O", "Kd1", "Ndxf7", "Raxb1",
+    ];
+
+    let mut legal = 0;
+    for san in black_box(sans) {
+        if let Ok(m) = san
+            .parse::<San>()
+            .expect("valid san")
+            .to_move(black_box(&pos))
+        {
+            black_box(m);
+            legal += 1;
+        }
+    }
+    legal
+}
+
 #[library_benchmark]
 fn bench_zobrist_hash() -> Zobrist64 {
     black_box(Chess::default()).zobrist_hash(EnPassantMode::Legal)
@@ -129,6 +156,47 @@ fn bench_packed_uci_roundtrip() {
     }
 }
 
+#[library_benchmark]
+fn bench_slider_attacks() -> Bitboard {
+    let mut occupied = Bitboard(0x9e37_79b9_7f4a_7c15);
+    let mut acc = Bitboard::EMPTY;
+    for _ in 0..16 {
+        for sq in Square::ALL {
+            acc ^= attacks::queen_attacks(black_box(sq), black_box(occupied));
+        }
+        occupied = occupied.shift(1) ^ acc;
+    }
+    acc
+}
+
+fn material_boards() -> Vec<Board> {
+    let fens = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R",
+        "rnbqkb1r/1p3ppp/p2p1n2/4p3/3NP3/2N1B3/PPP2PPP/R2QKB1R",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8",
+    ];
+    fens.iter()
+        .cycle()
+        .take(256)
+        .map(|fen| fen.parse().expect("valid board fen"))
+        .collect()
+}
+
+#[library_benchmark]
+#[bench::boards(setup = material_boards)]
+fn bench_material_batch(boards: Vec<Board>) -> Vec<ByColor<ByRole<u8>>> {
+    let mut material = vec![ByColor::default(); boards.len()];
+    Board::material_batch(black_box(&boards), &mut material);
+    material
+}
+
+#[library_benchmark]
+#[bench::boards(setup = material_boards)]
+fn bench_material_scalar(boards: Vec<Board>) -> Vec<ByColor<ByRole<u8>>> {
+    black_box(&boards).iter().map(Board::material).collect()
+}
+
 library_benchmark_group!(
     name = benches;
     benchmarks =
@@ -136,11 +204,15 @@ library_benchmark_group!(
         bench_kiwipete,
         bench_play_unchecked,
         bench_play_sans,
+        bench_san_to_move,
         bench_zobrist_hash,
         bench_san_roundtrip,
         bench_fen_roundtrip,
         bench_packed_setup_roundtrip,
         bench_packed_uci_roundtrip,
+        bench_slider_attacks,
+        bench_material_batch,
+        bench_material_scalar,
 );
 
 main!(library_benchmark_groups = benches);
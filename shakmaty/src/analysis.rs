@@ -0,0 +1,468 @@
+//! Analysis results, as reported by engines and exchanged via JSON APIs and
+//! PGN comments.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::analysis::{Depth, PgnEval, Score};
+//!
+//! let eval = PgnEval {
+//!     score: Score::Cp(-17),
+//!     depth: Some(Depth(24)),
+//! };
+//! assert_eq!(eval.to_string(), "[%eval -0.17,24]");
+//!
+//! let parsed = PgnEval::from_comment("Inaccuracy. [%eval -0.17,24] [%clk 0:01:00]");
+//! assert_eq!(parsed, Some(eval));
+//! ```
+
+use alloc::vec::Vec;
+use core::{error, fmt, ops::Neg, str::FromStr};
+
+use crate::{Color, uci::UciMove};
+
+/// Error when parsing an invalid score, principal variation or evaluation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseAnalysisError;
+
+impl fmt::Display for ParseAnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid analysis")
+    }
+}
+
+impl error::Error for ParseAnalysisError {}
+
+/// Search depth in plies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Depth(pub u32);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Depth {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Depth {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <u32 as serde::Deserialize>::deserialize(deserializer).map(Depth)
+    }
+}
+
+/// An evaluation from the point of view of the side to move.
+///
+/// # Display
+///
+/// Formatted as in UCI `info` lines, e.g., `cp 17` or `mate -3`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Score {
+    /// Score in centipawns.
+    Cp(i32),
+    /// Mate in the given number of moves (not plies). Negative if the side to
+    /// move is getting mated.
+    Mate(i32),
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Score::Cp(cp) => write!(f, "cp {cp}"),
+            Score::Mate(mate) => write!(f, "mate {mate}"),
+        }
+    }
+}
+
+/// Changes the point of view to the other side. Saturates at the bounds of
+/// [`i32`], so that `i32::MIN` does not overflow.
+impl Neg for Score {
+    type Output = Score;
+
+    fn neg(self) -> Score {
+        match self {
+            Score::Cp(cp) => Score::Cp(cp.saturating_neg()),
+            Score::Mate(mate) => Score::Mate(mate.saturating_neg()),
+        }
+    }
+}
+
+impl FromStr for Score {
+    type Err = ParseAnalysisError;
+
+    fn from_str(s: &str) -> Result<Score, ParseAnalysisError> {
+        let (kind, value) = s.split_once(' ').ok_or(ParseAnalysisError)?;
+        let value = value.parse().map_err(|_| ParseAnalysisError)?;
+        match kind {
+            "cp" => Ok(Score::Cp(value)),
+            "mate" => Ok(Score::Mate(value)),
+            _ => Err(ParseAnalysisError),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Score {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Score {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ScoreVisitor;
+
+        impl serde::de::Visitor<'_> for ScoreVisitor {
+            type Value = Score;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("score string")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Score, E>
+            where
+                E: serde::de::Error,
+            {
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ScoreVisitor)
+    }
+}
+
+/// A principal variation.
+///
+/// # Display
+///
+/// Formatted as space separated moves in UCI notation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Pv(pub Vec<UciMove>);
+
+impl fmt::Display for Pv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, m) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            fmt::Display::fmt(m, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Pv {
+    type Err = ParseAnalysisError;
+
+    fn from_str(s: &str) -> Result<Pv, ParseAnalysisError> {
+        s.split_ascii_whitespace()
+            .map(|m| m.parse().map_err(|_| ParseAnalysisError))
+            .collect::<Result<_, _>>()
+            .map(Pv)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pv {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Pv {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct PvVisitor;
+
+        impl serde::de::Visitor<'_> for PvVisitor {
+            type Value = Pv;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("space separated UCI moves")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Pv, E>
+            where
+                E: serde::de::Error,
+            {
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_str(PvVisitor)
+    }
+}
+
+/// One line of a (multi-PV) analysis.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnalysisLine {
+    /// 1-based index of the line.
+    pub multipv: u32,
+    /// Search depth, if reported.
+    pub depth: Option<Depth>,
+    /// Score from the point of view of the side to move.
+    pub score: Score,
+    /// Principal variation, starting with the best move.
+    pub pv: Pv,
+}
+
+impl AnalysisLine {
+    /// Gets the evaluation for a PGN comment, given the side to move in
+    /// the analysed position. The score is converted to the point of view
+    /// of White.
+    pub fn pgn_eval(&self, turn: Color) -> PgnEval {
+        PgnEval {
+            score: turn.fold_wb(self.score, -self.score),
+            depth: self.depth,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AnalysisLine {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct as _;
+
+        let mut line = serializer.serialize_struct("AnalysisLine", 4)?;
+        line.serialize_field("multipv", &self.multipv)?;
+        line.serialize_field("depth", &self.depth)?;
+        line.serialize_field("score", &self.score)?;
+        line.serialize_field("pv", &self.pv)?;
+        line.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AnalysisLine {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &["multipv", "depth", "score", "pv"];
+
+        struct AnalysisLineVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for AnalysisLineVisitor {
+            type Value = AnalysisLine;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("analysis line")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<AnalysisLine, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                use serde::de::{Error as _, IgnoredAny};
+
+                let mut multipv = None;
+                let mut depth = None;
+                let mut score = None;
+                let mut pv = None;
+                while let Some(key) = map.next_key::<alloc::string::String>()? {
+                    match key.as_str() {
+                        "multipv" => multipv = Some(map.next_value()?),
+                        "depth" => depth = map.next_value()?,
+                        "score" => score = Some(map.next_value()?),
+                        "pv" => pv = Some(map.next_value()?),
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(AnalysisLine {
+                    multipv: multipv.unwrap_or(1),
+                    depth,
+                    score: score.ok_or_else(|| A::Error::missing_field("score"))?,
+                    pv: pv.unwrap_or_default(),
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("AnalysisLine", FIELDS, AnalysisLineVisitor)
+    }
+}
+
+/// An evaluation in a PGN comment, like `[%eval 0.17]`, `[%eval #-3]`, or
+/// `[%eval 0.17,24]` with depth.
+///
+/// Unlike engine output, the score is from the point of view of White.
+/// Centipawn scores are written in pawns.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PgnEval {
+    /// Score from the point of view of White.
+    pub score: Score,
+    /// Search depth, if given.
+    pub depth: Option<Depth>,
+}
+
+impl PgnEval {
+    /// Finds and parses the first `[%eval ...]` command in the text of a
+    /// PGN comment.
+    pub fn from_comment(comment: &str) -> Option<PgnEval> {
+        let (_, tail) = comment.split_once("[%eval ")?;
+        let (value, _) = tail.split_once(']')?;
+        value.trim().parse().ok()
+    }
+}
+
+fn parse_pawns(s: &str) -> Option<i32> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if (int.is_empty() && frac.is_empty())
+        || !int
+            .bytes()
+            .chain(frac.bytes())
+            .all(|digit| digit.is_ascii_digit())
+    {
+        return None;
+    }
+    // Truncate to centipawns.
+    let mut cp: i32 = 0;
+    for digit in int.bytes().chain(frac.bytes().chain(*b"00").take(2)) {
+        cp = cp.checked_mul(10)?.checked_add(i32::from(digit - b'0'))?;
+    }
+    Some(if negative { -cp } else { cp })
+}
+
+impl FromStr for PgnEval {
+    type Err = ParseAnalysisError;
+
+    fn from_str(s: &str) -> Result<PgnEval, ParseAnalysisError> {
+        let (score, depth) = match s.split_once(',') {
+            Some((score, depth)) => (
+                score,
+                Some(Depth(depth.parse().map_err(|_| ParseAnalysisError)?)),
+            ),
+            None => (s, None),
+        };
+        let score = match score.strip_prefix('#') {
+            Some(mate) => Score::Mate(mate.parse().map_err(|_| ParseAnalysisError)?),
+            None => Score::Cp(parse_pawns(score).ok_or(ParseAnalysisError)?),
+        };
+        Ok(PgnEval { score, depth })
+    }
+}
+
+impl fmt::Display for PgnEval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[%eval ")?;
+        match self.score {
+            Score::Cp(cp) => {
+                let sign = if cp < 0 { "-" } else { "" };
+                let cp = cp.unsigned_abs();
+                write!(f, "{}{}.{:02}", sign, cp / 100, cp % 100)?;
+            }
+            Score::Mate(mate) => write!(f, "#{mate}")?,
+        }
+        if let Some(Depth(depth)) = self.depth {
+            write!(f, ",{depth}")?;
+        }
+        f.write_str("]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        for score in [
+            Score::Cp(0),
+            Score::Cp(-17),
+            Score::Mate(3),
+            Score::Mate(-1),
+        ] {
+            assert_eq!(score.to_string().parse(), Ok(score));
+        }
+        assert_eq!(-Score::Cp(i32::MIN), Score::Cp(i32::MAX));
+        assert_eq!(-Score::Mate(i32::MIN), Score::Mate(i32::MAX));
+        assert!("cp".parse::<Score>().is_err());
+        assert!("mate x".parse::<Score>().is_err());
+    }
+
+    #[test]
+    fn test_pgn_eval() {
+        for (s, score) in [
+            ("0.17", Score::Cp(17)),
+            ("-0.05", Score::Cp(-5)),
+            ("+1.5", Score::Cp(150)),
+            ("-12", Score::Cp(-1200)),
+            (".5", Score::Cp(50)),
+            ("3.141", Score::Cp(314)),
+            ("#-3", Score::Mate(-3)),
+            ("#0", Score::Mate(0)),
+        ] {
+            assert_eq!(
+                s.parse::<PgnEval>().map(|eval| eval.score),
+                Ok(score),
+                "{s}"
+            );
+        }
+        for s in ["", "-", ".", "1.x", "#", "a"] {
+            assert!(s.parse::<PgnEval>().is_err(), "{s}");
+        }
+
+        let eval = PgnEval {
+            score: Score::Cp(-150),
+            depth: None,
+        };
+        assert_eq!(eval.to_string(), "[%eval -1.50]");
+        assert_eq!(PgnEval::from_comment(&eval.to_string()), Some(eval));
+        assert_eq!(
+            PgnEval::from_comment("[%clk 0:00:10] [%eval #4,30]"),
+            Some(PgnEval {
+                score: Score::Mate(4),
+                depth: Some(Depth(30))
+            })
+        );
+        assert_eq!(PgnEval::from_comment("no eval"), None);
+    }
+
+    #[test]
+    fn test_analysis_line_pgn_eval() {
+        let line = AnalysisLine {
+            multipv: 1,
+            depth: Some(Depth(20)),
+            score: Score::Cp(35),
+            pv: Pv::default(),
+        };
+        assert_eq!(line.pgn_eval(Color::White).to_string(), "[%eval 0.35,20]");
+        assert_eq!(line.pgn_eval(Color::Black).to_string(), "[%eval -0.35,20]");
+
+        let line = AnalysisLine {
+            score: Score::Mate(-2),
+            ..line
+        };
+        assert_eq!(line.pgn_eval(Color::White).score, Score::Mate(-2));
+        assert_eq!(line.pgn_eval(Color::Black).score, Score::Mate(2));
+    }
+}
@@ -19,15 +19,48 @@
 //! assert!(attacks.contains(Square::G6));
 //! assert!(!attacks.contains(Square::H7));
 //! ```
-
+//!
+//! # Tables
+//!
+//! By default, all tables are computed at compile time and stored in static
+//! memory, about 730 KiB in total, most of it for the magic bitboards of
+//! sliding pieces. There is no initialization at runtime, and all lookups
+//! are `const fn`.
+//!
+//! The magic bitboards already use overlapping fixed shift tables
+//! (about 690 KiB). Black magics would only save a few more KiB, which
+//! does not change how the table fits into caches. Instead, the
+//! `compact-attacks` feature replaces magic bitboards with hyperbola
+//! quintessence for files and diagonals, and a 512 byte lookup table for
+//! ranks, bringing the total below 80 KiB. This is slower in isolation
+//! (about 3 times for random lookups, and about 6% in perft, see
+//! `benches/attacks.rs`), so magic bitboards remain the default. It
+//! may pay off when other hot data competes for L2 cache, for example in
+//! engines with large transposition tables or many threads per core.
+//! Results are identical either way.
+//!
+//! The `lazy-attacks` feature adds the [`lazy`] module, with separate
+//! lookups for sliding pieces that compute the magic bitboards at runtime,
+//! on the heap, on first use or when [`lazy::init()`] is called. They are
+//! not `const fn`, and the lookups in this module are not affected. The
+//! static table is only linked if it is used, for example by the move
+//! generation of this crate, so the lazy lookups can keep it out of
+//! binaries that only need attack lookups.
+
+#[cfg(any(not(feature = "compact-attacks"), feature = "lazy-attacks"))]
 use core::hint::assert_unchecked;
 
 use crate::{Bitboard, ByColor, Color, Piece, Role, Square};
 
+#[cfg_attr(
+    all(feature = "compact-attacks", not(feature = "lazy-attacks")),
+    allow(dead_code)
+)] // Only used by magics and tests
 const ROOK_DELTAS: [i32; 4] = [8, 1, -8, -1];
 const BISHOP_DELTAS: [i32; 4] = [9, 7, -9, -7];
 const KING_DELTAS: [i32; 8] = [9, 8, 7, 1, -9, -8, -7, -1];
 const KNIGHT_DELTAS: [i32; 8] = [17, 15, 10, 6, -17, -15, -10, -6];
+const ALFIL_DELTAS: [i32; 4] = [18, 14, -18, -14];
 const WHITE_PAWN_DELTAS: [i32; 2] = [7, 9];
 const BLACK_PAWN_DELTAS: [i32; 2] = [-7, -9];
 
@@ -69,6 +102,8 @@ const fn bootstrap_stepping_attacks(deltas: &[i32]) -> [u64; 64] {
 
 static KNIGHT_ATTACKS: [u64; 64] = bootstrap_stepping_attacks(&KNIGHT_DELTAS);
 static KING_ATTACKS: [u64; 64] = bootstrap_stepping_attacks(&KING_DELTAS);
+static FERZ_ATTACKS: [u64; 64] = bootstrap_stepping_attacks(&BISHOP_DELTAS);
+static ALFIL_ATTACKS: [u64; 64] = bootstrap_stepping_attacks(&ALFIL_DELTAS);
 static PAWN_ATTACKS: ByColor<[u64; 64]> = ByColor {
     white: bootstrap_stepping_attacks(&WHITE_PAWN_DELTAS),
     black: bootstrap_stepping_attacks(&BLACK_PAWN_DELTAS),
@@ -92,6 +127,21 @@ pub const fn king_attacks(sq: Square) -> Bitboard {
     Bitboard(KING_ATTACKS[sq.to_usize()])
 }
 
+/// Looks up attacks for a ferz (one step diagonally, like the queen in
+/// Shatranj) on `sq`.
+#[inline]
+pub const fn ferz_attacks(sq: Square) -> Bitboard {
+    Bitboard(FERZ_ATTACKS[sq.to_usize()])
+}
+
+/// Looks up attacks for an alfil (jumping two steps diagonally, like the
+/// bishop in Shatranj) on `sq`.
+#[inline]
+pub const fn alfil_attacks(sq: Square) -> Bitboard {
+    Bitboard(ALFIL_ATTACKS[sq.to_usize()])
+}
+
+#[cfg(any(not(feature = "compact-attacks"), feature = "lazy-attacks"))]
 struct Magic {
     pub mask: u64,
     pub factor: u64,
@@ -101,6 +151,7 @@ struct Magic {
 // Fixed shift white magics found by Volker Annuss.
 // From: http://www.talkchess.com/forum/viewtopic.php?p=727500&t=64790
 
+#[cfg(any(not(feature = "compact-attacks"), feature = "lazy-attacks"))]
 #[rustfmt::skip]
 static ROOK_MAGICS: [Magic; 64] = [
     Magic { mask: 0x0001_0101_0101_017e, factor: 0x0028_0077_ffeb_fffe, offset: 26304 },
@@ -169,6 +220,7 @@ static ROOK_MAGICS: [Magic; 64] = [
     Magic { mask: 0x7e80_8080_8080_8000, factor: 0x0001_ffff_9dff_a333, offset: 14826 },
 ];
 
+#[cfg(any(not(feature = "compact-attacks"), feature = "lazy-attacks"))]
 #[rustfmt::skip]
 static BISHOP_MAGICS: [Magic; 64] = [
     Magic { mask: 0x0040_2010_0804_0200, factor: 0x007f_bfbf_bfbf_bfff, offset:  5378 },
@@ -237,8 +289,8 @@ static BISHOP_MAGICS: [Magic; 64] = [
     Magic { mask: 0x0040_2010_0804_0200, factor: 0x007f_ff9f_df7f_f813, offset: 16076 },
 ];
 
-const fn bootstrap_magics() -> [u64; 88772] {
-    let mut table = [0; 88772];
+#[cfg(any(not(feature = "compact-attacks"), feature = "lazy-attacks"))]
+const fn fill_magics(table: &mut [u64; 88772]) {
     let mut square = 0;
     while square < 64 {
         let magic = &BISHOP_MAGICS[square as usize];
@@ -271,35 +323,174 @@ const fn bootstrap_magics() -> [u64; 88772] {
 
         square += 1;
     }
-    table
 }
 
-static ATTACKS: [u64; 88772] = bootstrap_magics();
+#[cfg(not(feature = "compact-attacks"))]
+static ATTACKS: [u64; 88772] = {
+    let mut table = [0; 88772];
+    fill_magics(&mut table);
+    table
+};
 
-/// Looks up attacks for a rook on `sq` with `occupied` squares.
+#[cfg(any(not(feature = "compact-attacks"), feature = "lazy-attacks"))]
 #[inline]
-pub const fn rook_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
-    let m = &ROOK_MAGICS[sq.to_usize()];
-
+const fn magic_lookup(table: &[u64; 88772], m: &Magic, bits: u32, occupied: Bitboard) -> Bitboard {
     // Safety: The attack table was generated with sufficient size
     // for all relevant occupancies (all subsets of m.mask). Omitting bounds
     // checks is worth about 2% in move generation and perft.
-    let idx = (m.factor.wrapping_mul(occupied.0 & m.mask) >> (64 - 12)) as usize + m.offset;
-    unsafe { assert_unchecked(idx < ATTACKS.len()) };
-    Bitboard(ATTACKS[idx])
+    let idx = (m.factor.wrapping_mul(occupied.0 & m.mask) >> (64 - bits)) as usize + m.offset;
+    unsafe { assert_unchecked(idx < table.len()) };
+    Bitboard(table[idx])
+}
+
+/// Looks up attacks for a rook on `sq` with `occupied` squares.
+#[cfg(not(feature = "compact-attacks"))]
+#[inline]
+pub const fn rook_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+    magic_lookup(&ATTACKS, &ROOK_MAGICS[sq.to_usize()], 12, occupied)
 }
 
 /// Looks up attacks for a bishop on `sq` with `occupied` squares.
+#[cfg(not(feature = "compact-attacks"))]
 #[inline]
 pub const fn bishop_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
-    let m = &BISHOP_MAGICS[sq.to_usize()];
+    magic_lookup(&ATTACKS, &BISHOP_MAGICS[sq.to_usize()], 9, occupied)
+}
 
-    // Safety: The attack table was generated with sufficient size
-    // for all relevant occupancies (all subsets of m.mask). Omitting bounds
-    // checks is worth about 2% in move generation and perft.
-    let idx = (m.factor.wrapping_mul(occupied.0 & m.mask) >> (64 - 9)) as usize + m.offset;
-    unsafe { assert_unchecked(idx < ATTACKS.len()) };
-    Bitboard(ATTACKS[idx])
+/// Sliding attack lookups with magic bitboards that are computed at runtime.
+///
+/// See [Tables](super#tables).
+#[cfg(feature = "lazy-attacks")]
+pub mod lazy {
+    use std::{boxed::Box, sync::OnceLock, vec};
+
+    use super::{BISHOP_MAGICS, ROOK_MAGICS, fill_magics, magic_lookup};
+    use crate::{Bitboard, Square};
+
+    static ATTACKS: OnceLock<Box<[u64; 88772]>> = OnceLock::new();
+
+    #[inline]
+    fn table() -> &'static [u64; 88772] {
+        ATTACKS.get_or_init(|| {
+            // Fill on the heap, to avoid a large temporary on the stack.
+            let mut table: Box<[u64; 88772]> = vec![0; 88772]
+                .into_boxed_slice()
+                .try_into()
+                .expect("table size");
+            fill_magics(&mut table);
+            table
+        })
+    }
+
+    /// Computes the tables, unless already done.
+    ///
+    /// Tables are also computed on the first lookup, so calling this is
+    /// optional. It allows paying the startup cost of a few milliseconds
+    /// at a chosen time.
+    pub fn init() {
+        table();
+    }
+
+    /// Looks up attacks for a rook on `sq` with `occupied` squares.
+    #[inline]
+    pub fn rook_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+        magic_lookup(table(), &ROOK_MAGICS[sq.to_usize()], 12, occupied)
+    }
+
+    /// Looks up attacks for a bishop on `sq` with `occupied` squares.
+    #[inline]
+    pub fn bishop_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+        magic_lookup(table(), &BISHOP_MAGICS[sq.to_usize()], 9, occupied)
+    }
+
+    /// Looks up attacks for a queen on `sq` with `occupied` squares.
+    #[inline]
+    pub fn queen_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+        rook_attacks(sq, occupied) ^ bishop_attacks(sq, occupied)
+    }
+}
+
+#[cfg(feature = "compact-attacks")]
+struct LineMasks {
+    file: u64,
+    diagonal: u64,
+    anti_diagonal: u64,
+}
+
+#[cfg(feature = "compact-attacks")]
+const fn bootstrap_line_masks() -> [LineMasks; 64] {
+    let mut table = [const {
+        LineMasks {
+            file: 0,
+            diagonal: 0,
+            anti_diagonal: 0,
+        }
+    }; 64];
+    let mut sq = 0;
+    while sq < 64 {
+        table[sq] = LineMasks {
+            file: sliding_attacks(sq as i32, 0, &[8, -8]),
+            diagonal: sliding_attacks(sq as i32, 0, &[9, -9]),
+            anti_diagonal: sliding_attacks(sq as i32, 0, &[7, -7]),
+        };
+        sq += 1;
+    }
+    table
+}
+
+#[cfg(feature = "compact-attacks")]
+const fn bootstrap_rank_attacks() -> [[u8; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut file = 0;
+    while file < 8 {
+        let mut inner = 0;
+        while inner < 64 {
+            table[file][inner] = sliding_attacks(file as i32, (inner as u64) << 1, &[1, -1]) as u8;
+            inner += 1;
+        }
+        file += 1;
+    }
+    table
+}
+
+#[cfg(feature = "compact-attacks")]
+static LINE_MASKS: [LineMasks; 64] = bootstrap_line_masks();
+
+// Rank attacks indexed by file and the 6 inner occupancy bits of the rank.
+#[cfg(feature = "compact-attacks")]
+static RANK_ATTACKS: [[u8; 64]; 8] = bootstrap_rank_attacks();
+
+/// Hyperbola quintessence: Attacks along a file or diagonal `mask`
+/// (excluding `sq`), computed by subtraction in both directions.
+#[cfg(feature = "compact-attacks")]
+#[inline]
+const fn line_attacks(sq: Square, occupied: u64, mask: u64) -> u64 {
+    let bit: u64 = 1 << sq.to_u32();
+    let forward = occupied & mask;
+    let reverse = forward.swap_bytes();
+    let forward = forward.wrapping_sub(bit << 1);
+    let reverse = reverse.wrapping_sub(bit.swap_bytes() << 1);
+    (forward ^ reverse.swap_bytes()) & mask
+}
+
+/// Looks up attacks for a rook on `sq` with `occupied` squares.
+#[cfg(feature = "compact-attacks")]
+#[inline]
+pub const fn rook_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+    let shift = sq.to_u32() & !7;
+    let inner = ((occupied.0 >> (shift + 1)) & 0x3f) as usize;
+    let rank = (RANK_ATTACKS[(sq.to_u32() & 7) as usize][inner] as u64) << shift;
+    Bitboard(line_attacks(sq, occupied.0, LINE_MASKS[sq.to_usize()].file) | rank)
+}
+
+/// Looks up attacks for a bishop on `sq` with `occupied` squares.
+#[cfg(feature = "compact-attacks")]
+#[inline]
+pub const fn bishop_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+    let m = &LINE_MASKS[sq.to_usize()];
+    Bitboard(
+        line_attacks(sq, occupied.0, m.diagonal) | line_attacks(sq, occupied.0, m.anti_diagonal),
+    )
 }
 
 /// Looks up attacks for a queen on `sq` with `occupied` squares.
@@ -431,4 +622,65 @@ mod tests {
             Bitboard(0x0008_3708_0800_0000)
         );
     }
+
+    #[test]
+    #[cfg(feature = "lazy-attacks")]
+    fn test_lazy() {
+        lazy::init();
+        lazy::init();
+        let mut occupied = 0x9e37_79b9_7f4a_7c15_u64;
+        for _ in 0..16 {
+            for sq in Square::ALL {
+                let occupied = Bitboard(occupied);
+                assert_eq!(lazy::rook_attacks(sq, occupied), rook_attacks(sq, occupied));
+                assert_eq!(
+                    lazy::bishop_attacks(sq, occupied),
+                    bishop_attacks(sq, occupied)
+                );
+                assert_eq!(
+                    lazy::queen_attacks(sq, occupied),
+                    queen_attacks(sq, occupied)
+                );
+            }
+            occupied ^= occupied << 13;
+            occupied ^= occupied >> 7;
+            occupied ^= occupied << 17;
+        }
+    }
+
+    #[test]
+    fn test_slider_attacks() {
+        let mut occupied = 0x9e37_79b9_7f4a_7c15_u64;
+        for _ in 0..64 {
+            for sq in Square::ALL {
+                let occupied = Bitboard(occupied);
+                assert_eq!(
+                    rook_attacks(sq, occupied).0,
+                    sliding_attacks(sq as i32, occupied.0, &ROOK_DELTAS)
+                );
+                assert_eq!(
+                    bishop_attacks(sq, occupied).0,
+                    sliding_attacks(sq as i32, occupied.0, &BISHOP_DELTAS)
+                );
+                assert_eq!(
+                    rook_attacks(sq, Bitboard::FULL),
+                    king_attacks(sq) & !ferz_attacks(sq)
+                );
+            }
+            occupied ^= occupied << 13;
+            occupied ^= occupied >> 7;
+            occupied ^= occupied << 17;
+        }
+    }
+
+    #[test]
+    fn test_leaper_attacks() {
+        assert_eq!(ferz_attacks(Square::H8), Bitboard::from(Square::G7));
+        assert_eq!(alfil_attacks(Square::A1), Bitboard::from(Square::C3));
+        assert_eq!(
+            alfil_attacks(Square::D4),
+            Bitboard::from_iter([Square::B2, Square::F2, Square::B6, Square::F6])
+        );
+        assert_eq!(alfil_attacks(Square::H7), Bitboard::from_iter([Square::F5]));
+    }
 }
@@ -22,7 +22,38 @@ use crate::{File, Rank, Square};
 /// // . 1 . . 1 . . .
 /// // . 1 . . . 1 . .
 /// ```
+///
+/// # Iteration order
+///
+/// Iterating over a bitboard yields squares in ascending order, i.e., from
+/// the least significant bit (`a1`, `b1`, ..., `h1`, `a2`, ...) to the most
+/// significant bit (`h8`). This order is guaranteed.
+///
+/// Use [`Bitboard::iter_rev()`] to scan from the most significant bit
+/// instead, i.e., ranks from Black's side (`h8`, `g8`, ..., `a8`, `h7`, ...),
+/// without collecting.
+///
+/// ```
+/// use shakmaty::{Bitboard, Square};
+///
+/// let bitboard = Bitboard::from_square(Square::B1) | Square::A8 | Square::H8;
+///
+/// let mut squares = bitboard.into_iter();
+/// assert_eq!(squares.next(), Some(Square::B1));
+/// assert_eq!(squares.next(), Some(Square::A8));
+/// assert_eq!(squares.next(), Some(Square::H8));
+///
+/// let mut squares = bitboard.iter_rev();
+/// assert_eq!(squares.next(), Some(Square::H8));
+/// assert_eq!(squares.next(), Some(Square::A8));
+/// assert_eq!(squares.next(), Some(Square::B1));
+/// ```
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(compare(PartialEq), derive(Debug))
+)]
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub struct Bitboard(pub u64);
 
@@ -449,6 +480,16 @@ impl Bitboard {
         }
     }
 
+    /// An iterator over the squares of this bitboard in descending order,
+    /// from `h8` to `a1`.
+    ///
+    /// This is the reverse of the [ascending order](Bitboard#iteration-order)
+    /// of [`Bitboard::into_iter()`].
+    #[inline]
+    pub const fn iter_rev(self) -> IterRev {
+        IterRev(self)
+    }
+
     /// An iterator over the subsets of this bitboard.
     ///
     /// # Examples
@@ -1144,6 +1185,53 @@ impl DoubleEndedIterator for IntoIter {
 
 impl FusedIterator for IntoIter {}
 
+/// Iterator over the squares of a [`Bitboard`] in descending order.
+///
+/// See [`Bitboard::iter_rev()`].
+#[derive(Debug, Default, Clone)]
+pub struct IterRev(Bitboard);
+
+impl Iterator for IterRev {
+    type Item = Square;
+
+    #[inline]
+    fn next(&mut self) -> Option<Square> {
+        self.0.pop_back()
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.0.count()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count();
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn last(self) -> Option<Square> {
+        self.0.first()
+    }
+}
+
+impl ExactSizeIterator for IterRev {
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.count()
+    }
+}
+
+impl DoubleEndedIterator for IterRev {
+    #[inline]
+    fn next_back(&mut self) -> Option<Square> {
+        self.0.pop_front()
+    }
+}
+
+impl FusedIterator for IterRev {}
+
 /// Iterator over the subsets of a [`Bitboard`].
 ///
 /// See [`Bitboard::carry_rippler()`].
@@ -1219,6 +1307,17 @@ mod tests {
         assert_eq!(Bitboard(0).last(), None);
     }
 
+    #[test]
+    fn test_iter_rev() {
+        let bitboard = Bitboard::from(Rank::First) | Bitboard::from(File::H);
+        assert!(bitboard.iter_rev().eq(bitboard.into_iter().rev()));
+        assert!(bitboard.iter_rev().rev().eq(bitboard));
+        assert_eq!(bitboard.iter_rev().len(), 15);
+        assert_eq!(bitboard.iter_rev().next(), Some(Square::H8));
+        assert_eq!(bitboard.iter_rev().last(), Some(Square::A1));
+        assert_eq!(Bitboard::EMPTY.iter_rev().next(), None);
+    }
+
     #[test]
     fn test_isolate_first() {
         assert_eq!(
@@ -3,6 +3,7 @@
 use core::{
     error, fmt,
     fmt::{Display, Write},
+    hash::{Hash, Hasher},
     iter::FusedIterator,
 };
 
@@ -27,7 +28,19 @@ use crate::{Bitboard, ByColor, ByRole, Color, File, Piece, Rank, Role, Square, a
 ///
 /// assert_eq!(board.piece_at(Square::E8), Some(Black.king()));
 /// ```
-#[derive(Clone, Eq, PartialEq, Hash)]
+///
+/// # Hashing
+///
+/// [`Hash`] feeds exactly 7 `u64` words to the hasher (one per role and
+/// one for the white pieces), and never variable-length data. This works
+/// well with fast word-based hashers like FxHash or ahash. To avoid hashing
+/// a position over and over, see [`zobrist::Prehashed`](crate::zobrist::Prehashed).
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize),
+    rkyv(derive(Debug))
+)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct Board {
     by_role: ByRole<Bitboard>,
     by_color: ByColor<Bitboard>,
@@ -147,6 +160,44 @@ impl Board {
         }
     }
 
+    #[cfg(feature = "variant")]
+    pub const fn shatranj() -> Board {
+        Board {
+            by_role: ByRole {
+                pawn: Bitboard(0x00ff_0000_0000_ff00),
+                knight: Bitboard(0x4200_0000_0000_0042),
+                bishop: Bitboard(0x2400_0000_0000_0024),
+                rook: Bitboard(0x8100_0000_0000_0081),
+                queen: Bitboard(0x1000_0000_0000_0008),
+                king: Bitboard(0x0800_0000_0000_0010),
+            },
+            by_color: ByColor {
+                black: Bitboard(0xffff_0000_0000_0000),
+                white: Bitboard(0xffff),
+            },
+            occupied: Bitboard(0xffff_0000_0000_ffff),
+        }
+    }
+
+    #[cfg(feature = "variant")]
+    pub const fn makruk() -> Board {
+        Board {
+            by_role: ByRole {
+                pawn: Bitboard(0x0000_ff00_00ff_0000),
+                knight: Bitboard(0x4200_0000_0000_0042),
+                bishop: Bitboard(0x2400_0000_0000_0024),
+                rook: Bitboard(0x8100_0000_0000_0081),
+                queen: Bitboard(0x0800_0000_0000_0010),
+                king: Bitboard(0x1000_0000_0000_0008),
+            },
+            by_color: ByColor {
+                black: Bitboard(0xff00_ff00_0000_0000),
+                white: Bitboard(0x00ff_00ff),
+            },
+            occupied: Bitboard(0xff00_ff00_00ff_00ff),
+        }
+    }
+
     #[cfg(feature = "variant")]
     pub const fn horde() -> Board {
         Board {
@@ -299,6 +350,16 @@ impl Board {
         self.occupied.toggle(sq);
     }
 
+    /// Adds `piece` on `sq` if it is empty, or removes it if `sq` holds
+    /// exactly that piece. Any other state of `sq` results in an
+    /// inconsistent board.
+    #[inline]
+    pub(crate) fn toggle_piece_at(&mut self, sq: Square, Piece { color, role }: Piece) {
+        self.by_role.get_mut(role).toggle(sq);
+        self.by_color.get_mut(color).toggle(sq);
+        self.occupied.toggle(sq);
+    }
+
     #[inline]
     pub(crate) fn set_new_piece_at(&mut self, sq: Square, Piece { color, role }: Piece) {
         assert!(self.occupied.insert(sq));
@@ -347,6 +408,46 @@ impl Board {
         ByColor::new_with(|color| self.material_side(color))
     }
 
+    /// Computes the [material](Board::material()) of many boards at once,
+    /// for example to extract features for training.
+    ///
+    /// Uses AVX2 on x86-64, if available. Availability is detected at
+    /// runtime with the `std` feature, and at compile time otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `boards` and `material` have different lengths.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Board, ByColor, ByRole};
+    ///
+    /// let boards = [Board::new(), Board::empty()];
+    /// let mut material = [ByColor::<ByRole<u8>>::default(); 2];
+    /// Board::material_batch(&boards, &mut material);
+    /// assert_eq!(material[0].white.pawn, 8);
+    /// assert_eq!(material[1].black.king, 0);
+    /// ```
+    pub fn material_batch(boards: &[Board], material: &mut [ByColor<ByRole<u8>>]) {
+        assert_eq!(
+            boards.len(),
+            material.len(),
+            "boards and material must have the same length"
+        );
+
+        #[cfg(target_arch = "x86_64")]
+        if has_avx2() {
+            // Safety: AVX2 is available.
+            unsafe { material_batch_avx2(boards, material) };
+            return;
+        }
+
+        for (board, material) in boards.iter().zip(material) {
+            *material = board.material();
+        }
+    }
+
     fn transform<F>(&mut self, f: F)
     where
         F: Fn(Bitboard) -> Bitboard,
@@ -453,12 +554,94 @@ impl Board {
     }
 }
 
+fn piece_masks(board: &Board) -> [u64; 12] {
+    let mut masks = [0; 12];
+    for color in Color::ALL {
+        for role in Role::ALL {
+            masks[piece_index(color, role)] = board.by_piece(role.of(color)).0;
+        }
+    }
+    masks
+}
+
+fn piece_index(color: Color, role: Role) -> usize {
+    usize::from(color) * 6 + usize::from(role) - 1
+}
+
+#[cfg(target_arch = "x86_64")]
+fn has_avx2() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "avx2")
+    }
+}
+
+/// Counts the squares of all 12 piece bitboards of each board, 4 at a time,
+/// using a nibble lookup table.
+///
+/// # Safety
+///
+/// AVX2 must be available.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn material_batch_avx2(boards: &[Board], material: &mut [ByColor<ByRole<u8>>]) {
+    use core::arch::x86_64::{
+        _mm256_add_epi8, _mm256_and_si256, _mm256_loadu_si256, _mm256_sad_epu8, _mm256_set1_epi8,
+        _mm256_setr_epi8, _mm256_setzero_si256, _mm256_shuffle_epi8, _mm256_srli_epi16,
+        _mm256_storeu_si256,
+    };
+
+    // Safety: AVX2 is available. Loads and stores are unaligned and within
+    // chunks of exactly 4 u64 (256 bits).
+    unsafe {
+        #[rustfmt::skip]
+        let lookup = _mm256_setr_epi8(
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+            0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+        );
+        let nibble = _mm256_set1_epi8(0x0f);
+        let zero = _mm256_setzero_si256();
+
+        for (board, material) in boards.iter().zip(material) {
+            let masks = piece_masks(board);
+            let mut counts = [0u64; 12];
+            for (masks, counts) in masks.chunks_exact(4).zip(counts.chunks_exact_mut(4)) {
+                let v = _mm256_loadu_si256(masks.as_ptr().cast());
+                let lo = _mm256_and_si256(v, nibble);
+                let hi = _mm256_and_si256(_mm256_srli_epi16(v, 4), nibble);
+                let bytes = _mm256_add_epi8(
+                    _mm256_shuffle_epi8(lookup, lo),
+                    _mm256_shuffle_epi8(lookup, hi),
+                );
+                _mm256_storeu_si256(counts.as_mut_ptr().cast(), _mm256_sad_epu8(bytes, zero));
+            }
+            *material = ByColor::new_with(|color| {
+                ByRole::new_with(|role| counts[piece_index(color, role)] as u8)
+            });
+        }
+    }
+}
+
 impl Default for Board {
     fn default() -> Board {
         Board::new()
     }
 }
 
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Black pieces and occupied squares are implied.
+        for bitboard in self.by_role {
+            state.write_u64(bitboard.0);
+        }
+        state.write_u64(self.by_color.white.0);
+    }
+}
+
 impl fmt::Debug for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for rank in Rank::ALL.into_iter().rev() {
@@ -492,6 +675,174 @@ impl FromIterator<(Square, Piece)> for Board {
     }
 }
 
+/// A [`Board`] with a redundant square-to-piece array, so that
+/// [`MailboxBoard::piece_at()`] and related lookups are O(1).
+///
+/// Useful for workloads that query many individual squares, like rendering,
+/// notation, or evaluation. Set-wise queries go through
+/// [`MailboxBoard::as_board()`]. Updates are slightly more expensive than on
+/// a plain [`Board`], which remains the better choice for move generation.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Board, Color::White, MailboxBoard, Square};
+///
+/// let mut board = MailboxBoard::from(Board::new());
+/// assert_eq!(board.piece_at(Square::E1), Some(White.king()));
+///
+/// board.set_piece_at(Square::E4, White.pawn());
+/// assert_eq!(board.remove_piece_at(Square::E2), Some(White.pawn()));
+/// assert_eq!(board.as_board().pawns().count(), 16);
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct MailboxBoard {
+    board: Board,
+    mailbox: [Option<Piece>; 64],
+}
+
+impl MailboxBoard {
+    /// A board with the standard starting position.
+    pub fn new() -> MailboxBoard {
+        MailboxBoard::from(Board::new())
+    }
+
+    /// An empty board.
+    pub const fn empty() -> MailboxBoard {
+        MailboxBoard {
+            board: Board::empty(),
+            mailbox: [None; 64],
+        }
+    }
+
+    #[inline]
+    pub const fn as_board(&self) -> &Board {
+        &self.board
+    }
+
+    #[inline]
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+
+    /// The contents of all squares, indexed by [`Square::to_usize()`].
+    #[inline]
+    pub const fn mailbox(&self) -> &[Option<Piece>; 64] {
+        &self.mailbox
+    }
+
+    #[inline]
+    pub const fn piece_at(&self, sq: Square) -> Option<Piece> {
+        self.mailbox[sq.to_usize()]
+    }
+
+    #[inline]
+    pub const fn color_at(&self, sq: Square) -> Option<Color> {
+        match self.piece_at(sq) {
+            Some(piece) => Some(piece.color),
+            None => None,
+        }
+    }
+
+    #[inline]
+    pub const fn role_at(&self, sq: Square) -> Option<Role> {
+        match self.piece_at(sq) {
+            Some(piece) => Some(piece.role),
+            None => None,
+        }
+    }
+
+    #[must_use = "use MailboxBoard::discard_piece_at() if return value is not needed"]
+    #[inline]
+    pub fn remove_piece_at(&mut self, sq: Square) -> Option<Piece> {
+        let piece = self.mailbox[sq.to_usize()].take();
+        if let Some(Piece { color, role }) = piece {
+            self.board.by_role.get_mut(role).toggle(sq);
+            self.board.by_color.get_mut(color).toggle(sq);
+            self.board.occupied.toggle(sq);
+        }
+        piece
+    }
+
+    #[inline]
+    pub fn discard_piece_at(&mut self, sq: Square) {
+        let _ = self.remove_piece_at(sq);
+    }
+
+    #[inline]
+    pub fn set_piece_at(&mut self, sq: Square, piece: Piece) {
+        self.discard_piece_at(sq);
+        self.board.by_role.get_mut(piece.role).toggle(sq);
+        self.board.by_color.get_mut(piece.color).toggle(sq);
+        self.board.occupied.toggle(sq);
+        self.mailbox[sq.to_usize()] = Some(piece);
+    }
+
+    pub fn attacks_from(&self, sq: Square) -> Bitboard {
+        self.piece_at(sq).map_or(Bitboard(0), |piece| {
+            attacks::attacks(sq, piece, self.board.occupied)
+        })
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.board.iter()
+    }
+}
+
+impl Default for MailboxBoard {
+    fn default() -> MailboxBoard {
+        MailboxBoard::new()
+    }
+}
+
+impl fmt::Debug for MailboxBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.board, f)
+    }
+}
+
+impl From<Board> for MailboxBoard {
+    fn from(board: Board) -> MailboxBoard {
+        let mut mailbox = [None; 64];
+        for (sq, piece) in &board {
+            mailbox[sq.to_usize()] = Some(piece);
+        }
+        MailboxBoard { board, mailbox }
+    }
+}
+
+impl Hash for MailboxBoard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // The mailbox is implied by the board.
+        self.board.hash(state);
+    }
+}
+
+impl From<MailboxBoard> for Board {
+    fn from(board: MailboxBoard) -> Board {
+        board.into_board()
+    }
+}
+
+impl Extend<(Square, Piece)> for MailboxBoard {
+    fn extend<T: IntoIterator<Item = (Square, Piece)>>(&mut self, iter: T) {
+        for (sq, piece) in iter {
+            self.set_piece_at(sq, piece);
+        }
+    }
+}
+
+impl FromIterator<(Square, Piece)> for MailboxBoard {
+    fn from_iter<T>(iter: T) -> MailboxBoard
+    where
+        T: IntoIterator<Item = (Square, Piece)>,
+    {
+        let mut board = MailboxBoard::empty();
+        board.extend(iter);
+        board
+    }
+}
+
 /// Error when trying to create a [`Board`] from inconsistent bitboards.
 #[derive(Debug, Clone)]
 pub struct InconsistentBitboardsError {
@@ -560,6 +911,23 @@ impl<Config> bincode::Decode<Config> for Board {
 #[cfg(feature = "bincode")]
 bincode::impl_borrow_decode!(Board);
 
+#[cfg(feature = "rkyv")]
+impl<D> rkyv::Deserialize<Board, D> for ArchivedBoard
+where
+    D: rkyv::rancor::Fallible + ?Sized,
+    D::Error: rkyv::rancor::Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Board, D::Error> {
+        use rkyv::rancor::Source as _;
+
+        Board::try_from_bitboards(
+            self.by_role.deserialize(deserializer)?,
+            self.by_color.deserialize(deserializer)?,
+        )
+        .map_err(D::Error::new)
+    }
+}
+
 /// Iterator over the pieces of a [`Board`].
 #[derive(Debug, Clone)]
 pub struct Iter<'a> {
@@ -744,6 +1112,91 @@ mod tests {
         assert_eq!(board.piece_at(Square::A3), Some(White.pawn()));
     }
 
+    #[test]
+    fn test_mailbox_board() {
+        let mut board = MailboxBoard::new();
+        board.set_piece_at(Square::E4, White.pawn());
+        board.set_piece_at(Square::E8, Black.queen());
+        assert_eq!(board.remove_piece_at(Square::E2), Some(White.pawn()));
+        assert_eq!(board.remove_piece_at(Square::E3), None);
+        board.discard_piece_at(Square::A1);
+
+        let mut expected = Board::new();
+        expected.set_piece_at(Square::E4, White.pawn());
+        expected.set_piece_at(Square::E8, Black.queen());
+        expected.discard_piece_at(Square::E2);
+        expected.discard_piece_at(Square::A1);
+        assert_eq!(board.as_board(), &expected);
+
+        for sq in Square::ALL {
+            assert_eq!(board.piece_at(sq), expected.piece_at(sq));
+            assert_eq!(board.attacks_from(sq), expected.attacks_from(sq));
+        }
+        assert!(board.iter().eq(expected.iter()));
+        assert_eq!(board, MailboxBoard::from(expected));
+    }
+
+    #[test]
+    fn test_material_batch() {
+        let boards = [
+            Board::new(),
+            Board::empty(),
+            "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP"
+                .parse()
+                .expect("valid fen"),
+            "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ".parse().expect("valid fen"),
+            "1qrb4/1k2n3/1P2p3/1N1K4/1BQ5/1R1R4/1Q2B3/1K3N2"
+                .parse()
+                .expect("valid fen"),
+        ];
+        let mut material = [ByColor::default(); 5];
+        Board::material_batch(&boards, &mut material);
+        for (board, material) in boards.iter().zip(material) {
+            assert_eq!(material, board.material());
+        }
+    }
+
+    #[test]
+    fn test_material_batch_random() {
+        // Deterministic xorshift, to place up to 64 random pieces.
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let boards: [Board; 256] = core::array::from_fn(|_| {
+            let mut board = Board::empty();
+            let density = next() % 65;
+            for sq in Square::ALL {
+                if next() % 64 < density {
+                    let color = Color::from_white(next() % 2 == 0);
+                    let role = Role::ALL[(next() % 6) as usize];
+                    board.set_piece_at(sq, role.of(color));
+                }
+            }
+            board
+        });
+
+        let mut material = [ByColor::default(); 256];
+        Board::material_batch(&boards, &mut material);
+        for (board, material) in boards.iter().zip(&material) {
+            assert_eq!(*material, board.material());
+        }
+
+        #[cfg(target_arch = "x86_64")]
+        if has_avx2() {
+            let mut material = [ByColor::default(); 256];
+            // Safety: AVX2 is available.
+            unsafe { material_batch_avx2(&boards, &mut material) };
+            for (board, material) in boards.iter().zip(&material) {
+                assert_eq!(*material, board.material());
+            }
+        }
+    }
+
     #[test]
     fn test_promoted() {
         let board: Board = "4k3/8/8/8/8/8/8/2q~1K3".parse().expect("valid fen");
@@ -809,4 +1262,14 @@ mod tests {
             bincode::decode_from_slice(&buffer, config).unwrap();
         assert_eq!((&board, encoded_bytes), (&decoded, decoded_bytes));
     }
+
+    #[cfg(all(feature = "rkyv", feature = "alloc"))]
+    #[test]
+    fn test_rkyv() {
+        let board = Board::default();
+
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&board).unwrap();
+        let decoded = rkyv::from_bytes::<Board, rkyv::rancor::Error>(&bytes).unwrap();
+        assert_eq!(board, decoded);
+    }
 }
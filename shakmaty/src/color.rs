@@ -8,6 +8,11 @@ use crate::{ByRole, Piece, Rank, Role, util::out_of_range_error};
 #[allow(missing_docs)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(compare(PartialEq), derive(Debug))
+)]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub enum Color {
     Black = 0,
@@ -178,6 +183,9 @@ impl FromStr for Color {
     }
 }
 
+#[cfg(feature = "serde")]
+serde_static_str_impl! { Color, name, "color name" }
+
 from_enum_as_int_impl! { Color, u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize }
 
 macro_rules! try_color_from_int_impl {
@@ -202,6 +210,11 @@ try_color_from_int_impl! { u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize }
 /// Container with values for each [`Color`].
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug), archive_bounds(T::Archived: core::fmt::Debug))
+)]
 #[derive(Copy, Clone, Default, Eq, PartialEq, Debug, Hash)]
 pub struct ByColor<T> {
     pub black: T,
@@ -434,10 +447,43 @@ impl<T> ByColor<ByRole<T>> {
     }
 }
 
-#[cfg(feature = "variant")]
 impl ByColor<ByRole<u8>> {
-    pub(crate) fn count(self) -> usize {
-        self.iter().map(|&role| role.count()).sum()
+    /// Counts the pieces in both pockets.
+    pub fn count(&self) -> usize {
+        self.iter().map(ByRole::count).sum()
+    }
+
+    /// Adds `piece` to the pocket of its color. Saturates at [`u8::MAX`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{ByColor, ByRole, Color, Role};
+    ///
+    /// let mut pockets = ByColor::<ByRole<u8>>::default();
+    /// pockets.add(Color::White.knight());
+    /// pockets.add(Color::Black.queen());
+    /// assert_eq!(pockets.count(), 2);
+    ///
+    /// assert!(pockets.remove(Color::White.knight()));
+    /// assert!(!pockets.remove(Color::White.knight()));
+    /// assert_eq!(pockets.white.knight, 0);
+    /// ```
+    pub fn add(&mut self, piece: Piece) {
+        let count = self.piece_mut(piece);
+        *count = count.saturating_add(1);
+    }
+
+    /// Removes `piece` from the pocket of its color. Returns `false` if
+    /// there was no such piece.
+    pub fn remove(&mut self, piece: Piece) -> bool {
+        let count = self.piece_mut(piece);
+        if *count > 0 {
+            *count -= 1;
+            true
+        } else {
+            false
+        }
     }
 }
 
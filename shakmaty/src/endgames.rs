@@ -0,0 +1,379 @@
+//! Classify positions into well-known endgame families.
+//!
+//! Classification only looks at the material on the board, so it is cheap
+//! enough to specialize evaluation, and it does not depend on the variant.
+//! The attached metadata is a textbook rule of thumb, not a verdict on the
+//! concrete position. Use a tablebase for that.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{Board, Color, endgames::{self, Endgame, Tendency}};
+//!
+//! let board: Board = "8/8/8/4k3/8/8/3P4/4K3".parse()?;
+//! let classification = endgames::classify(&board).expect("known endgame");
+//! assert_eq!(classification.endgame, Endgame::KingPawnVsKing);
+//! assert_eq!(classification.strong_side, Some(Color::White));
+//! assert_eq!(classification.endgame.tendency(), Tendency::Depends);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use core::cmp::Ordering;
+
+use crate::{Bitboard, Board, ByRole, Color, Role};
+
+/// A family of endgames, defined by the material on the board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Endgame {
+    /// King and queen versus king.
+    KingQueenVsKing,
+    /// King and rook versus king.
+    KingRookVsKing,
+    /// King, bishop and knight versus king.
+    KingBishopKnightVsKing,
+    /// King and pawn versus king.
+    KingPawnVsKing,
+    /// King and queen versus king and pawn.
+    KingQueenVsKingPawn,
+    /// King and queen versus king and rook.
+    KingQueenVsKingRook,
+    /// King and rook versus king and pawn.
+    KingRookVsKingPawn,
+    /// King and rook versus king and bishop.
+    KingRookVsKingBishop,
+    /// King and rook versus king and knight.
+    KingRookVsKingKnight,
+    /// Only kings and pawns, with pawns for both sides or more than one
+    /// pawn.
+    PawnEnding,
+    /// One rook for each side, and any number of pawns.
+    RookEnding,
+    /// One queen for each side, and any number of pawns.
+    QueenEnding,
+    /// One bishop for each side, on squares of opposite colors, and any
+    /// number of pawns.
+    OppositeColoredBishops,
+}
+
+impl Endgame {
+    pub const ALL: [Endgame; 13] = [
+        Endgame::KingQueenVsKing,
+        Endgame::KingRookVsKing,
+        Endgame::KingBishopKnightVsKing,
+        Endgame::KingPawnVsKing,
+        Endgame::KingQueenVsKingPawn,
+        Endgame::KingQueenVsKingRook,
+        Endgame::KingRookVsKingPawn,
+        Endgame::KingRookVsKingBishop,
+        Endgame::KingRookVsKingKnight,
+        Endgame::PawnEnding,
+        Endgame::RookEnding,
+        Endgame::QueenEnding,
+        Endgame::OppositeColoredBishops,
+    ];
+
+    /// Short name, like `KPK`, or a descriptive name for families without
+    /// a fixed material signature.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Endgame::KingQueenVsKing => "KQK",
+            Endgame::KingRookVsKing => "KRK",
+            Endgame::KingBishopKnightVsKing => "KBNK",
+            Endgame::KingPawnVsKing => "KPK",
+            Endgame::KingQueenVsKingPawn => "KQKP",
+            Endgame::KingQueenVsKingRook => "KQKR",
+            Endgame::KingRookVsKingPawn => "KRKP",
+            Endgame::KingRookVsKingBishop => "KRKB",
+            Endgame::KingRookVsKingKnight => "KRKN",
+            Endgame::PawnEnding => "pawn ending",
+            Endgame::RookEnding => "rook ending",
+            Endgame::QueenEnding => "queen ending",
+            Endgame::OppositeColoredBishops => "opposite-colored bishops",
+        }
+    }
+
+    /// Tests if the family has a fixed material signature, so that a
+    /// stronger side is always known. Otherwise, the stronger side depends
+    /// on the number of pawns.
+    pub const fn is_asymmetric(self) -> bool {
+        !matches!(
+            self,
+            Endgame::PawnEnding
+                | Endgame::RookEnding
+                | Endgame::QueenEnding
+                | Endgame::OppositeColoredBishops
+        )
+    }
+
+    /// The usual result with best play, from the point of view of the
+    /// stronger side.
+    pub const fn tendency(self) -> Tendency {
+        match self {
+            Endgame::KingQueenVsKing
+            | Endgame::KingRookVsKing
+            | Endgame::KingBishopKnightVsKing
+            | Endgame::KingQueenVsKingPawn
+            | Endgame::KingQueenVsKingRook => Tendency::Win,
+            Endgame::KingRookVsKingBishop
+            | Endgame::KingRookVsKingKnight
+            | Endgame::OppositeColoredBishops => Tendency::Draw,
+            Endgame::KingPawnVsKing
+            | Endgame::KingRookVsKingPawn
+            | Endgame::PawnEnding
+            | Endgame::RookEnding
+            | Endgame::QueenEnding => Tendency::Depends,
+        }
+    }
+
+    /// A textbook rule of thumb for the family.
+    pub const fn rule_of_thumb(self) -> &'static str {
+        match self {
+            Endgame::KingQueenVsKing | Endgame::KingRookVsKing => {
+                "Elementary mate: drive the king to the edge of the board."
+            }
+            Endgame::KingBishopKnightVsKing => {
+                "Mate is forced only in a corner of the bishop's color, within 33 moves."
+            }
+            Endgame::KingPawnVsKing => {
+                "Won if the attacking king reaches a key square in front of the pawn. Rook pawns are usually drawn."
+            }
+            Endgame::KingQueenVsKingPawn => {
+                "Won, except against a rook or bishop pawn on the seventh rank supported by its king."
+            }
+            Endgame::KingQueenVsKingRook => {
+                "Won, but the technique is difficult. Aim for the Philidor position."
+            }
+            Endgame::KingRookVsKingPawn => {
+                "Won if the defending king is far from the pawn, otherwise often drawn."
+            }
+            Endgame::KingRookVsKingBishop => {
+                "Drawn if the defending king reaches the corner not of the bishop's color."
+            }
+            Endgame::KingRookVsKingKnight => {
+                "Drawn if the knight stays close to its king and away from the edge."
+            }
+            Endgame::PawnEnding => {
+                "Count tempi. King activity, the opposition and outside passed pawns decide."
+            }
+            Endgame::RookEnding => {
+                "An extra pawn is often not enough to win. Keep the rook active and behind passed pawns, and know the Lucena and Philidor positions."
+            }
+            Endgame::QueenEnding => {
+                "Perpetual check is the main defensive resource. Passed pawns advanced furthest matter most."
+            }
+            Endgame::OppositeColoredBishops => {
+                "Drawish even with one or two extra pawns, unless passed pawns are far apart."
+            }
+        }
+    }
+}
+
+/// The usual result of an [`Endgame`] family with best play.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Tendency {
+    /// Usually won by the stronger side.
+    Win,
+    /// Usually drawn.
+    Draw,
+    /// Depends on the concrete position.
+    Depends,
+}
+
+/// Result of [`classify()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Classification {
+    /// The endgame family.
+    pub endgame: Endgame,
+    /// The side with more material, or `None` if material is balanced.
+    ///
+    /// Always known for [asymmetric](Endgame::is_asymmetric()) families.
+    /// Otherwise, this is the side with more pawns.
+    pub strong_side: Option<Color>,
+}
+
+/// Classifies the material on `board` into a known [`Endgame`] family.
+///
+/// Returns `None` if the material does not match any known family,
+/// including boards without exactly one king per side.
+pub fn classify(board: &Board) -> Option<Classification> {
+    let material = board.material();
+    if material.white.king != 1 || material.black.king != 1 {
+        return None;
+    }
+
+    for strong_side in Color::ALL {
+        let strong = *material.get(strong_side);
+        let weak = *material.get(!strong_side);
+        if let Some(endgame) = classify_asymmetric(strong, weak) {
+            return Some(Classification {
+                endgame,
+                strong_side: Some(strong_side),
+            });
+        }
+    }
+
+    let (white, black) = (material.white, material.black);
+    let endgame = if has_pieces(white, &[]) && has_pieces(black, &[]) {
+        Endgame::PawnEnding
+    } else if has_pieces(white, &[Role::Rook]) && has_pieces(black, &[Role::Rook]) {
+        Endgame::RookEnding
+    } else if has_pieces(white, &[Role::Queen]) && has_pieces(black, &[Role::Queen]) {
+        Endgame::QueenEnding
+    } else if has_pieces(white, &[Role::Bishop])
+        && has_pieces(black, &[Role::Bishop])
+        && (board.bishops() & Bitboard::DARK_SQUARES).count() == 1
+    {
+        Endgame::OppositeColoredBishops
+    } else {
+        return None;
+    };
+
+    if endgame == Endgame::PawnEnding && board.pawns().is_empty() {
+        return None;
+    }
+
+    Some(Classification {
+        endgame,
+        strong_side: match white.pawn.cmp(&black.pawn) {
+            Ordering::Greater => Some(Color::White),
+            Ordering::Less => Some(Color::Black),
+            Ordering::Equal => None,
+        },
+    })
+}
+
+fn classify_asymmetric(strong: ByRole<u8>, weak: ByRole<u8>) -> Option<Endgame> {
+    Some(if has_exactly(weak, &[]) {
+        if has_exactly(strong, &[Role::Queen]) {
+            Endgame::KingQueenVsKing
+        } else if has_exactly(strong, &[Role::Rook]) {
+            Endgame::KingRookVsKing
+        } else if has_exactly(strong, &[Role::Bishop, Role::Knight]) {
+            Endgame::KingBishopKnightVsKing
+        } else if has_exactly(strong, &[Role::Pawn]) {
+            Endgame::KingPawnVsKing
+        } else {
+            return None;
+        }
+    } else if has_exactly(strong, &[Role::Queen]) && has_exactly(weak, &[Role::Pawn]) {
+        Endgame::KingQueenVsKingPawn
+    } else if has_exactly(strong, &[Role::Queen]) && has_exactly(weak, &[Role::Rook]) {
+        Endgame::KingQueenVsKingRook
+    } else if has_exactly(strong, &[Role::Rook]) && has_exactly(weak, &[Role::Pawn]) {
+        Endgame::KingRookVsKingPawn
+    } else if has_exactly(strong, &[Role::Rook]) && has_exactly(weak, &[Role::Bishop]) {
+        Endgame::KingRookVsKingBishop
+    } else if has_exactly(strong, &[Role::Rook]) && has_exactly(weak, &[Role::Knight]) {
+        Endgame::KingRookVsKingKnight
+    } else {
+        return None;
+    })
+}
+
+/// Tests if the material is exactly the king and `roles`.
+fn has_exactly(material: ByRole<u8>, roles: &[Role]) -> bool {
+    let mut expected = ByRole {
+        king: 1,
+        ..ByRole::default()
+    };
+    for &role in roles {
+        *expected.get_mut(role) += 1;
+    }
+    material == expected
+}
+
+/// Tests if the material is exactly the king and `roles`, plus any number
+/// of pawns.
+fn has_pieces(material: ByRole<u8>, roles: &[Role]) -> bool {
+    has_exactly(
+        ByRole {
+            pawn: 0,
+            ..material
+        },
+        roles,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify_fen(fen: &str) -> Option<Classification> {
+        classify(&fen.parse().expect("valid board fen"))
+    }
+
+    #[test]
+    fn test_classify() {
+        for (fen, endgame, strong_side) in [
+            (
+                "8/8/8/4k3/8/8/8/3QK3",
+                Endgame::KingQueenVsKing,
+                Some(Color::White),
+            ),
+            (
+                "8/8/8/4k3/8/8/8/1NB1K3",
+                Endgame::KingBishopKnightVsKing,
+                Some(Color::White),
+            ),
+            (
+                "4k3/4p3/8/8/8/8/8/4K3",
+                Endgame::KingPawnVsKing,
+                Some(Color::Black),
+            ),
+            (
+                "3rk3/8/8/8/8/8/8/3QK3",
+                Endgame::KingQueenVsKingRook,
+                Some(Color::White),
+            ),
+            (
+                "3rk3/8/8/8/8/8/1P6/4K3",
+                Endgame::KingRookVsKingPawn,
+                Some(Color::Black),
+            ),
+            ("4k3/pp6/8/8/8/8/PP6/4K3", Endgame::PawnEnding, None),
+            (
+                "4k3/8/8/8/8/8/PP6/4K3",
+                Endgame::PawnEnding,
+                Some(Color::White),
+            ),
+            (
+                "r3k3/pp6/8/8/8/8/PPP5/R3K3",
+                Endgame::RookEnding,
+                Some(Color::White),
+            ),
+            ("r3k3/pp6/8/8/8/8/PP6/R3K3", Endgame::RookEnding, None),
+            (
+                "q3k3/ppp5/8/8/8/8/P7/Q3K3",
+                Endgame::QueenEnding,
+                Some(Color::Black),
+            ),
+            (
+                "2b1k3/pp6/8/8/8/8/PPP5/2B1K3",
+                Endgame::OppositeColoredBishops,
+                Some(Color::White),
+            ),
+        ] {
+            assert_eq!(
+                classify_fen(fen),
+                Some(Classification {
+                    endgame,
+                    strong_side
+                }),
+                "{fen}"
+            );
+            if endgame.is_asymmetric() {
+                assert!(strong_side.is_some());
+            }
+        }
+
+        for fen in [
+            "8/8/8/4k3/8/8/8/4K3",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+            "3bk3/pp6/8/8/8/8/PPP5/2B1K3",
+            "8/8/8/4k3/8/8/8/2BBK3",
+            "8/8/8/8/8/8/8/3QK3",
+        ] {
+            assert_eq!(classify_fen(fen), None, "{fen}");
+        }
+    }
+}
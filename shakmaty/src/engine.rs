@@ -0,0 +1,838 @@
+//! Drive chess engines speaking the Universal Chess Interface.
+//!
+//! [`Engine`] spawns an engine process, performs the handshake, and
+//! exchanges commands. Lines sent by the engine during a search are parsed
+//! into [`Info`] and [`BestMove`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use shakmaty::{engine::{Engine, Go}, uci::UciMove};
+//!
+//! let mut engine = Engine::spawn("stockfish")?;
+//! println!("{}", engine.name().unwrap_or("unknown engine"));
+//!
+//! engine.set_option("Threads", "4")?;
+//! engine.set_position(None, &["e2e4".parse()?])?;
+//!
+//! let search = engine.go(&Go {
+//!     depth: Some(20),
+//!     ..Go::default()
+//! })?;
+//!
+//! if let Some(info) = search.infos.last() {
+//!     println!("score {:?}, pv {:?}", info.score, info.pv);
+//! }
+//! println!("bestmove {:?}", search.best_move.m);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Output of a running engine can also be parsed independently:
+//!
+//! ```
+//! use shakmaty::engine::{Depth, Info, Score};
+//!
+//! let info: Info = "info depth 12 seldepth 18 score mate -3 nodes 42000 pv e2e4 e7e5".parse()?;
+//! assert_eq!(info.depth, Some(Depth(12)));
+//! assert_eq!(info.score, Some(Score::Mate(-3)));
+//! assert_eq!(info.pv.map(|pv| pv.0.len()), Some(2));
+//! # Ok::<_, shakmaty::engine::ParseEngineLineError>(())
+//! ```
+
+use std::{
+    error,
+    ffi::OsStr,
+    fmt, format,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    str::FromStr,
+    string::{String, ToString},
+    thread,
+    time::{Duration, Instant},
+    vec::Vec,
+};
+
+use crate::{analysis::AnalysisLine, fen::Fen, uci::UciMove};
+pub use crate::{
+    analysis::{Depth, Pv, Score},
+    uci::{EngineOption, InvalidOptionValueError, OptionKind, ParseEngineOptionError},
+};
+
+/// Error when parsing an invalid line sent by an engine.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseEngineLineError;
+
+impl fmt::Display for ParseEngineLineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid engine output")
+    }
+}
+
+impl error::Error for ParseEngineLineError {}
+
+/// Whether the score is exact, or only a bound.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ScoreBound {
+    #[default]
+    Exact,
+    /// The score is at least as good as reported (`lowerbound`).
+    Lower,
+    /// The score is at most as good as reported (`upperbound`).
+    Upper,
+}
+
+/// Win/draw/loss statistics in permille, from the point of view of the side
+/// to move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Wdl {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+/// An `info` line.
+///
+/// All fields are optional, since engines are free to send any subset.
+/// Unknown fields are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Info {
+    pub depth: Option<Depth>,
+    pub seldepth: Option<Depth>,
+    /// Time searched in milliseconds.
+    pub time: Option<u64>,
+    pub nodes: Option<u64>,
+    pub pv: Option<Pv>,
+    /// 1-based index of the line, when searching multiple lines.
+    pub multipv: Option<u32>,
+    pub score: Option<Score>,
+    pub score_bound: ScoreBound,
+    pub wdl: Option<Wdl>,
+    pub currmove: Option<UciMove>,
+    pub currmovenumber: Option<u32>,
+    /// Hash table usage in permille.
+    pub hashfull: Option<u32>,
+    pub nps: Option<u64>,
+    pub tbhits: Option<u64>,
+    /// Free-form text (`string ...`), which extends to the end of the line.
+    pub string: Option<String>,
+}
+
+impl Info {
+    /// Parses an `info` line.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseEngineLineError`] if the line does not start with
+    /// `info`, or if a known field has an invalid value.
+    pub fn from_line(line: &str) -> Result<Info, ParseEngineLineError> {
+        let mut tokens = line.split_ascii_whitespace().peekable();
+        if tokens.next() != Some("info") {
+            return Err(ParseEngineLineError);
+        }
+
+        let mut info = Info::default();
+        while let Some(token) = tokens.next() {
+            match token {
+                "depth" => info.depth = Some(Depth(parse_next(&mut tokens)?)),
+                "seldepth" => info.seldepth = Some(Depth(parse_next(&mut tokens)?)),
+                "time" => info.time = Some(parse_next(&mut tokens)?),
+                "nodes" => info.nodes = Some(parse_next(&mut tokens)?),
+                "multipv" => info.multipv = Some(parse_next(&mut tokens)?),
+                "currmove" => info.currmove = Some(parse_next(&mut tokens)?),
+                "currmovenumber" => info.currmovenumber = Some(parse_next(&mut tokens)?),
+                "hashfull" => info.hashfull = Some(parse_next(&mut tokens)?),
+                "nps" => info.nps = Some(parse_next(&mut tokens)?),
+                "tbhits" => info.tbhits = Some(parse_next(&mut tokens)?),
+                "score" => {
+                    info.score = Some(match tokens.next() {
+                        Some("cp") => Score::Cp(parse_next(&mut tokens)?),
+                        Some("mate") => Score::Mate(parse_next(&mut tokens)?),
+                        _ => return Err(ParseEngineLineError),
+                    });
+                    match tokens.peek() {
+                        Some(&"lowerbound") => {
+                            tokens.next();
+                            info.score_bound = ScoreBound::Lower;
+                        }
+                        Some(&"upperbound") => {
+                            tokens.next();
+                            info.score_bound = ScoreBound::Upper;
+                        }
+                        _ => (),
+                    }
+                }
+                "wdl" => {
+                    info.wdl = Some(Wdl {
+                        wins: parse_next(&mut tokens)?,
+                        draws: parse_next(&mut tokens)?,
+                        losses: parse_next(&mut tokens)?,
+                    });
+                }
+                "pv" => {
+                    let mut pv = Vec::new();
+                    while let Some(Ok(m)) = tokens.peek().map(|token| token.parse::<UciMove>()) {
+                        tokens.next();
+                        pv.push(m);
+                    }
+                    info.pv = Some(Pv(pv));
+                }
+                "string" => {
+                    info.string = Some(tokens.by_ref().collect::<Vec<_>>().join(" "));
+                }
+                _ => (),
+            }
+        }
+        Ok(info)
+    }
+
+    /// Gets the analysis line reported by this `info` line, if it has a
+    /// score and principal variation.
+    ///
+    /// The `multipv` index defaults to `1`.
+    pub fn analysis_line(&self) -> Option<AnalysisLine> {
+        Some(AnalysisLine {
+            multipv: self.multipv.unwrap_or(1),
+            depth: self.depth,
+            score: self.score?,
+            pv: self.pv.clone()?,
+        })
+    }
+}
+
+impl FromStr for Info {
+    type Err = ParseEngineLineError;
+
+    fn from_str(line: &str) -> Result<Info, ParseEngineLineError> {
+        Info::from_line(line)
+    }
+}
+
+/// A `bestmove` line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BestMove {
+    /// The best move, or `None` if there are no legal moves (`bestmove
+    /// (none)` or `bestmove 0000`).
+    pub m: Option<UciMove>,
+    /// The expected reply to ponder on.
+    pub ponder: Option<UciMove>,
+}
+
+impl BestMove {
+    /// Parses a `bestmove` line.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseEngineLineError`] if the line does not start with
+    /// `bestmove`, or if a move is invalid.
+    pub fn from_line(line: &str) -> Result<BestMove, ParseEngineLineError> {
+        let mut tokens = line.split_ascii_whitespace();
+        if tokens.next() != Some("bestmove") {
+            return Err(ParseEngineLineError);
+        }
+        let m = match tokens.next() {
+            Some("(none)") | None => None,
+            Some(m) => match m.parse().map_err(|_| ParseEngineLineError)? {
+                UciMove::Null => None,
+                m => Some(m),
+            },
+        };
+        let ponder = match (tokens.next(), tokens.next()) {
+            (Some("ponder"), Some(m)) => Some(m.parse().map_err(|_| ParseEngineLineError)?),
+            _ => None,
+        };
+        Ok(BestMove { m, ponder })
+    }
+}
+
+impl FromStr for BestMove {
+    type Err = ParseEngineLineError;
+
+    fn from_str(line: &str) -> Result<BestMove, ParseEngineLineError> {
+        BestMove::from_line(line)
+    }
+}
+
+fn parse_next<'a, T: FromStr>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<T, ParseEngineLineError> {
+    tokens
+        .next()
+        .and_then(|token| token.parse().ok())
+        .ok_or(ParseEngineLineError)
+}
+
+/// Parameters of a `go` command. Fields that are `None` are omitted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Go {
+    /// Remaining time of White in milliseconds.
+    pub wtime: Option<u64>,
+    /// Remaining time of Black in milliseconds.
+    pub btime: Option<u64>,
+    /// Increment of White in milliseconds.
+    pub winc: Option<u64>,
+    /// Increment of Black in milliseconds.
+    pub binc: Option<u64>,
+    pub movestogo: Option<u32>,
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    /// Search for a mate in the given number of moves.
+    pub mate: Option<u32>,
+    /// Search for exactly the given number of milliseconds.
+    pub movetime: Option<u64>,
+    /// Restrict the search to the given moves.
+    pub searchmoves: Vec<UciMove>,
+    /// Search until told to stop. See [`Engine::start_search()`].
+    pub infinite: bool,
+    /// Search in pondering mode, until told to stop or that the expected
+    /// move was played. See [`Engine::start_search()`].
+    pub ponder: bool,
+}
+
+impl fmt::Display for Go {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("go")?;
+        if self.ponder {
+            f.write_str(" ponder")?;
+        }
+        let limits = [
+            ("wtime", self.wtime),
+            ("btime", self.btime),
+            ("winc", self.winc),
+            ("binc", self.binc),
+            ("movestogo", self.movestogo.map(u64::from)),
+            ("depth", self.depth.map(u64::from)),
+            ("nodes", self.nodes),
+            ("mate", self.mate.map(u64::from)),
+            ("movetime", self.movetime),
+        ];
+        for (name, value) in limits {
+            if let Some(value) = value {
+                write!(f, " {name} {value}")?;
+            }
+        }
+        if !self.searchmoves.is_empty() {
+            f.write_str(" searchmoves")?;
+            for m in &self.searchmoves {
+                write!(f, " {m}")?;
+            }
+        }
+        if self.infinite {
+            f.write_str(" infinite")?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of [`Engine::go()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Search {
+    /// All `info` lines sent during the search, in order.
+    pub infos: Vec<Info>,
+    pub best_move: BestMove,
+}
+
+/// A running engine process.
+///
+/// When the `Engine` is dropped, the process is asked to quit, and killed
+/// if it does not exit within half a second.
+#[derive(Debug)]
+pub struct Engine {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    name: Option<String>,
+    author: Option<String>,
+    options: Vec<String>,
+    line: String,
+}
+
+impl Engine {
+    /// Spawns the engine at the given path and performs the handshake.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when spawning or communicating with the process, or if the
+    /// process exits before completing the handshake.
+    pub fn spawn<S: AsRef<OsStr>>(program: S) -> io::Result<Engine> {
+        Engine::from_command(Command::new(program))
+    }
+
+    /// Spawns the engine from a prepared command (e.g., with arguments or
+    /// a working directory), and performs the handshake.
+    ///
+    /// Standard input and output of the command are overwritten to
+    /// communicate with the engine.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when spawning or communicating with the process, or if the
+    /// process exits before completing the handshake.
+    pub fn from_command(mut command: Command) -> io::Result<Engine> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(io::Error::other("engine stdio not captured"));
+        };
+        let mut engine = Engine {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            name: None,
+            author: None,
+            options: Vec::new(),
+            line: String::new(),
+        };
+
+        engine.send("uci")?;
+        loop {
+            let line = engine.read_line()?.to_string();
+            if line == "uciok" {
+                break;
+            } else if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = Some(name.to_string());
+            } else if let Some(author) = line.strip_prefix("id author ") {
+                engine.author = Some(author.to_string());
+            } else if line.starts_with("option ") {
+                engine.options.push(line);
+            }
+        }
+        Ok(engine)
+    }
+
+    /// Name of the engine, as announced during the handshake.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Author of the engine, as announced during the handshake.
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// Raw `option ...` lines announced during the handshake.
+    pub fn option_lines(&self) -> &[String] {
+        &self.options
+    }
+
+    /// Options announced during the handshake. Lines that can not be parsed
+    /// are skipped.
+    pub fn options(&self) -> impl Iterator<Item = EngineOption> + '_ {
+        self.options
+            .iter()
+            .filter_map(|line| EngineOption::from_line(line).ok())
+    }
+
+    /// Sends a raw command line.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when writing to the process.
+    pub fn send(&mut self, command: &str) -> io::Result<()> {
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()
+    }
+
+    /// Reads the next non-empty line sent by the engine, without the line
+    /// terminator.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when reading from the process, or
+    /// [`io::ErrorKind::UnexpectedEof`] if the process closed its output.
+    pub fn read_line(&mut self) -> io::Result<&str> {
+        loop {
+            self.line.clear();
+            if self.stdout.read_line(&mut self.line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "engine process closed output",
+                ));
+            }
+            if !self.line.trim().is_empty() {
+                return Ok(self.line.trim());
+            }
+        }
+    }
+
+    /// Sends `isready` and waits for `readyok`.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when communicating with the process.
+    pub fn is_ready(&mut self) -> io::Result<()> {
+        self.send("isready")?;
+        while self.read_line()? != "readyok" {}
+        Ok(())
+    }
+
+    /// Sets an option and waits until the engine is ready.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when communicating with the process.
+    pub fn set_option(&mut self, name: &str, value: &str) -> io::Result<()> {
+        self.send(&format!("setoption name {name} value {value}"))?;
+        self.is_ready()
+    }
+
+    /// Announces a new game and waits until the engine is ready.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when communicating with the process.
+    pub fn new_game(&mut self) -> io::Result<()> {
+        self.send("ucinewgame")?;
+        self.is_ready()
+    }
+
+    /// Sets the position to search, given by a starting position (or the
+    /// standard starting position if `None`) and moves played from there.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when writing to the process.
+    pub fn set_position(&mut self, fen: Option<&Fen>, moves: &[UciMove]) -> io::Result<()> {
+        let mut command = match fen {
+            Some(fen) => format!("position fen {fen}"),
+            None => "position startpos".to_string(),
+        };
+        if !moves.is_empty() {
+            command.push_str(" moves");
+            for m in moves {
+                command.push(' ');
+                command.push_str(&m.to_string());
+            }
+        }
+        self.send(&command)
+    }
+
+    /// Starts searching the current position and blocks until the engine
+    /// sends `bestmove`.
+    ///
+    /// `info` lines that can not be parsed are skipped.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when communicating with the process,
+    /// [`io::ErrorKind::InvalidData`] if the engine sends an invalid
+    /// `bestmove` line, or [`io::ErrorKind::InvalidInput`] if the search
+    /// is [`infinite`](Go::infinite) or in [`ponder`](Go::ponder) mode,
+    /// and would never finish on its own.
+    pub fn go(&mut self, go: &Go) -> io::Result<Search> {
+        self.go_with(go, |_| ())
+    }
+
+    /// Like [`Engine::go()`], but also calls `on_info` as soon as each
+    /// `info` line arrives.
+    ///
+    /// # Errors
+    ///
+    /// See [`Engine::go()`].
+    pub fn go_with<F>(&mut self, go: &Go, on_info: F) -> io::Result<Search>
+    where
+        F: FnMut(&Info),
+    {
+        if go.infinite || go.ponder {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "infinite or ponder search would never finish",
+            ));
+        }
+        self.start_search(go)?;
+        self.wait_search_with(on_info)
+    }
+
+    /// Starts searching the current position, without waiting for the
+    /// result.
+    ///
+    /// This allows [`infinite`](Go::infinite) and [`ponder`](Go::ponder)
+    /// searches. Call [`Engine::stop()`] or [`Engine::ponder_hit()`] as
+    /// needed, and then [`Engine::wait_search()`] to collect the result.
+    ///
+    /// Lines sent by the engine are only read while waiting, so avoid
+    /// leaving long searches unattended.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when writing to the process.
+    pub fn start_search(&mut self, go: &Go) -> io::Result<()> {
+        self.send(&go.to_string())
+    }
+
+    /// Blocks until the engine sends `bestmove` for a search started with
+    /// [`Engine::start_search()`].
+    ///
+    /// `info` lines that can not be parsed are skipped.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when communicating with the process, or
+    /// [`io::ErrorKind::InvalidData`] if the engine sends an invalid
+    /// `bestmove` line.
+    pub fn wait_search(&mut self) -> io::Result<Search> {
+        self.wait_search_with(|_| ())
+    }
+
+    /// Like [`Engine::wait_search()`], but also calls `on_info` as soon as
+    /// each `info` line arrives.
+    ///
+    /// # Errors
+    ///
+    /// See [`Engine::wait_search()`].
+    pub fn wait_search_with<F>(&mut self, mut on_info: F) -> io::Result<Search>
+    where
+        F: FnMut(&Info),
+    {
+        let mut infos = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            if line.starts_with("info ") {
+                if let Ok(info) = Info::from_line(line) {
+                    on_info(&info);
+                    infos.push(info);
+                }
+            } else if line.starts_with("bestmove") {
+                let best_move = BestMove::from_line(line).map_err(invalid_data)?;
+                return Ok(Search { infos, best_move });
+            }
+        }
+    }
+
+    /// Asks the engine to stop searching as soon as possible.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when writing to the process.
+    pub fn stop(&mut self) -> io::Result<()> {
+        self.send("stop")
+    }
+
+    /// Tells the engine that the expected move of a
+    /// [`ponder`](Go::ponder) search was played, so that it continues with
+    /// a normal search.
+    ///
+    /// # Errors
+    ///
+    /// I/O error when writing to the process.
+    pub fn ponder_hit(&mut self) -> io::Result<()> {
+        self.send("ponderhit")
+    }
+}
+
+const QUIT_TIMEOUT: Duration = Duration::from_millis(500);
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        if self.send("quit").is_ok() {
+            let deadline = Instant::now() + QUIT_TIMEOUT;
+            while Instant::now() < deadline {
+                match self.child.try_wait() {
+                    Ok(None) => thread::sleep(Duration::from_millis(10)),
+                    Ok(Some(_)) => return,
+                    Err(_) => break,
+                }
+            }
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn invalid_data(err: ParseEngineLineError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+
+    use super::*;
+    use crate::{Color, Square};
+
+    #[test]
+    fn test_parse_info() {
+        let info = Info::from_line(
+            "info depth 24 seldepth 33 multipv 2 score cp -17 upperbound wdl 10 900 90 nodes 2148753 nps 1074376 hashfull 512 tbhits 0 time 2000 pv g1f3 d7d5 string hello  world",
+        )
+        .expect("valid info");
+        assert_eq!(info.depth, Some(Depth(24)));
+        assert_eq!(info.seldepth, Some(Depth(33)));
+        assert_eq!(info.multipv, Some(2));
+        assert_eq!(info.score, Some(Score::Cp(-17)));
+        assert_eq!(info.score_bound, ScoreBound::Upper);
+        assert_eq!(
+            info.wdl,
+            Some(Wdl {
+                wins: 10,
+                draws: 900,
+                losses: 90
+            })
+        );
+        assert_eq!(info.nps, Some(1074376));
+        assert_eq!(info.time, Some(2000));
+        assert_eq!(
+            info.pv,
+            Some(Pv(vec![
+                UciMove::Normal {
+                    from: Square::G1,
+                    to: Square::F3,
+                    promotion: None
+                },
+                UciMove::Normal {
+                    from: Square::D7,
+                    to: Square::D5,
+                    promotion: None
+                },
+            ]))
+        );
+        assert_eq!(info.string.as_deref(), Some("hello world"));
+        assert_eq!(
+            info.analysis_line()
+                .map(|line| line.pgn_eval(Color::White).to_string()),
+            Some("[%eval -0.17,24]".to_string())
+        );
+
+        assert!(Info::from_line("info depth x").is_err());
+        assert!(Info::from_line("bestmove e2e4").is_err());
+    }
+
+    #[test]
+    fn test_parse_bestmove() {
+        let best_move = BestMove::from_line("bestmove e2e4 ponder e7e5").expect("valid");
+        assert_eq!(best_move.m.map(|m| m.to_string()).as_deref(), Some("e2e4"));
+        assert_eq!(
+            best_move.ponder.map(|m| m.to_string()).as_deref(),
+            Some("e7e5")
+        );
+        assert_eq!(
+            BestMove::from_line("bestmove (none)"),
+            Ok(BestMove {
+                m: None,
+                ponder: None
+            })
+        );
+        assert_eq!(BestMove::from_line("bestmove 0000").map(|b| b.m), Ok(None));
+    }
+
+    #[test]
+    fn test_go() {
+        let go = Go {
+            wtime: Some(60000),
+            btime: Some(50000),
+            movestogo: Some(10),
+            searchmoves: vec!["e2e4".parse().expect("valid uci")],
+            ..Go::default()
+        };
+        assert_eq!(
+            go.to_string(),
+            "go wtime 60000 btime 50000 movestogo 10 searchmoves e2e4"
+        );
+        assert_eq!(Go::default().to_string(), "go");
+
+        let go = Go {
+            ponder: true,
+            infinite: true,
+            ..Go::default()
+        };
+        assert_eq!(go.to_string(), "go ponder infinite");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_engine() -> io::Result<()> {
+        // A minimal engine written in shell.
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(
+            r#"while read -r cmd rest; do
+                case "$cmd" in
+                    uci) echo "id name Fake Engine"; echo "option name Hash type spin default 16 min 1 max 1024"; echo uciok ;;
+                    isready) echo readyok ;;
+                    go) case "$rest" in
+                        *infinite*|*ponder*) echo "info depth 1 score cp 20 pv g1f3" ;;
+                        *) echo "info depth 1 score cp 10 pv e2e4"; echo "info depth x"; echo "info depth 2 score mate 1 pv d2d4 e7e5"; echo "bestmove d2d4 ponder e7e5" ;;
+                    esac ;;
+                    stop) echo "bestmove g1f3" ;;
+                    ponderhit) echo "info depth 2 score cp 30 pv g1f3"; echo "bestmove g1f3" ;;
+                    quit) exit 0 ;;
+                esac
+            done"#,
+        );
+        let mut engine = Engine::from_command(command)?;
+        assert_eq!(engine.name(), Some("Fake Engine"));
+        assert_eq!(engine.option_lines().len(), 1);
+        assert_eq!(
+            engine.options().next().map(|option| option.kind),
+            Some(OptionKind::Spin {
+                default: 16,
+                min: 1,
+                max: 1024
+            })
+        );
+
+        engine.set_option("Hash", "32")?;
+        engine.new_game()?;
+        engine.set_position(None, &["e2e4".parse().expect("valid uci")])?;
+
+        let mut seen = 0;
+        let search = engine.go_with(&Go::default(), |_| seen += 1)?;
+        assert_eq!(seen, 2);
+        assert_eq!(search.infos[1].score, Some(Score::Mate(1)));
+        assert_eq!(
+            search.best_move.m.map(|m| m.to_string()).as_deref(),
+            Some("d2d4")
+        );
+
+        let infinite = Go {
+            infinite: true,
+            ..Go::default()
+        };
+        let err = engine.go(&infinite).expect_err("would never finish");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        engine.start_search(&infinite)?;
+        engine.stop()?;
+        let search = engine.wait_search()?;
+        assert_eq!(search.infos.len(), 1);
+        assert_eq!(
+            search.best_move.m.map(|m| m.to_string()).as_deref(),
+            Some("g1f3")
+        );
+
+        engine.start_search(&Go {
+            ponder: true,
+            wtime: Some(1000),
+            btime: Some(1000),
+            ..Go::default()
+        })?;
+        engine.ponder_hit()?;
+        let search = engine.wait_search()?;
+        assert_eq!(search.infos.len(), 2);
+        assert_eq!(search.infos[1].score, Some(Score::Cp(30)));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_engine_quit() -> io::Result<()> {
+        let marker = std::env::temp_dir().join(format!("shakmaty-quit-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+
+        // Takes a moment to clean up when asked to quit.
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(
+            r#"while read -r cmd rest; do
+                case "$cmd" in
+                    uci) echo uciok ;;
+                    quit) sleep 0.1; touch "$1"; exit 0 ;;
+                esac
+            done"#,
+        );
+        command.arg("sh").arg(&marker);
+        drop(Engine::from_command(command)?);
+
+        assert!(marker.exists(), "engine was killed before it could quit");
+        std::fs::remove_file(&marker)
+    }
+}
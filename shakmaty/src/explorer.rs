@@ -0,0 +1,367 @@
+//! Aggregate game statistics by position, as in an opening explorer.
+//!
+//! Positions are keyed by their [`Zobrist64`] hash, which is stable across
+//! versions, and moves by their [`UciMove`] representation. Statistics can be
+//! collected from separate chunks of a game database and merged afterwards.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{Chess, Color, KnownOutcome, Position, explorer::Explorer, uci::UciMove};
+//!
+//! let pos = Chess::default();
+//! let e4 = UciMove::from_ascii(b"e2e4")?.to_move(&pos)?;
+//! let d4 = UciMove::from_ascii(b"d2d4")?.to_move(&pos)?;
+//!
+//! let mut explorer = Explorer::new();
+//! explorer.record(&pos, e4, KnownOutcome::Decisive { winner: Color::White }, Some(2100));
+//! explorer.record(&pos, e4, KnownOutcome::Draw, Some(1900));
+//!
+//! let mut other = Explorer::new();
+//! other.record(&pos, d4, KnownOutcome::Decisive { winner: Color::Black }, None);
+//! explorer.merge(other);
+//!
+//! let stats = explorer.get(&pos).expect("recorded");
+//! assert_eq!(stats.total().games(), 3);
+//!
+//! let e4 = stats.get(UciMove::from_ascii(b"e2e4")?).expect("recorded");
+//! assert_eq!(e4.wins(Color::White), 1);
+//! assert_eq!(e4.draws, 1);
+//! assert_eq!(e4.average_rating(), Some(2000));
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use alloc::{
+    collections::{BTreeMap, btree_map},
+    vec::Vec,
+};
+use core::ops::{Add, AddAssign};
+
+use crate::{
+    Color, EnPassantMode, KnownOutcome, Move, Position,
+    uci::UciMove,
+    zobrist::{Zobrist64, ZobristHash},
+};
+
+/// Results of the games in which a move was played.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct MoveStats {
+    /// Number of games won by White.
+    pub white: u64,
+    /// Number of drawn games.
+    pub draws: u64,
+    /// Number of games won by Black.
+    pub black: u64,
+    /// Sum of the ratings of the players who played the move, in games with
+    /// known ratings.
+    pub rating_sum: u64,
+    /// Number of games with known ratings.
+    pub rated_games: u64,
+}
+
+impl MoveStats {
+    /// Adds a game with the given result and the rating of the player who
+    /// played the move, if known.
+    pub fn record(&mut self, outcome: KnownOutcome, rating: Option<u16>) {
+        match outcome {
+            KnownOutcome::Decisive {
+                winner: Color::White,
+            } => self.white += 1,
+            KnownOutcome::Decisive {
+                winner: Color::Black,
+            } => self.black += 1,
+            KnownOutcome::Draw => self.draws += 1,
+        }
+        if let Some(rating) = rating {
+            self.rating_sum += u64::from(rating);
+            self.rated_games += 1;
+        }
+    }
+
+    /// Total number of games.
+    pub const fn games(&self) -> u64 {
+        self.white + self.draws + self.black
+    }
+
+    /// Number of games won by `color`.
+    pub const fn wins(&self, color: Color) -> u64 {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black,
+        }
+    }
+
+    /// Number of games lost by `color`.
+    pub const fn losses(&self, color: Color) -> u64 {
+        self.wins(color.other())
+    }
+
+    /// Average rating of the players who played the move, rounded down, or
+    /// `None` if no ratings are known.
+    pub const fn average_rating(&self) -> Option<u64> {
+        self.rating_sum.checked_div(self.rated_games)
+    }
+
+    /// Adds all games from `other`.
+    pub fn merge(&mut self, other: &MoveStats) {
+        self.white += other.white;
+        self.draws += other.draws;
+        self.black += other.black;
+        self.rating_sum += other.rating_sum;
+        self.rated_games += other.rated_games;
+    }
+}
+
+impl AddAssign for MoveStats {
+    fn add_assign(&mut self, other: MoveStats) {
+        self.merge(&other);
+    }
+}
+
+impl Add for MoveStats {
+    type Output = MoveStats;
+
+    fn add(mut self, other: MoveStats) -> MoveStats {
+        self.merge(&other);
+        self
+    }
+}
+
+/// Statistics for all moves played in a position.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PositionStats {
+    moves: Vec<(UciMove, MoveStats)>,
+}
+
+impl PositionStats {
+    /// Creates empty statistics.
+    pub const fn new() -> PositionStats {
+        PositionStats { moves: Vec::new() }
+    }
+
+    /// Gets the statistics of a move, if it was played.
+    pub fn get(&self, m: UciMove) -> Option<&MoveStats> {
+        self.moves
+            .iter()
+            .find_map(|(candidate, stats)| (*candidate == m).then_some(stats))
+    }
+
+    /// Gets the statistics of a move for modification, inserting empty
+    /// statistics if it was not played yet.
+    pub fn entry(&mut self, m: UciMove) -> &mut MoveStats {
+        let index = match self.moves.iter().position(|(candidate, _)| *candidate == m) {
+            Some(index) => index,
+            None => {
+                self.moves.push((m, MoveStats::default()));
+                self.moves.len() - 1
+            }
+        };
+        &mut self.moves[index].1
+    }
+
+    /// Adds a game in which `m` was played. See [`MoveStats::record()`].
+    pub fn record(&mut self, m: UciMove, outcome: KnownOutcome, rating: Option<u16>) {
+        self.entry(m).record(outcome, rating);
+    }
+
+    /// Iterates over all moves and their statistics, in the order they were
+    /// first seen.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (UciMove, &MoveStats)> + '_ {
+        self.moves.iter().map(|(m, stats)| (*m, stats))
+    }
+
+    /// Sorts the moves by number of games, most popular first.
+    pub fn sort_by_popularity(&mut self) {
+        self.moves
+            .sort_by_key(|(_, stats)| core::cmp::Reverse(stats.games()));
+    }
+
+    /// Number of distinct moves.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Tests if no moves were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Combined statistics of all moves.
+    pub fn total(&self) -> MoveStats {
+        self.moves
+            .iter()
+            .fold(MoveStats::default(), |acc, (_, stats)| acc + *stats)
+    }
+
+    /// Adds all games from `other`.
+    pub fn merge(&mut self, other: &PositionStats) {
+        for (m, stats) in &other.moves {
+            self.entry(*m).merge(stats);
+        }
+    }
+}
+
+/// Statistics for many positions, keyed by their [`Zobrist64`] hash with
+/// [`EnPassantMode::Legal`].
+///
+/// Collisions of hashes are not detected, which is negligible for typical
+/// game databases.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Explorer {
+    positions: BTreeMap<Zobrist64, PositionStats>,
+}
+
+impl Explorer {
+    /// Creates an empty explorer.
+    pub const fn new() -> Explorer {
+        Explorer {
+            positions: BTreeMap::new(),
+        }
+    }
+
+    /// Computes the key of a position.
+    pub fn key<P: Position>(pos: &P) -> Zobrist64 {
+        pos.zobrist_hash(EnPassantMode::Legal)
+    }
+
+    /// Adds a game in which the legal move `m` was played in `pos`. See
+    /// [`MoveStats::record()`].
+    pub fn record<P: Position>(
+        &mut self,
+        pos: &P,
+        m: Move,
+        outcome: KnownOutcome,
+        rating: Option<u16>,
+    ) {
+        self.record_by_key(
+            Explorer::key(pos),
+            UciMove::from_move(m, pos.castles().mode()),
+            outcome,
+            rating,
+        );
+    }
+
+    /// Adds a game in which `m` was played in the position with the given
+    /// key.
+    pub fn record_by_key(
+        &mut self,
+        key: Zobrist64,
+        m: UciMove,
+        outcome: KnownOutcome,
+        rating: Option<u16>,
+    ) {
+        self.positions
+            .entry(key)
+            .or_default()
+            .record(m, outcome, rating);
+    }
+
+    /// Gets the statistics of a position, if any games were recorded.
+    pub fn get<P: Position>(&self, pos: &P) -> Option<&PositionStats> {
+        self.get_by_key(Explorer::key(pos))
+    }
+
+    /// Gets the statistics of the position with the given key.
+    pub fn get_by_key(&self, key: Zobrist64) -> Option<&PositionStats> {
+        self.positions.get(&key)
+    }
+
+    /// Iterates over all positions and their statistics, ordered by key.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Zobrist64, &PositionStats)> + '_ {
+        self.positions.iter().map(|(key, stats)| (*key, stats))
+    }
+
+    /// Number of distinct positions.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Tests if no positions were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Adds all games from `other`.
+    pub fn merge(&mut self, other: Explorer) {
+        for (key, stats) in other.positions {
+            match self.positions.entry(key) {
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(stats);
+                }
+                btree_map::Entry::Occupied(mut entry) => entry.get_mut().merge(&stats),
+            }
+        }
+    }
+}
+
+impl Extend<(Zobrist64, PositionStats)> for Explorer {
+    fn extend<T: IntoIterator<Item = (Zobrist64, PositionStats)>>(&mut self, iter: T) {
+        for (key, stats) in iter {
+            self.positions.entry(key).or_default().merge(&stats);
+        }
+    }
+}
+
+impl FromIterator<(Zobrist64, PositionStats)> for Explorer {
+    fn from_iter<T: IntoIterator<Item = (Zobrist64, PositionStats)>>(iter: T) -> Explorer {
+        let mut explorer = Explorer::new();
+        explorer.extend(iter);
+        explorer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Chess, Square};
+
+    #[test]
+    fn test_merge() {
+        let pos = Chess::default();
+        let e4 = UciMove::Normal {
+            from: Square::E2,
+            to: Square::E4,
+            promotion: None,
+        };
+        let d4 = UciMove::Normal {
+            from: Square::D2,
+            to: Square::D4,
+            promotion: None,
+        };
+        let white = KnownOutcome::Decisive {
+            winner: Color::White,
+        };
+
+        let mut a = Explorer::new();
+        a.record_by_key(Explorer::key(&pos), e4, white, Some(1500));
+        a.record_by_key(Explorer::key(&pos), d4, KnownOutcome::Draw, None);
+
+        let mut b = Explorer::new();
+        b.record_by_key(Explorer::key(&pos), d4, white, Some(2500));
+        b.record_by_key(Zobrist64(1), e4, KnownOutcome::Draw, None);
+
+        let mut merged = a.clone();
+        merged.merge(b.clone());
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged,
+            a.iter()
+                .chain(b.iter())
+                .map(|(key, stats)| (key, stats.clone()))
+                .collect()
+        );
+
+        let mut stats = merged.get(&pos).expect("recorded").clone();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats.total().games(), 3);
+        assert_eq!(stats.total().losses(Color::Black), 2);
+        assert_eq!(stats.total().average_rating(), Some(2000));
+
+        stats.sort_by_popularity();
+        let (m, d4_stats) = stats.iter().next().expect("moves");
+        assert_eq!(m, d4);
+        assert_eq!(d4_stats.draws, 1);
+        assert_eq!(d4_stats.average_rating(), Some(2500));
+        assert_eq!(stats.get(e4).map(MoveStats::games), Some(1));
+    }
+}
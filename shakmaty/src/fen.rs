@@ -35,8 +35,10 @@
 //!
 //! # Writing
 //!
-//! Writes X-FEN with `[q]` style for Crazyhouse pockets and `3+3` style
-//! for remaining checks in Three-Check.
+//! Writes X-FEN with `[q]` style for Crazyhouse pockets (or `/q` style with
+//! [`Fen::with_slash_pockets()`]) and `3+3` style for remaining checks in
+//! Three-Check (or `+0+0` style checks given with
+//! [`Fen::with_lichess_checks()`]).
 //!
 //! [`Fen`] and [`Epd`] implement [`Display`]:
 //!
@@ -62,7 +64,8 @@ use bitflags::bitflags;
 
 use crate::{
     Bitboard, Board, ByColor, ByRole, CastlingMode, Color, EnPassantMode, File, FromSetup, Piece,
-    Position, PositionError, Rank, RemainingChecks, Setup, Square, util::AppendAscii,
+    Position, PositionError, Rank, RemainingChecks, Setup, Square,
+    util::{AppendAscii, ErrorPayload},
 };
 
 fn append_castling<W: AppendAscii>(
@@ -101,22 +104,6 @@ fn append_castling<W: AppendAscii>(
     Ok(())
 }
 
-fn append_pockets<W: AppendAscii>(
-    f: &mut W,
-    pockets: &ByColor<ByRole<u8>>,
-) -> Result<(), W::Error> {
-    f.append_ascii('[')?;
-    for (color, side) in pockets.zip_color() {
-        for (role, count) in side.zip_role() {
-            let piece = Piece { color, role };
-            for _ in 0..count {
-                f.append_ascii(piece.char())?;
-            }
-        }
-    }
-    f.append_ascii(']')
-}
-
 fn append_epd<W: AppendAscii>(
     f: &mut W,
     board: &Board,
@@ -126,11 +113,16 @@ fn append_epd<W: AppendAscii>(
     castling_rights: Bitboard,
     ep_square: Option<Square>,
     remaining_checks: &Option<ByColor<RemainingChecks>>,
+    slash_pockets: bool,
 ) -> Result<(), W::Error> {
     f.reserve(21);
     BoardFen { board, promoted }.append_to(f)?;
     if let Some(pockets) = pockets {
-        append_pockets(f, pockets)?;
+        PocketFen {
+            pockets,
+            slash: slash_pockets,
+        }
+        .append_to(f)?;
     }
     f.append_ascii(' ')?;
     f.append_ascii(turn.char())?;
@@ -180,6 +172,89 @@ impl Display for ParseFenError {
 
 impl error::Error for ParseFenError {}
 
+/// Error when parsing a FEN and setting up a position. See
+/// [`Fen::parse_position()`].
+pub enum ParsePositionError<P> {
+    /// The FEN could not be parsed.
+    Fen(ParseFenError),
+    /// The FEN does not describe a legal position.
+    Position(PositionError<P>),
+}
+
+impl<P> fmt::Debug for ParsePositionError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePositionError::Fen(err) => f.debug_tuple("Fen").field(err).finish(),
+            ParsePositionError::Position(err) => f.debug_tuple("Position").field(err).finish(),
+        }
+    }
+}
+
+impl<P> Display for ParsePositionError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePositionError::Fen(err) => Display::fmt(err, f),
+            ParsePositionError::Position(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl<P> error::Error for ParsePositionError<P> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ParsePositionError::Fen(err) => Some(err),
+            ParsePositionError::Position(_) => None,
+        }
+    }
+}
+
+impl<P> From<ParseFenError> for ParsePositionError<P> {
+    fn from(err: ParseFenError) -> ParsePositionError<P> {
+        ParsePositionError::Fen(err)
+    }
+}
+
+impl<P> From<PositionError<P>> for ParsePositionError<P> {
+    fn from(err: PositionError<P>) -> ParsePositionError<P> {
+        ParsePositionError::Position(err)
+    }
+}
+
+/// Parses many FENs in parallel and sets up the positions, using a
+/// [`rayon`](https://docs.rs/rayon/1) thread pool. Results are in the same
+/// order as the input.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{CastlingMode, Chess, fen};
+///
+/// let fens = [
+///     "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+///     "not a fen",
+///     "8/8/8/8/8/8/8/8 w - - 0 1",
+/// ];
+/// let positions = fen::par_parse_positions::<Chess, _>(&fens, CastlingMode::Standard);
+/// assert!(positions[0].is_ok());
+/// assert!(matches!(positions[1], Err(fen::ParsePositionError::Fen(_))));
+/// assert!(matches!(positions[2], Err(fen::ParsePositionError::Position(_))));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_parse_positions<P, T>(
+    fens: &[T],
+    mode: CastlingMode,
+) -> alloc::vec::Vec<Result<P, ParsePositionError<P>>>
+where
+    P: FromSetup + Send,
+    T: AsRef<[u8]> + Sync,
+{
+    use rayon::prelude::*;
+
+    fens.par_iter()
+        .map(|fen| Fen::parse_position(fen.as_ref(), mode))
+        .collect()
+}
+
 fn parse_board_fen(board_fen: &[u8]) -> Result<(Board, Bitboard), ParseFenError> {
     let mut promoted = Bitboard(0);
     let mut board = Board::empty();
@@ -309,11 +384,19 @@ bitflags! {
 /// See [`LossyFenErrorKinds`] for possible reasons.
 #[derive(Debug, Clone)]
 pub struct LossyFenError<F> {
-    fen: F,
+    fen: ErrorPayload<F>,
     errors: LossyFenErrorKinds,
 }
 
 impl<F> LossyFenError<F> {
+    #[cold]
+    fn new(fen: F, errors: LossyFenErrorKinds) -> LossyFenError<F> {
+        LossyFenError {
+            fen: ErrorPayload::new(fen),
+            errors,
+        }
+    }
+
     /// Returns the reasons for this error.
     pub fn kinds(&self) -> LossyFenErrorKinds {
         self.errors
@@ -321,7 +404,7 @@ impl<F> LossyFenError<F> {
 
     /// Ignores all information that cannot be losslessly represented.
     pub fn ignore(self) -> F {
-        self.fen
+        self.fen.into_inner()
     }
 }
 
@@ -364,10 +447,7 @@ impl Board {
         if promoted == fen.promoted {
             Ok(fen)
         } else {
-            Err(LossyFenError {
-                fen,
-                errors: LossyFenErrorKinds::PROMOTED,
-            })
+            Err(LossyFenError::new(fen, LossyFenErrorKinds::PROMOTED))
         }
     }
 }
@@ -446,6 +526,102 @@ impl Display for BoardFen<'_> {
     }
 }
 
+impl ByColor<ByRole<u8>> {
+    /// Parses pockets like `[Nq]`, as well as `/Nq` (the slash style) and
+    /// plain `Nq`.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`ParseFenError::InvalidPocket`] if the pockets are not
+    /// valid or contain more than 64 pieces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{ByColor, ByRole};
+    ///
+    /// let pockets = ByColor::<ByRole<u8>>::from_ascii_pocket_fen(b"[NNq]")?;
+    /// assert_eq!(pockets.white.knight, 2);
+    /// assert_eq!(pockets.black.queen, 1);
+    ///
+    /// assert_eq!(pockets.pocket_fen().to_string(), "[NNq]");
+    /// assert_eq!(pockets.slash_pocket_fen().to_string(), "/NNq");
+    /// # Ok::<_, shakmaty::fen::ParseFenError>(())
+    /// ```
+    pub fn from_ascii_pocket_fen(s: &[u8]) -> Result<ByColor<ByRole<u8>>, ParseFenError> {
+        let s = if let Some(s) = s.strip_prefix(b"[") {
+            s.strip_suffix(b"]").ok_or(ParseFenError::InvalidPocket)?
+        } else {
+            s.strip_prefix(b"/").unwrap_or(s)
+        };
+        parse_pockets(s).ok_or(ParseFenError::InvalidPocket)
+    }
+
+    /// Create pockets FEN such as `[Nq]`, as used in X-FEN.
+    ///
+    /// Returns a [`PocketFen`] which implements [`Display`].
+    pub const fn pocket_fen(&self) -> PocketFen<'_> {
+        PocketFen {
+            pockets: self,
+            slash: false,
+        }
+    }
+
+    /// Create pockets FEN such as `/Nq`, to be appended to the board part of
+    /// a FEN.
+    ///
+    /// Returns a [`PocketFen`] which implements [`Display`].
+    pub const fn slash_pocket_fen(&self) -> PocketFen<'_> {
+        PocketFen {
+            pockets: self,
+            slash: true,
+        }
+    }
+}
+
+/// Displays pockets with notation like `[Nq]` or `/Nq`.
+///
+/// See [`ByColor::pocket_fen`] and [`ByColor::slash_pocket_fen`].
+#[derive(Debug)]
+pub struct PocketFen<'p> {
+    pockets: &'p ByColor<ByRole<u8>>,
+    slash: bool,
+}
+
+impl PocketFen<'_> {
+    fn append_to<W: AppendAscii>(&self, f: &mut W) -> Result<(), W::Error> {
+        f.append_ascii(if self.slash { '/' } else { '[' })?;
+        for (color, side) in self.pockets.zip_color() {
+            for (role, count) in side.zip_role() {
+                let piece = Piece { color, role };
+                for _ in 0..count {
+                    f.append_ascii(piece.char())?;
+                }
+            }
+        }
+        if !self.slash {
+            f.append_ascii(']')?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn append_to_string(&self, s: &mut alloc::string::String) {
+        let _ = self.append_to(s);
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn append_ascii_to(&self, buf: &mut alloc::vec::Vec<u8>) {
+        let _ = self.append_to(buf);
+    }
+}
+
+impl Display for PocketFen<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.append_to(f)
+    }
+}
+
 /// A FEN like `rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1`.
 #[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
 pub struct Fen {
@@ -488,7 +664,7 @@ impl Fen {
     /// let fen = Fen::try_from_setup(setup).unwrap_or_else(LossyFenError::ignore);
     /// assert_eq!(fen.to_string(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w GQkq - 0 1");
     /// ```
-    #[allow(clippy::result_large_err)] // Ok variant is also large
+    #[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
     pub fn try_from_setup(setup: Setup) -> Result<Fen, LossyFenError<Fen>> {
         let mut errors = LossyFenErrorKinds::empty();
 
@@ -517,7 +693,7 @@ impl Fen {
         if errors.is_empty() {
             Ok(fen)
         } else {
-            Err(LossyFenError { fen, errors })
+            Err(LossyFenError::new(fen, errors))
         }
     }
 
@@ -696,21 +872,142 @@ impl Fen {
         P::from_setup(self.setup, mode)
     }
 
+    /// Parses a FEN and sets up a [`Position`]. Shorthand for
+    /// [`Fen::from_ascii()`] and [`Fen::into_position()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParsePositionError`] if the FEN is invalid or the setup does
+    /// not meet basic validity requirements.
+    pub fn parse_position<P: FromSetup>(
+        fen: &[u8],
+        mode: CastlingMode,
+    ) -> Result<P, ParsePositionError<P>> {
+        Ok(Fen::from_ascii(fen)?.into_position(mode)?)
+    }
+
+    /// Displays the FEN with `/Nq` style Crazyhouse pockets, instead of the
+    /// default `[Nq]` style.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::fen::Fen;
+    ///
+    /// let fen: Fen = "4k3/8/8/8/8/8/8/4K2Q~[Nq] b - - 0 1".parse()?;
+    /// assert_eq!(
+    ///     fen.with_slash_pockets().to_string(),
+    ///     "4k3/8/8/8/8/8/8/4K2Q~/Nq b - - 0 1"
+    /// );
+    /// # Ok::<_, shakmaty::fen::ParseFenError>(())
+    /// ```
+    pub const fn with_slash_pockets(&self) -> StyledFen<'_> {
+        StyledFen::new(self).with_slash_pockets()
+    }
+
+    /// Displays the FEN with Three-Check checks given in `+2+1` style after
+    /// the move counters (as used by lichess.org), instead of the default
+    /// `1+2` style remaining checks before the move counters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::fen::Fen;
+    ///
+    /// let fen: Fen = "4k3/8/8/8/8/8/8/4K3 w - - 1+2 0 1".parse()?;
+    /// assert_eq!(
+    ///     fen.with_lichess_checks().to_string(),
+    ///     "4k3/8/8/8/8/8/8/4K3 w - - 0 1 +2+1"
+    /// );
+    /// # Ok::<_, shakmaty::fen::ParseFenError>(())
+    /// ```
+    pub const fn with_lichess_checks(&self) -> StyledFen<'_> {
+        StyledFen::new(self).with_lichess_checks()
+    }
+
+    fn append_to<W: AppendAscii>(&self, f: &mut W) -> Result<(), W::Error> {
+        StyledFen::new(self).append_to(f)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn append_to_string(&self, s: &mut alloc::string::String) {
+        let _ = self.append_to(s);
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn append_ascii_to(&self, buf: &mut alloc::vec::Vec<u8>) {
+        let _ = self.append_to(buf);
+    }
+}
+
+/// Displays a [`Fen`] with alternative notations for Crazyhouse pockets
+/// and Three-Check checks.
+///
+/// See [`Fen::with_slash_pockets()`] and [`Fen::with_lichess_checks()`].
+#[derive(Debug, Clone)]
+pub struct StyledFen<'f> {
+    fen: &'f Fen,
+    slash_pockets: bool,
+    lichess_checks: bool,
+}
+
+impl<'f> StyledFen<'f> {
+    const fn new(fen: &'f Fen) -> StyledFen<'f> {
+        StyledFen {
+            fen,
+            slash_pockets: false,
+            lichess_checks: false,
+        }
+    }
+
+    /// Uses `/Nq` style Crazyhouse pockets.
+    #[must_use]
+    pub const fn with_slash_pockets(mut self) -> StyledFen<'f> {
+        self.slash_pockets = true;
+        self
+    }
+
+    /// Uses `+2+1` style checks given in Three-Check. Falls back to the
+    /// default style if a side has more than 3 remaining checks, which can
+    /// not be expressed as checks given.
+    #[must_use]
+    pub const fn with_lichess_checks(mut self) -> StyledFen<'f> {
+        self.lichess_checks = true;
+        self
+    }
+
     fn append_to<W: AppendAscii>(&self, f: &mut W) -> Result<(), W::Error> {
+        let setup = &self.fen.setup;
+        let checks_given = setup.remaining_checks.filter(|remaining_checks| {
+            self.lichess_checks && remaining_checks.iter().all(|r| u32::from(*r) <= 3)
+        });
         append_epd(
             f,
-            &self.setup.board,
-            self.setup.promoted,
-            &self.setup.pockets,
-            self.setup.turn,
-            self.setup.castling_rights,
-            self.setup.ep_square,
-            &self.setup.remaining_checks,
+            &setup.board,
+            setup.promoted,
+            &setup.pockets,
+            setup.turn,
+            setup.castling_rights,
+            setup.ep_square,
+            if checks_given.is_some() {
+                &None
+            } else {
+                &setup.remaining_checks
+            },
+            self.slash_pockets,
         )?;
         f.append_ascii(' ')?;
-        f.append_u32(self.setup.halfmoves)?;
+        f.append_u32(setup.halfmoves)?;
         f.append_ascii(' ')?;
-        f.append_u32(u32::from(self.setup.fullmoves))
+        f.append_u32(u32::from(setup.fullmoves))?;
+        if let Some(remaining_checks) = checks_given {
+            f.append_ascii(' ')?;
+            for remaining in remaining_checks {
+                f.append_ascii('+')?;
+                f.append_u32(3 - u32::from(remaining))?;
+            }
+        }
+        Ok(())
     }
 
     #[cfg(feature = "alloc")]
@@ -724,6 +1021,12 @@ impl Fen {
     }
 }
 
+impl Display for StyledFen<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.append_to(f)
+    }
+}
+
 impl TryFrom<Setup> for Fen {
     type Error = LossyFenError<Fen>;
 
@@ -838,12 +1141,12 @@ impl Epd {
         Epd::from_setup_unchecked(fen.setup)
     }
 
-    #[allow(clippy::result_large_err)] // Ok variant also large
+    #[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
     pub fn try_from_setup(setup: Setup) -> Result<Epd, LossyFenError<Epd>> {
         match Fen::try_from_setup(setup) {
             Ok(fen) => Ok(Epd::from_fen(fen)),
             Err(LossyFenError { fen, errors }) => Err(LossyFenError {
-                fen: Epd::from_fen(fen),
+                fen: fen.map(Epd::from_fen),
                 errors,
             }),
         }
@@ -885,6 +1188,7 @@ impl Epd {
             self.castling_rights,
             self.ep_square,
             &self.remaining_checks,
+            false,
         )
     }
 
@@ -1110,6 +1414,30 @@ mod tests {
         assert_eq!(setup.fullmoves.get(), 2);
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_lichess_checks_roundtrip() {
+        use alloc::string::ToString as _;
+
+        let xfen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 1+3 1 2";
+        let lichess = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 1 2 +2+0";
+        for input in [xfen, lichess] {
+            let fen = input.parse::<Fen>().expect("valid fen");
+            assert_eq!(fen.to_string(), xfen);
+            assert_eq!(fen.with_lichess_checks().to_string(), lichess);
+        }
+
+        // Five-Check counts can not be written as checks given.
+        let fen = "4k3/8/8/8/8/8/8/4K3 w - - 5+5 0 1"
+            .parse::<Fen>()
+            .expect("valid fen");
+        assert_eq!(fen.with_lichess_checks().to_string(), fen.to_string());
+
+        // Without remaining checks, there is nothing to write.
+        let fen = Fen::default();
+        assert_eq!(fen.with_lichess_checks().to_string(), fen.to_string());
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn test_castling_right_without_rook() {
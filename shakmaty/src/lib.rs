@@ -43,7 +43,7 @@
 //! ```
 //!
 //! Also supports [FEN](fen), [SAN](san) and
-//! [UCI](uci) formats for positions and moves.
+//! [UCI](mod@uci) formats for positions and moves.
 //!
 //! # Feature flags
 //!
@@ -53,6 +53,14 @@
 //! * `std`: Implies `alloc`. Enabled by default.
 //!   For `no_std` environments, this must be disabled with `default-features = false`.
 //! * `variant`: Enables support for all Lichess variants.
+//! * `compact-attacks`: Computes attacks of sliding pieces with small
+//!   tables instead of magic bitboards, trading some speed for much less
+//!   cache pressure. See [`attacks`](attacks#tables).
+//! * `lazy-attacks`: Implies `std`. Adds [`attacks::lazy`], with lookups
+//!   for sliding pieces that compute their tables at runtime.
+//!   See [`attacks`](attacks#tables).
+//! * `engine`: Implies `std`. Enables the [`engine`] module to drive UCI
+//!   engine processes.
 //! * `arbitrary`: Implements [`arbitrary::Arbitrary`](https://docs.rs/arbitrary/1/arbitrary/trait.Arbitrary.html)
 //!   for vocabulary types.
 //! * `bincode`: Implements [`bincode`](https://docs.rs/bincode/2)
@@ -64,6 +72,21 @@
 //! * `serde`: Implements [`serde`](https://docs.rs/serde/1)
 //!   serialization/deserialization for types with unique natural
 //!   representations.
+//! * `rkyv`: Implements [`rkyv`](https://docs.rs/rkyv/0.8) zero-copy
+//!   serialization for [`Board`], [`Setup`] and
+//!   [`PackedSetup`](packed::PackedSetup), and the types they are composed
+//!   of. Deserializing a [`Board`] validates its bitboards.
+//! * `wasm`: Implies `std`. Enables the [`wasm`] module with JavaScript
+//!   bindings for web frontends.
+//! * `python`: Implies `std`. Enables the [`python`] module with Python
+//!   bindings.
+//! * `proptest`: Implies `std`. Enables the [`proptest`] module with
+//!   strategies for property testing.
+//! * `rayon`: Implies `std`. Enables
+//!   [`fen::par_parse_positions()`](fen::par_parse_positions) to parse FENs
+//!   in parallel using [`rayon`](https://docs.rs/rayon/1).
+//! * `render`: Implies `alloc`. Enables the [`render`] module to draw SVG
+//!   board diagrams.
 //! * `nohash-hasher`: Implements
 //!   [`nohash_hasher::IsEnabled`](https://docs.rs/nohash-hasher/0.2/nohash_hasher/trait.IsEnabled.html)
 //!   for sensible types.
@@ -89,6 +112,7 @@ mod util;
 mod castling_side;
 mod color;
 mod m;
+mod macros;
 mod perft;
 mod position;
 mod role;
@@ -96,27 +120,47 @@ mod setup;
 mod square;
 mod types;
 
+#[cfg(feature = "alloc")]
+pub mod analysis;
 pub mod attacks;
 pub mod bitboard;
 pub mod board;
+pub mod endgames;
+#[cfg(feature = "engine")]
+pub mod engine;
+#[cfg(feature = "alloc")]
+pub mod explorer;
 pub mod fen;
+pub mod nnue;
 pub mod packed;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+pub mod psqt;
+#[cfg(feature = "alloc")]
+pub mod puzzle;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "render")]
+pub mod render;
 pub mod san;
+pub mod training;
 pub mod uci;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod zobrist;
 
 #[cfg(feature = "variant")]
 pub mod variant;
 
 pub use bitboard::Bitboard;
-pub use board::Board;
+pub use board::{Board, MailboxBoard};
 pub use castling_side::{ByCastlingSide, CastlingSide};
 pub use color::{ByColor, Color, ParseColorError};
 pub use m::{Move, MoveList};
 pub use perft::perft;
 pub use position::{
-    Chess, FromSetup, KnownOutcome, Outcome, ParseOutcomeError, PlayError, Position, PositionError,
-    PositionErrorKinds,
+    Chess, FromSetup, InsufficientMaterialRule, KnownOutcome, LegalDestinations, LegalMovesIter,
+    Odds, Outcome, ParseOutcomeError, PlayError, Position, PositionError, PositionErrorKinds,
 };
 pub use role::{ByRole, Role};
 pub use setup::{Castles, Setup};
@@ -5,7 +5,7 @@ use core::{
 
 use arrayvec::ArrayVec;
 
-use crate::{CastlingSide, Role, Square};
+use crate::{CastlingSide, Color, Piece, Role, Square};
 
 /// Information about a move.
 ///
@@ -13,7 +13,7 @@ use crate::{CastlingSide, Role, Square};
 ///
 /// `Move` implements [`Display`] using long algebraic notation. If a position
 /// is available for context, it is more common to use [SAN](crate::san)
-/// (for human interfaces) or [UCI](crate::uci) (for text-based protocols).
+/// (for human interfaces) or [UCI](mod@crate::uci) (for text-based protocols).
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum Move {
@@ -166,6 +166,50 @@ impl Move {
             },
         }
     }
+
+    /// Pieces that are removed from and added to the board when `turn`
+    /// plays the move. Side effects of variant rules are not included.
+    pub(crate) fn board_changes(self, turn: Color) -> BoardChanges {
+        let mut changes = BoardChanges::default();
+        match self {
+            Move::Normal {
+                role,
+                from,
+                capture,
+                to,
+                promotion,
+            } => {
+                changes.removed.push((from, role.of(turn)));
+                if let Some(capture) = capture {
+                    changes.removed.push((to, capture.of(!turn)));
+                }
+                changes.added.push((to, promotion.unwrap_or(role).of(turn)));
+            }
+            Move::EnPassant { from, to } => {
+                changes.removed.push((from, turn.pawn()));
+                changes
+                    .removed
+                    .push((Square::from_coords(to.file(), from.rank()), (!turn).pawn()));
+                changes.added.push((to, turn.pawn()));
+            }
+            Move::Castle { king, rook } => {
+                let side = CastlingSide::from_king_side(king < rook);
+                changes.removed.push((king, turn.king()));
+                changes.removed.push((rook, turn.rook()));
+                changes.added.push((side.king_to(turn), turn.king()));
+                changes.added.push((side.rook_to(turn), turn.rook()));
+            }
+            Move::Put { role, to } => changes.added.push((to, role.of(turn))),
+        }
+        changes
+    }
+}
+
+/// See [`Move::board_changes()`].
+#[derive(Default)]
+pub(crate) struct BoardChanges {
+    pub removed: ArrayVec<(Square, Piece), 2>,
+    pub added: ArrayVec<(Square, Piece), 2>,
 }
 
 impl Display for Move {
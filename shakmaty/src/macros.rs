@@ -0,0 +1,67 @@
+/// Parses a [`Square`](crate::Square) at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Square, sq};
+///
+/// const E4: Square = sq!("e4");
+/// assert_eq!(E4, Square::E4);
+/// ```
+///
+/// Invalid squares are rejected at compile time:
+///
+/// ```compile_fail
+/// let _ = shakmaty::sq!("i9");
+/// ```
+#[macro_export]
+macro_rules! sq {
+    ($square:literal) => {
+        const {
+            match $crate::Square::from_ascii($square.as_bytes()) {
+                Ok(square) => square,
+                Err(_) => panic!(concat!("invalid square: ", $square)),
+            }
+        }
+    };
+}
+
+/// Parses a [`UciMove`](crate::uci::UciMove) at compile time, for example
+/// to write opening lines as constants.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Chess, Position, uci, uci::UciMove};
+///
+/// const ITALIAN: [UciMove; 5] = [
+///     uci!("e2e4"),
+///     uci!("e7e5"),
+///     uci!("g1f3"),
+///     uci!("b8c6"),
+///     uci!("f1c4"),
+/// ];
+///
+/// let mut pos = Chess::default();
+/// for uci in ITALIAN {
+///     pos.play_unchecked(uci.to_move(&pos)?);
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Syntactically invalid moves are rejected at compile time:
+///
+/// ```compile_fail
+/// let _ = shakmaty::uci!("e2e9");
+/// ```
+#[macro_export]
+macro_rules! uci {
+    ($uci:literal) => {
+        const {
+            match $crate::uci::UciMove::from_ascii($uci.as_bytes()) {
+                Ok(uci) => uci,
+                Err(_) => panic!(concat!("invalid uci: ", $uci)),
+            }
+        }
+    };
+}
@@ -0,0 +1,232 @@
+//! Feature indices for NNUE evaluation.
+//!
+//! NNUE networks take sparse binary inputs, one for each piece on the board,
+//! relative to the square of the king of the perspective side. Engines keep
+//! an accumulator for each perspective and update it incrementally with
+//! the features that are added and removed by each move.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{Chess, Color, Position, nnue::FeatureSet, uci::UciMove};
+//!
+//! let pos = Chess::default();
+//! let features = FeatureSet::HalfKp
+//!     .active_features(pos.board(), Color::White)
+//!     .expect("king on board");
+//! assert_eq!(features.count(), 30);
+//!
+//! let m = UciMove::from_ascii(b"e2e4")?.to_move(&pos)?;
+//! let delta = FeatureSet::HalfKp
+//!     .delta(&pos, m, Color::White)
+//!     .expect("no refresh needed");
+//! assert_eq!(delta.removed.len(), 1);
+//! assert_eq!(delta.added.len(), 1);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use arrayvec::ArrayVec;
+
+use crate::{Board, Color, Move, Piece, Position, Role, Square};
+
+/// Input feature set of an NNUE network.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FeatureSet {
+    /// `HalfKP` with 41024 inputs, as used by the first NNUE networks in
+    /// Stockfish 12. Kings are not features. Squares are rotated by 180° for
+    /// the black perspective.
+    HalfKp,
+    /// `HalfKAv2` with 45056 inputs, as used by Stockfish 14. Both kings
+    /// share a feature plane. Squares are flipped vertically for the black
+    /// perspective.
+    HalfKaV2,
+}
+
+impl FeatureSet {
+    /// Gets the number of inputs.
+    pub const fn dimensions(self) -> usize {
+        self.planes() * 64
+    }
+
+    const fn planes(self) -> usize {
+        match self {
+            FeatureSet::HalfKp => 10 * 64 + 1,
+            FeatureSet::HalfKaV2 => 11 * 64,
+        }
+    }
+
+    const fn orient(self, perspective: Color, sq: Square) -> usize {
+        match (self, perspective) {
+            (_, Color::White) => sq.to_usize(),
+            (FeatureSet::HalfKp, Color::Black) => sq.rotate_180().to_usize(),
+            (FeatureSet::HalfKaV2, Color::Black) => sq.flip_vertical().to_usize(),
+        }
+    }
+
+    /// Gets the feature index of `piece` on `sq`, from the point of view of
+    /// `perspective` with its king on `king`.
+    ///
+    /// Returns `None` for kings, if they are not features of this set.
+    pub const fn index(
+        self,
+        perspective: Color,
+        king: Square,
+        sq: Square,
+        piece: Piece,
+    ) -> Option<usize> {
+        let theirs = piece.color as usize != perspective as usize;
+        let plane = match (self, piece.role) {
+            (FeatureSet::HalfKp, Role::King) => return None,
+            (FeatureSet::HalfKp, role) => 1 + (2 * (role as usize - 1) + theirs as usize) * 64,
+            (FeatureSet::HalfKaV2, Role::King) => 10 * 64,
+            (FeatureSet::HalfKaV2, role) => (2 * (role as usize - 1) + theirs as usize) * 64,
+        };
+        Some(self.orient(perspective, sq) + plane + self.planes() * self.orient(perspective, king))
+    }
+
+    /// Iterates over the indices of all active features, from the point of
+    /// view of `perspective`.
+    ///
+    /// Returns `None` if there is no king of `perspective` on the board.
+    pub fn active_features(
+        self,
+        board: &Board,
+        perspective: Color,
+    ) -> Option<impl Iterator<Item = usize> + '_> {
+        let king = board.king_of(perspective)?;
+        Some(
+            board
+                .iter()
+                .filter_map(move |(sq, piece)| self.index(perspective, king, sq, piece)),
+        )
+    }
+
+    /// Computes the features that are removed and added when `m` is played
+    /// in `pos`, from the point of view of `perspective`.
+    ///
+    /// Returns `None` if there is no king of `perspective` on the board, or
+    /// if the move changes its square, so that the accumulator has to be
+    /// refreshed from [`FeatureSet::active_features()`].
+    ///
+    /// The move is assumed to be legal. Side effects of variant rules, like
+    /// explosions in Atomic chess, are not taken into account.
+    pub fn delta<P: Position>(self, pos: &P, m: Move, perspective: Color) -> Option<FeatureDelta> {
+        let king = pos.board().king_of(perspective)?;
+        let changes = m.board_changes(pos.turn());
+        if changes.removed.contains(&(king, perspective.king())) {
+            return None;
+        }
+
+        let features = |changes: &[(Square, Piece)]| {
+            changes
+                .iter()
+                .filter_map(|&(sq, piece)| self.index(perspective, king, sq, piece))
+                .collect()
+        };
+        Some(FeatureDelta {
+            removed: features(&changes.removed),
+            added: features(&changes.added),
+        })
+    }
+}
+
+/// Features removed and added by a move. See [`FeatureSet::delta()`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct FeatureDelta {
+    pub removed: ArrayVec<usize, 2>,
+    pub added: ArrayVec<usize, 2>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastlingMode, Chess, fen::Fen};
+
+    fn assert_deltas(pos: &Chess, depth: u32) {
+        for feature_set in [FeatureSet::HalfKp, FeatureSet::HalfKaV2] {
+            for perspective in Color::ALL {
+                let mut before: ArrayVec<usize, 32> = feature_set
+                    .active_features(pos.board(), perspective)
+                    .unwrap()
+                    .collect();
+                before.sort_unstable();
+                for m in pos.legal_moves() {
+                    let mut after = pos.clone();
+                    after.play_unchecked(m);
+                    let mut expected: ArrayVec<usize, 32> = feature_set
+                        .active_features(after.board(), perspective)
+                        .unwrap()
+                        .collect();
+                    expected.sort_unstable();
+
+                    let Some(delta) = feature_set.delta(pos, m, perspective) else {
+                        assert!(m.role() == Role::King && pos.turn() == perspective);
+                        continue;
+                    };
+                    let mut actual = before.clone();
+                    actual.retain(|index| !delta.removed.contains(index));
+                    actual.extend(delta.added);
+                    actual.sort_unstable();
+                    assert_eq!(actual, expected, "{feature_set:?} {perspective:?} {m:?}");
+                    assert!(
+                        expected
+                            .iter()
+                            .all(|&index| index < feature_set.dimensions())
+                    );
+                }
+            }
+        }
+
+        if depth > 1 {
+            for m in pos.legal_moves() {
+                let mut after = pos.clone();
+                after.play_unchecked(m);
+                assert_deltas(&after, depth - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_index() {
+        assert_eq!(FeatureSet::HalfKp.dimensions(), 41024);
+        assert_eq!(FeatureSet::HalfKaV2.dimensions(), 45056);
+
+        let white_pawn = Color::White.pawn();
+        assert_eq!(
+            FeatureSet::HalfKp.index(Color::White, Square::E1, Square::A2, white_pawn),
+            Some(8 + 1 + 641 * 4)
+        );
+        assert_eq!(
+            FeatureSet::HalfKp.index(Color::Black, Square::E8, Square::A2, white_pawn),
+            Some(55 + 65 + 641 * 3)
+        );
+        assert_eq!(
+            FeatureSet::HalfKaV2.index(Color::Black, Square::E8, Square::A2, white_pawn),
+            Some(48 + 64 + 704 * 4)
+        );
+        assert_eq!(
+            FeatureSet::HalfKp.index(Color::White, Square::E1, Square::E8, Color::Black.king()),
+            None
+        );
+        assert_eq!(
+            FeatureSet::HalfKaV2.index(Color::White, Square::E1, Square::E8, Color::Black.king()),
+            Some(60 + 640 + 704 * 4)
+        );
+    }
+
+    #[test]
+    fn test_delta() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ] {
+            let pos: Chess = fen
+                .parse::<Fen>()
+                .unwrap()
+                .into_position(CastlingMode::Standard)
+                .unwrap();
+            assert_deltas(&pos, 2);
+        }
+    }
+}
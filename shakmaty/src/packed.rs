@@ -7,6 +7,8 @@
 //! in such a case a migration by unpacking with the previous version may be
 //! required.
 
+#[cfg(feature = "alloc")]
+use core::iter::FusedIterator;
 use core::{array::TryFromSliceError, error, fmt, fmt::Display, mem, num::NonZeroU32};
 
 #[cfg(feature = "variant")]
@@ -15,6 +17,8 @@ use crate::{
     Bitboard, Board, ByColor, ByRole, Color, Piece, Rank, Role, Setup, Square, uci::UciMove,
     util::try_from_slice_error,
 };
+#[cfg(feature = "alloc")]
+use crate::{Move, Position};
 
 /// A compactly encoded board, standard chess setup, or variant setup.
 ///
@@ -22,6 +26,20 @@ use crate::{
 /// counters. Maximum size is 64 bytes for not strictly legal variant positions
 /// with move counters.
 ///
+/// # Variant data
+///
+/// The encoding is versioned by its variant byte, which follows the
+/// board and move counters. Variant specific data is appended after it:
+///
+/// * Crazyhouse: 5 bytes of pocket counts (white and black nibbles for pawns,
+///   knights, bishops, rooks and queens), followed by 8 bytes with the
+///   bitboard of promoted pieces, if any.
+/// * Three-Check: 1 byte with the remaining checks (white and black nibbles).
+///
+/// So `PackedSetup::pack_variant()` and `PackedSetup::unpack_variant()`
+/// losslessly roundtrip variant setups, while encodings of standard chess
+/// setups stay as compact as possible.
+///
 /// # Packing
 ///
 /// ```
@@ -52,6 +70,11 @@ use crate::{
 /// #     Ok(())
 /// # }
 /// ```
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PackedSetup {
     pub(crate) inner: [u8; PackedSetup::MAX_BYTES],
@@ -89,6 +112,35 @@ impl PackedSetup {
         bytes
     }
 
+    /// Pad the packed byte representation with zeros to exactly `N` bytes,
+    /// for storage in fixed-size columns.
+    ///
+    /// Packing the same setup always yields the same bytes, so fixed-size
+    /// representations can be compared directly. Use
+    /// [`PackedSetup::try_from_bytes()`] to read them back, which requires
+    /// `N <= PackedSetup::MAX_BYTES`.
+    ///
+    /// Returns `None` if the packed representation is longer than `N` bytes.
+    /// Standard legal chess positions without move counters (see
+    /// [`PackedSetup::pack_standard_normalized()`]) fit in 24 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Setup, packed::PackedSetup};
+    ///
+    /// let packed = PackedSetup::pack_standard_normalized(&Setup::default())?;
+    /// let bytes: [u8; 32] = packed.to_fixed_bytes().expect("fits");
+    /// assert_eq!(PackedSetup::try_from_bytes(&bytes)?, packed);
+    /// # Ok::<_, Box<dyn core::error::Error>>(())
+    /// ```
+    pub fn to_fixed_bytes<const N: usize>(&self) -> Option<[u8; N]> {
+        let bytes = self.as_bytes();
+        let mut fixed = [0; N];
+        fixed.get_mut(..bytes.len())?.copy_from_slice(bytes);
+        Some(fixed)
+    }
+
     /// Wrap a given byte represetation.
     ///
     /// Ignores trailing zero bytes within the maximum length.
@@ -318,57 +370,17 @@ impl PackedSetup {
     }
 
     fn unpack_internal(&self) -> Result<(Setup, u8), UnpackSetupError> {
-        let mut reader = Reader::new(&self.inner);
-
         let mut setup = Setup::empty();
 
-        #[rustfmt::skip]
-        let mut unpack_piece = |sq: Square, packed: u8| -> Result<(), UnpackSetupError> {
-            setup.board.set_piece_at(
-                sq,
-                match packed {
-                    0 => Piece { color: Color::White, role: Role::Pawn },
-                    1 => Piece { color: Color::Black, role: Role::Pawn },
-                    2 => Piece { color: Color::White, role: Role::Knight },
-                    3 => Piece { color: Color::Black, role: Role::Knight },
-                    4 => Piece { color: Color::White, role: Role::Bishop },
-                    5 => Piece { color: Color::Black, role: Role::Bishop },
-                    6 => Piece { color: Color::White, role: Role::Rook },
-                    7 => Piece { color: Color::Black, role: Role::Rook },
-                    8 => Piece { color: Color::White, role: Role::Queen },
-                    9 => Piece { color: Color::Black, role: Role::Queen },
-                    10 => Piece { color: Color::White, role: Role::King },
-                    11 => Piece { color: Color::Black, role: Role::King },
-                    12 => {
-                        setup.ep_square = Some(sq.xor(Square::A2));
-                        Color::from_white(sq.rank() <= Rank::Fourth).pawn()
-                    },
-                    13 => {
-                        setup.castling_rights.add(sq);
-                        Piece { color: Color::White, role: Role::Rook }
-                    },
-                    14 => {
-                        setup.castling_rights.add(sq);
-                        Piece { color: Color::Black, role: Role::Rook }
-                    },
-                    15 => {
-                        setup.turn = Color::Black;
-                        Piece { color: Color::Black, role: Role::King }
-                    },
-                    _ => return Err(UnpackSetupError { _priv: () }) // Invalid packed piece
-                }
-            );
-            Ok(())
-        };
-
-        let mut occupied = Bitboard(reader.read_u64()).into_iter();
-        while let Some(sq) = occupied.next() {
-            let (lo, hi) = reader.read_nibbles();
-            unpack_piece(sq, lo)?;
-            if let Some(sq) = occupied.next() {
-                unpack_piece(sq, hi)?;
+        let mut reader = self.read_pieces(|sq, packed| {
+            match packed {
+                12 => setup.ep_square = Some(sq.xor(Square::A2)),
+                13 | 14 => setup.castling_rights.add(sq),
+                15 => setup.turn = Color::Black,
+                _ => (),
             }
-        }
+            setup.board.set_piece_at(sq, unpack_piece(sq, packed));
+        });
 
         setup.halfmoves = reader
             .read_leb128(5)
@@ -429,6 +441,68 @@ impl PackedSetup {
         let mut reader = Reader::new(&self.inner);
         Bitboard(reader.read_u64())
     }
+
+    /// Unpack the side to move, without unpacking the full setup.
+    pub fn unpack_turn(&self) -> Color {
+        let mut black_king_to_move = false;
+        let mut reader = self.read_pieces(|_, packed| black_king_to_move |= packed == 15);
+        let _halfmoves = reader.read_leb128(5);
+        let ply = reader.read_leb128(5);
+        Color::from_white(!black_king_to_move && ply % 2 == 0)
+    }
+
+    /// Unpack the number of pieces of each color and type on the board,
+    /// without unpacking the full setup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Board, packed::PackedSetup};
+    ///
+    /// let board = Board::default();
+    /// let packed = PackedSetup::pack_board(&board);
+    /// assert_eq!(packed.unpack_material(), board.material());
+    /// ```
+    pub fn unpack_material(&self) -> ByColor<ByRole<u8>> {
+        let mut material = ByColor::<ByRole<u8>>::default();
+        self.read_pieces(|sq, packed| *material.piece_mut(unpack_piece(sq, packed)) += 1);
+        material
+    }
+
+    fn read_pieces<F>(&self, mut f: F) -> Reader<'_>
+    where
+        F: FnMut(Square, u8),
+    {
+        let mut reader = Reader::new(&self.inner);
+        let mut occupied = Bitboard(reader.read_u64()).into_iter();
+        while let Some(sq) = occupied.next() {
+            let (lo, hi) = reader.read_nibbles();
+            f(sq, lo);
+            if let Some(sq) = occupied.next() {
+                f(sq, hi);
+            }
+        }
+        reader
+    }
+}
+
+#[rustfmt::skip]
+fn unpack_piece(sq: Square, packed: u8) -> Piece {
+    match packed {
+        0 => Piece { color: Color::White, role: Role::Pawn },
+        1 => Piece { color: Color::Black, role: Role::Pawn },
+        2 => Piece { color: Color::White, role: Role::Knight },
+        3 => Piece { color: Color::Black, role: Role::Knight },
+        4 => Piece { color: Color::White, role: Role::Bishop },
+        5 => Piece { color: Color::Black, role: Role::Bishop },
+        6 | 13 => Piece { color: Color::White, role: Role::Rook },
+        7 | 14 => Piece { color: Color::Black, role: Role::Rook },
+        8 => Piece { color: Color::White, role: Role::Queen },
+        9 => Piece { color: Color::Black, role: Role::Queen },
+        10 => Piece { color: Color::White, role: Role::King },
+        12 => Color::from_white(sq.rank() <= Rank::Fourth).pawn(),
+        _ => Piece { color: Color::Black, role: Role::King }, // 11 or 15
+    }
 }
 
 /// Error when unpacking an invalid or unexpected encoding.
@@ -493,6 +567,10 @@ fn variant_to_byte(variant: Variant) -> u8 {
         Variant::Atomic => 7,
         Variant::Horde => 8,
         Variant::RacingKings => 9,
+        Variant::FogOfWar => 10,
+        Variant::Shatranj => 11,
+        Variant::Makruk => 12,
+        Variant::NoCastling => 13,
     }
 }
 
@@ -509,6 +587,10 @@ fn variant_from_byte(variant: u8) -> Result<Variant, UnpackSetupError> {
         7 => Variant::Atomic,
         8 => Variant::Horde,
         9 => Variant::RacingKings,
+        10 => Variant::FogOfWar,
+        11 => Variant::Shatranj,
+        12 => Variant::Makruk,
+        13 => Variant::NoCastling,
         _ => return Err(UnpackSetupError { _priv: () }),
     })
 }
@@ -753,6 +835,265 @@ impl<'de> serde::Deserialize<'de> for PackedUciMove {
     }
 }
 
+/// A game's moves, compactly stored in memory by their index in the sorted
+/// list of legal moves, using only as many bits per ply as needed to
+/// distinguish the legal moves (on average about 5 bits in typical games).
+///
+/// Decoding requires the starting position of the game.
+///
+/// Legal moves are sorted by the integer value of their [`PackedUciMove`]
+/// in little endian, using [`UciMove::from_chess960()`], so that the
+/// encoding is independent of the order of move generation.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Chess, Position, packed::CompactMoveSeq, uci::UciMove};
+///
+/// let pos = Chess::default();
+/// let moves = ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5"]
+///     .iter()
+///     .map(|uci| uci.parse::<UciMove>())
+///     .collect::<Result<Vec<_>, _>>()?;
+///
+/// let mut seq = CompactMoveSeq::new();
+/// let mut replay = pos.clone();
+/// for uci in &moves {
+///     let m = uci.to_move(&replay)?;
+///     seq.push(&replay, m)?;
+///     replay.play_unchecked(m);
+/// }
+/// assert_eq!(seq.len(), 5);
+/// assert!(seq.bit_len() <= 5 * 6);
+///
+/// let mut decoded = seq.moves(pos);
+/// for uci in &moves {
+///     assert_eq!(decoded.next().transpose()?.map(|m| UciMove::from_standard(m)), Some(*uci));
+/// }
+/// assert_eq!(decoded.next().transpose()?, None);
+/// assert_eq!(decoded.into_position(), replay);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CompactMoveSeq {
+    bytes: alloc::vec::Vec<u8>,
+    bit_len: usize,
+    plies: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl CompactMoveSeq {
+    /// Creates an empty sequence.
+    pub const fn new() -> CompactMoveSeq {
+        CompactMoveSeq {
+            bytes: alloc::vec::Vec::new(),
+            bit_len: 0,
+            plies: 0,
+        }
+    }
+
+    /// Encodes the moves played from `pos`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if any of the moves is not legal.
+    pub fn encode<P, I>(pos: &P, moves: I) -> Result<CompactMoveSeq, PackMoveSeqError>
+    where
+        P: Position + Clone,
+        I: IntoIterator<Item = Move>,
+    {
+        let mut pos = pos.clone();
+        let mut seq = CompactMoveSeq::new();
+        for m in moves {
+            seq.push(&pos, m)?;
+            pos.play_unchecked(m);
+        }
+        Ok(seq)
+    }
+
+    /// Appends the move `m` played in `pos`. Does not play the move.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `m` is not legal in `pos`.
+    pub fn push<P: Position>(&mut self, pos: &P, m: Move) -> Result<(), PackMoveSeqError> {
+        let legals = pos.legal_moves();
+        if !legals.contains(&m) {
+            return Err(PackMoveSeqError { _priv: () });
+        }
+        let key = move_key(m);
+        let index = legals
+            .iter()
+            .filter(|legal| move_key(**legal) < key)
+            .count();
+        self.write_bits(index, index_bits(legals.len()));
+        self.plies += 1;
+        Ok(())
+    }
+
+    /// Number of plies.
+    pub fn len(&self) -> usize {
+        self.plies
+    }
+
+    /// Tests if there are no moves.
+    pub fn is_empty(&self) -> bool {
+        self.plies == 0
+    }
+
+    /// Number of bits used to encode the moves.
+    pub fn bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    /// Shrinks the allocation to fit the encoded moves.
+    pub fn shrink_to_fit(&mut self) {
+        self.bytes.shrink_to_fit();
+    }
+
+    /// Decodes the moves, starting from `pos`, which must be the position
+    /// used for encoding.
+    pub fn moves<P: Position>(&self, pos: P) -> CompactMoves<'_, P> {
+        CompactMoves {
+            seq: self,
+            pos,
+            cursor: 0,
+            ply: 0,
+        }
+    }
+
+    fn write_bits(&mut self, value: usize, bits: u32) {
+        for i in 0..bits {
+            if self.bit_len % 8 == 0 {
+                self.bytes.push(0);
+            }
+            if value & (1 << i) != 0 {
+                *self.bytes.last_mut().expect("pushed") |= 1 << (self.bit_len % 8);
+            }
+            self.bit_len += 1;
+        }
+    }
+
+    fn read_bits(&self, cursor: usize, bits: u32) -> Option<usize> {
+        if cursor + bits as usize > self.bit_len {
+            return None;
+        }
+        Some((0..bits).fold(0, |value, i| {
+            let bit = cursor + i as usize;
+            value | (usize::from((self.bytes[bit / 8] >> (bit % 8)) & 1) << i)
+        }))
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn move_key(m: Move) -> u16 {
+    u16::from_le_bytes(PackedUciMove::pack(UciMove::from_chess960(m)).to_bytes())
+}
+
+#[cfg(feature = "alloc")]
+fn index_bits(legals: usize) -> u32 {
+    usize::BITS - legals.saturating_sub(1).leading_zeros()
+}
+
+/// Iterator over the moves of a [`CompactMoveSeq`], playing them on a
+/// position.
+///
+/// See [`CompactMoveSeq::moves()`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct CompactMoves<'a, P> {
+    seq: &'a CompactMoveSeq,
+    pos: P,
+    cursor: usize,
+    ply: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<P> CompactMoves<'_, P> {
+    /// The position after the moves decoded so far.
+    pub fn position(&self) -> &P {
+        &self.pos
+    }
+
+    /// Returns the position after the moves decoded so far.
+    pub fn into_position(self) -> P {
+        self.pos
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<P: Position> Iterator for CompactMoves<'_, P> {
+    type Item = Result<Move, UnpackMoveSeqError>;
+
+    fn next(&mut self) -> Option<Result<Move, UnpackMoveSeqError>> {
+        if self.ply >= self.seq.plies {
+            return None;
+        }
+        let mut legals = self.pos.legal_moves();
+        let bits = index_bits(legals.len());
+        legals.sort_unstable_by_key(|m| move_key(*m));
+        let Some(&m) = self
+            .seq
+            .read_bits(self.cursor, bits)
+            .and_then(|index| legals.get(index))
+        else {
+            self.ply = self.seq.plies;
+            return Some(Err(UnpackMoveSeqError { _priv: () }));
+        };
+        self.pos.play_unchecked(m);
+        self.cursor += bits as usize;
+        self.ply += 1;
+        Some(Ok(m))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.seq.plies - self.ply;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<P: Position> ExactSizeIterator for CompactMoves<'_, P> {}
+
+#[cfg(feature = "alloc")]
+impl<P: Position> FusedIterator for CompactMoves<'_, P> {}
+
+/// Error when encoding an illegal move in a [`CompactMoveSeq`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct PackMoveSeqError {
+    _priv: (),
+}
+
+#[cfg(feature = "alloc")]
+impl Display for PackMoveSeqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("illegal move in move sequence")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl error::Error for PackMoveSeqError {}
+
+/// Error when decoding a [`CompactMoveSeq`] from a position other than the
+/// one used for encoding.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct UnpackMoveSeqError {
+    _priv: (),
+}
+
+#[cfg(feature = "alloc")]
+impl Display for UnpackMoveSeqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("move sequence does not match position")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl error::Error for UnpackMoveSeqError {}
+
 struct Writer<'a> {
     inner: &'a mut [u8],
 }
@@ -846,6 +1187,78 @@ impl Reader<'_> {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_compact_move_seq() {
+        use alloc::vec::Vec;
+
+        use crate::Chess;
+
+        let start = Chess::default();
+        let mut pos = start.clone();
+        let mut moves = Vec::new();
+        for ply in 0..300 {
+            let legals = pos.legal_moves();
+            if legals.is_empty() {
+                break;
+            }
+            let m = legals[ply * 7 % legals.len()];
+            moves.push(m);
+            pos.play_unchecked(m);
+        }
+
+        let seq = CompactMoveSeq::encode(&start, moves.iter().copied()).expect("legal");
+        assert_eq!(seq.len(), moves.len());
+        assert!(seq.bit_len() < 7 * moves.len());
+
+        let mut decoded = seq.moves(start.clone());
+        assert_eq!(decoded.len(), moves.len());
+        assert!(
+            (&mut decoded)
+                .map(|m| m.expect("valid"))
+                .eq(moves.iter().copied())
+        );
+        assert_eq!(decoded.into_position(), pos);
+
+        assert!(seq.moves(pos).any(|m| m.is_err()));
+
+        let illegal = start.legal_moves()[0];
+        assert!(CompactMoveSeq::encode(&start, [illegal, illegal]).is_err());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_compact_move_seq_other_position() {
+        use crate::{CastlingMode, Chess, fen::Fen, uci::UciMove};
+
+        // Bare kings have only a few legal moves, so each ply is encoded in
+        // 2 or 3 bits.
+        let start: Chess = "7k/8/8/8/8/8/8/K7 w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Standard)
+            .expect("legal position");
+        let mut seq = CompactMoveSeq::new();
+        let mut pos = start.clone();
+        for uci in ["a1b1", "h8h7", "b1a1"] {
+            let m = uci
+                .parse::<UciMove>()
+                .expect("valid uci")
+                .to_move(&pos)
+                .expect("legal");
+            seq.push(&pos, m).expect("legal");
+            pos.play_unchecked(m);
+        }
+        assert_eq!(seq.bit_len(), 7);
+
+        // The starting position has 20 legal moves, so each ply needs 5
+        // bits, running past the end of the sequence.
+        let decoded = seq.moves(Chess::default()).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded[0].is_ok());
+        assert!(decoded.last().expect("decoded").is_err());
+    }
+
     #[test]
     fn test_read_write_u8() {
         let mut buf = [0; 2];
@@ -922,6 +1335,41 @@ mod tests {
         assert_eq!(roundtripped, setup);
     }
 
+    #[test]
+    fn test_fixed_bytes() {
+        let packed = PackedSetup::pack_standard(&Setup::default()).expect("representable");
+        assert_eq!(packed.to_fixed_bytes::<23>(), None);
+
+        let fixed = packed.to_fixed_bytes::<24>().expect("fits");
+        assert_eq!(fixed[..], *packed.as_bytes());
+
+        let fixed = packed.to_fixed_bytes::<32>().expect("fits");
+        assert_eq!(fixed[24..], [0; 8]);
+        assert_eq!(
+            PackedSetup::try_from_bytes(&fixed).expect("valid length"),
+            packed
+        );
+    }
+
+    #[test]
+    fn test_unpack_partial() {
+        use crate::fen::Fen;
+
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+            "8/8/8/8/8/8/8/8 b - - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 b - - 12 40",
+        ] {
+            let setup = fen.parse::<Fen>().expect("valid fen").into_setup();
+            let packed = PackedSetup::pack_standard(&setup).expect("representable");
+            assert_eq!(packed.unpack_occupied(), setup.board.occupied(), "{fen}");
+            assert_eq!(packed.unpack_turn(), setup.turn, "{fen}");
+            assert_eq!(packed.unpack_material(), setup.board.material(), "{fen}");
+        }
+    }
+
     #[cfg(feature = "variant")]
     #[test]
     fn test_read_write_crazyhouse_setup() {
@@ -1013,4 +1461,38 @@ mod tests {
             bincode::decode_from_slice(&buffer, config).unwrap();
         assert_eq!((&packed_setup, encoded_bytes), (&decoded, decoded_bytes));
     }
+
+    #[cfg(all(feature = "rkyv", feature = "alloc"))]
+    #[test]
+    fn test_rkyv() {
+        use crate::{Color, Square, fen::Fen};
+
+        let setup: Setup = "rnbqkbnr/pp1ppppp/8/2pP4/8/8/PPP1PPPP/RNBQKBNR w KQkq c6 0 2"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_setup();
+        let packed_setup = PackedSetup::pack_standard(&setup).expect("representable");
+
+        let bytes =
+            rkyv::to_bytes::<rkyv::rancor::Error>(&(setup.clone(), packed_setup.clone())).unwrap();
+
+        // Access without deserialization.
+        let archived =
+            rkyv::access::<rkyv::Archived<(Setup, PackedSetup)>, rkyv::rancor::Error>(&bytes)
+                .unwrap();
+        assert!(archived.0.turn == Color::White);
+        assert!(
+            archived
+                .0
+                .ep_square
+                .as_ref()
+                .is_some_and(|sq| *sq == Square::C6)
+        );
+        assert_eq!(archived.0.halfmoves, 0);
+
+        let (decoded_setup, decoded_packed_setup) =
+            rkyv::from_bytes::<(Setup, PackedSetup), rkyv::rancor::Error>(&bytes).unwrap();
+        assert_eq!(setup, decoded_setup);
+        assert_eq!(packed_setup, decoded_packed_setup);
+    }
 }
@@ -1,4 +1,13 @@
+#[cfg(feature = "variant")]
+use core::{error, fmt};
+
 use crate::Position;
+#[cfg(feature = "variant")]
+use crate::{
+    CastlingMode, PositionError,
+    fen::Fen,
+    variant::{Variant, VariantPosition},
+};
 
 /// Counts legal move paths of a given length.
 ///
@@ -46,6 +55,228 @@ pub fn perft<P: Position + Clone>(pos: &P, depth: u32) -> u64 {
     }
 }
 
+/// A known-good perft result for a tricky variant position.
+///
+/// See [`PERFT_VECTORS`](crate::variant::PERFT_VECTORS).
+#[cfg(feature = "variant")]
+#[derive(Debug, Clone, Copy)]
+pub struct PerftVector {
+    /// Short identifier of the position.
+    pub id: &'static str,
+    /// The variant to play.
+    pub variant: Variant,
+    /// The position as X-FEN or EPD, with Chess960 castling rights.
+    pub epd: &'static str,
+    /// Node counts for depths `1..=nodes.len()`.
+    pub nodes: &'static [u64],
+}
+
+#[cfg(feature = "variant")]
+impl PerftVector {
+    /// Sets up the position of the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is invalid. The built-in vectors are valid.
+    pub fn position(&self) -> VariantPosition {
+        let setup = self
+            .epd
+            .parse::<Fen>()
+            .expect("valid perft vector fen")
+            .into_setup();
+        VariantPosition::from_setup(self.variant, setup, CastlingMode::Chess960)
+            .or_else(PositionError::ignore_impossible_check)
+            .expect("legal perft vector position")
+    }
+
+    /// Compares [`perft()`] of the position against all known node counts
+    /// that do not exceed `node_limit`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PerftMismatch`] for the first depth with a different
+    /// result.
+    pub fn check(&self, node_limit: u64) -> Result<(), PerftMismatch> {
+        let pos = self.position();
+        for (depth, &expected) in (1..).zip(self.nodes) {
+            if expected > node_limit {
+                break;
+            }
+            let actual = perft(&pos, depth);
+            if actual != expected {
+                return Err(PerftMismatch {
+                    id: self.id,
+                    depth,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Built-in variant perft vectors, covering atomic explosions near kings,
+/// en passant as a forced capture in antichess, pawn pushes from the first
+/// rank in horde, drops and promoted pieces in crazyhouse, and more.
+#[cfg(feature = "variant")]
+pub const PERFT_VECTORS: &[PerftVector] = &[
+    PerftVector {
+        id: "atomic-start",
+        variant: Variant::Atomic,
+        epd: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+        nodes: &[20, 400, 8902, 197326],
+    },
+    PerftVector {
+        id: "atomic-programfox-2",
+        variant: Variant::Atomic,
+        epd: "rn1qkb1r/p5pp/2p5/3p4/N3P3/5P2/PPP4P/R1BQK3 w Qkq -",
+        nodes: &[28, 833, 23353, 714499],
+    },
+    PerftVector {
+        id: "atomic-explosion-near-king",
+        variant: Variant::Atomic,
+        epd: "4k3/8/8/8/8/8/3rK3/3QB3 w - -",
+        nodes: &[3, 57, 1162, 17114],
+    },
+    PerftVector {
+        id: "atomic-connected-kings",
+        variant: Variant::Atomic,
+        epd: "8/8/8/8/8/3k4/3rK3/8 w - -",
+        nodes: &[4, 59, 236, 4433],
+    },
+    PerftVector {
+        id: "atomic960-castle",
+        variant: Variant::Atomic,
+        epd: "Rr2k1rR/3K4/3p4/8/8/8/7P/8 w kq -",
+        nodes: &[21, 465, 10631, 241478],
+    },
+    PerftVector {
+        id: "antichess-start",
+        variant: Variant::Antichess,
+        epd: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - -",
+        nodes: &[20, 400, 8067, 153299],
+    },
+    PerftVector {
+        id: "antichess-forced-en-passant",
+        variant: Variant::Antichess,
+        epd: "k7/8/8/3pP3/8/8/8/4K3 w - d6",
+        nodes: &[1, 3, 18, 108, 804],
+    },
+    PerftVector {
+        id: "antichess-a-pawn-vs-c-pawn",
+        variant: Variant::Antichess,
+        epd: "8/2p5/8/8/8/8/P7/8 w - -",
+        nodes: &[2, 4, 4, 4, 4, 4, 4, 4, 12, 36, 312, 2557],
+    },
+    PerftVector {
+        id: "horde-start",
+        variant: Variant::Horde,
+        epd: "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq -",
+        nodes: &[8, 128, 1274, 23310],
+    },
+    PerftVector {
+        id: "horde-first-rank-pushes",
+        variant: Variant::Horde,
+        epd: "4k3/8/8/8/8/8/8/PPPPPPPP w - -",
+        nodes: &[16, 80, 1240, 8432],
+    },
+    PerftVector {
+        id: "horde-first-rank-captures",
+        variant: Variant::Horde,
+        epd: "4k3/8/8/8/8/8/1p6/P1P5 w - -",
+        nodes: &[6, 62, 210, 2504],
+    },
+    PerftVector {
+        id: "horde-en-passant",
+        variant: Variant::Horde,
+        epd: "k7/5p2/4p2P/3p2P1/2p2P2/1p2P2P/p2P2P1/2P2P2 w - -",
+        nodes: &[13, 172, 2205, 33781],
+    },
+    PerftVector {
+        id: "crazyhouse-all-drop-types",
+        variant: Variant::Crazyhouse,
+        epd: "2k5/8/8/8/8/8/8/4K3[QRBNPqrbnp] w - -",
+        nodes: &[301, 75353],
+    },
+    PerftVector {
+        id: "crazyhouse-promoted",
+        variant: Variant::Crazyhouse,
+        epd: "4k3/1Q~6/8/8/4b3/8/Kpp5/8/ b - -",
+        nodes: &[20, 360, 5445, 132758],
+    },
+    PerftVector {
+        id: "racingkings-start",
+        variant: Variant::RacingKings,
+        epd: "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - -",
+        nodes: &[21, 421, 11264, 296242],
+    },
+    PerftVector {
+        id: "racingkings-occupied-goal",
+        variant: Variant::RacingKings,
+        epd: "4brn1/2K2k2/8/8/8/8/8/8 w - -",
+        nodes: &[6, 33, 178, 3151, 12981],
+    },
+    PerftVector {
+        id: "3check-castling",
+        variant: Variant::ThreeCheck,
+        epd: "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 1+1",
+        nodes: &[26, 562, 13410],
+    },
+];
+
+/// Checks move generation against all [`PERFT_VECTORS`], skipping node
+/// counts larger than `node_limit`.
+///
+/// This is intended for downstream integrators to validate their builds,
+/// for example on unusual targets.
+///
+/// # Errors
+///
+/// Returns [`PerftMismatch`] for the first differing result.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::variant::perft_self_test;
+///
+/// assert!(perft_self_test(1_000).is_ok());
+/// ```
+#[cfg(feature = "variant")]
+pub fn perft_self_test(node_limit: u64) -> Result<(), PerftMismatch> {
+    PERFT_VECTORS
+        .iter()
+        .try_for_each(|vector| vector.check(node_limit))
+}
+
+/// A perft result that does not match a [`PerftVector`].
+#[cfg(feature = "variant")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PerftMismatch {
+    /// Identifier of the vector.
+    pub id: &'static str,
+    /// Depth with the differing result.
+    pub depth: u32,
+    /// Known-good node count.
+    pub expected: u64,
+    /// Node count computed by this build.
+    pub actual: u64,
+}
+
+#[cfg(feature = "variant")]
+impl fmt::Display for PerftMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "perft mismatch for {} at depth {}: expected {}, got {}",
+            self.id, self.depth, self.expected, self.actual
+        )
+    }
+}
+
+#[cfg(feature = "variant")]
+impl error::Error for PerftMismatch {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,4 +288,10 @@ mod tests {
         assert_eq!(perft(&pos, 0), 1);
         assert_eq!(perft(&pos, 1), 20);
     }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_perft_vectors() {
+        assert_eq!(perft_self_test(100_000), Ok(()));
+    }
 }
@@ -1,7 +1,10 @@
 use core::{
     error, fmt,
     hash::{Hash, Hasher},
+    iter::FusedIterator,
+    mem::MaybeUninit,
     num::NonZeroU32,
+    ptr,
     str::FromStr,
 };
 
@@ -12,7 +15,9 @@ use crate::{
     Color::{Black, White},
     EnPassantMode, Move, MoveList, Piece, Rank, RemainingChecks, Role, Setup, Square, attacks,
     bitboard::Direction,
+    fen::{Fen, ParsePositionError},
     setup::EnPassant,
+    util::ErrorPayload,
 };
 
 /// A definitive outcome of a game.
@@ -64,6 +69,9 @@ impl fmt::Display for KnownOutcome {
     }
 }
 
+#[cfg(feature = "serde")]
+serde_static_str_impl! { KnownOutcome, as_str, "game result string" }
+
 impl FromStr for KnownOutcome {
     type Err = ParseOutcomeError;
 
@@ -174,6 +182,9 @@ impl fmt::Display for Outcome {
     }
 }
 
+#[cfg(feature = "serde")]
+serde_static_str_impl! { Outcome, as_str, "game result string" }
+
 /// Error when parsing an [`Outcome`] or [`KnownOutcome`].
 #[derive(Clone, Debug)]
 pub struct ParseOutcomeError;
@@ -230,13 +241,71 @@ impl<Config> bincode::Decode<Config> for Outcome {
 #[cfg(feature = "bincode")]
 bincode::impl_borrow_decode!(Outcome);
 
+/// The rule by which a side was found to have insufficient winning material.
+///
+/// See [`Position::insufficient_material_rule()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InsufficientMaterialRule {
+    /// Only the king is left.
+    BareKing,
+    /// A single piece that can not force mate, even with help from the
+    /// opponent's pieces.
+    LonePiece,
+    /// Two knights against a bare king (Atomic).
+    TwoKnights,
+    /// All bishops are on the same color complex.
+    SameColoredBishops,
+    /// Bishops on opposite color complexes can never capture, explode, or be
+    /// captured by each other (Antichess and Atomic).
+    OppositeColoredBishops,
+    /// A knight against a knight, with the wrong parity to ever be forced to
+    /// capture (Antichess).
+    KnightParity,
+    /// The opponent has already won by losing all their pieces (Antichess).
+    OpponentWon,
+    /// Too little material left in the game, even counting pockets
+    /// (Crazyhouse).
+    TooFewPieces,
+    /// The horde can not mate the king with its remaining pieces (Horde).
+    HordeCannotMate,
+    /// The position does not report a more specific rule.
+    Other,
+}
+
 /// Error when trying to play an illegal move.
+///
+/// The position is boxed if the `alloc` feature is enabled, so that
+/// `Result<P, PlayError<P>>` is not much larger than `P`.
 #[derive(Debug)]
 pub struct PlayError<P> {
+    m: Move,
+    position: ErrorPayload<P>,
+}
+
+impl<P> PlayError<P> {
+    #[cold]
+    fn new(m: Move, position: P) -> PlayError<P> {
+        PlayError {
+            m,
+            position: ErrorPayload::new(position),
+        }
+    }
+
     /// The move that was not played.
-    pub m: Move,
+    pub fn m(&self) -> Move {
+        self.m
+    }
+
     /// The unchanged position.
-    pub position: P,
+    pub fn position(&self) -> &P {
+        self.position.get()
+    }
+
+    /// Returns the unchanged position.
+    pub fn into_position(self) -> P {
+        self.position.into_inner()
+    }
 }
 
 impl<P: fmt::Debug> fmt::Display for PlayError<P> {
@@ -321,23 +390,34 @@ bitflags! {
 /// Error when trying to create a [`Position`] from an illegal [`Setup`].
 ///
 /// See [`PositionErrorKinds`] for possible reasons.
+///
+/// The position is boxed if the `alloc` feature is enabled, so that
+/// `Result<P, PositionError<P>>` is not much larger than `P`.
 #[derive(Clone)]
 pub struct PositionError<P> {
-    pub(crate) pos: P,
+    pub(crate) pos: ErrorPayload<P>,
     pub(crate) errors: PositionErrorKinds,
 }
 
 impl<P> PositionError<P> {
-    fn ignore(mut self, ignore: PositionErrorKinds) -> Result<P, Self> {
-        self.errors -= ignore;
-        match self {
-            PositionError { pos, errors } if errors.is_empty() => Ok(pos),
-            _ => Err(self),
+    pub(crate) fn strict(pos: P, errors: PositionErrorKinds) -> Result<P, Self> {
+        if errors.is_empty() {
+            Ok(pos)
+        } else {
+            Err(PositionError {
+                pos: ErrorPayload::new(pos),
+                errors,
+            })
         }
     }
 
-    fn strict(self) -> Result<P, Self> {
-        self.ignore(PositionErrorKinds::empty())
+    fn ignore(mut self, ignore: PositionErrorKinds) -> Result<P, Self> {
+        self.errors -= ignore;
+        if self.errors.is_empty() {
+            Ok(self.pos.into_inner())
+        } else {
+            Err(self)
+        }
     }
 
     /// Discards invalid castling rights to recover from
@@ -420,6 +500,100 @@ impl<P> fmt::Display for PositionError<P> {
 
 impl<P> error::Error for PositionError<P> {}
 
+/// Destination squares of legal moves, by origin square. See
+/// [`Position::legal_destinations()`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LegalDestinations {
+    origins: Bitboard,
+    destinations: [Bitboard; 64],
+}
+
+impl LegalDestinations {
+    fn new() -> LegalDestinations {
+        LegalDestinations {
+            origins: Bitboard::EMPTY,
+            destinations: [Bitboard::EMPTY; 64],
+        }
+    }
+
+    fn add(&mut self, from: Square, to: Square) {
+        self.origins.add(from);
+        self.destinations[usize::from(from)].add(to);
+    }
+
+    /// Squares of pieces with at least one legal move.
+    pub const fn origins(&self) -> Bitboard {
+        self.origins
+    }
+
+    /// Legal destinations of the piece on `from`.
+    pub const fn get(&self, from: Square) -> Bitboard {
+        self.destinations[from as usize]
+    }
+
+    /// Iterates over origin squares and their destinations.
+    pub fn iter(&self) -> impl Iterator<Item = (Square, Bitboard)> + '_ {
+        self.origins.into_iter().map(|from| (from, self.get(from)))
+    }
+}
+
+/// Iterator over legal moves, generated on demand. See
+/// [`Position::legal_moves_iter()`].
+#[derive(Debug)]
+pub struct LegalMovesIter<'a, P> {
+    pos: &'a P,
+    origins: Bitboard,
+    drops: bool,
+    current: <MoveList as IntoIterator>::IntoIter,
+}
+
+impl<'a, P: Position> LegalMovesIter<'a, P> {
+    /// Generates the moves of each piece with [`Position::legal_moves_from()`]
+    /// when needed. Piece drops come last.
+    pub(crate) fn lazy(pos: &'a P) -> LegalMovesIter<'a, P> {
+        LegalMovesIter {
+            pos,
+            origins: pos.us(),
+            drops: pos.pockets().is_some(),
+            current: MoveList::new().into_iter(),
+        }
+    }
+
+    /// Generates all legal moves at once.
+    pub(crate) fn buffered(pos: &'a P) -> LegalMovesIter<'a, P> {
+        LegalMovesIter {
+            pos,
+            origins: Bitboard::EMPTY,
+            drops: false,
+            current: pos.legal_moves().into_iter(),
+        }
+    }
+}
+
+impl<P: Position> Iterator for LegalMovesIter<'_, P> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        loop {
+            if let Some(m) = self.current.next() {
+                return Some(m);
+            }
+            if let Some(from) = self.origins.pop_front() {
+                self.current = self.pos.legal_moves_from(from).into_iter();
+            } else if self.drops {
+                self.drops = false;
+                let mut moves = self.pos.legal_moves();
+                moves.retain(|m| m.from().is_none());
+                self.current = moves.into_iter();
+            } else {
+                return None;
+            }
+        }
+    }
+}
+
+impl<P: Position> FusedIterator for LegalMovesIter<'_, P> {}
+
 /// Validate and set up a playable [`Position`]. All provided chess variants
 /// support this.
 pub trait FromSetup: Sized {
@@ -448,6 +622,37 @@ pub trait FromSetup: Sized {
     /// # Ok::<_, PositionError<_>>(())
     /// ```
     fn from_setup(setup: Setup, mode: CastlingMode) -> Result<Self, PositionError<Self>>;
+
+    /// Parses a FEN and sets up a playable [`Position`]. Shorthand for
+    /// [`Fen::parse_position()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParsePositionError`] if the FEN is invalid, or if the
+    /// position does not meet
+    /// [basic validity requirements](PositionErrorKinds).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{CastlingMode, Chess, FromSetup, Position, fen::ParsePositionError};
+    ///
+    /// let pos = Chess::from_fen(
+    ///     "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+    ///     CastlingMode::Standard,
+    /// )?;
+    /// assert_eq!(pos.fullmoves().get(), 1);
+    ///
+    /// assert!(matches!(
+    ///     Chess::from_fen("not a fen", CastlingMode::Standard),
+    ///     Err(ParsePositionError::Fen(_))
+    /// ));
+    /// # Ok::<_, ParsePositionError<_>>(())
+    /// ```
+    fn from_fen(fen: &str, mode: CastlingMode) -> Result<Self, ParsePositionError<Self>> /* FINAL */
+    {
+        Fen::parse_position(fen.as_bytes(), mode)
+    }
 }
 
 /// A playable chess or chess variant position. See [`Chess`] for a concrete
@@ -514,6 +719,112 @@ pub trait Position {
     /// Generates all legal moves.
     fn legal_moves(&self) -> MoveList;
 
+    /// Generates all legal moves and appends them to `moves`, for example to
+    /// reuse the allocation of a move stack.
+    ///
+    /// [`Chess`] and variants based on it generate moves directly into
+    /// `moves`, without an intermediate [`MoveList`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Position};
+    ///
+    /// let mut moves = Vec::with_capacity(256);
+    /// Chess::default().legal_moves_into(&mut moves);
+    /// assert_eq!(moves.len(), 20);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn legal_moves_into(&self, moves: &mut alloc::vec::Vec<Move>) {
+        moves.extend(self.legal_moves());
+    }
+
+    /// Generates all legal moves into the beginning of `buffer` and returns
+    /// the initialized part.
+    ///
+    /// [`Chess`] and variants based on it generate moves directly into
+    /// `buffer`, without an intermediate [`MoveList`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is shorter than the number of legal moves. A
+    /// buffer with the capacity of [`MoveList`] is always sufficient.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::mem::MaybeUninit;
+    ///
+    /// use shakmaty::{Chess, Move, Position};
+    ///
+    /// let mut buffer = [MaybeUninit::<Move>::uninit(); 256];
+    /// let moves = Chess::default().legal_moves_into_uninit(&mut buffer);
+    /// assert_eq!(moves.len(), 20);
+    /// ```
+    #[track_caller]
+    fn legal_moves_into_uninit<'a>(&self, buffer: &'a mut [MaybeUninit<Move>]) -> &'a mut [Move] {
+        let mut sink = UninitSink::new(buffer);
+        for m in self.legal_moves() {
+            sink.push(m);
+        }
+        sink.into_slice()
+    }
+
+    /// Iterates over all legal moves.
+    ///
+    /// [`Chess`] and variants based on it generate the moves piece by piece
+    /// on demand. This is cheaper than [`Position::legal_moves()`] when the
+    /// iteration stops early, but more expensive otherwise. The default
+    /// implementation generates all legal moves at once.
+    ///
+    /// The order may differ from [`Position::legal_moves()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Position};
+    ///
+    /// let pos = Chess::default();
+    /// assert!(!pos.legal_moves_iter().any(|m| m.is_capture()));
+    /// assert_eq!(pos.legal_moves_iter().count(), 20);
+    /// ```
+    fn legal_moves_iter(&self) -> LegalMovesIter<'_, Self>
+    where
+        Self: Sized,
+    {
+        LegalMovesIter::buffered(self)
+    }
+
+    /// Generates the legal moves of the piece on `from`. Piece drops are
+    /// not included.
+    ///
+    /// The default implementation filters [`Position::legal_moves()`].
+    /// [`Chess`] and variants based on it generate only the moves of the
+    /// given piece.
+    fn legal_moves_from(&self, from: Square) -> MoveList {
+        let mut moves = self.legal_moves();
+        moves.retain(|m| m.from() == Some(from));
+        moves
+    }
+
+    /// Tests if there is at least one legal move, stopping at the first one
+    /// found. Used by [`Position::is_game_over()`] and
+    /// [`Position::outcome()`].
+    ///
+    /// The default implementation generates all legal moves. [`Chess`] and
+    /// variants based on it generate moves piece by piece and return early.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Position};
+    ///
+    /// assert!(Chess::default().has_legal_moves());
+    /// ```
+    fn has_legal_moves(&self) -> bool {
+        !self.legal_moves().is_empty()
+    }
+
     /// Generates a subset of legal moves: All piece moves and drops of type
     /// `role` to the square `to`, excluding castling moves.
     fn san_candidates(&self, role: Role, to: Square) -> MoveList {
@@ -550,6 +861,35 @@ pub trait Position {
         moves
     }
 
+    /// Generates all legal moves, with captures and promotions first, as a
+    /// cheap default move ordering for simple engines.
+    ///
+    /// The moves are partitioned as they are generated, without sorting.
+    /// Within each partition, the order of [`Position::legal_moves()`] is
+    /// kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{CastlingMode, Chess, Position, fen::Fen};
+    ///
+    /// let pos: Chess = "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1"
+    ///     .parse::<Fen>()?
+    ///     .into_position(CastlingMode::Standard)?;
+    ///
+    /// let moves = pos.legal_moves_captures_first();
+    /// assert!(moves[0].is_capture());
+    /// assert!(moves[1..].iter().all(|m| !m.is_capture()));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    fn legal_moves_captures_first(&self) -> MoveList {
+        let mut sink = CapturesFirstSink::default();
+        for m in self.legal_moves() {
+            sink.push(m);
+        }
+        sink.into_list()
+    }
+
     /// Tests if a move is irreversible.
     ///
     /// In standard chess, pawn moves, captures, moves that destroy castling
@@ -583,6 +923,16 @@ pub trait Position {
         self.board().attacks_to(square, attacker, occupied)
     }
 
+    /// Bitboard of pieces giving check.
+    ///
+    /// [`Chess`] computes this once per position and returns the cached
+    /// value.
+    fn checkers(&self) -> Bitboard {
+        self.our(Role::King).first().map_or(Bitboard(0), |king| {
+            self.king_attackers(king, !self.turn(), self.board().occupied())
+        })
+    }
+
     /// Checks if the game is over due to a special variant end condition.
     ///
     /// Note that for example stalemate is not considered a variant-specific
@@ -611,6 +961,17 @@ pub trait Position {
     /// <https://chasolver.org/>.
     fn has_insufficient_material(&self, color: Color) -> bool;
 
+    /// Like [`Position::has_insufficient_material()`], but reports which
+    /// variant-specific rule applies, or `None` if `color` might still have
+    /// sufficient winning material.
+    ///
+    /// The default implementation reports [`InsufficientMaterialRule::Other`].
+    /// All positions in this crate report a more specific rule.
+    fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
+        self.has_insufficient_material(color)
+            .then_some(InsufficientMaterialRule::Other)
+    }
+
     /// Tests special variant winning, losing and drawing conditions.
     fn variant_outcome(&self) -> Outcome;
 
@@ -663,6 +1024,106 @@ pub trait Position {
         moves.contains(&m)
     }
 
+    /// Collects the destination squares of all legal moves by origin
+    /// square, in one pass, for example to highlight the targets of a
+    /// selected piece in a GUI. Piece drops are not included.
+    ///
+    /// Castling moves are included with the square of the rook. In
+    /// [`CastlingMode::Standard`] also with the square the king moves to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Bitboard, Chess, Position, Square};
+    ///
+    /// let pos = Chess::default();
+    /// let dests = pos.legal_destinations();
+    /// assert_eq!(dests.origins().count(), 10);
+    /// assert_eq!(dests.get(Square::G1), Bitboard::from(Square::F3) | Square::H3);
+    /// assert!(dests.get(Square::E1).is_empty());
+    /// ```
+    fn legal_destinations(&self) -> LegalDestinations /* FINAL */ {
+        let mut destinations = LegalDestinations::new();
+        let mode = self.castles().mode();
+        for m in self.legal_moves() {
+            match m {
+                Move::Castle { king, rook } => {
+                    destinations.add(king, rook);
+                    if mode.is_standard() {
+                        let side = CastlingSide::from_king_side(king < rook);
+                        destinations.add(king, side.king_to(self.turn()));
+                    }
+                }
+                Move::Put { .. } => (),
+                _ => {
+                    if let Some(from) = m.from() {
+                        destinations.add(from, m.to());
+                    }
+                }
+            }
+        }
+        destinations
+    }
+
+    /// Squares that the piece on `from` could move to with a premove, i.e.,
+    /// a move that may become legal after the next move of the opponent, as
+    /// defined by Lichess.
+    ///
+    /// The current occupancy of the board is ignored: Pawns may push or
+    /// capture diagonally, sliding pieces move as on an empty board, and
+    /// squares of own pieces are included, since they may be captured. King
+    /// moves include castling (see [`Position::legal_destinations()`]) if the
+    /// castling right is still available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Position, Square};
+    ///
+    /// let pos = Chess::default();
+    /// assert!(pos.is_premove(Square::E7, Square::D6));
+    /// assert!(pos.is_premove(Square::C8, Square::H3));
+    /// assert!(!pos.is_premove(Square::E7, Square::E4));
+    /// ```
+    fn premove_destinations(&self, from: Square) -> Bitboard /* FINAL */ {
+        let Some(piece) = self.board().piece_at(from) else {
+            return Bitboard::EMPTY;
+        };
+        let color = piece.color;
+        match piece.role {
+            Role::Pawn => {
+                let mut destinations = attacks::pawn_attacks(color, from);
+                let forward = color.fold_wb(8, -8);
+                if let Some(single) = from.offset(forward) {
+                    destinations.add(single);
+                    if color.relative_rank(from.rank()) <= Rank::Second {
+                        destinations.extend(single.offset(forward));
+                    }
+                }
+                destinations
+            }
+            Role::King => {
+                let mut destinations = attacks::king_attacks(from);
+                for side in CastlingSide::ALL {
+                    if let Some(rook) = self.castles().rook(color, side) {
+                        destinations.add(rook);
+                        if self.castles().mode().is_standard() {
+                            destinations.add(side.king_to(color));
+                        }
+                    }
+                }
+                destinations.without(from)
+            }
+            _ => attacks::attacks(from, piece, Bitboard::EMPTY),
+        }
+    }
+
+    /// Tests if moving the piece on `from` to `to` is a valid premove. See
+    /// [`Position::premove_destinations()`].
+    fn is_premove(&self, from: Square, to: Square) -> bool /* FINAL */ {
+        self.premove_destinations(from).contains(to)
+    }
+
     /// The en passant square, if it is the target of a
     /// [pseudo-legal](`EnPassantMode::PseudoLegal`) en passant move.
     fn pseudo_legal_ep_square(&self) -> Option<Square> /* FINAL */ {
@@ -688,13 +1149,6 @@ pub trait Position {
         }
     }
 
-    /// Bitboard of pieces giving check.
-    fn checkers(&self) -> Bitboard /* FINAL */ {
-        self.our(Role::King).first().map_or(Bitboard(0), |king| {
-            self.king_attackers(king, !self.turn(), self.board().occupied())
-        })
-    }
-
     /// Tests if the king is in check.
     fn is_check(&self) -> bool /* FINAL */ {
         self.checkers().any()
@@ -702,12 +1156,12 @@ pub trait Position {
 
     /// Tests for checkmate.
     fn is_checkmate(&self) -> bool /* FINAL */ {
-        !self.checkers().is_empty() && self.legal_moves().is_empty()
+        !self.checkers().is_empty() && !self.has_legal_moves()
     }
 
     /// Tests for stalemate.
     fn is_stalemate(&self) -> bool /* FINAL */ {
-        self.checkers().is_empty() && !self.is_variant_end() && self.legal_moves().is_empty()
+        self.checkers().is_empty() && !self.is_variant_end() && !self.has_legal_moves()
     }
 
     /// Tests if both sides
@@ -721,7 +1175,7 @@ pub trait Position {
     /// [insufficient material](Position::is_insufficient_material) or
     /// [variant end](Position::is_variant_end).
     fn is_game_over(&self) -> bool /* FINAL */ {
-        self.is_variant_end() || self.legal_moves().is_empty() || self.is_insufficient_material()
+        self.is_variant_end() || !self.has_legal_moves() || self.is_insufficient_material()
     }
 
     /// The outcome of the game, or [`Outcome::Unknown`] if the game is not over.
@@ -732,7 +1186,7 @@ pub trait Position {
             return variant_outcome;
         }
 
-        if self.legal_moves().is_empty() {
+        if !self.has_legal_moves() {
             if self.is_check() {
                 Outcome::Known(KnownOutcome::Decisive {
                     winner: !self.turn(),
@@ -762,10 +1216,67 @@ pub trait Position {
             self.play_unchecked(m);
             Ok(self)
         } else {
-            Err(PlayError { m, position: self })
+            Err(PlayError::new(m, self))
         }
     }
 
+    /// Finds the legal move that results in `board`, for example to
+    /// reconcile the position with a board observed from an electronic
+    /// chess board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Board, Chess, Position, uci::UciMove};
+    ///
+    /// let pos = Chess::default();
+    /// let board: Board = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR".parse()?;
+    /// let m = pos.infer_move(&board).expect("e2e4");
+    /// assert_eq!(UciMove::from_standard(m).to_string(), "e2e4");
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    fn infer_move(&self, board: &Board) -> Option<Move>
+    /* FINAL */
+    where
+        Self: Sized + Clone,
+    {
+        self.legal_moves().into_iter().find(|&m| {
+            let mut after = self.clone();
+            after.play_unchecked(m);
+            after.board() == board
+        })
+    }
+
+    /// Finds the shortest sequence of at most `max_plies` legal moves that
+    /// results in `board`, for example if moves were missed while reading
+    /// an electronic chess board. The sequence is empty if the board already
+    /// matches.
+    ///
+    /// The search is exhaustive, so the running time grows exponentially
+    /// with `max_plies`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Board, Chess, Position};
+    ///
+    /// let pos = Chess::default();
+    /// let board: Board = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR".parse()?;
+    /// assert_eq!(pos.infer_moves(&board, 1), None);
+    /// assert_eq!(pos.infer_moves(&board, 4).map(|moves| moves.len()), Some(2));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    fn infer_moves(&self, board: &Board, max_plies: usize) -> Option<MoveList>
+    /* FINAL */
+    where
+        Self: Sized + Clone,
+    {
+        let mut moves = MoveList::new();
+        (0..=max_plies.min(moves.capacity()))
+            .find(|&plies| infer_moves(self, board, plies, &mut moves))
+            .map(|_| moves)
+    }
+
     /// Swap turns and discard en passant rights. This is sometimes called
     /// "playing a null move".
     ///
@@ -799,9 +1310,49 @@ pub struct Chess {
     ep_square: Option<EnPassant>,
     halfmoves: u32,
     fullmoves: NonZeroU32,
+    // Derived from board and turn. See Chess::update_derived().
+    checkers: Bitboard,
+    blockers: Bitboard,
 }
 
 impl Chess {
+    fn gen_legal_moves<M: MoveSink>(&self, moves: &mut M) {
+        let king = self
+            .board()
+            .king_of(self.turn())
+            .expect("king in standard chess");
+
+        let has_ep = gen_en_passant(self.board(), self.turn(), self.ep_square, moves);
+
+        let checkers = self.checkers();
+        if checkers.is_empty() {
+            let target = !self.us();
+            gen_non_king(self, target, moves);
+            gen_safe_king(self, king, target, moves);
+            gen_castling_moves(self, &self.castles, king, CastlingSide::KingSide, moves);
+            gen_castling_moves(self, &self.castles, king, CastlingSide::QueenSide, moves);
+        } else {
+            evasions(self, king, checkers, moves);
+        }
+
+        let blockers = self.blockers;
+        if blockers.any() || has_ep {
+            moves.retain(|m| is_safe(self, king, *m, blockers));
+        }
+    }
+
+    /// Computes the pieces giving check and the pieces blocking sliders
+    /// from attacking the king, once per position, so that they can be
+    /// reused by move generation, legality checks and game end detection.
+    fn update_derived(&mut self) {
+        (self.checkers, self.blockers) = match self.our(Role::King).first() {
+            Some(king) => (
+                self.king_attackers(king, !self.turn, self.board.occupied()),
+                slider_blockers(&self.board, self.them(), king),
+            ),
+            None => (Bitboard::EMPTY, Bitboard::EMPTY),
+        };
+    }
     #[cfg(feature = "variant")]
     fn gives_check(&self, m: Move) -> bool {
         let mut pos = self.clone();
@@ -837,14 +1388,17 @@ impl Chess {
             }
         };
 
-        let pos = Chess {
+        let mut pos = Chess {
             board: setup.board,
             turn: setup.turn,
             castles,
             ep_square,
             halfmoves: setup.halfmoves,
             fullmoves: setup.fullmoves,
+            checkers: Bitboard::EMPTY,
+            blockers: Bitboard::EMPTY,
         };
+        pos.update_derived();
 
         errors |= validate(&pos, ep_square);
 
@@ -860,32 +1414,152 @@ impl Chess {
             ep_square: None,
             halfmoves: 0,
             fullmoves: NonZeroU32::MIN,
+            checkers: Bitboard::EMPTY,
+            blockers: Bitboard::EMPTY,
         }
     }
-}
-
-impl Default for Chess {
-    fn default() -> Chess {
-        Chess::new()
-    }
-}
-
-impl Hash for Chess {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.board.hash(state);
-        self.turn.hash(state);
-        self.castles.castling_rights().hash(state);
-        // Optimization: Not hashing legal_ep_square(), but still considered
-        // for equality.
-    }
-}
 
-impl PartialEq for Chess {
-    fn eq(&self, other: &Chess) -> bool {
-        self.board == other.board
-            && self.turn == other.turn
-            && self.castles.castling_rights() == other.castles.castling_rights()
-            && self.legal_ep_square() == other.legal_ep_square()
+    /// Plays a move, skipping even the consistency checks that
+    /// [`Position::play_unchecked()`] performs while updating the board.
+    /// The board is updated purely from the information in `m`, without
+    /// looking at the squares involved.
+    ///
+    /// This is intended for engines that only ever play moves that were
+    /// generated by this crate for the very same position. Legality is
+    /// checked with `debug_assert!` only.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if the move is not legal. In release builds,
+    /// illegal moves silently corrupt the position (but cannot cause
+    /// undefined behavior), and may cause panics or nonsensical results on
+    /// future calls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Position};
+    ///
+    /// let mut pos = Chess::default();
+    /// let m = pos.legal_moves()[0];
+    /// let mut expected = pos.clone();
+    /// expected.play_unchecked(m);
+    ///
+    /// pos.play_unchecked_unvalidated(m);
+    /// assert_eq!(pos, expected);
+    /// ```
+    #[inline]
+    pub fn play_unchecked_unvalidated(&mut self, m: Move) {
+        debug_assert!(self.is_legal(m), "illegal move {m:?}");
+        do_move_trusted(
+            &mut self.board,
+            &mut self.turn,
+            &mut self.castles,
+            &mut self.ep_square,
+            &mut self.halfmoves,
+            &mut self.fullmoves,
+            m,
+        );
+        self.update_derived();
+    }
+
+    /// Initial position of a game where one side gives the other
+    /// [`Odds`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{CastlingSide, Chess, Color, Odds, Position, Square};
+    ///
+    /// let pos = Chess::with_odds(Odds::Rook);
+    /// assert_eq!(pos.board().piece_at(Square::A1), None);
+    /// assert!(!pos.castles().has(Color::White, CastlingSide::QueenSide));
+    /// assert!(pos.castles().has(Color::White, CastlingSide::KingSide));
+    /// ```
+    pub fn with_odds(odds: Odds) -> Chess {
+        let mut setup = Setup::initial();
+        setup.board.discard_piece_at(odds.square());
+        setup.castling_rights.discard(odds.square());
+        Chess::from_setup(setup, CastlingMode::Standard).expect("valid odds position")
+    }
+}
+
+impl Default for Chess {
+    fn default() -> Chess {
+        Chess::new()
+    }
+}
+
+/// A traditional handicap, where the stronger player starts the game
+/// without some material.
+///
+/// See [`Chess::with_odds()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Odds {
+    /// Black plays without the f7 pawn, and White moves first.
+    PawnAndMove,
+    /// White plays without the knight on b1.
+    Knight,
+    /// White plays without the rook on a1, and can castle only kingside.
+    Rook,
+    /// White plays without the queen.
+    Queen,
+}
+
+impl Odds {
+    pub const ALL: [Odds; 4] = [Odds::PawnAndMove, Odds::Knight, Odds::Rook, Odds::Queen];
+
+    /// The side giving odds.
+    pub const fn giver(self) -> Color {
+        match self {
+            Odds::PawnAndMove => Black,
+            Odds::Knight | Odds::Rook | Odds::Queen => White,
+        }
+    }
+
+    /// The square of the piece that is removed from the initial position.
+    pub const fn square(self) -> Square {
+        match self {
+            Odds::PawnAndMove => Square::F7,
+            Odds::Knight => Square::B1,
+            Odds::Rook => Square::A1,
+            Odds::Queen => Square::D1,
+        }
+    }
+
+    /// The traditional name, e.g., `Pawn and move`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Odds::PawnAndMove => "Pawn and move",
+            Odds::Knight => "Knight odds",
+            Odds::Rook => "Rook odds",
+            Odds::Queen => "Queen odds",
+        }
+    }
+}
+
+impl fmt::Display for Odds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Hash for Chess {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.board.hash(state);
+        self.turn.hash(state);
+        self.castles.castling_rights().hash(state);
+        // Optimization: Not hashing legal_ep_square(), but still considered
+        // for equality.
+    }
+}
+
+impl PartialEq for Chess {
+    fn eq(&self, other: &Chess) -> bool {
+        self.board == other.board
+            && self.turn == other.turn
+            && self.castles.castling_rights() == other.castles.castling_rights()
+            && self.legal_ep_square() == other.legal_ep_square()
     }
 }
 
@@ -915,10 +1589,61 @@ impl Eq for Chess {}
 impl FromSetup for Chess {
     fn from_setup(setup: Setup, mode: CastlingMode) -> Result<Chess, PositionError<Chess>> {
         let (pos, _, _, errors) = Chess::from_setup_unchecked(setup, mode);
-        PositionError { pos, errors }.strict()
+        PositionError::strict(pos, errors)
+    }
+}
+
+/// Parses a FEN with [`CastlingMode::Standard`]. Use
+/// [`FromSetup::from_fen()`] to select a different castling mode.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Chess, Position};
+///
+/// let pos: Chess = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1".parse()?;
+/// assert_eq!(pos.legal_moves().len(), 20);
+/// # Ok::<_, shakmaty::fen::ParsePositionError<_>>(())
+/// ```
+impl FromStr for Chess {
+    type Err = ParsePositionError<Chess>;
+
+    fn from_str(s: &str) -> Result<Chess, ParsePositionError<Chess>> {
+        Chess::from_fen(s, CastlingMode::Standard)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl bincode::Encode for Chess {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        let packed =
+            crate::packed::PackedSetup::pack_standard(&self.to_setup(EnPassantMode::Legal))
+                .map_err(|_| bincode::error::EncodeError::Other("unrepresentable Chess"))?;
+        self.castles.mode().is_chess960().encode(encoder)?;
+        packed.encode(encoder)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<Config> bincode::Decode<Config> for Chess {
+    fn decode<D: bincode::de::Decoder>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let mode = CastlingMode::from_chess960(bool::decode(decoder)?);
+        let setup = crate::packed::PackedSetup::decode(decoder)?
+            .unpack_standard()
+            .map_err(|_| bincode::error::DecodeError::Other("invalid Chess"))?;
+        Chess::from_setup(setup, mode)
+            .map_err(|_| bincode::error::DecodeError::Other("illegal Chess"))
     }
 }
 
+#[cfg(feature = "bincode")]
+bincode::impl_borrow_decode!(Chess);
+
 #[cfg(feature = "arbitrary")]
 impl arbitrary::Arbitrary<'_> for Chess {
     fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Chess> {
@@ -980,49 +1705,127 @@ impl Position for Chess {
             &mut self.fullmoves,
             m,
         );
+        self.update_derived();
+    }
+
+    fn checkers(&self) -> Bitboard {
+        self.checkers
     }
 
     fn legal_moves(&self) -> MoveList {
         let mut moves = MoveList::new();
+        self.gen_legal_moves(&mut moves);
+        moves
+    }
+
+    #[cfg(feature = "alloc")]
+    fn legal_moves_into(&self, moves: &mut alloc::vec::Vec<Move>) {
+        let start = moves.len();
+        self.gen_legal_moves(&mut VecSink { moves, start });
+    }
+
+    fn legal_moves_into_uninit<'a>(&self, buffer: &'a mut [MaybeUninit<Move>]) -> &'a mut [Move] {
+        let mut sink = UninitSink::new(buffer);
+        self.gen_legal_moves(&mut sink);
+        sink.into_slice()
+    }
+
+    fn legal_moves_captures_first(&self) -> MoveList {
+        let mut sink = CapturesFirstSink::default();
+        self.gen_legal_moves(&mut sink);
+        sink.into_list()
+    }
+
+    fn legal_moves_iter(&self) -> LegalMovesIter<'_, Chess> {
+        LegalMovesIter::lazy(self)
+    }
+
+    fn legal_moves_from(&self, from: Square) -> MoveList {
+        let mut moves = MoveList::new();
+
+        let Some(role) = self
+            .board()
+            .role_at(from)
+            .filter(|_| self.us().contains(from))
+        else {
+            return moves;
+        };
 
         let king = self
             .board()
             .king_of(self.turn())
             .expect("king in standard chess");
 
-        let has_ep = gen_en_passant(self.board(), self.turn(), self.ep_square, &mut moves);
-
         let checkers = self.checkers();
-        if checkers.is_empty() {
-            let target = !self.us();
-            gen_non_king(self, target, &mut moves);
-            gen_safe_king(self, king, target, &mut moves);
-            gen_castling_moves(
-                self,
-                &self.castles,
-                king,
-                CastlingSide::KingSide,
-                &mut moves,
-            );
-            gen_castling_moves(
-                self,
-                &self.castles,
-                king,
-                CastlingSide::QueenSide,
-                &mut moves,
-            );
+        if role == Role::King {
+            if checkers.is_empty() {
+                gen_safe_king(self, king, !self.us(), &mut moves);
+                gen_castling_moves(
+                    self,
+                    &self.castles,
+                    king,
+                    CastlingSide::KingSide,
+                    &mut moves,
+                );
+                gen_castling_moves(
+                    self,
+                    &self.castles,
+                    king,
+                    CastlingSide::QueenSide,
+                    &mut moves,
+                );
+            } else {
+                gen_king_evasions(self, king, checkers, !self.us(), &mut moves);
+            }
+            return moves;
+        }
+
+        let target = if checkers.is_empty() {
+            !self.us()
+        } else if let Some(checker) = checkers.single_square() {
+            attacks::between(king, checker).with(checker)
         } else {
-            evasions(self, king, checkers, &mut moves);
+            Bitboard::EMPTY
+        };
+
+        let mut has_ep = false;
+        match role {
+            Role::Pawn => {
+                has_ep = gen_en_passant(self.board(), self.turn(), self.ep_square, &mut moves);
+                gen_pawn_moves(self, target, &mut moves);
+            }
+            Role::Knight => KnightTag::gen_moves(self, target, &mut moves),
+            Role::Bishop => BishopTag::gen_moves(self, target, &mut moves),
+            Role::Rook => RookTag::gen_moves(self, target, &mut moves),
+            Role::Queen => QueenTag::gen_moves(self, target, &mut moves),
+            Role::King => unreachable!(),
         }
+        moves.retain(|m| m.from() == Some(from));
 
-        let blockers = slider_blockers(self.board(), self.them(), king);
-        if blockers.any() || has_ep {
+        let blockers = self.blockers;
+        if blockers.contains(from) || has_ep {
             moves.retain(|m| is_safe(self, king, *m, blockers));
         }
 
         moves
     }
 
+    fn has_legal_moves(&self) -> bool {
+        let king = self
+            .board()
+            .king_of(self.turn())
+            .expect("king in standard chess");
+
+        // Only the king can move out of double check.
+        !self.legal_moves_from(king).is_empty()
+            || (!self.checkers().more_than_one()
+                && self
+                    .us()
+                    .without(king)
+                    .into_iter()
+                    .any(|from| !self.legal_moves_from(from).is_empty()))
+    }
+
     fn castling_moves(&self, side: CastlingSide) -> MoveList {
         let mut moves = MoveList::new();
         let king = self
@@ -1041,7 +1844,7 @@ impl Position for Chess {
                 .board()
                 .king_of(self.turn())
                 .expect("king in standard chess");
-            let blockers = slider_blockers(self.board(), self.them(), king);
+            let blockers = self.blockers;
             moves.retain(|m| is_safe(self, king, *m, blockers));
         }
 
@@ -1064,7 +1867,7 @@ impl Position for Chess {
             moves.retain(|m| m.is_promotion());
         }
 
-        let blockers = slider_blockers(self.board(), self.them(), king);
+        let blockers = self.blockers;
         if blockers.any() {
             moves.retain(|m| is_safe(self, king, *m, blockers));
         }
@@ -1081,20 +1884,31 @@ impl Position for Chess {
             .expect("king in standard chess");
         let checkers = self.checkers();
 
-        if checkers.is_empty() {
-            let piece_from = match role {
-                Role::Pawn | Role::King => Bitboard(0),
-                Role::Knight => attacks::knight_attacks(to),
-                Role::Bishop => attacks::bishop_attacks(to, self.board().occupied()),
-                Role::Rook => attacks::rook_attacks(to, self.board().occupied()),
-                Role::Queen => attacks::queen_attacks(to, self.board().occupied()),
+        // Narrow down origin squares with attack bitboards, instead of
+        // generating all legal moves (or evasions) and filtering them.
+        let to_bb = Bitboard::from_square(to) & !self.us();
+        if role == Role::King {
+            gen_king_evasions(self, king, checkers, to_bb, &mut moves);
+        } else {
+            let target = if checkers.is_empty() {
+                to_bb
+            } else if let Some(checker) = checkers.single_square() {
+                to_bb & attacks::between(king, checker).with(checker)
+            } else {
+                Bitboard::EMPTY
             };
 
-            if !self.us().contains(to) {
-                match role {
-                    Role::Pawn => gen_pawn_moves(self, Bitboard::from_square(to), &mut moves),
-                    Role::King => gen_safe_king(self, king, Bitboard::from_square(to), &mut moves),
-                    _ => {}
+            if target.any() {
+                let piece_from = match role {
+                    Role::Pawn | Role::King => Bitboard(0),
+                    Role::Knight => attacks::knight_attacks(to),
+                    Role::Bishop => attacks::bishop_attacks(to, self.board().occupied()),
+                    Role::Rook => attacks::rook_attacks(to, self.board().occupied()),
+                    Role::Queen => attacks::queen_attacks(to, self.board().occupied()),
+                };
+
+                if role == Role::Pawn {
+                    gen_pawn_moves(self, target, &mut moves);
                 }
 
                 for from in piece_from & self.our(role) {
@@ -1107,16 +1921,13 @@ impl Position for Chess {
                     });
                 }
             }
-        } else {
-            evasions(self, king, checkers, &mut moves);
-            filter_san_candidates(role, to, &mut moves);
         }
 
         let has_ep = role == Role::Pawn
             && self.ep_square.map(Square::from) == Some(to)
             && gen_en_passant(self.board(), self.turn(), self.ep_square, &mut moves);
 
-        let blockers = slider_blockers(self.board(), self.them(), king);
+        let blockers = self.blockers;
         if blockers.any() || has_ep {
             moves.retain(|m| is_safe(self, king, *m, blockers));
         }
@@ -1125,10 +1936,14 @@ impl Position for Chess {
     }
 
     fn has_insufficient_material(&self, color: Color) -> bool {
+        self.insufficient_material_rule(color).is_some()
+    }
+
+    fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
         // Pawns, rooks and queens are never insufficient material.
         if (self.board.by_color(color) & (self.board.pawns() | self.board.rooks_and_queens())).any()
         {
-            return false;
+            return None;
         }
 
         // Knights are only insufficient material if:
@@ -1136,9 +1951,10 @@ impl Position for Chess {
         // (2) The opponent does not have pawns, knights, bishops or rooks.
         //     These would allow self mate.
         if (self.board.by_color(color) & self.board.knights()).any() {
-            return self.board.by_color(color).count() <= 2
+            return (self.board.by_color(color).count() <= 2
                 && (self.board.by_color(!color) & !self.board.kings() & !self.board().queens())
-                    .is_empty();
+                    .is_empty())
+            .then_some(InsufficientMaterialRule::LonePiece);
         }
 
         // Bishops are only insufficient material if:
@@ -1149,12 +1965,13 @@ impl Position for Chess {
         if (self.board.by_color(color) & self.board.bishops()).any() {
             let same_color = (self.board().bishops() & Bitboard::DARK_SQUARES).is_empty()
                 || (self.board().bishops() & Bitboard::LIGHT_SQUARES).is_empty();
-            return same_color
+            return (same_color
                 && self.board().knights().is_empty()
-                && self.board().pawns().is_empty();
+                && self.board().pawns().is_empty())
+            .then_some(InsufficientMaterialRule::SameColoredBishops);
         }
 
-        true
+        Some(InsufficientMaterialRule::BareKing)
     }
 
     /// Always returns `false`.
@@ -1275,7 +2092,7 @@ pub(crate) mod variant {
                 errors.remove(PositionErrorKinds::MISSING_KING);
             }
 
-            PositionError { pos, errors }.strict()
+            PositionError::strict(pos, errors)
         }
     }
 
@@ -1421,15 +2238,19 @@ pub(crate) mod variant {
         }
 
         fn has_insufficient_material(&self, color: Color) -> bool {
+            self.insufficient_material_rule(color).is_some()
+        }
+
+        fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
             // Remaining material does not matter if the opponents king is already
             // exploded.
             if (self.board.by_color(!color) & self.board.kings()).is_empty() {
-                return false;
+                return None;
             }
 
             // Bare king can not mate.
             if (self.board.by_color(color) & !self.board.kings()).is_empty() {
-                return true;
+                return Some(InsufficientMaterialRule::BareKing);
             }
 
             // As long as the opponent king is not alone there is always a chance
@@ -1443,7 +2264,8 @@ pub(crate) mod variant {
                         return (self.board().bishops()
                             & self.board().black()
                             & Bitboard::LIGHT_SQUARES)
-                            .is_empty();
+                            .is_empty()
+                            .then_some(InsufficientMaterialRule::OppositeColoredBishops);
                     }
                     if (self.board().bishops() & self.board().white() & Bitboard::LIGHT_SQUARES)
                         .is_empty()
@@ -1451,30 +2273,41 @@ pub(crate) mod variant {
                         return (self.board().bishops()
                             & self.board().black()
                             & Bitboard::DARK_SQUARES)
-                            .is_empty();
+                            .is_empty()
+                            .then_some(InsufficientMaterialRule::OppositeColoredBishops);
                     }
                 }
 
-                return false;
+                return None;
             }
 
             // Queen or pawn (future queen) can give mate against bare king.
             if self.board().queens().any() || self.board.pawns().any() {
-                return false;
+                return None;
             }
 
             // Single knight, bishop or rook can not mate against bare king.
             if (self.board().knights() | self.board().bishops() | self.board().rooks()).count() == 1
             {
-                return true;
+                return Some(InsufficientMaterialRule::LonePiece);
             }
 
             // Two knights can not mate against bare king.
             if self.board().occupied() == self.board().kings() | self.board().knights() {
-                return self.board().knights().count() <= 2;
+                return (self.board().knights().count() <= 2)
+                    .then_some(InsufficientMaterialRule::TwoKnights);
             }
 
-            false
+            // Bishops on a single color complex can not cover the flight
+            // squares of the other color, and there is nothing to explode.
+            if self.board().occupied() == self.board().kings() | self.board().bishops()
+                && ((self.board().bishops() & Bitboard::DARK_SQUARES).is_empty()
+                    || (self.board().bishops() & Bitboard::LIGHT_SQUARES).is_empty())
+            {
+                return Some(InsufficientMaterialRule::SameColoredBishops);
+            }
+
+            None
         }
 
         fn variant_outcome(&self) -> Outcome {
@@ -1510,6 +2343,29 @@ pub(crate) mod variant {
                 fullmoves: NonZeroU32::MIN,
             }
         }
+
+        /// Tests if the side to move is obliged to capture. In this case, the
+        /// [legal moves](Position::legal_moves()) are exactly the
+        /// [capture moves](Position::capture_moves()).
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use shakmaty::{CastlingMode, Position, fen::Fen, variant::Antichess};
+        ///
+        /// let pos = Antichess::default();
+        /// assert!(!pos.must_capture());
+        ///
+        /// let pos: Antichess = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 2"
+        ///     .parse::<Fen>()?
+        ///     .into_position(CastlingMode::Standard)?;
+        /// assert!(pos.must_capture());
+        /// assert_eq!(pos.legal_moves(), pos.capture_moves());
+        /// # Ok::<_, Box<dyn std::error::Error>>(())
+        /// ```
+        pub fn must_capture(&self) -> bool {
+            !self.capture_moves().is_empty()
+        }
     }
 
     impl Default for Antichess {
@@ -1547,7 +2403,7 @@ pub(crate) mod variant {
             let ep_square = match EnPassant::from_setup(&setup) {
                 Ok(ep_square) => ep_square,
                 Err(()) => {
-                    errors |= PositionErrorKinds::INVALID_CASTLING_RIGHTS;
+                    errors |= PositionErrorKinds::INVALID_EP_SQUARE;
                     None
                 }
             };
@@ -1571,7 +2427,7 @@ pub(crate) mod variant {
                 - PositionErrorKinds::OPPOSITE_CHECK
                 - PositionErrorKinds::IMPOSSIBLE_CHECK;
 
-            PositionError { pos, errors }.strict()
+            PositionError::strict(pos, errors)
         }
     }
 
@@ -1680,10 +2536,14 @@ pub(crate) mod variant {
         }
 
         fn has_insufficient_material(&self, color: Color) -> bool {
+            self.insufficient_material_rule(color).is_some()
+        }
+
+        fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
             if self.board.by_color(color).is_empty() {
-                false
+                None
             } else if self.board.by_color(!color).is_empty() {
-                true
+                Some(InsufficientMaterialRule::OpponentWon)
             } else if self.board.occupied() == self.board.bishops() {
                 // In a position with only bishops, check if all our bishops
                 // can be captured.
@@ -1693,22 +2553,20 @@ pub(crate) mod variant {
                     (self.board.by_color(!color) & Bitboard::LIGHT_SQUARES).is_empty();
                 let they_all_on_light =
                     (self.board.by_color(!color) & Bitboard::DARK_SQUARES).is_empty();
-                (we_some_on_light && they_all_on_dark) || (we_some_on_dark && they_all_on_light)
+                ((we_some_on_light && they_all_on_dark) || (we_some_on_dark && they_all_on_light))
+                    .then_some(InsufficientMaterialRule::OppositeColoredBishops)
             } else if self.board.occupied() == self.board.knights() {
                 match (
                     self.board.white().single_square(),
                     self.board.black().single_square(),
                 ) {
-                    (Some(white_single_knight), Some(black_single_knight)) => {
-                        self.turn
-                            == color
-                                ^ white_single_knight.is_light()
-                                ^ black_single_knight.is_dark()
-                    }
-                    _ => false,
+                    (Some(white_single_knight), Some(black_single_knight)) => (self.turn
+                        == color ^ white_single_knight.is_light() ^ black_single_knight.is_dark())
+                    .then_some(InsufficientMaterialRule::KnightParity),
+                    _ => None,
                 }
             } else {
-                false
+                None
             }
         }
 
@@ -1743,11 +2601,7 @@ pub(crate) mod variant {
             mode: CastlingMode,
         ) -> Result<KingOfTheHill, PositionError<KingOfTheHill>> {
             let (chess, _, _, errors) = Chess::from_setup_unchecked(setup, mode);
-            PositionError {
-                errors,
-                pos: KingOfTheHill { chess },
-            }
-            .strict()
+            PositionError::strict(KingOfTheHill { chess }, errors)
         }
     }
 
@@ -1813,6 +2667,48 @@ pub(crate) mod variant {
             }
         }
 
+        #[cfg(feature = "alloc")]
+        fn legal_moves_into(&self, moves: &mut alloc::vec::Vec<Move>) {
+            if !self.is_variant_end() {
+                self.chess.legal_moves_into(moves);
+            }
+        }
+
+        fn legal_moves_into_uninit<'a>(
+            &self,
+            buffer: &'a mut [MaybeUninit<Move>],
+        ) -> &'a mut [Move] {
+            if self.is_variant_end() {
+                &mut []
+            } else {
+                self.chess.legal_moves_into_uninit(buffer)
+            }
+        }
+
+        fn legal_moves_captures_first(&self) -> MoveList {
+            if self.is_variant_end() {
+                MoveList::new()
+            } else {
+                self.chess.legal_moves_captures_first()
+            }
+        }
+
+        fn legal_moves_iter(&self) -> LegalMovesIter<'_, Self> {
+            LegalMovesIter::lazy(self)
+        }
+
+        fn legal_moves_from(&self, from: Square) -> MoveList {
+            if self.is_variant_end() {
+                MoveList::new()
+            } else {
+                self.chess.legal_moves_from(from)
+            }
+        }
+
+        fn has_legal_moves(&self) -> bool {
+            !self.is_variant_end() && self.chess.has_legal_moves()
+        }
+
         fn castling_moves(&self, side: CastlingSide) -> MoveList {
             if self.is_variant_end() {
                 MoveList::new()
@@ -1856,51 +2752,240 @@ pub(crate) mod variant {
         }
     }
 
-    /// A Three-Check position.
-    #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
-    pub struct ThreeCheck {
+    /// A position of standard chess without castling, as played in some
+    /// events to steer away from opening theory.
+    ///
+    /// Castling rights are rejected when setting up a position.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct NoCastling {
         chess: Chess,
-        remaining_checks: ByColor<RemainingChecks>,
     }
 
-    impl ThreeCheck {
-        pub const fn new() -> ThreeCheck {
-            ThreeCheck {
-                chess: Chess::new(),
-                remaining_checks: ByColor {
-                    black: RemainingChecks::new(3),
-                    white: RemainingChecks::new(3),
+    impl NoCastling {
+        pub const fn new() -> NoCastling {
+            NoCastling {
+                chess: Chess {
+                    castles: Castles::empty(CastlingMode::Standard),
+                    ..Chess::new()
                 },
             }
         }
     }
 
-    impl FromSetup for ThreeCheck {
+    impl Default for NoCastling {
+        fn default() -> NoCastling {
+            NoCastling::new()
+        }
+    }
+
+    impl FromSetup for NoCastling {
         fn from_setup(
             setup: Setup,
             mode: CastlingMode,
-        ) -> Result<ThreeCheck, PositionError<ThreeCheck>> {
-            let (chess, _, remaining_checks, mut errors) = Chess::from_setup_unchecked(setup, mode);
-
-            let remaining_checks = remaining_checks.unwrap_or_default();
-            if remaining_checks.iter().all(|remaining| remaining.is_zero()) {
-                errors |= PositionErrorKinds::VARIANT;
-            }
-
-            PositionError {
-                errors,
-                pos: ThreeCheck {
-                    chess,
-                    remaining_checks,
+        ) -> Result<NoCastling, PositionError<NoCastling>> {
+            let has_castling_rights = setup.castling_rights.any();
+            let (chess, _, _, mut errors) = Chess::from_setup_unchecked(
+                Setup {
+                    castling_rights: Bitboard::EMPTY,
+                    ..setup
                 },
+                mode,
+            );
+            if has_castling_rights {
+                errors |= PositionErrorKinds::INVALID_CASTLING_RIGHTS;
             }
-            .strict()
+            PositionError::strict(NoCastling { chess }, errors)
         }
     }
 
     #[cfg(feature = "arbitrary")]
-    impl arbitrary::Arbitrary<'_> for ThreeCheck {
-        fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<ThreeCheck> {
+    impl arbitrary::Arbitrary<'_> for NoCastling {
+        fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<NoCastling> {
+            NoCastling::from_setup(Setup::arbitrary(u)?, CastlingMode::Chess960)
+                .map_err(|_| arbitrary::Error::IncorrectFormat)
+        }
+
+        #[inline]
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            <Setup as arbitrary::Arbitrary>::size_hint(depth)
+        }
+    }
+
+    impl Position for NoCastling {
+        fn board(&self) -> &Board {
+            self.chess.board()
+        }
+
+        fn promoted(&self) -> Bitboard {
+            Bitboard::EMPTY
+        }
+
+        fn castles(&self) -> &Castles {
+            self.chess.castles()
+        }
+
+        fn pockets(&self) -> Option<&ByColor<ByRole<u8>>> {
+            None
+        }
+
+        fn turn(&self) -> Color {
+            self.chess.turn()
+        }
+
+        fn maybe_ep_square(&self) -> Option<Square> {
+            self.chess.maybe_ep_square()
+        }
+
+        fn remaining_checks(&self) -> Option<&ByColor<RemainingChecks>> {
+            None
+        }
+
+        fn halfmoves(&self) -> u32 {
+            self.chess.halfmoves()
+        }
+
+        fn fullmoves(&self) -> NonZeroU32 {
+            self.chess.fullmoves()
+        }
+
+        fn play_unchecked(&mut self, m: Move) {
+            self.chess.play_unchecked(m);
+        }
+
+        fn legal_moves(&self) -> MoveList {
+            self.chess.legal_moves()
+        }
+
+        #[cfg(feature = "alloc")]
+        fn legal_moves_into(&self, moves: &mut alloc::vec::Vec<Move>) {
+            self.chess.legal_moves_into(moves);
+        }
+
+        fn legal_moves_into_uninit<'a>(
+            &self,
+            buffer: &'a mut [MaybeUninit<Move>],
+        ) -> &'a mut [Move] {
+            self.chess.legal_moves_into_uninit(buffer)
+        }
+
+        fn legal_moves_captures_first(&self) -> MoveList {
+            self.chess.legal_moves_captures_first()
+        }
+
+        fn legal_moves_iter(&self) -> LegalMovesIter<'_, Self> {
+            LegalMovesIter::lazy(self)
+        }
+
+        fn legal_moves_from(&self, from: Square) -> MoveList {
+            self.chess.legal_moves_from(from)
+        }
+
+        fn has_legal_moves(&self) -> bool {
+            self.chess.has_legal_moves()
+        }
+
+        fn castling_moves(&self, _side: CastlingSide) -> MoveList {
+            MoveList::new()
+        }
+
+        fn en_passant_moves(&self) -> MoveList {
+            self.chess.en_passant_moves()
+        }
+
+        fn san_candidates(&self, role: Role, to: Square) -> MoveList {
+            self.chess.san_candidates(role, to)
+        }
+
+        fn has_insufficient_material(&self, color: Color) -> bool {
+            self.chess.has_insufficient_material(color)
+        }
+
+        fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
+            self.chess.insufficient_material_rule(color)
+        }
+
+        fn is_variant_end(&self) -> bool {
+            false
+        }
+
+        fn variant_outcome(&self) -> Outcome {
+            Outcome::Unknown
+        }
+    }
+
+    /// A Three-Check position.
+    ///
+    /// Also used for N-Check, where the required number of checks is set by
+    /// the initial [remaining checks](Position::remaining_checks()), for
+    /// example using [`ThreeCheck::with_checks()`] or a FEN like
+    /// `rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 5+5 0 1`.
+    #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+    pub struct ThreeCheck {
+        chess: Chess,
+        remaining_checks: ByColor<RemainingChecks>,
+    }
+
+    impl ThreeCheck {
+        pub const fn new() -> ThreeCheck {
+            ThreeCheck {
+                chess: Chess::new(),
+                remaining_checks: ByColor {
+                    black: RemainingChecks::new(3),
+                    white: RemainingChecks::new(3),
+                },
+            }
+        }
+
+        /// The starting position of an N-Check game, where each side needs
+        /// to give `checks` checks to win.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use shakmaty::{Position, RemainingChecks, variant::ThreeCheck};
+        ///
+        /// let pos = ThreeCheck::with_checks(RemainingChecks::new(5));
+        /// assert_eq!(
+        ///     pos.remaining_checks().map(|checks| checks.white),
+        ///     Some(RemainingChecks::new(5))
+        /// );
+        /// ```
+        pub const fn with_checks(checks: RemainingChecks) -> ThreeCheck {
+            ThreeCheck {
+                chess: Chess::new(),
+                remaining_checks: ByColor {
+                    black: checks,
+                    white: checks,
+                },
+            }
+        }
+    }
+
+    impl FromSetup for ThreeCheck {
+        fn from_setup(
+            setup: Setup,
+            mode: CastlingMode,
+        ) -> Result<ThreeCheck, PositionError<ThreeCheck>> {
+            let (chess, _, remaining_checks, mut errors) = Chess::from_setup_unchecked(setup, mode);
+
+            let remaining_checks = remaining_checks.unwrap_or_default();
+            if remaining_checks.iter().all(|remaining| remaining.is_zero()) {
+                errors |= PositionErrorKinds::VARIANT;
+            }
+
+            PositionError::strict(
+                ThreeCheck {
+                    chess,
+                    remaining_checks,
+                },
+                errors,
+            )
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    impl arbitrary::Arbitrary<'_> for ThreeCheck {
+        fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<ThreeCheck> {
             ThreeCheck::from_setup(Setup::arbitrary(u)?, CastlingMode::Chess960)
                 .map_err(|_| arbitrary::Error::IncorrectFormat)
         }
@@ -1965,6 +3050,48 @@ pub(crate) mod variant {
             }
         }
 
+        #[cfg(feature = "alloc")]
+        fn legal_moves_into(&self, moves: &mut alloc::vec::Vec<Move>) {
+            if !self.is_variant_end() {
+                self.chess.legal_moves_into(moves);
+            }
+        }
+
+        fn legal_moves_into_uninit<'a>(
+            &self,
+            buffer: &'a mut [MaybeUninit<Move>],
+        ) -> &'a mut [Move] {
+            if self.is_variant_end() {
+                &mut []
+            } else {
+                self.chess.legal_moves_into_uninit(buffer)
+            }
+        }
+
+        fn legal_moves_captures_first(&self) -> MoveList {
+            if self.is_variant_end() {
+                MoveList::new()
+            } else {
+                self.chess.legal_moves_captures_first()
+            }
+        }
+
+        fn legal_moves_iter(&self) -> LegalMovesIter<'_, Self> {
+            LegalMovesIter::lazy(self)
+        }
+
+        fn legal_moves_from(&self, from: Square) -> MoveList {
+            if self.is_variant_end() {
+                MoveList::new()
+            } else {
+                self.chess.legal_moves_from(from)
+            }
+        }
+
+        fn has_legal_moves(&self) -> bool {
+            !self.is_variant_end() && self.chess.has_legal_moves()
+        }
+
         fn castling_moves(&self, side: CastlingSide) -> MoveList {
             if self.is_variant_end() {
                 MoveList::new()
@@ -1990,8 +3117,14 @@ pub(crate) mod variant {
         }
 
         fn has_insufficient_material(&self, color: Color) -> bool {
+            self.insufficient_material_rule(color).is_some()
+        }
+
+        fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
             // Any remaining piece can give check.
-            (self.board().by_color(color) & !self.board().kings()).is_empty()
+            (self.board().by_color(color) & !self.board().kings())
+                .is_empty()
+                .then_some(InsufficientMaterialRule::BareKing)
         }
 
         fn is_irreversible(&self, m: Move) -> bool {
@@ -2047,6 +3180,12 @@ pub(crate) mod variant {
             }
         }
 
+        /// Tests for equality like [`PartialEq`], but without
+        /// distinguishing promoted pieces from original pieces.
+        pub fn eq_ignore_promoted(&self, other: &Crazyhouse) -> bool {
+            self.chess == other.chess && self.pockets == other.pockets
+        }
+
         fn our_pocket(&self) -> &ByRole<u8> {
             self.pockets.get(self.turn())
         }
@@ -2120,15 +3259,14 @@ pub(crate) mod variant {
                 errors |= PositionErrorKinds::TOO_MUCH_MATERIAL;
             }
 
-            PositionError {
-                errors,
-                pos: Crazyhouse {
+            PositionError::strict(
+                Crazyhouse {
                     chess,
                     promoted,
                     pockets,
                 },
-            }
-            .strict()
+                errors,
+            )
         }
     }
 
@@ -2216,6 +3354,7 @@ pub(crate) mod variant {
                 &mut self.chess.fullmoves,
                 m,
             );
+            self.chess.update_derived();
         }
 
         fn legal_moves(&self) -> MoveList {
@@ -2244,6 +3383,14 @@ pub(crate) mod variant {
             moves
         }
 
+        fn legal_moves_iter(&self) -> LegalMovesIter<'_, Self> {
+            LegalMovesIter::lazy(self)
+        }
+
+        fn legal_moves_from(&self, from: Square) -> MoveList {
+            self.chess.legal_moves_from(from)
+        }
+
         fn castling_moves(&self, side: CastlingSide) -> MoveList {
             self.chess.castling_moves(side)
         }
@@ -2293,6 +3440,11 @@ pub(crate) mod variant {
                 && self.pockets.black.queen == 0
         }
 
+        fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
+            self.has_insufficient_material(color)
+                .then_some(InsufficientMaterialRule::TooFewPieces)
+        }
+
         fn is_variant_end(&self) -> bool {
             false
         }
@@ -2301,6 +3453,161 @@ pub(crate) mod variant {
         }
     }
 
+    /// One of the two boards in a [`Bughouse`] game.
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+    pub enum BughouseBoard {
+        A,
+        B,
+    }
+
+    impl BughouseBoard {
+        /// `A` and `B`, in this order.
+        pub const ALL: [BughouseBoard; 2] = [BughouseBoard::A, BughouseBoard::B];
+
+        /// Gets the partner board.
+        #[must_use]
+        pub const fn other(self) -> BughouseBoard {
+            match self {
+                BughouseBoard::A => BughouseBoard::B,
+                BughouseBoard::B => BughouseBoard::A,
+            }
+        }
+    }
+
+    /// A Bughouse game, consisting of two Crazyhouse boards.
+    ///
+    /// The team playing White on board [`A`](BughouseBoard::A) plays Black on
+    /// board [`B`](BughouseBoard::B) and vice versa. Pieces captured on one
+    /// board are added to the pocket of the partner on the other board.
+    /// Captured promoted pieces are added as pawns.
+    ///
+    /// Both boards move independently. Clocks are left to the caller.
+    #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+    pub struct Bughouse {
+        boards: [Crazyhouse; 2],
+    }
+
+    impl Bughouse {
+        pub const fn new() -> Bughouse {
+            Bughouse {
+                boards: [Crazyhouse::new(), Crazyhouse::new()],
+            }
+        }
+
+        /// Pairs two Crazyhouse positions.
+        pub const fn from_positions(a: Crazyhouse, b: Crazyhouse) -> Bughouse {
+            Bughouse { boards: [a, b] }
+        }
+
+        /// Gets the position on `board`.
+        pub const fn position(&self, board: BughouseBoard) -> &Crazyhouse {
+            &self.boards[board as usize]
+        }
+
+        /// Splits the game into the positions on board
+        /// [`A`](BughouseBoard::A) and [`B`](BughouseBoard::B).
+        pub fn into_positions(self) -> (Crazyhouse, Crazyhouse) {
+            let [a, b] = self.boards;
+            (a, b)
+        }
+
+        /// Generates legal moves on `board`, including drops from the pocket
+        /// of the side to move there.
+        pub fn legal_moves(&self, board: BughouseBoard) -> MoveList {
+            self.position(board).legal_moves()
+        }
+
+        /// Tests if a move is legal on `board`.
+        pub fn is_legal(&self, board: BughouseBoard, m: Move) -> bool {
+            self.position(board).is_legal(m)
+        }
+
+        /// Plays a move on `board`, transferring any captured piece to the
+        /// partner board.
+        ///
+        /// # Errors
+        ///
+        /// Returns a [`PlayError`] if the move is not legal on `board`.
+        #[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
+        pub fn play(
+            mut self,
+            board: BughouseBoard,
+            m: Move,
+        ) -> Result<Bughouse, PlayError<Bughouse>> {
+            if self.is_legal(board, m) {
+                self.play_unchecked(board, m);
+                Ok(self)
+            } else {
+                Err(PlayError::new(m, self))
+            }
+        }
+
+        /// Plays a move on `board`, transferring any captured piece to the
+        /// partner board.
+        ///
+        /// It is the callers responsibility to ensure the move is legal.
+        ///
+        /// # Panics
+        ///
+        /// Illegal moves can corrupt the state of the position and may
+        /// (or may not) panic or cause panics on future calls.
+        pub fn play_unchecked(&mut self, board: BughouseBoard, m: Move) {
+            let pos = &mut self.boards[board as usize];
+            let opponent = !pos.turn();
+            let capture = match m {
+                Move::Normal {
+                    capture: Some(capture),
+                    to,
+                    ..
+                } => Some(if pos.promoted.contains(to) {
+                    Role::Pawn
+                } else {
+                    capture
+                }),
+                Move::EnPassant { .. } => Some(Role::Pawn),
+                _ => None,
+            };
+
+            pos.play_unchecked(m);
+
+            if let Some(capture) = capture {
+                // Crazyhouse adds the capture to our own pocket. Instead, the
+                // partner receives it, playing the captured color on the
+                // other board.
+                *pos.pockets.get_mut(!opponent).get_mut(capture) -= 1;
+                *self.boards[board.other() as usize]
+                    .pockets
+                    .get_mut(opponent)
+                    .get_mut(capture) += 1;
+            }
+        }
+
+        /// Gets the outcome of the game, which ends as soon as one of the
+        /// boards is decided.
+        ///
+        /// Teams are identified by the color they play on board
+        /// [`A`](BughouseBoard::A).
+        pub fn outcome(&self) -> Outcome {
+            for board in BughouseBoard::ALL {
+                match self.position(board).outcome() {
+                    Outcome::Known(KnownOutcome::Decisive { winner }) => {
+                        return Outcome::Known(KnownOutcome::Decisive {
+                            winner: match board {
+                                BughouseBoard::A => winner,
+                                BughouseBoard::B => !winner,
+                            },
+                        });
+                    }
+                    Outcome::Known(KnownOutcome::Draw) => {
+                        return Outcome::Known(KnownOutcome::Draw);
+                    }
+                    Outcome::Unknown => (),
+                }
+            }
+            Outcome::Unknown
+        }
+    }
+
     /// A Racing Kings position.
     #[derive(Clone, Debug)]
     pub struct RacingKings {
@@ -2321,6 +3628,49 @@ pub(crate) mod variant {
                 fullmoves: NonZeroU32::MIN,
             }
         }
+
+        /// Tests if the king of `color` has reached the eighth rank.
+        pub fn has_finished(&self, color: Color) -> bool {
+            (self.board.kings() & self.board.by_color(color) & Rank::Eighth).any()
+        }
+
+        /// Tests if White has reached the eighth rank, but Black, to move,
+        /// can still reach the eighth rank with this move to draw the game.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use shakmaty::{CastlingMode, Color, Position, fen::Fen, variant::RacingKings};
+        ///
+        /// let pos: RacingKings = "4K3/6k1/8/8/8/8/8/8 b - - 0 1"
+        ///     .parse::<Fen>()?
+        ///     .into_position(CastlingMode::Standard)?;
+        /// assert!(pos.has_finished(Color::White));
+        /// assert!(pos.can_equalize());
+        /// assert!(!pos.is_game_over());
+        /// # Ok::<_, Box<dyn std::error::Error>>(())
+        /// ```
+        pub fn can_equalize(&self) -> bool {
+            if self.turn.is_white() || !self.has_finished(White) || self.has_finished(Black) {
+                return false;
+            }
+
+            let black_king = self.board.king_of(Black).expect("king in racingkings");
+            (attacks::king_attacks(black_king) & Rank::Eighth & !self.board.black())
+                .into_iter()
+                .any(|target| {
+                    self.king_attackers(target, White, self.board.occupied())
+                        .is_empty()
+                })
+        }
+
+        /// Gets the number of king moves `color` needs to reach the eighth
+        /// rank, ignoring all other pieces.
+        pub fn distance_to_finish(&self, color: Color) -> u32 {
+            self.board
+                .king_of(color)
+                .map_or(0, |king| king.rank().distance(Rank::Eighth))
+        }
     }
 
     impl Default for RacingKings {
@@ -2398,7 +3748,7 @@ pub(crate) mod variant {
 
             errors |= validate(&pos, None);
 
-            PositionError { pos, errors }.strict()
+            PositionError::strict(pos, errors)
         }
     }
 
@@ -2503,28 +3853,8 @@ pub(crate) mod variant {
         }
 
         fn is_variant_end(&self) -> bool {
-            let in_goal = self.board().kings() & Rank::Eighth;
-            if in_goal.is_empty() {
-                return false;
-            }
-
-            if self.turn().is_white() || (in_goal & self.board().black()).any() {
-                return true;
-            }
-
-            // White has reached the backrank. Check if black can catch up.
-            let black_king = self.board().king_of(Black).expect("king in racingkings");
-            for target in attacks::king_attacks(black_king) & Rank::Eighth & !self.board().black() {
-                if self
-                    .king_attackers(target, White, self.board().occupied())
-                    .is_empty()
-                {
-                    return false;
-                }
-            }
-
-            true
-        }
+            (self.has_finished(White) || self.has_finished(Black)) && !self.can_equalize()
+        }
 
         fn variant_outcome(&self) -> Outcome {
             if self.is_variant_end() {
@@ -2544,6 +3874,14 @@ pub(crate) mod variant {
     }
 
     /// A Horde position.
+    ///
+    /// The side without a king (the horde) wins by checkmating the opponent,
+    /// and loses once all of its pieces have been captured. Pawns of the
+    /// horde on its first rank may also advance two squares, but such
+    /// advances do not allow en passant captures.
+    ///
+    /// Custom armies are supported, for example using
+    /// [`Horde::with_pawns()`].
     #[derive(Clone, Debug)]
     pub struct Horde {
         board: Board,
@@ -2570,6 +3908,52 @@ pub(crate) mod variant {
         }
     }
 
+    impl Horde {
+        /// Sets up a Horde game with a custom army of white pawns on `pawns`,
+        /// against the standard black pieces. White moves first.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the resulting position is not legal, for example if the
+        /// army overlaps the black pieces ([`PositionErrorKinds::VARIANT`])
+        /// or gives check.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use shakmaty::{Bitboard, Position, Rank, variant::Horde};
+        ///
+        /// let pos = Horde::with_pawns(Bitboard::from(Rank::First) | Rank::Second)?;
+        /// assert_eq!(pos.board().pawns().count(), 16 + 8);
+        /// # Ok::<_, shakmaty::PositionError<_>>(())
+        /// ```
+        #[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
+        pub fn with_pawns(pawns: Bitboard) -> Result<Horde, PositionError<Horde>> {
+            let mut setup = Horde::default().to_setup(EnPassantMode::Legal);
+            for sq in setup.board.white() {
+                setup.board.discard_piece_at(sq);
+            }
+
+            let errors = if (pawns & setup.board.occupied()).any() {
+                PositionErrorKinds::VARIANT
+            } else {
+                PositionErrorKinds::empty()
+            };
+
+            for sq in pawns & !setup.board.occupied() {
+                setup.board.set_piece_at(sq, White.pawn());
+            }
+
+            match Horde::from_setup(setup, CastlingMode::Standard) {
+                Ok(pos) => PositionError::strict(pos, errors),
+                Err(err) => Err(PositionError {
+                    errors: err.errors | errors,
+                    ..err
+                }),
+            }
+        }
+    }
+
     impl Hash for Horde {
         fn hash<H: Hasher>(&self, state: &mut H) {
             self.board.hash(state);
@@ -2638,17 +4022,14 @@ pub(crate) mod variant {
                     if (pos.board().pawns() & us & Bitboard::BACKRANKS).any() {
                         errors |= PositionErrorKinds::PAWNS_ON_BACKRANK;
                     }
-                } else {
-                    if us.count() > 36 {
-                        errors |= PositionErrorKinds::TOO_MUCH_MATERIAL;
-                    }
-                    if (pos.board().pawns() & us & (!color).backrank()).any() {
-                        errors |= PositionErrorKinds::PAWNS_ON_BACKRANK;
-                    }
+                } else if (pos.board().pawns() & us & (!color).backrank()).any() {
+                    // Custom armies may be arbitrarily large, but pawns can
+                    // not stand on the promotion rank.
+                    errors |= PositionErrorKinds::PAWNS_ON_BACKRANK;
                 }
             }
 
-            PositionError { pos, errors }.strict()
+            PositionError::strict(pos, errors)
         }
     }
 
@@ -3007,6 +4388,11 @@ pub(crate) mod variant {
             true
         }
 
+        fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
+            self.has_insufficient_material(color)
+                .then_some(InsufficientMaterialRule::HordeCannotMate)
+        }
+
         fn variant_outcome(&self) -> Outcome {
             if self.board().occupied().is_empty() {
                 Outcome::Known(KnownOutcome::Draw)
@@ -3020,185 +4406,1344 @@ pub(crate) mod variant {
         }
     }
 
-    fn add_king_promotions(moves: &mut MoveList) {
-        let mut king_promotions = MoveList::new();
+    /// A Fog of War position, also known as Dark Chess.
+    ///
+    /// Players only see the squares that their pieces can move to (see
+    /// [`FogOfWar::visible_squares()`]). There is no check: Kings may move
+    /// into or remain in check, and castling out of, through or into check is
+    /// allowed. The game is won by capturing the opponent's king.
+    #[derive(Clone, Debug)]
+    pub struct FogOfWar {
+        board: Board,
+        turn: Color,
+        castles: Castles,
+        ep_square: Option<EnPassant>,
+        halfmoves: u32,
+        fullmoves: NonZeroU32,
+    }
 
-        for m in &moves[..] {
-            if let Move::Normal {
-                role,
-                from,
-                capture,
-                to,
-                promotion: Some(Role::Queen),
-            } = *m
-            {
-                king_promotions.push(Move::Normal {
-                    role,
-                    from,
-                    capture,
-                    to,
-                    promotion: Some(Role::King),
-                });
+    impl FogOfWar {
+        pub const fn new() -> FogOfWar {
+            FogOfWar {
+                board: Board::new(),
+                turn: White,
+                castles: Castles::new(),
+                ep_square: None,
+                halfmoves: 0,
+                fullmoves: NonZeroU32::MIN,
             }
         }
 
-        moves.extend(king_promotions);
-    }
-}
-
-#[allow(clippy::too_many_arguments)] // But typesafe
-fn do_move(
-    board: &mut Board,
-    promoted: &mut Bitboard,
-    turn: &mut Color,
-    castles: &mut Castles,
-    ep_square: &mut Option<EnPassant>,
-    halfmoves: &mut u32,
-    fullmoves: &mut NonZeroU32,
-    m: Move,
-) {
-    let color = *turn;
-    ep_square.take();
-
-    *halfmoves = if m.is_zeroing() {
-        0
-    } else {
-        halfmoves.saturating_add(1)
-    };
-
-    match m {
-        Move::Normal {
-            role,
-            from,
-            capture,
-            to,
-            promotion,
-        } => {
-            if role == Role::Pawn && to - from == 16 && from.rank() == Rank::Second {
-                *ep_square = from.offset(8).map(EnPassant);
-            } else if role == Role::Pawn && from - to == 16 && from.rank() == Rank::Seventh {
-                *ep_square = from.offset(-8).map(EnPassant);
+        /// Squares visible to `color`.
+        ///
+        /// These are the squares occupied by its own pieces, and the squares
+        /// its pieces could move to if it were its turn. Sliding pieces see
+        /// up to and including the first blocker. Pawns see the squares they
+        /// can advance to, and diagonal squares only if they could capture
+        /// there (including en passant).
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use shakmaty::{Bitboard, Color, Square, variant::FogOfWar};
+        ///
+        /// let pos = FogOfWar::new();
+        /// let visible = pos.visible_squares(Color::White);
+        /// assert_eq!(visible, Bitboard::SOUTH);
+        /// assert!(!visible.contains(Square::E5));
+        /// ```
+        pub fn visible_squares(&self, color: Color) -> Bitboard {
+            let ours = self.board.by_color(color);
+            let mut visible = ours;
+
+            for from in ours & !self.board.pawns() {
+                visible |= self.board.attacks_from(from);
             }
 
-            if role == Role::King {
-                castles.discard_color(color);
-            } else if role == Role::Rook {
-                castles.discard_rook(from);
+            let pawns = ours & self.board.pawns();
+            let mut capturable = self.board.by_color(!color);
+            if color == self.turn {
+                if let Some(ep_square) = self.ep_square {
+                    capturable.add(ep_square.square());
+                }
             }
-
-            if capture == Some(Role::Rook) {
-                castles.discard_rook(to);
+            for from in pawns {
+                visible |= attacks::pawn_attacks(color, from) & capturable;
             }
 
-            board.discard_piece_at(from);
-            board.set_piece_at(to, promotion.map_or(role.of(color), |p| p.of(color)));
-
-            let is_promoted = promoted.remove(from) || promotion.is_some();
-            promoted.set(to, is_promoted);
+            let single_moves = pawns.shift(color.fold_wb(8, -8)) & !self.board.occupied();
+            let double_moves = single_moves.shift(color.fold_wb(8, -8))
+                & color.fold_wb(Bitboard::SOUTH, Bitboard::NORTH)
+                & !self.board.occupied();
+            visible | single_moves | double_moves
         }
-        Move::Castle { king, rook } => {
-            let side = CastlingSide::from_queen_side(rook < king);
-            board.discard_piece_at(king);
-            board.discard_piece_at(rook);
-            board.set_new_piece_at(
-                Square::from_coords(side.rook_to_file(), rook.rank()),
-                color.rook(),
-            );
-            board.set_new_piece_at(
-                Square::from_coords(side.king_to_file(), king.rank()),
-                color.king(),
-            );
-            castles.discard_color(color);
+
+        /// The board as seen by `color`, with all pieces on squares that
+        /// are not [visible](FogOfWar::visible_squares()) removed.
+        ///
+        /// This is what a server would send to the player of `color`.
+        pub fn visible_board(&self, color: Color) -> Board {
+            let mut board = self.board.clone();
+            for sq in board.occupied() & !self.visible_squares(color) {
+                board.discard_piece_at(sq);
+            }
+            board
         }
-        Move::EnPassant { from, to } => {
-            board.discard_piece_at(Square::from_coords(to.file(), from.rank())); // captured pawn
-            board.discard_piece_at(from);
-            board.set_new_piece_at(to, color.pawn());
+    }
+
+    impl Default for FogOfWar {
+        fn default() -> FogOfWar {
+            FogOfWar::new()
         }
-        Move::Put { role, to } => {
-            board.set_new_piece_at(to, Piece { color, role });
+    }
+
+    impl Hash for FogOfWar {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.board.hash(state);
+            self.turn.hash(state);
+            self.castles.castling_rights().hash(state);
         }
     }
 
-    if color.is_black() {
-        *fullmoves = NonZeroU32::new(fullmoves.get().saturating_add(1)).unwrap();
+    impl PartialEq for FogOfWar {
+        fn eq(&self, other: &Self) -> bool {
+            self.board == other.board
+                && self.turn == other.turn
+                && self.castles.castling_rights() == other.castles.castling_rights()
+                && self.legal_ep_square() == other.legal_ep_square()
+        }
     }
 
-    *turn = !color;
-}
+    impl Eq for FogOfWar {}
 
-fn validate<P: Position>(pos: &P, ep_square: Option<EnPassant>) -> PositionErrorKinds {
-    let mut errors = PositionErrorKinds::empty();
+    impl FromSetup for FogOfWar {
+        fn from_setup(
+            setup: Setup,
+            mode: CastlingMode,
+        ) -> Result<FogOfWar, PositionError<FogOfWar>> {
+            let mut errors = PositionErrorKinds::empty();
 
-    if pos.board().occupied().is_empty() {
-        errors |= PositionErrorKinds::EMPTY_BOARD;
-    }
+            let castles = match Castles::from_setup(&setup, mode) {
+                Ok(castles) => castles,
+                Err(castles) => {
+                    errors |= PositionErrorKinds::INVALID_CASTLING_RIGHTS;
+                    castles
+                }
+            };
 
-    if (pos.board().pawns() & Bitboard::BACKRANKS).any() {
-        errors |= PositionErrorKinds::PAWNS_ON_BACKRANK;
-    }
+            let ep_square = match EnPassant::from_setup(&setup) {
+                Ok(ep_square) => ep_square,
+                Err(()) => {
+                    errors |= PositionErrorKinds::INVALID_EP_SQUARE;
+                    None
+                }
+            };
 
-    for color in Color::ALL {
-        let kings = pos.board().kings() & pos.board().by_color(color);
-        if kings.is_empty() {
-            errors |= PositionErrorKinds::MISSING_KING;
-        } else if kings.more_than_one() {
-            errors |= PositionErrorKinds::TOO_MANY_KINGS;
-        }
+            let pos = FogOfWar {
+                board: setup.board,
+                turn: setup.turn,
+                castles,
+                ep_square,
+                halfmoves: setup.halfmoves,
+                fullmoves: setup.fullmoves,
+            };
 
-        if !is_standard_material(pos.board(), color) {
-            errors |= PositionErrorKinds::TOO_MUCH_MATERIAL;
+            errors |= validate(&pos, ep_square);
+
+            if (pos.them() & pos.board().kings()).any() {
+                // Our king was just captured. Game over, but valid position.
+                errors.remove(PositionErrorKinds::MISSING_KING);
+            }
+
+            PositionError::strict(pos, errors)
         }
     }
 
-    if let Some(their_king) = pos.board().king_of(!pos.turn()) {
-        if pos
-            .king_attackers(their_king, pos.turn(), pos.board().occupied())
-            .any()
-        {
-            errors |= PositionErrorKinds::OPPOSITE_CHECK;
+    #[cfg(feature = "arbitrary")]
+    impl arbitrary::Arbitrary<'_> for FogOfWar {
+        fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<FogOfWar> {
+            FogOfWar::from_setup(Setup::arbitrary(u)?, CastlingMode::Chess960)
+                .map_err(|_| arbitrary::Error::IncorrectFormat)
         }
-    }
 
-    let checkers = pos.checkers();
-    if let (Some(a), Some(b), Some(our_king)) = (
-        checkers.first(),
-        checkers.last(),
-        pos.board().king_of(pos.turn()),
-    ) {
-        if let Some(ep_square) = ep_square {
-            // The pushed pawn must be the only checker, or it has uncovered
-            // check by a single sliding piece.
-            if a != b
-                || (a != ep_square.pawn_pushed_to()
-                    && pos
-                        .king_attackers(
-                            our_king,
-                            !pos.turn(),
-                            pos.board()
-                                .occupied()
-                                .without(ep_square.pawn_pushed_to())
-                                .with(ep_square.pawn_pushed_from()),
-                        )
-                        .any())
-            {
-                errors |= PositionErrorKinds::IMPOSSIBLE_CHECK;
-            }
-        } else {
-            // There can be at most two checkers, and discovered checkers
-            // cannot be aligned.
-            if a != b && (checkers.count() > 2 || attacks::aligned(a, our_king, b)) {
-                errors |= PositionErrorKinds::IMPOSSIBLE_CHECK;
-            }
+        #[inline]
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            <Setup as arbitrary::Arbitrary>::size_hint(depth)
         }
     }
 
-    errors
-}
+    impl Position for FogOfWar {
+        fn board(&self) -> &Board {
+            &self.board
+        }
 
-const fn is_standard_material(board: &Board, color: Color) -> bool {
-    let our = board.by_color(color);
-    let promoted_pieces = board
+        fn promoted(&self) -> Bitboard {
+            Bitboard::EMPTY
+        }
+
+        fn pockets(&self) -> Option<&ByColor<ByRole<u8>>> {
+            None
+        }
+
+        fn turn(&self) -> Color {
+            self.turn
+        }
+
+        fn castles(&self) -> &Castles {
+            &self.castles
+        }
+
+        fn maybe_ep_square(&self) -> Option<Square> {
+            self.ep_square.map(EnPassant::square)
+        }
+
+        fn remaining_checks(&self) -> Option<&ByColor<RemainingChecks>> {
+            None
+        }
+
+        fn halfmoves(&self) -> u32 {
+            self.halfmoves
+        }
+
+        fn fullmoves(&self) -> NonZeroU32 {
+            self.fullmoves
+        }
+
+        fn play_unchecked(&mut self, m: Move) {
+            do_move(
+                &mut self.board,
+                &mut Bitboard(0),
+                &mut self.turn,
+                &mut self.castles,
+                &mut self.ep_square,
+                &mut self.halfmoves,
+                &mut self.fullmoves,
+                m,
+            );
+        }
+
+        fn legal_moves(&self) -> MoveList {
+            let mut moves = MoveList::new();
+
+            if self.is_variant_end() {
+                return moves;
+            }
+
+            gen_en_passant(self.board(), self.turn(), self.ep_square, &mut moves);
+            let target = !self.us();
+            gen_non_king(self, target, &mut moves);
+            KingTag::gen_moves(self, target, &mut moves);
+            if let Some(king) = self.board().king_of(self.turn()) {
+                for side in CastlingSide::ALL {
+                    gen_castling_moves(self, &self.castles, king, side, &mut moves);
+                }
+            }
+
+            moves
+        }
+
+        fn king_attackers(
+            &self,
+            _square: Square,
+            _attacker: Color,
+            _occupied: Bitboard,
+        ) -> Bitboard {
+            Bitboard(0)
+        }
+
+        fn has_insufficient_material(&self, _color: Color) -> bool {
+            // Even a lone king can capture the opponent's king.
+            false
+        }
+
+        fn is_variant_end(&self) -> bool {
+            self.board().kings().count() < 2
+        }
+
+        fn variant_outcome(&self) -> Outcome {
+            for color in Color::ALL {
+                if (self.board().by_color(color) & self.board().kings()).is_empty() {
+                    return Outcome::Known(KnownOutcome::Decisive { winner: !color });
+                }
+            }
+            Outcome::Unknown
+        }
+    }
+
+    enum FerzTag {}
+
+    impl Stepper for FerzTag {
+        const ROLE: Role = Role::Queen;
+        fn attacks(from: Square) -> Bitboard {
+            attacks::ferz_attacks(from)
+        }
+    }
+
+    enum AlfilTag {}
+
+    impl Stepper for AlfilTag {
+        const ROLE: Role = Role::Bishop;
+        fn attacks(from: Square) -> Bitboard {
+            attacks::alfil_attacks(from)
+        }
+    }
+
+    /// Generates pawn moves without double steps and en passant, promoting
+    /// to queens on `promotion_squares`.
+    fn gen_single_step_pawn_moves<P: Position>(
+        pos: &P,
+        promotion_squares: Bitboard,
+        moves: &mut MoveList,
+    ) {
+        for from in pos.our(Role::Pawn) {
+            let mut targets = attacks::pawn_attacks(pos.turn(), from) & pos.them();
+            if let Some(push) = from.offset(pos.turn().fold_wb(8, -8)) {
+                if !pos.board().occupied().contains(push) {
+                    targets.add(push);
+                }
+            }
+            for to in targets {
+                moves.push(Move::Normal {
+                    role: Role::Pawn,
+                    from,
+                    capture: pos.board().role_at(to),
+                    to,
+                    promotion: promotion_squares.contains(to).then_some(Role::Queen),
+                });
+            }
+        }
+    }
+
+    /// Filters pseudo legal moves that leave the king in check.
+    fn retain_king_safe<P: Position + Clone>(pos: &P, moves: &mut MoveList) {
+        moves.retain(|m| {
+            let mut after = pos.clone();
+            after.play_unchecked(*m);
+            after.board().king_of(pos.turn()).is_some_and(|our_king| {
+                after
+                    .king_attackers(our_king, !pos.turn(), after.board().occupied())
+                    .is_empty()
+            })
+        });
+    }
+
+    /// A Shatranj position.
+    ///
+    /// Queens move like a ferz (one step diagonally), and bishops move like
+    /// an alfil (jumping two steps diagonally). Pawns advance only one step
+    /// and promote to queens. There is no castling and no en passant.
+    ///
+    /// Stalemate wins for the side delivering it. A player left with only
+    /// their king (bare king) loses, unless they can immediately bare the
+    /// opponent's king as well, which is a draw.
+    #[derive(Clone, Debug)]
+    pub struct Shatranj {
+        board: Board,
+        turn: Color,
+        castles: Castles,
+        halfmoves: u32,
+        fullmoves: NonZeroU32,
+    }
+
+    impl Shatranj {
+        pub const fn new() -> Shatranj {
+            Shatranj {
+                board: Board::shatranj(),
+                turn: White,
+                castles: Castles::empty(CastlingMode::Standard),
+                halfmoves: 0,
+                fullmoves: NonZeroU32::MIN,
+            }
+        }
+
+        fn is_bare(&self, color: Color) -> bool {
+            (self.board.by_color(color) & !self.board.kings()).is_empty()
+        }
+
+        fn bare_king_outcome(&self) -> Option<KnownOutcome> {
+            match (self.is_bare(self.turn), self.is_bare(!self.turn)) {
+                (true, true) => Some(KnownOutcome::Draw),
+                (false, true) => Some(KnownOutcome::Decisive { winner: self.turn }),
+                (true, false)
+                    if (self.them() & !self.board.kings()).more_than_one()
+                        || !self.gen_legal_moves().iter().any(|m| m.is_capture()) =>
+                {
+                    Some(KnownOutcome::Decisive { winner: !self.turn })
+                }
+                _ => None,
+            }
+        }
+
+        fn gen_legal_moves(&self) -> MoveList {
+            let mut moves = MoveList::new();
+
+            let target = !self.us();
+            gen_single_step_pawn_moves(self, Bitboard::BACKRANKS, &mut moves);
+            KnightTag::gen_moves(self, target, &mut moves);
+            AlfilTag::gen_moves(self, target, &mut moves);
+            RookTag::gen_moves(self, target, &mut moves);
+            FerzTag::gen_moves(self, target, &mut moves);
+            KingTag::gen_moves(self, target, &mut moves);
+
+            retain_king_safe(self, &mut moves);
+            moves
+        }
+    }
+
+    impl Default for Shatranj {
+        fn default() -> Shatranj {
+            Shatranj::new()
+        }
+    }
+
+    impl Hash for Shatranj {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.board.hash(state);
+            self.turn.hash(state);
+        }
+    }
+
+    impl PartialEq for Shatranj {
+        fn eq(&self, other: &Self) -> bool {
+            self.board == other.board && self.turn == other.turn
+        }
+    }
+
+    impl Eq for Shatranj {}
+
+    impl FromSetup for Shatranj {
+        fn from_setup(
+            setup: Setup,
+            mode: CastlingMode,
+        ) -> Result<Shatranj, PositionError<Shatranj>> {
+            let mut errors = PositionErrorKinds::empty();
+
+            if setup.castling_rights.any() {
+                errors |= PositionErrorKinds::INVALID_CASTLING_RIGHTS;
+            }
+
+            if setup.ep_square.is_some() {
+                errors |= PositionErrorKinds::INVALID_EP_SQUARE;
+            }
+
+            let pos = Shatranj {
+                board: setup.board,
+                turn: setup.turn,
+                castles: Castles::empty(mode),
+                halfmoves: setup.halfmoves,
+                fullmoves: setup.fullmoves,
+            };
+
+            errors |= validate(&pos, None);
+
+            PositionError::strict(pos, errors)
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    impl arbitrary::Arbitrary<'_> for Shatranj {
+        fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Shatranj> {
+            Shatranj::from_setup(Setup::arbitrary(u)?, CastlingMode::Chess960)
+                .map_err(|_| arbitrary::Error::IncorrectFormat)
+        }
+
+        #[inline]
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            <Setup as arbitrary::Arbitrary>::size_hint(depth)
+        }
+    }
+
+    impl Position for Shatranj {
+        fn board(&self) -> &Board {
+            &self.board
+        }
+
+        fn promoted(&self) -> Bitboard {
+            Bitboard::EMPTY
+        }
+
+        fn pockets(&self) -> Option<&ByColor<ByRole<u8>>> {
+            None
+        }
+
+        fn turn(&self) -> Color {
+            self.turn
+        }
+
+        fn castles(&self) -> &Castles {
+            &self.castles
+        }
+
+        fn maybe_ep_square(&self) -> Option<Square> {
+            None
+        }
+
+        fn remaining_checks(&self) -> Option<&ByColor<RemainingChecks>> {
+            None
+        }
+
+        fn halfmoves(&self) -> u32 {
+            self.halfmoves
+        }
+
+        fn fullmoves(&self) -> NonZeroU32 {
+            self.fullmoves
+        }
+
+        fn play_unchecked(&mut self, m: Move) {
+            do_move(
+                &mut self.board,
+                &mut Bitboard(0),
+                &mut self.turn,
+                &mut self.castles,
+                &mut None,
+                &mut self.halfmoves,
+                &mut self.fullmoves,
+                m,
+            );
+        }
+
+        fn legal_moves(&self) -> MoveList {
+            if self.is_variant_end() {
+                MoveList::new()
+            } else {
+                self.gen_legal_moves()
+            }
+        }
+
+        fn king_attackers(&self, square: Square, attacker: Color, occupied: Bitboard) -> Bitboard {
+            self.board.by_color(attacker)
+                & ((attacks::rook_attacks(square, occupied) & self.board.rooks())
+                    | (attacks::ferz_attacks(square) & self.board.queens())
+                    | (attacks::alfil_attacks(square) & self.board.bishops())
+                    | (attacks::knight_attacks(square) & self.board.knights())
+                    | (attacks::king_attacks(square) & self.board.kings())
+                    | (attacks::pawn_attacks(!attacker, square) & self.board.pawns()))
+        }
+
+        fn has_insufficient_material(&self, color: Color) -> bool {
+            // A bare king can at best draw.
+            self.is_bare(color)
+        }
+
+        fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
+            self.has_insufficient_material(color)
+                .then_some(InsufficientMaterialRule::BareKing)
+        }
+
+        fn is_variant_end(&self) -> bool {
+            self.bare_king_outcome().is_some()
+        }
+
+        fn variant_outcome(&self) -> Outcome {
+            if let Some(outcome) = self.bare_king_outcome() {
+                Outcome::Known(outcome)
+            } else if self.is_stalemate() {
+                Outcome::Known(KnownOutcome::Decisive { winner: !self.turn })
+            } else {
+                Outcome::Unknown
+            }
+        }
+    }
+
+    /// Attacks of a khon (silver general) of `color` on `sq`: One step
+    /// diagonally or straight forward.
+    fn khon_attacks(color: Color, sq: Square) -> Bitboard {
+        attacks::ferz_attacks(sq) | Bitboard::from_iter(sq.offset(color.fold_wb(8, -8)))
+    }
+
+    /// A Makruk (Thai chess) position.
+    ///
+    /// Queens move like a met (one step diagonally), and bishops move like
+    /// a khon (one step diagonally or straight forward). Pawns start on the
+    /// third rank, advance only one step and promote to queens on the sixth
+    /// rank. There is no castling and no en passant.
+    ///
+    /// Counting rules apply once there are no pawns left (see
+    /// [`Makruk::counting_limit()`]).
+    #[derive(Clone, Debug)]
+    pub struct Makruk {
+        board: Board,
+        turn: Color,
+        castles: Castles,
+        halfmoves: u32,
+        fullmoves: NonZeroU32,
+    }
+
+    impl Makruk {
+        pub const fn new() -> Makruk {
+            Makruk {
+                board: Board::makruk(),
+                turn: White,
+                castles: Castles::empty(CastlingMode::Standard),
+                halfmoves: 0,
+                fullmoves: NonZeroU32::MIN,
+            }
+        }
+
+        /// The number of moves after which the game is drawn by counting
+        /// rules, if a counting rule applies.
+        ///
+        /// If one side has only its king left, and the other side has no
+        /// pawns, pieces' honour counting applies: The limit is 8 moves with
+        /// two rooks, 16 with one rook, 22 with two khons, 32 with two
+        /// knights, 44 with one khon, and 64 otherwise. Counting starts at
+        /// one more than the number of pieces on the board, so the result is
+        /// the number of remaining moves.
+        ///
+        /// Otherwise, if there are no pawns, board's honour counting applies
+        /// with a limit of 64 moves.
+        ///
+        /// Counts are measured by the halfmove clock, so that they restart
+        /// with each capture.
+        pub fn counting_limit(&self) -> Option<u32> {
+            if self.board.pawns().any() {
+                return None;
+            }
+
+            for color in Color::ALL {
+                if (self.board.by_color(color) & !self.board.kings()).is_empty() {
+                    let strong = self.board.material_side(!color);
+                    let limit: u32 = if strong.rook >= 2 {
+                        8
+                    } else if strong.rook == 1 {
+                        16
+                    } else if strong.bishop >= 2 {
+                        22
+                    } else if strong.knight >= 2 {
+                        32
+                    } else if strong.bishop == 1 {
+                        44
+                    } else {
+                        64
+                    };
+                    return Some(limit.saturating_sub(self.board.occupied().count() as u32 + 1));
+                }
+            }
+
+            Some(64)
+        }
+
+        fn gen_legal_moves(&self) -> MoveList {
+            let mut moves = MoveList::new();
+
+            let target = !self.us();
+            gen_single_step_pawn_moves(
+                self,
+                Bitboard::from(self.turn.fold_wb(Rank::Sixth, Rank::Third)),
+                &mut moves,
+            );
+            KnightTag::gen_moves(self, target, &mut moves);
+            for from in self.our(Role::Bishop) {
+                for to in khon_attacks(self.turn, from) & target {
+                    moves.push(Move::Normal {
+                        role: Role::Bishop,
+                        from,
+                        capture: self.board.role_at(to),
+                        to,
+                        promotion: None,
+                    });
+                }
+            }
+            RookTag::gen_moves(self, target, &mut moves);
+            FerzTag::gen_moves(self, target, &mut moves);
+            KingTag::gen_moves(self, target, &mut moves);
+
+            retain_king_safe(self, &mut moves);
+            moves
+        }
+    }
+
+    impl Default for Makruk {
+        fn default() -> Makruk {
+            Makruk::new()
+        }
+    }
+
+    impl Hash for Makruk {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.board.hash(state);
+            self.turn.hash(state);
+        }
+    }
+
+    impl PartialEq for Makruk {
+        fn eq(&self, other: &Self) -> bool {
+            self.board == other.board && self.turn == other.turn
+        }
+    }
+
+    impl Eq for Makruk {}
+
+    impl FromSetup for Makruk {
+        fn from_setup(setup: Setup, mode: CastlingMode) -> Result<Makruk, PositionError<Makruk>> {
+            let mut errors = PositionErrorKinds::empty();
+
+            if setup.castling_rights.any() {
+                errors |= PositionErrorKinds::INVALID_CASTLING_RIGHTS;
+            }
+
+            if setup.ep_square.is_some() {
+                errors |= PositionErrorKinds::INVALID_EP_SQUARE;
+            }
+
+            // Pawns in the promotion zone would have been promoted.
+            let promotion_zone = ByColor {
+                white: Bitboard::NORTH & !Bitboard::from(Rank::Fifth),
+                black: Bitboard::SOUTH & !Bitboard::from(Rank::Fourth),
+            };
+            if Color::ALL.into_iter().any(|color| {
+                (setup.board.by_piece(color.pawn()) & *promotion_zone.get(color)).any()
+            }) {
+                errors |= PositionErrorKinds::PAWNS_ON_BACKRANK;
+            }
+
+            let pos = Makruk {
+                board: setup.board,
+                turn: setup.turn,
+                castles: Castles::empty(mode),
+                halfmoves: setup.halfmoves,
+                fullmoves: setup.fullmoves,
+            };
+
+            // Khons are not bound to a color complex.
+            errors |= validate(&pos, None) - PositionErrorKinds::TOO_MUCH_MATERIAL;
+
+            PositionError::strict(pos, errors)
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    impl arbitrary::Arbitrary<'_> for Makruk {
+        fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Makruk> {
+            Makruk::from_setup(Setup::arbitrary(u)?, CastlingMode::Chess960)
+                .map_err(|_| arbitrary::Error::IncorrectFormat)
+        }
+
+        #[inline]
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            <Setup as arbitrary::Arbitrary>::size_hint(depth)
+        }
+    }
+
+    impl Position for Makruk {
+        fn board(&self) -> &Board {
+            &self.board
+        }
+
+        fn promoted(&self) -> Bitboard {
+            Bitboard::EMPTY
+        }
+
+        fn pockets(&self) -> Option<&ByColor<ByRole<u8>>> {
+            None
+        }
+
+        fn turn(&self) -> Color {
+            self.turn
+        }
+
+        fn castles(&self) -> &Castles {
+            &self.castles
+        }
+
+        fn maybe_ep_square(&self) -> Option<Square> {
+            None
+        }
+
+        fn remaining_checks(&self) -> Option<&ByColor<RemainingChecks>> {
+            None
+        }
+
+        fn halfmoves(&self) -> u32 {
+            self.halfmoves
+        }
+
+        fn fullmoves(&self) -> NonZeroU32 {
+            self.fullmoves
+        }
+
+        fn play_unchecked(&mut self, m: Move) {
+            do_move(
+                &mut self.board,
+                &mut Bitboard(0),
+                &mut self.turn,
+                &mut self.castles,
+                &mut None,
+                &mut self.halfmoves,
+                &mut self.fullmoves,
+                m,
+            );
+        }
+
+        fn legal_moves(&self) -> MoveList {
+            if self.is_variant_end() {
+                MoveList::new()
+            } else {
+                self.gen_legal_moves()
+            }
+        }
+
+        fn king_attackers(&self, square: Square, attacker: Color, occupied: Bitboard) -> Bitboard {
+            self.board.by_color(attacker)
+                & ((attacks::rook_attacks(square, occupied) & self.board.rooks())
+                    | (attacks::ferz_attacks(square) & self.board.queens())
+                    | (khon_attacks(!attacker, square) & self.board.bishops())
+                    | (attacks::knight_attacks(square) & self.board.knights())
+                    | (attacks::king_attacks(square) & self.board.kings())
+                    | (attacks::pawn_attacks(!attacker, square) & self.board.pawns()))
+        }
+
+        fn has_insufficient_material(&self, color: Color) -> bool {
+            // A bare king can at best draw.
+            (self.board.by_color(color) & !self.board.kings()).is_empty()
+        }
+
+        fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
+            self.has_insufficient_material(color)
+                .then_some(InsufficientMaterialRule::BareKing)
+        }
+
+        fn is_variant_end(&self) -> bool {
+            self.counting_limit()
+                .is_some_and(|limit| self.halfmoves >= 2 * limit)
+        }
+
+        fn variant_outcome(&self) -> Outcome {
+            if self.is_variant_end() {
+                Outcome::Known(KnownOutcome::Draw)
+            } else {
+                Outcome::Unknown
+            }
+        }
+    }
+
+    /// Hooks to customize the rules of standard chess, for use with
+    /// [`Custom`].
+    ///
+    /// All hooks default to the rules of standard chess. The rules can carry
+    /// state, which is updated in [`Rules::play()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, MoveList, Position, variant::{Custom, Rules}};
+    ///
+    /// #[derive(Clone, Debug, Default)]
+    /// struct NoCastling;
+    ///
+    /// impl Rules for NoCastling {
+    ///     fn filter_moves(&self, _pos: &Chess, moves: &mut MoveList) {
+    ///         moves.retain(|m| !m.is_castle());
+    ///     }
+    /// }
+    ///
+    /// let pos = Custom::new(NoCastling);
+    /// assert_eq!(pos.legal_moves().len(), 20);
+    /// ```
+    pub trait Rules: Clone {
+        /// Removes moves from the legal moves of standard chess. Adding moves
+        /// is not supported.
+        fn filter_moves(&self, _pos: &Chess, _moves: &mut MoveList) {}
+
+        /// Additional winning, losing and drawing conditions. Checked before
+        /// the moves are generated, so a known outcome ends the game.
+        fn variant_outcome(&self, _pos: &Chess) -> Outcome {
+            Outcome::Unknown
+        }
+
+        /// Tests if a side has insufficient winning material (see
+        /// [`Position::has_insufficient_material()`]).
+        fn has_insufficient_material(&self, pos: &Chess, color: Color) -> bool {
+            pos.has_insufficient_material(color)
+        }
+
+        /// Additional validation of a position set up by
+        /// [`FromSetup::from_setup()`]. Return
+        /// [`PositionErrorKinds::VARIANT`] to reject it.
+        fn validate(&self, _pos: &Chess) -> PositionErrorKinds {
+            PositionErrorKinds::empty()
+        }
+
+        /// Called with the position before a move is played, to update the
+        /// state of the rules.
+        fn play(&mut self, _pos: &Chess, _m: Move) {}
+    }
+
+    /// A position of standard chess with custom [`Rules`].
+    #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct Custom<R> {
+        chess: Chess,
+        rules: R,
+    }
+
+    impl<R: Rules> Custom<R> {
+        /// The starting position.
+        pub fn new(rules: R) -> Custom<R> {
+            Custom {
+                chess: Chess::new(),
+                rules,
+            }
+        }
+
+        /// Like [`FromSetup::from_setup()`], but with the given `rules`.
+        ///
+        /// # Errors
+        ///
+        /// Errors if the setup is not legal in standard chess, or rejected
+        /// by [`Rules::validate()`].
+        #[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
+        pub fn from_setup_with_rules(
+            setup: Setup,
+            mode: CastlingMode,
+            rules: R,
+        ) -> Result<Custom<R>, PositionError<Custom<R>>> {
+            let (chess, _, _, mut errors) = Chess::from_setup_unchecked(setup, mode);
+            errors |= rules.validate(&chess);
+            PositionError::strict(Custom { chess, rules }, errors)
+        }
+
+        /// The underlying standard chess position.
+        pub const fn chess(&self) -> &Chess {
+            &self.chess
+        }
+
+        /// The custom rules and their state.
+        pub const fn rules(&self) -> &R {
+            &self.rules
+        }
+
+        fn filtered(&self, mut moves: MoveList) -> MoveList {
+            if self.is_variant_end() {
+                moves.clear();
+            } else {
+                self.rules.filter_moves(&self.chess, &mut moves);
+            }
+            moves
+        }
+    }
+
+    impl<R: Rules + Default> FromSetup for Custom<R> {
+        fn from_setup(
+            setup: Setup,
+            mode: CastlingMode,
+        ) -> Result<Custom<R>, PositionError<Custom<R>>> {
+            Custom::from_setup_with_rules(setup, mode, R::default())
+        }
+    }
+
+    impl<R: Rules> Position for Custom<R> {
+        fn board(&self) -> &Board {
+            self.chess.board()
+        }
+
+        fn promoted(&self) -> Bitboard {
+            Bitboard::EMPTY
+        }
+
+        fn castles(&self) -> &Castles {
+            self.chess.castles()
+        }
+
+        fn pockets(&self) -> Option<&ByColor<ByRole<u8>>> {
+            None
+        }
+
+        fn turn(&self) -> Color {
+            self.chess.turn()
+        }
+
+        fn maybe_ep_square(&self) -> Option<Square> {
+            self.chess.maybe_ep_square()
+        }
+
+        fn remaining_checks(&self) -> Option<&ByColor<RemainingChecks>> {
+            None
+        }
+
+        fn halfmoves(&self) -> u32 {
+            self.chess.halfmoves()
+        }
+
+        fn fullmoves(&self) -> NonZeroU32 {
+            self.chess.fullmoves()
+        }
+
+        fn play_unchecked(&mut self, m: Move) {
+            self.rules.play(&self.chess, m);
+            self.chess.play_unchecked(m);
+        }
+
+        fn legal_moves(&self) -> MoveList {
+            self.filtered(self.chess.legal_moves())
+        }
+
+        fn castling_moves(&self, side: CastlingSide) -> MoveList {
+            self.filtered(self.chess.castling_moves(side))
+        }
+
+        fn en_passant_moves(&self) -> MoveList {
+            self.filtered(self.chess.en_passant_moves())
+        }
+
+        fn san_candidates(&self, role: Role, to: Square) -> MoveList {
+            self.filtered(self.chess.san_candidates(role, to))
+        }
+
+        fn has_insufficient_material(&self, color: Color) -> bool {
+            self.rules.has_insufficient_material(&self.chess, color)
+        }
+
+        fn is_variant_end(&self) -> bool {
+            self.variant_outcome().is_known()
+        }
+
+        fn variant_outcome(&self) -> Outcome {
+            self.rules.variant_outcome(&self.chess)
+        }
+    }
+
+    /// An additional win condition, to be layered on top of standard chess
+    /// with [`WithWinCondition`].
+    ///
+    /// Tuples of win conditions are satisfied as soon as any of them is.
+    pub trait WinCondition: Clone {
+        /// Tests if `color` has won the game by this condition.
+        fn is_won(&self, pos: &Chess, color: Color) -> bool;
+    }
+
+    /// Wins by capturing all pieces of the given role.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct CaptureAll(pub Role);
+
+    impl WinCondition for CaptureAll {
+        fn is_won(&self, pos: &Chess, color: Color) -> bool {
+            (pos.board().by_role(self.0) & pos.board().by_color(!color)).is_empty()
+        }
+    }
+
+    /// Wins by moving a piece of the given role to one of the target squares.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct ReachSquare {
+        pub role: Role,
+        pub targets: Bitboard,
+    }
+
+    impl WinCondition for ReachSquare {
+        fn is_won(&self, pos: &Chess, color: Color) -> bool {
+            (pos.board().by_piece(self.role.of(color)) & self.targets).any()
+        }
+    }
+
+    impl<A: WinCondition, B: WinCondition> WinCondition for (A, B) {
+        fn is_won(&self, pos: &Chess, color: Color) -> bool {
+            self.0.is_won(pos, color) || self.1.is_won(pos, color)
+        }
+    }
+
+    /// [`Rules`] of standard chess with an additional [`WinCondition`].
+    ///
+    /// If both sides satisfy the condition, the side that just moved wins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{
+    ///     Bitboard, Position, Role,
+    ///     san::San,
+    ///     variant::{CaptureAll, Custom, ReachSquare, WithWinCondition},
+    /// };
+    ///
+    /// // Win by capturing all knights, or by getting a pawn to the center.
+    /// let pos = Custom::new(WithWinCondition((
+    ///     CaptureAll(Role::Knight),
+    ///     ReachSquare {
+    ///         role: Role::Pawn,
+    ///         targets: Bitboard::CENTER,
+    ///     },
+    /// )));
+    /// assert!(!pos.is_variant_end());
+    ///
+    /// let m = "e4".parse::<San>()?.to_move(&pos)?;
+    /// let pos = pos.play(m)?;
+    /// assert!(pos.is_variant_end());
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct WithWinCondition<W>(pub W);
+
+    impl<W: WinCondition> Rules for WithWinCondition<W> {
+        fn variant_outcome(&self, pos: &Chess) -> Outcome {
+            [!pos.turn(), pos.turn()]
+                .into_iter()
+                .find(|&color| self.0.is_won(pos, color))
+                .map_or(Outcome::Unknown, |winner| {
+                    Outcome::Known(KnownOutcome::Decisive { winner })
+                })
+        }
+    }
+
+    fn add_king_promotions(moves: &mut MoveList) {
+        let mut king_promotions = MoveList::new();
+
+        for m in &moves[..] {
+            if let Move::Normal {
+                role,
+                from,
+                capture,
+                to,
+                promotion: Some(Role::Queen),
+            } = *m
+            {
+                king_promotions.push(Move::Normal {
+                    role,
+                    from,
+                    capture,
+                    to,
+                    promotion: Some(Role::King),
+                });
+            }
+        }
+
+        moves.extend(king_promotions);
+    }
+}
+
+#[allow(clippy::too_many_arguments)] // But typesafe
+fn do_move(
+    board: &mut Board,
+    promoted: &mut Bitboard,
+    turn: &mut Color,
+    castles: &mut Castles,
+    ep_square: &mut Option<EnPassant>,
+    halfmoves: &mut u32,
+    fullmoves: &mut NonZeroU32,
+    m: Move,
+) {
+    let color = *turn;
+
+    match m {
+        Move::Normal {
+            role,
+            from,
+            to,
+            promotion,
+            ..
+        } => {
+            board.discard_piece_at(from);
+            board.set_piece_at(to, promotion.map_or(role.of(color), |p| p.of(color)));
+
+            let is_promoted = promoted.remove(from) || promotion.is_some();
+            promoted.set(to, is_promoted);
+        }
+        Move::Castle { king, rook } => {
+            let side = CastlingSide::from_queen_side(rook < king);
+            board.discard_piece_at(king);
+            board.discard_piece_at(rook);
+            board.set_new_piece_at(
+                Square::from_coords(side.rook_to_file(), rook.rank()),
+                color.rook(),
+            );
+            board.set_new_piece_at(
+                Square::from_coords(side.king_to_file(), king.rank()),
+                color.king(),
+            );
+        }
+        Move::EnPassant { from, to } => {
+            board.discard_piece_at(Square::from_coords(to.file(), from.rank())); // captured pawn
+            board.discard_piece_at(from);
+            board.set_new_piece_at(to, color.pawn());
+        }
+        Move::Put { role, to } => {
+            board.set_new_piece_at(to, Piece { color, role });
+        }
+    }
+
+    do_move_state(turn, castles, ep_square, halfmoves, fullmoves, m);
+}
+
+/// Like [`do_move()`], but updates the board by toggling the squares
+/// described by `m`, without looking at their current contents.
+fn do_move_trusted(
+    board: &mut Board,
+    turn: &mut Color,
+    castles: &mut Castles,
+    ep_square: &mut Option<EnPassant>,
+    halfmoves: &mut u32,
+    fullmoves: &mut NonZeroU32,
+    m: Move,
+) {
+    let color = *turn;
+
+    match m {
+        Move::Normal {
+            role,
+            from,
+            capture,
+            to,
+            promotion,
+        } => {
+            board.toggle_piece_at(from, role.of(color));
+            if let Some(capture) = capture {
+                board.toggle_piece_at(to, capture.of(!color));
+            }
+            board.toggle_piece_at(to, promotion.unwrap_or(role).of(color));
+        }
+        Move::Castle { king, rook } => {
+            // Toggling in this order is correct even if the king or rook
+            // target coincides with one of the origins.
+            let side = CastlingSide::from_queen_side(rook < king);
+            board.toggle_piece_at(king, color.king());
+            board.toggle_piece_at(rook, color.rook());
+            board.toggle_piece_at(
+                Square::from_coords(side.rook_to_file(), rook.rank()),
+                color.rook(),
+            );
+            board.toggle_piece_at(
+                Square::from_coords(side.king_to_file(), king.rank()),
+                color.king(),
+            );
+        }
+        Move::EnPassant { from, to } => {
+            board.toggle_piece_at(Square::from_coords(to.file(), from.rank()), (!color).pawn());
+            board.toggle_piece_at(from, color.pawn());
+            board.toggle_piece_at(to, color.pawn());
+        }
+        Move::Put { role, to } => {
+            board.toggle_piece_at(to, Piece { color, role });
+        }
+    }
+
+    do_move_state(turn, castles, ep_square, halfmoves, fullmoves, m);
+}
+
+/// Updates everything except the board after a move.
+fn do_move_state(
+    turn: &mut Color,
+    castles: &mut Castles,
+    ep_square: &mut Option<EnPassant>,
+    halfmoves: &mut u32,
+    fullmoves: &mut NonZeroU32,
+    m: Move,
+) {
+    let color = *turn;
+    ep_square.take();
+
+    *halfmoves = if m.is_zeroing() {
+        0
+    } else {
+        halfmoves.saturating_add(1)
+    };
+
+    match m {
+        Move::Normal {
+            role,
+            from,
+            capture,
+            to,
+            ..
+        } => {
+            if role == Role::Pawn && to - from == 16 && from.rank() == Rank::Second {
+                *ep_square = from.offset(8).map(EnPassant);
+            } else if role == Role::Pawn && from - to == 16 && from.rank() == Rank::Seventh {
+                *ep_square = from.offset(-8).map(EnPassant);
+            }
+
+            if role == Role::King {
+                castles.discard_color(color);
+            } else if role == Role::Rook {
+                castles.discard_rook(from);
+            }
+
+            if capture == Some(Role::Rook) {
+                castles.discard_rook(to);
+            }
+        }
+        Move::Castle { .. } => {
+            castles.discard_color(color);
+        }
+        Move::EnPassant { .. } | Move::Put { .. } => (),
+    }
+
+    if color.is_black() {
+        *fullmoves = NonZeroU32::new(fullmoves.get().saturating_add(1)).unwrap();
+    }
+
+    *turn = !color;
+}
+
+fn validate<P: Position>(pos: &P, ep_square: Option<EnPassant>) -> PositionErrorKinds {
+    let mut errors = PositionErrorKinds::empty();
+
+    if pos.board().occupied().is_empty() {
+        errors |= PositionErrorKinds::EMPTY_BOARD;
+    }
+
+    if (pos.board().pawns() & Bitboard::BACKRANKS).any() {
+        errors |= PositionErrorKinds::PAWNS_ON_BACKRANK;
+    }
+
+    for color in Color::ALL {
+        let kings = pos.board().kings() & pos.board().by_color(color);
+        if kings.is_empty() {
+            errors |= PositionErrorKinds::MISSING_KING;
+        } else if kings.more_than_one() {
+            errors |= PositionErrorKinds::TOO_MANY_KINGS;
+        }
+
+        if !is_standard_material(pos.board(), color) {
+            errors |= PositionErrorKinds::TOO_MUCH_MATERIAL;
+        }
+    }
+
+    if let Some(their_king) = pos.board().king_of(!pos.turn()) {
+        if pos
+            .king_attackers(their_king, pos.turn(), pos.board().occupied())
+            .any()
+        {
+            errors |= PositionErrorKinds::OPPOSITE_CHECK;
+        }
+    }
+
+    let checkers = pos.checkers();
+    if let (Some(a), Some(b), Some(our_king)) = (
+        checkers.first(),
+        checkers.last(),
+        pos.board().king_of(pos.turn()),
+    ) {
+        if let Some(ep_square) = ep_square {
+            // The pushed pawn must be the only checker, or it has uncovered
+            // check by a single sliding piece.
+            if a != b
+                || (a != ep_square.pawn_pushed_to()
+                    && pos
+                        .king_attackers(
+                            our_king,
+                            !pos.turn(),
+                            pos.board()
+                                .occupied()
+                                .without(ep_square.pawn_pushed_to())
+                                .with(ep_square.pawn_pushed_from()),
+                        )
+                        .any())
+            {
+                errors |= PositionErrorKinds::IMPOSSIBLE_CHECK;
+            }
+        } else {
+            // There can be at most two checkers, and discovered checkers
+            // cannot be aligned.
+            if a != b && (checkers.count() > 2 || attacks::aligned(a, our_king, b)) {
+                errors |= PositionErrorKinds::IMPOSSIBLE_CHECK;
+            }
+        }
+    }
+
+    errors
+}
+
+const fn is_standard_material(board: &Board, color: Color) -> bool {
+    let our = board.by_color(color);
+    let promoted_pieces = board
         .queens()
         .intersect_const(our)
         .count()
@@ -3224,7 +5769,124 @@ const fn is_standard_material(board: &Board, color: Color) -> bool {
     board.pawns().intersect_const(our).count() + promoted_pieces <= 8
 }
 
-fn gen_non_king<P: Position>(pos: &P, target: Bitboard, moves: &mut MoveList) {
+/// Destination of generated moves.
+trait MoveSink {
+    fn push(&mut self, m: Move);
+
+    /// Retains only those moves pushed to this sink that satisfy `f`.
+    fn retain<F: FnMut(&Move) -> bool>(&mut self, f: F);
+}
+
+impl MoveSink for MoveList {
+    #[inline]
+    fn push(&mut self, m: Move) {
+        MoveList::push(self, m);
+    }
+
+    fn retain<F: FnMut(&Move) -> bool>(&mut self, mut f: F) {
+        MoveList::retain(self, |m| f(m));
+    }
+}
+
+/// Appends to a vector, preserving its previous contents.
+#[cfg(feature = "alloc")]
+struct VecSink<'a> {
+    moves: &'a mut alloc::vec::Vec<Move>,
+    start: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl MoveSink for VecSink<'_> {
+    #[inline]
+    fn push(&mut self, m: Move) {
+        self.moves.push(m);
+    }
+
+    fn retain<F: FnMut(&Move) -> bool>(&mut self, mut f: F) {
+        let mut len = self.start;
+        for i in self.start..self.moves.len() {
+            let m = self.moves[i];
+            if f(&m) {
+                self.moves[len] = m;
+                len += 1;
+            }
+        }
+        self.moves.truncate(len);
+    }
+}
+
+/// Fills the beginning of an uninitialized buffer.
+struct UninitSink<'a> {
+    buffer: &'a mut [MaybeUninit<Move>],
+    len: usize,
+}
+
+impl<'a> UninitSink<'a> {
+    fn new(buffer: &'a mut [MaybeUninit<Move>]) -> UninitSink<'a> {
+        UninitSink { buffer, len: 0 }
+    }
+
+    fn into_slice(self) -> &'a mut [Move] {
+        let buffer = &mut self.buffer[..self.len];
+        // Safety: The first len elements have been initialized.
+        unsafe { &mut *(ptr::from_mut(buffer) as *mut [Move]) }
+    }
+}
+
+impl MoveSink for UninitSink<'_> {
+    #[inline]
+    #[track_caller]
+    fn push(&mut self, m: Move) {
+        self.buffer[self.len].write(m);
+        self.len += 1;
+    }
+
+    fn retain<F: FnMut(&Move) -> bool>(&mut self, mut f: F) {
+        let mut len = 0;
+        for i in 0..self.len {
+            // Safety: The first len elements have been initialized.
+            let m = unsafe { self.buffer[i].assume_init() };
+            if f(&m) {
+                self.buffer[len].write(m);
+                len += 1;
+            }
+        }
+        self.len = len;
+    }
+}
+
+/// Partitions moves as they are pushed, with captures and promotions first.
+#[derive(Default)]
+struct CapturesFirstSink {
+    tactical: MoveList,
+    quiet: MoveList,
+}
+
+impl CapturesFirstSink {
+    fn into_list(self) -> MoveList {
+        let mut moves = self.tactical;
+        moves.extend(self.quiet);
+        moves
+    }
+}
+
+impl MoveSink for CapturesFirstSink {
+    #[inline]
+    fn push(&mut self, m: Move) {
+        if m.is_capture() || m.is_promotion() {
+            self.tactical.push(m);
+        } else {
+            self.quiet.push(m);
+        }
+    }
+
+    fn retain<F: FnMut(&Move) -> bool>(&mut self, mut f: F) {
+        self.tactical.retain(|m| f(m));
+        self.quiet.retain(|m| f(m));
+    }
+}
+
+fn gen_non_king<P: Position, M: MoveSink>(pos: &P, target: Bitboard, moves: &mut M) {
     gen_pawn_moves(pos, target, moves);
     KnightTag::gen_moves(pos, target, moves);
     BishopTag::gen_moves(pos, target, moves);
@@ -3232,7 +5894,7 @@ fn gen_non_king<P: Position>(pos: &P, target: Bitboard, moves: &mut MoveList) {
     QueenTag::gen_moves(pos, target, moves);
 }
 
-fn gen_safe_king<P: Position>(pos: &P, king: Square, target: Bitboard, moves: &mut MoveList) {
+fn gen_safe_king<P: Position, M: MoveSink>(pos: &P, king: Square, target: Bitboard, moves: &mut M) {
     for to in attacks::king_attacks(king) & target {
         if pos
             .board()
@@ -3250,7 +5912,22 @@ fn gen_safe_king<P: Position>(pos: &P, king: Square, target: Bitboard, moves: &m
     }
 }
 
-fn evasions<P: Position>(pos: &P, king: Square, checkers: Bitboard, moves: &mut MoveList) {
+fn evasions<P: Position, M: MoveSink>(pos: &P, king: Square, checkers: Bitboard, moves: &mut M) {
+    gen_king_evasions(pos, king, checkers, !pos.us(), moves);
+
+    if let Some(checker) = checkers.single_square() {
+        let target = attacks::between(king, checker).with(checker);
+        gen_non_king(pos, target, moves);
+    }
+}
+
+fn gen_king_evasions<P: Position, M: MoveSink>(
+    pos: &P,
+    king: Square,
+    checkers: Bitboard,
+    target: Bitboard,
+    moves: &mut M,
+) {
     let sliders = checkers & pos.board().sliders();
 
     let mut attacked = Bitboard(0);
@@ -3258,20 +5935,15 @@ fn evasions<P: Position>(pos: &P, king: Square, checkers: Bitboard, moves: &mut
         attacked |= attacks::ray(checker, king) ^ checker;
     }
 
-    gen_safe_king(pos, king, !pos.us() & !attacked, moves);
-
-    if let Some(checker) = checkers.single_square() {
-        let target = attacks::between(king, checker).with(checker);
-        gen_non_king(pos, target, moves);
-    }
+    gen_safe_king(pos, king, target & !attacked, moves);
 }
 
-fn gen_castling_moves<P: Position>(
+fn gen_castling_moves<P: Position, M: MoveSink>(
     pos: &P,
     castles: &Castles,
     king: Square,
     side: CastlingSide,
-    moves: &mut MoveList,
+    moves: &mut M,
 ) {
     if let Some(rook) = castles.rook(pos.turn(), side) {
         let path = castles.path(pos.turn(), side);
@@ -3310,7 +5982,7 @@ trait Stepper {
 
     fn attacks(from: Square) -> Bitboard;
 
-    fn gen_moves<P: Position>(pos: &P, target: Bitboard, moves: &mut MoveList) {
+    fn gen_moves<P: Position, M: MoveSink>(pos: &P, target: Bitboard, moves: &mut M) {
         for from in pos.our(Self::ROLE) {
             for to in Self::attacks(from) & target {
                 moves.push(Move::Normal {
@@ -3329,7 +6001,7 @@ trait Slider {
     const ROLE: Role;
     fn attacks(from: Square, occupied: Bitboard) -> Bitboard;
 
-    fn gen_moves<P: Position>(pos: &P, target: Bitboard, moves: &mut MoveList) {
+    fn gen_moves<P: Position, M: MoveSink>(pos: &P, target: Bitboard, moves: &mut M) {
         for from in pos.our(Self::ROLE) {
             for to in Self::attacks(from, pos.board().occupied()) & target {
                 moves.push(Move::Normal {
@@ -3377,18 +6049,18 @@ impl Slider for QueenTag {
     }
 }
 
-fn gen_pawn_moves<P: Position>(pos: &P, target: Bitboard, moves: &mut MoveList) {
+fn gen_pawn_moves<P: Position, M: MoveSink>(pos: &P, target: Bitboard, moves: &mut M) {
     // Safety of unchecked offset calculations: If we shift a set of squares
     // by an offset, then the negated offset is valid for all resulting
     // squares.
 
     // Generate captures.
     #[inline(always)]
-    fn gen_pawn_captures<P: Position>(
+    fn gen_pawn_captures<P: Position, M: MoveSink>(
         pos: &P,
         dir: Direction,
         target: Bitboard,
-        moves: &mut MoveList,
+        moves: &mut M,
     ) {
         let captures = dir.translate(pos.our(Role::Pawn)) & pos.them() & target;
 
@@ -3465,7 +6137,7 @@ fn gen_pawn_moves<P: Position>(pos: &P, target: Bitboard, moves: &mut MoveList)
     }
 }
 
-fn push_promotions(moves: &mut MoveList, from: Square, to: Square, capture: Option<Role>) {
+fn push_promotions<M: MoveSink>(moves: &mut M, from: Square, to: Square, capture: Option<Role>) {
     for promotion in [Role::Queen, Role::Rook, Role::Bishop, Role::Knight] {
         moves.push(Move::Normal {
             role: Role::Pawn,
@@ -3477,11 +6149,11 @@ fn push_promotions(moves: &mut MoveList, from: Square, to: Square, capture: Opti
     }
 }
 
-fn gen_en_passant(
+fn gen_en_passant<M: MoveSink>(
     board: &Board,
     turn: Color,
     ep_square: Option<EnPassant>,
-    moves: &mut MoveList,
+    moves: &mut M,
 ) -> bool {
     let mut found = false;
 
@@ -3536,6 +6208,27 @@ fn is_safe<P: Position>(pos: &P, king: Square, m: Move, blockers: Bitboard) -> b
     }
 }
 
+fn infer_moves<P: Position + Clone>(
+    pos: &P,
+    board: &Board,
+    plies: usize,
+    moves: &mut MoveList,
+) -> bool {
+    if plies == 0 {
+        return pos.board() == board;
+    }
+    for m in pos.legal_moves() {
+        let mut after = pos.clone();
+        after.play_unchecked(m);
+        moves.push(m);
+        if infer_moves(&after, board, plies - 1, moves) {
+            return true;
+        }
+        moves.pop();
+    }
+    false
+}
+
 fn filter_san_candidates(role: Role, to: Square, moves: &mut MoveList) {
     moves.retain(|m| match *m {
         Move::Normal { role: r, to: t, .. } | Move::Put { role: r, to: t } => to == t && role == r,
@@ -3553,10 +6246,23 @@ mod tests {
     struct _AssertObjectSafe(alloc::boxed::Box<dyn Position>);
 
     fn setup_fen<T: Position + FromSetup>(fen: &str) -> T {
-        fen.parse::<Fen>()
-            .expect("valid fen")
-            .into_position::<T>(CastlingMode::Chess960)
-            .expect("legal position")
+        T::from_fen(fen, CastlingMode::Chess960).expect("legal position")
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_from_fen() {
+        use crate::variant::{Antichess, Horde};
+
+        assert!(Antichess::from_fen("8/8/8/8/8/8/8/6N1 b - - 0 1", CastlingMode::Standard).is_ok());
+        assert!(matches!(
+            Chess::from_fen("8/8/8/8/8/8/8/6N1 b - - 0 1", CastlingMode::Standard),
+            Err(ParsePositionError::Position(_))
+        ));
+        assert!(matches!(
+            Horde::from_fen("rnbqkbnr/pppppppp/9/8 w - - 0 1", CastlingMode::Standard),
+            Err(ParsePositionError::Fen(_))
+        ));
     }
 
     #[test]
@@ -3585,6 +6291,327 @@ mod tests {
         assert_eq!(moves.len(), 1);
     }
 
+    #[test]
+    fn test_legal_destinations() {
+        let pos: Chess = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Standard)
+            .expect("legal position");
+        let dests = pos.legal_destinations();
+        for m in pos.legal_moves() {
+            let from = m.from().expect("no drops");
+            assert!(dests.get(from).contains(m.to()));
+            assert!(pos.legal_moves_from(from).contains(&m));
+        }
+        assert_eq!(
+            dests.get(Square::E1),
+            Bitboard::from(Square::D1)
+                | Square::F1
+                | Square::C1
+                | Square::G1
+                | Square::A1
+                | Square::H1
+        );
+        assert_eq!(dests.iter().count(), dests.origins().count());
+
+        let pos: Chess =
+            setup_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        assert_eq!(
+            pos.legal_destinations().get(Square::E1),
+            Bitboard::from(Square::D1) | Square::F1 | Square::A1 | Square::H1
+        );
+    }
+
+    #[test]
+    fn test_premove_destinations() {
+        let pos: Chess =
+            setup_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+        assert_eq!(
+            pos.premove_destinations(Square::A7),
+            Bitboard::from(Square::A6) | Square::A5 | Square::B6
+        );
+        assert_eq!(
+            pos.premove_destinations(Square::B4),
+            Bitboard::from(Square::B3) | Square::A3 | Square::C3
+        );
+        assert_eq!(
+            pos.premove_destinations(Square::E8),
+            Bitboard::from(Square::D8)
+                | Square::F8
+                | Square::D7
+                | Square::E7
+                | Square::F7
+                | Square::A8
+                | Square::H8
+        );
+        assert_eq!(pos.premove_destinations(Square::A8).count(), 14);
+        assert!(pos.is_premove(Square::E7, Square::A3));
+        assert!(!pos.is_premove(Square::E7, Square::E7));
+        assert!(pos.premove_destinations(Square::E5).contains(Square::D7));
+        assert!(pos.premove_destinations(Square::D4).is_empty());
+    }
+
+    #[test]
+    fn test_infer_moves() {
+        let pos: Chess =
+            setup_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+
+        let m = Move::Castle {
+            king: Square::E1,
+            rook: Square::H1,
+        };
+        let after = pos.clone().play(m).expect("legal");
+        assert_eq!(pos.infer_move(after.board()), Some(m));
+        assert_eq!(pos.infer_move(pos.board()), None);
+        assert_eq!(pos.infer_moves(pos.board(), 2), Some(MoveList::new()));
+
+        let reply = Move::Normal {
+            role: Role::Pawn,
+            from: Square::H3,
+            capture: Some(Role::Pawn),
+            to: Square::G2,
+            promotion: None,
+        };
+        let after = after.play(reply).expect("legal");
+        let moves = pos.infer_moves(after.board(), 3).expect("two plies");
+        assert_eq!(moves.as_slice(), [m, reply]);
+    }
+
+    #[test]
+    fn test_legal_moves_iter() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "8/8/8/8/k2Pp2Q/8/8/3K4 b - d3 0 1",
+            "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1",
+            "4k3/8/4r3/8/8/8/3P4/R3K2R w KQ - 0 1",
+        ] {
+            let pos: Chess = setup_fen(fen);
+            for m in pos.legal_moves() {
+                let after = pos.clone().play(m).expect("legal");
+                for pos in [&pos, &after] {
+                    let mut expected = pos.legal_moves();
+                    let mut actual: MoveList = pos.legal_moves_iter().collect();
+                    expected.sort_unstable_by_key(|m| (m.from(), m.to(), m.promotion()));
+                    actual.sort_unstable_by_key(|m| (m.from(), m.to(), m.promotion()));
+                    assert_eq!(actual, expected, "{fen} {m:?}");
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_legal_moves_iter_variants() {
+        use crate::variant::{Variant, VariantPosition};
+
+        fn sorted(mut moves: MoveList) -> MoveList {
+            moves.sort_unstable_by_key(|m| (m.from(), m.to(), m.role(), m.promotion()));
+            moves
+        }
+
+        for variant in Variant::ALL {
+            let mut pos = VariantPosition::new(variant);
+            for ply in 0..80 {
+                let legals = pos.legal_moves();
+                assert_eq!(
+                    sorted(pos.legal_moves_iter().collect()),
+                    sorted(legals.clone()),
+                    "{variant:?} ply {ply}"
+                );
+                if legals.is_empty() {
+                    break;
+                }
+                pos.play_unchecked(legals[ply * 7 % legals.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_legal_moves_captures_first() {
+        let pos: Chess = setup_fen("4k3/1P6/8/3p4/4P3/8/8/4K3 w - - 0 1");
+        let moves = pos.legal_moves_captures_first();
+        let tactical = moves
+            .iter()
+            .take_while(|m| m.is_capture() || m.is_promotion())
+            .count();
+        assert_eq!(tactical, 5);
+        assert!(
+            moves[tactical..]
+                .iter()
+                .all(|m| !m.is_capture() && !m.is_promotion())
+        );
+
+        let is_tactical = |m: &&Move| m.is_capture() || m.is_promotion();
+        assert!(
+            moves[..tactical]
+                .iter()
+                .eq(pos.legal_moves().iter().filter(is_tactical))
+        );
+        assert!(
+            moves[tactical..]
+                .iter()
+                .eq(pos.legal_moves().iter().filter(|m| !is_tactical(m)))
+        );
+
+        let mut expected = pos.legal_moves();
+        let mut actual = moves;
+        expected.sort_unstable_by_key(|m| (m.from(), m.to(), m.promotion()));
+        actual.sort_unstable_by_key(|m| (m.from(), m.to(), m.promotion()));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_legal_moves_into() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            // Pinned pieces and en passant, so that moves are filtered.
+            "8/8/8/K2pP2q/8/8/8/4k3 w - d6 0 2",
+            "4k3/8/8/b7/8/2N5/5R2/4K2r w - - 0 1",
+        ] {
+            let pos: Chess = setup_fen(fen);
+            let expected = pos.legal_moves();
+
+            #[cfg(feature = "alloc")]
+            {
+                let mut moves = alloc::vec![Move::Put {
+                    role: Role::Pawn,
+                    to: Square::A1,
+                }];
+                pos.legal_moves_into(&mut moves);
+                assert_eq!(
+                    moves[0],
+                    Move::Put {
+                        role: Role::Pawn,
+                        to: Square::A1,
+                    }
+                );
+                assert_eq!(moves[1..], expected[..], "{fen}");
+            }
+
+            let mut buffer = [MaybeUninit::uninit(); 256];
+            assert_eq!(
+                pos.legal_moves_into_uninit(&mut buffer)[..],
+                expected[..],
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_legal_moves_into_uninit_too_small() {
+        let mut buffer = [MaybeUninit::uninit(); 19];
+        Chess::default().legal_moves_into_uninit(&mut buffer);
+    }
+
+    #[test]
+    fn test_play_unchecked_unvalidated() {
+        fn check(pos: &Chess, depth: u32) {
+            for m in pos.legal_moves() {
+                let mut expected = pos.clone();
+                expected.play_unchecked(m);
+                let mut pos = pos.clone();
+                pos.play_unchecked_unvalidated(m);
+                assert_eq!(pos, expected, "{m:?}");
+                assert_eq!(pos.board(), expected.board());
+                assert_eq!(pos.checkers(), expected.checkers());
+                assert_eq!(pos.halfmoves(), expected.halfmoves());
+                assert_eq!(pos.fullmoves(), expected.fullmoves());
+                if depth > 1 {
+                    check(&pos, depth - 1);
+                }
+            }
+        }
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r1k1r2q/p1ppp1pp/8/8/8/8/P1PPP1PP/R1K1R2Q w KQkq - 0 1",
+            "2r1kr2/8/8/8/8/8/8/1R2K1R1 w GBfc - 0 1",
+            "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+        ] {
+            check(&setup_fen(fen), 2);
+        }
+    }
+
+    #[test]
+    fn test_san_candidates_match_legal_moves() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/8/8/8/k2Pp2Q/8/8/3K4 b - d3 0 1",
+            "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1",
+            "4k3/8/8/8/8/8/3r1r2/1R2K3 w - - 0 1",
+            "4k3/4r3/8/8/8/5n2/8/4K3 w - - 0 1",
+            "4k3/4p3/8/b7/8/2N5/5R2/4K2r w - - 0 1",
+            "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+        ] {
+            let pos: Chess = setup_fen(fen);
+            let legals = pos.legal_moves();
+            for role in Role::ALL {
+                for to in Square::ALL {
+                    let mut expected = legals.clone();
+                    filter_san_candidates(role, to, &mut expected);
+                    let candidates = pos.san_candidates(role, to);
+                    assert_eq!(candidates.len(), expected.len(), "{fen} {role:?} {to}");
+                    assert!(
+                        candidates.iter().all(|m| expected.contains(m)),
+                        "{fen} {role:?} {to}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_has_legal_moves() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/8/8/8/k2Pp2Q/8/8/3K4 b - d3 0 1",
+            "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+            "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",
+            "4k3/8/8/8/8/8/3r1r2/1R2K3 w - - 0 1",
+            "4k3/4r3/8/8/8/5n2/8/4K3 w - - 0 1",
+            "8/8/8/8/8/5k2/5p2/5K2 w - - 0 1",
+            "5k2/8/8/8/8/8/3ppp2/5KR1 w G - 0 1",
+        ] {
+            let pos: Chess = setup_fen(fen);
+            for m in pos.legal_moves() {
+                let after = pos.clone().play(m).expect("legal");
+                assert_eq!(after.has_legal_moves(), !after.legal_moves().is_empty());
+            }
+            assert_eq!(
+                pos.has_legal_moves(),
+                !pos.legal_moves().is_empty(),
+                "{fen}"
+            );
+        }
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_size() {
+        // Cheap to copy in copy-make search and to store in bulk.
+        assert_eq!(core::mem::size_of::<Castles>(), 16);
+        assert_eq!(core::mem::size_of::<Chess>(), 120);
+        assert_eq!(core::mem::size_of::<Setup>(), 128);
+
+        // Errors do not inflate results.
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(
+                core::mem::size_of::<Result<Chess, PlayError<Chess>>>(),
+                core::mem::size_of::<Chess>()
+            );
+            assert_eq!(
+                core::mem::size_of::<Result<Chess, PositionError<Chess>>>(),
+                core::mem::size_of::<Chess>()
+            );
+        }
+    }
+
     #[test]
     fn test_promotion() {
         let pos: Chess = setup_fen("3r3K/6PP/8/8/8/2k5/8/8 w - - 0 1");
@@ -3720,63 +6747,358 @@ mod tests {
         assert_eq!(hash(&pos), hash(&pos_with_irrelevant_ep));
     }
 
-    #[cfg(feature = "variant")]
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_variant_insufficient_material() {
+        use super::variant::*;
+
+        let false_negative = false;
+
+        assert_insufficient_material::<Atomic>("8/3k4/8/8/2N5/8/3K4/8 b - - 0 1", true, true);
+        assert_insufficient_material::<Atomic>("8/4rk2/8/8/8/8/3K4/8 w - - 0 1", true, true);
+        assert_insufficient_material::<Atomic>("8/4qk2/8/8/8/8/3K4/8 w - - 0 1", true, false);
+        assert_insufficient_material::<Atomic>("8/1k6/8/2n5/8/3NK3/8/8 b - - 0 1", false, false);
+        assert_insufficient_material::<Atomic>("8/4bk2/8/8/8/8/3KB3/8 w - - 0 1", true, true);
+        assert_insufficient_material::<Atomic>("4b3/5k2/8/8/8/8/3KB3/8 w - - 0 1", false, false);
+        assert_insufficient_material::<Atomic>("3Q4/5kKB/8/8/8/8/8/8 b - - 0 1", false, true);
+        assert_insufficient_material::<Atomic>("8/5k2/8/8/8/8/5K2/4bb2 w - - 0 1", true, false);
+        assert_insufficient_material::<Atomic>("8/5k2/8/8/8/8/5K2/4nb2 w - - 0 1", true, false);
+
+        assert_insufficient_material::<Antichess>("8/4bk2/8/8/8/8/3KB3/8 w - - 0 1", false, false);
+        assert_insufficient_material::<Antichess>("4b3/5k2/8/8/8/8/3KB3/8 w - - 0 1", false, false);
+        assert_insufficient_material::<Antichess>("8/8/8/6b1/8/3B4/4B3/5B2 w - - 0 1", true, true);
+        assert_insufficient_material::<Antichess>("8/8/5b2/8/8/3B4/3B4/8 w - - 0 1", true, false);
+        assert_insufficient_material::<Antichess>(
+            "8/5p2/5P2/8/3B4/1bB5/8/8 b - - 0 1",
+            false_negative,
+            false_negative,
+        );
+        assert_insufficient_material::<Antichess>(
+            "8/8/6b1/8/3P4/8/5B2/8 w - - 0 1",
+            false_negative,
+            false,
+        );
+
+        assert_insufficient_material::<KingOfTheHill>(
+            "8/5k2/8/8/8/8/3K4/8 w - - 0 1",
+            false,
+            false,
+        );
+
+        assert_insufficient_material::<RacingKings>("8/5k2/8/8/8/8/3K4/8 w - - 0 1", false, false);
+
+        assert_insufficient_material::<ThreeCheck>("8/5k2/8/8/8/8/3K4/8 w - - 0 1", true, true);
+        assert_insufficient_material::<ThreeCheck>("8/5k2/8/8/8/8/3K2N1/8 w - - 0 1", false, true);
+
+        assert_insufficient_material::<Crazyhouse>("8/5k2/8/8/8/8/3K2N1/8 w - - 0 1", true, true);
+        assert_insufficient_material::<Crazyhouse>(
+            "8/5k2/8/8/8/5B2/3KB3/8 w - - 0 1",
+            false,
+            false,
+        );
+        assert_insufficient_material::<Crazyhouse>(
+            "8/8/8/8/3k4/3N~4/3K4/8 w - - 0 1",
+            false,
+            false,
+        );
+
+        assert_insufficient_material::<Horde>("8/5k2/8/8/8/4NN2/8/8 w - - 0 1", true, false);
+        assert_insufficient_material::<Horde>("8/8/8/7k/7P/7P/8/8 b - - 0 58", false, false);
+
+        assert_insufficient_material::<Atomic>("8/5k2/8/8/8/3B4/3KB3/8 w - - 0 1", true, true);
+        assert_insufficient_material::<Atomic>("8/5k2/8/8/8/4B3/3KB3/8 w - - 0 1", false, true);
+    }
+
+    #[test]
+    fn test_odds() {
+        for odds in Odds::ALL {
+            let pos = Chess::with_odds(odds);
+            assert_eq!(pos.board().occupied().count(), 31);
+            assert_eq!(pos.board().by_color(odds.giver()).count(), 15);
+            assert_eq!(pos.turn(), White);
+        }
+
+        let pos = Chess::with_odds(Odds::Rook);
+        assert_eq!(
+            pos.castles().castling_rights(),
+            Bitboard::CORNERS.without(Square::A1)
+        );
+        assert_eq!(
+            Chess::with_odds(Odds::Knight).castles().castling_rights(),
+            Bitboard::CORNERS
+        );
+    }
+
+    #[test]
+    fn test_insufficient_material_rule() {
+        let pos: Chess = setup_fen("8/5k2/8/8/8/4B3/3K1B2/8 w - - 0 1");
+        assert_eq!(
+            pos.insufficient_material_rule(White),
+            Some(InsufficientMaterialRule::SameColoredBishops)
+        );
+        assert_eq!(
+            pos.insufficient_material_rule(Black),
+            Some(InsufficientMaterialRule::BareKing)
+        );
+
+        let pos: Chess = setup_fen("8/4rk2/8/8/8/8/3K4/8 w - - 0 1");
+        assert_eq!(pos.insufficient_material_rule(Black), None);
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_variant_insufficient_material_rule() {
+        use super::variant::*;
+
+        let pos: Atomic = setup_fen("8/1k6/8/8/8/8/2NNK3/8 w - - 0 1");
+        assert_eq!(
+            pos.insufficient_material_rule(White),
+            Some(InsufficientMaterialRule::TwoKnights)
+        );
+
+        let pos: Atomic = setup_fen("4b3/5k2/8/8/8/8/3KB3/8 w - - 0 1");
+        assert_eq!(pos.insufficient_material_rule(White), None);
+        let pos: Atomic = setup_fen("8/4bk2/8/8/8/8/3KB3/8 w - - 0 1");
+        assert_eq!(
+            pos.insufficient_material_rule(White),
+            Some(InsufficientMaterialRule::OppositeColoredBishops)
+        );
+
+        let pos: Antichess = setup_fen("8/8/5b2/8/8/3B4/3B4/8 w - - 0 1");
+        assert_eq!(
+            pos.insufficient_material_rule(White),
+            Some(InsufficientMaterialRule::OppositeColoredBishops)
+        );
+
+        let pos: KingOfTheHill = setup_fen("8/5k2/8/8/8/8/3K4/8 w - - 0 1");
+        assert_eq!(pos.insufficient_material_rule(White), None);
+
+        let pos: Horde = setup_fen("8/5k2/8/8/8/4NN2/8/8 w - - 0 1");
+        assert_eq!(
+            pos.insufficient_material_rule(White),
+            Some(InsufficientMaterialRule::HordeCannotMate)
+        );
+        assert_eq!(pos.insufficient_material_rule(Black), None);
+
+        let pos = crate::variant::VariantPosition::Horde(pos);
+        assert_eq!(
+            pos.insufficient_material_rule(White),
+            Some(InsufficientMaterialRule::HordeCannotMate)
+        );
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_custom_rules() {
+        use super::variant::{Custom, Rules};
+
+        // No castling, and the first check wins.
+        #[derive(Clone, Debug, Default)]
+        struct FirstCheck {
+            plies: u32,
+        }
+
+        impl Rules for FirstCheck {
+            fn filter_moves(&self, _pos: &Chess, moves: &mut MoveList) {
+                moves.retain(|m| !m.is_castle());
+            }
+
+            fn variant_outcome(&self, pos: &Chess) -> Outcome {
+                if pos.is_check() {
+                    Outcome::Known(KnownOutcome::Decisive {
+                        winner: !pos.turn(),
+                    })
+                } else {
+                    Outcome::Unknown
+                }
+            }
+
+            fn play(&mut self, _pos: &Chess, _m: Move) {
+                self.plies += 1;
+            }
+        }
+
+        let pos: Custom<FirstCheck> =
+            setup_fen("r3k2r/pppq1ppp/8/4p3/4P3/8/PPPQ1PPP/R3K2R w KQkq - 0 1");
+        assert!(pos.legal_moves().iter().all(|m| !m.is_castle()));
+        assert!(pos.castling_moves(CastlingSide::KingSide).is_empty());
+
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::Queen,
+                from: Square::D2,
+                capture: Some(Role::Queen),
+                to: Square::D7,
+                promotion: None,
+            })
+            .expect("legal move");
+        assert_eq!(pos.rules().plies, 1);
+        assert!(pos.is_variant_end());
+        assert!(pos.legal_moves().is_empty());
+        assert_eq!(
+            pos.outcome(),
+            Outcome::Known(KnownOutcome::Decisive { winner: White })
+        );
+    }
+
+    #[cfg(feature = "bincode")]
     #[test]
-    fn test_variant_insufficient_material() {
-        use super::variant::*;
+    fn test_bincode() {
+        let config = bincode::config::standard();
+        let mut buffer = [0; 128];
 
-        let false_negative = false;
+        for (fen, mode) in [
+            (
+                "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+                CastlingMode::Standard,
+            ),
+            (
+                "1r2k2r/8/8/8/8/8/8/R3K1R1 b GAhb - 12 40",
+                CastlingMode::Chess960,
+            ),
+        ] {
+            let pos: Chess = fen
+                .parse::<Fen>()
+                .expect("valid fen")
+                .into_position(mode)
+                .expect("legal position");
+            let n = bincode::encode_into_slice(&pos, &mut buffer, config).expect("encode");
+            let (decoded, m): (Chess, usize) =
+                bincode::decode_from_slice(&buffer[..n], config).expect("decode");
+            assert_eq!(n, m);
+            assert_eq!(decoded, pos);
+            assert_eq!(decoded.castles().mode(), mode);
+            assert_eq!(decoded.halfmoves(), pos.halfmoves());
+            assert_eq!(decoded.fullmoves(), pos.fullmoves());
+        }
+
+        // Setups need not be legal.
+        let setup = "8/8/8/8/8/8/8/8[Kk] b KQ e3 0 1 +1+2"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_setup();
+        let n = bincode::encode_into_slice(&setup, &mut buffer, config).expect("encode");
+        let (decoded, m): (Setup, usize) =
+            bincode::decode_from_slice(&buffer[..n], config).expect("decode");
+        assert_eq!(n, m);
+        assert_eq!(decoded, setup);
+    }
 
-        assert_insufficient_material::<Atomic>("8/3k4/8/8/2N5/8/3K4/8 b - - 0 1", true, true);
-        assert_insufficient_material::<Atomic>("8/4rk2/8/8/8/8/3K4/8 w - - 0 1", true, true);
-        assert_insufficient_material::<Atomic>("8/4qk2/8/8/8/8/3K4/8 w - - 0 1", true, false);
-        assert_insufficient_material::<Atomic>("8/1k6/8/2n5/8/3NK3/8/8 b - - 0 1", false, false);
-        assert_insufficient_material::<Atomic>("8/4bk2/8/8/8/8/3KB3/8 w - - 0 1", true, true);
-        assert_insufficient_material::<Atomic>("4b3/5k2/8/8/8/8/3KB3/8 w - - 0 1", false, false);
-        assert_insufficient_material::<Atomic>("3Q4/5kKB/8/8/8/8/8/8 b - - 0 1", false, true);
-        assert_insufficient_material::<Atomic>("8/5k2/8/8/8/8/5K2/4bb2 w - - 0 1", true, false);
-        assert_insufficient_material::<Atomic>("8/5k2/8/8/8/8/5K2/4nb2 w - - 0 1", true, false);
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_win_condition() {
+        use super::variant::{CaptureAll, Custom, ReachSquare, WithWinCondition};
 
-        assert_insufficient_material::<Antichess>("8/4bk2/8/8/8/8/3KB3/8 w - - 0 1", false, false);
-        assert_insufficient_material::<Antichess>("4b3/5k2/8/8/8/8/3KB3/8 w - - 0 1", false, false);
-        assert_insufficient_material::<Antichess>("8/8/8/6b1/8/3B4/4B3/5B2 w - - 0 1", true, true);
-        assert_insufficient_material::<Antichess>("8/8/5b2/8/8/3B4/3B4/8 w - - 0 1", true, false);
-        assert_insufficient_material::<Antichess>(
-            "8/5p2/5P2/8/3B4/1bB5/8/8 b - - 0 1",
-            false_negative,
-            false_negative,
-        );
-        assert_insufficient_material::<Antichess>(
-            "8/8/6b1/8/3P4/8/5B2/8 w - - 0 1",
-            false_negative,
-            false,
+        let capture_queens = WithWinCondition(CaptureAll(Role::Queen));
+        let pos = Custom::from_setup_with_rules(
+            "4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1"
+                .parse::<Fen>()
+                .expect("valid fen")
+                .into_setup(),
+            CastlingMode::Standard,
+            capture_queens,
+        )
+        .expect("legal position");
+        assert!(!pos.is_variant_end());
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::Queen,
+                from: Square::D1,
+                capture: Some(Role::Queen),
+                to: Square::D5,
+                promotion: None,
+            })
+            .expect("legal move");
+        assert!(pos.is_variant_end());
+        assert!(pos.legal_moves().is_empty());
+        assert_eq!(
+            pos.outcome(),
+            Outcome::Known(KnownOutcome::Decisive { winner: White })
         );
 
-        assert_insufficient_material::<KingOfTheHill>(
-            "8/5k2/8/8/8/8/3K4/8 w - - 0 1",
-            false,
-            false,
+        // Both sides already reached the targets: the side that just moved
+        // wins.
+        let reach_fourth_rank = WithWinCondition(ReachSquare {
+            role: Role::Pawn,
+            targets: Bitboard::from_rank(Rank::Fourth) | Bitboard::from_rank(Rank::Fifth),
+        });
+        let pos: Custom<_> = Custom::from_setup_with_rules(
+            "4k3/8/8/4p3/4P3/8/8/4K3 w - - 0 1"
+                .parse::<Fen>()
+                .expect("valid fen")
+                .into_setup(),
+            CastlingMode::Standard,
+            reach_fourth_rank,
+        )
+        .expect("legal position");
+        assert_eq!(
+            pos.variant_outcome(),
+            Outcome::Known(KnownOutcome::Decisive { winner: Black })
         );
+    }
 
-        assert_insufficient_material::<RacingKings>("8/5k2/8/8/8/8/3K4/8 w - - 0 1", false, false);
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_no_castling() {
+        use super::variant::NoCastling;
 
-        assert_insufficient_material::<ThreeCheck>("8/5k2/8/8/8/8/3K4/8 w - - 0 1", true, true);
-        assert_insufficient_material::<ThreeCheck>("8/5k2/8/8/8/8/3K2N1/8 w - - 0 1", false, true);
+        let pos = NoCastling::new();
+        assert!(pos.castles().is_empty());
+        assert_eq!(pos.board(), Chess::new().board());
+        assert_eq!(pos.legal_moves().len(), 20);
 
-        assert_insufficient_material::<Crazyhouse>("8/5k2/8/8/8/8/3K2N1/8 w - - 0 1", true, true);
-        assert_insufficient_material::<Crazyhouse>(
-            "8/5k2/8/8/8/5B2/3KB3/8 w - - 0 1",
-            false,
-            false,
+        let pos: NoCastling = setup_fen("r3k2r/8/8/8/8/8/8/R3K2R w - - 0 1");
+        assert!(pos.legal_moves().iter().all(|m| !m.is_castle()));
+
+        let err = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position::<NoCastling>(CastlingMode::Standard)
+            .expect_err("castling rights");
+        assert_eq!(err.kinds(), PositionErrorKinds::INVALID_CASTLING_RIGHTS);
+        let pos = err
+            .ignore_invalid_castling_rights()
+            .expect("otherwise legal");
+        assert!(pos.castles().is_empty());
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_horde_custom_army() {
+        use super::variant::Horde;
+
+        // More than the 36 pawns of the standard horde.
+        let pos: Horde = setup_fen(
+            "rnbqkbnr/pppppppp/8/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1",
         );
-        assert_insufficient_material::<Crazyhouse>(
-            "8/8/8/8/3k4/3N~4/3K4/8 w - - 0 1",
-            false,
-            false,
+        assert_eq!(pos.board().pawns().count(), 8 + 40);
+        assert_eq!(
+            Horde::with_pawns(Bitboard::SOUTH | Rank::Fifth)
+                .expect("legal army")
+                .board(),
+            pos.board()
         );
 
-        assert_insufficient_material::<Horde>("8/5k2/8/8/8/4NN2/8/8 w - - 0 1", true, false);
-        assert_insufficient_material::<Horde>("8/8/8/7k/7P/7P/8/8 b - - 0 58", false, false);
+        // Pawns on the first rank may advance two squares, without
+        // allowing en passant.
+        let pos = Horde::with_pawns(Bitboard::from(Square::A1) | Square::B4).expect("legal army");
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::Pawn,
+                from: Square::A1,
+                capture: None,
+                to: Square::A3,
+                promotion: None,
+            })
+            .expect("double step from first rank");
+        assert_eq!(pos.maybe_ep_square(), None);
+
+        assert_eq!(
+            Horde::with_pawns(Bitboard::from(Square::A7))
+                .expect_err("overlaps black pawn")
+                .kinds(),
+            PositionErrorKinds::VARIANT
+        );
     }
 
     #[cfg(feature = "variant")]
@@ -3868,6 +7190,344 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_fog_of_war_king_capture() {
+        use super::variant::FogOfWar;
+
+        // Kings may stay in check.
+        let pos: FogOfWar = setup_fen("4k3/8/8/8/8/8/4r3/4K3 b - - 0 1");
+        assert!(!pos.is_check());
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::Rook,
+                from: Square::E2,
+                capture: Some(Role::King),
+                to: Square::E1,
+                promotion: None,
+            })
+            .expect("king capture");
+        assert!(pos.is_variant_end());
+        assert!(pos.legal_moves().is_empty());
+        assert_eq!(
+            pos.outcome(),
+            Outcome::Known(KnownOutcome::Decisive {
+                winner: Color::Black
+            })
+        );
+
+        // Castling through check.
+        let pos: FogOfWar = setup_fen("4kr2/8/8/8/8/8/8/4K2R w K - 0 1");
+        assert!(pos.legal_moves().contains(&Move::Castle {
+            king: Square::E1,
+            rook: Square::H1,
+        }));
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_fog_of_war_visible_squares() {
+        use super::variant::FogOfWar;
+
+        // Blocked pawns see neither the blocker nor empty diagonals.
+        let pos: FogOfWar = setup_fen("4k3/8/8/3p4/3P4/8/8/4K3 w - - 0 1");
+        assert_eq!(
+            pos.visible_squares(Color::White),
+            Bitboard::from_iter([
+                Square::D1,
+                Square::E1,
+                Square::F1,
+                Square::D2,
+                Square::E2,
+                Square::F2,
+                Square::D4,
+            ])
+        );
+        assert_eq!(
+            pos.visible_board(Color::White).occupied(),
+            Bitboard::from_iter([Square::E1, Square::D4])
+        );
+
+        // Sliders see the first blocker, pawns see possible captures.
+        let pos: FogOfWar = setup_fen("4k3/8/8/3p4/4P3/8/8/B3K3 w - - 0 1");
+        let visible = pos.visible_squares(Color::White);
+        assert!(visible.contains(Square::D5));
+        assert!(visible.contains(Square::E5));
+        assert!(!visible.contains(Square::F5));
+        assert!(visible.contains(Square::D4));
+        assert!(!visible.contains(Square::C6));
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_shatranj() {
+        use super::variant::Shatranj;
+
+        // Single pawn pushes, knights and leaping alfils.
+        let pos = Shatranj::new();
+        assert_eq!(pos.legal_moves().len(), 16);
+        assert!(pos.legal_moves().contains(&Move::Normal {
+            role: Role::Bishop,
+            from: Square::C1,
+            capture: None,
+            to: Square::A3,
+            promotion: None,
+        }));
+
+        // Stalemate wins.
+        let pos: Shatranj = setup_fen("7k/7P/6K1/8/8/p7/R7/8 b - - 0 1");
+        assert!(pos.is_stalemate());
+        assert_eq!(
+            pos.outcome(),
+            Outcome::Known(KnownOutcome::Decisive {
+                winner: Color::White
+            })
+        );
+
+        // Bare king loses.
+        let pos: Shatranj = setup_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 1");
+        assert!(pos.is_variant_end());
+        assert_eq!(
+            pos.outcome(),
+            Outcome::Known(KnownOutcome::Decisive {
+                winner: Color::White
+            })
+        );
+
+        // Unless the opponent can be bared immediately.
+        let pos: Shatranj = setup_fen("8/8/8/8/8/8/1k6/R3K3 b - - 0 1");
+        assert_eq!(pos.outcome(), Outcome::Unknown);
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::King,
+                from: Square::B2,
+                capture: Some(Role::Rook),
+                to: Square::A1,
+                promotion: None,
+            })
+            .expect("legal capture");
+        assert_eq!(pos.outcome(), Outcome::Known(KnownOutcome::Draw));
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_racing_kings_finish() {
+        use super::variant::RacingKings;
+
+        let pos = RacingKings::new();
+        assert_eq!(pos.distance_to_finish(White), 6);
+        assert_eq!(pos.distance_to_finish(Black), 6);
+        assert!(!pos.has_finished(White));
+
+        // Black can not catch up.
+        let pos: RacingKings = setup_fen("4K3/8/1k6/8/8/8/8/8 b - - 0 1");
+        assert!(pos.has_finished(White));
+        assert!(!pos.can_equalize());
+        assert_eq!(pos.distance_to_finish(Black), 2);
+        assert_eq!(
+            pos.outcome(),
+            Outcome::Known(KnownOutcome::Decisive { winner: White })
+        );
+
+        // Black catches up.
+        let pos: RacingKings = setup_fen("4K3/1k6/8/8/8/8/8/8 b - - 0 1");
+        assert!(pos.can_equalize());
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::King,
+                from: Square::B7,
+                capture: None,
+                to: Square::B8,
+                promotion: None,
+            })
+            .expect("legal move");
+        assert!(!pos.can_equalize());
+        assert_eq!(pos.outcome(), Outcome::Known(KnownOutcome::Draw));
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_crazyhouse_pocket_drop() {
+        use super::variant::Crazyhouse;
+        use crate::san::San;
+
+        let pos: Crazyhouse =
+            setup_fen("rnbqkb1r/pppppppp/8/8/8/8/PPPPPPPP/RNBQKB1R[Nn] w KQkq - 0 3");
+        let m = "N@f3"
+            .parse::<San>()
+            .expect("valid san")
+            .to_move(&pos)
+            .expect("legal drop");
+        assert_eq!(
+            m,
+            Move::Put {
+                role: Role::Knight,
+                to: Square::F3
+            }
+        );
+
+        let pos = pos.play(m).expect("legal drop");
+        let pockets = pos.pockets().expect("pockets");
+        assert_eq!(pockets.count(), 1);
+        assert_eq!(pockets.black.knight, 1);
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_crazyhouse_promoted_bookkeeping() {
+        use super::variant::Crazyhouse;
+
+        let pos: Crazyhouse = setup_fen("6kr/6P1/8/8/8/8/8/4K3[] w - - 0 1");
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::Pawn,
+                from: Square::G7,
+                capture: Some(Role::Rook),
+                to: Square::H8,
+                promotion: Some(Role::Queen),
+            })
+            .expect("legal promotion");
+        assert_eq!(pos.promoted(), Bitboard::from(Square::H8));
+        assert_eq!(pos.pockets().expect("pockets").white.rook, 1);
+
+        // Both pocket styles.
+        for fen in [
+            "6kQ~/8/8/8/8/8/8/4K3[R] b - - 0 1",
+            "6kQ~/8/8/8/8/8/8/4K3/R b - - 0 1",
+        ] {
+            let parsed: Crazyhouse = setup_fen(fen);
+            assert_eq!(parsed, pos);
+            assert_eq!(parsed.promoted(), pos.promoted());
+        }
+
+        // The captured promoted queen goes to the pocket as a pawn.
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::King,
+                from: Square::G8,
+                capture: Some(Role::Queen),
+                to: Square::H8,
+                promotion: None,
+            })
+            .expect("legal capture");
+        assert!(pos.promoted().is_empty());
+        let pockets = pos.pockets().expect("pockets");
+        assert_eq!(pockets.black.pawn, 1);
+        assert_eq!(pockets.black.queen, 0);
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_five_check() {
+        use super::variant::ThreeCheck;
+
+        let pos: ThreeCheck =
+            setup_fen("rnbqkbnr/ppp2ppp/8/3pp3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1+5 0 3");
+        assert_eq!(pos.outcome(), Outcome::Unknown);
+        let pos = pos
+            .play(Move::Normal {
+                role: Role::Bishop,
+                from: Square::F1,
+                capture: None,
+                to: Square::B5,
+                promotion: None,
+            })
+            .expect("legal check");
+        assert_eq!(
+            pos.remaining_checks()
+                .map(|checks| (checks.white, checks.black)),
+            Some((RemainingChecks::new(0), RemainingChecks::new(5)))
+        );
+        assert_eq!(
+            pos.outcome(),
+            Outcome::Known(KnownOutcome::Decisive {
+                winner: Color::White
+            })
+        );
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_bughouse() {
+        use super::variant::{Bughouse, BughouseBoard};
+        use crate::uci::UciMove;
+
+        let game = Bughouse::new();
+        let exd5 = Move::Normal {
+            role: Role::Pawn,
+            from: Square::E4,
+            capture: Some(Role::Pawn),
+            to: Square::D5,
+            promotion: None,
+        };
+        let game = ["e2e4", "d7d5"]
+            .into_iter()
+            .fold(game, |game, uci| {
+                let m = uci
+                    .parse::<UciMove>()
+                    .expect("valid uci")
+                    .to_move(game.position(BughouseBoard::A))
+                    .expect("legal uci");
+                game.play(BughouseBoard::A, m).expect("legal move")
+            })
+            .play(BughouseBoard::A, exd5)
+            .expect("legal capture");
+
+        // The captured black pawn goes to the partner playing Black on B.
+        let a = game.position(BughouseBoard::A);
+        let b = game.position(BughouseBoard::B);
+        assert_eq!(a.pockets().expect("pockets").count(), 0);
+        assert_eq!(b.pockets().expect("pockets").black.pawn, 1);
+        assert_eq!(b.pockets().expect("pockets").white.pawn, 0);
+
+        // It can not be dropped by White on B.
+        assert!(
+            !game
+                .legal_moves(BughouseBoard::B)
+                .iter()
+                .any(|m| matches!(m, Move::Put { .. }))
+        );
+        assert_eq!(game.outcome(), Outcome::Unknown);
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_makruk() {
+        use super::variant::Makruk;
+
+        let pos = Makruk::new();
+        assert_eq!(pos.legal_moves().len(), 23);
+        assert_eq!(pos.counting_limit(), None);
+
+        // Khon steps straight forward, but not backward.
+        let pos: Makruk = setup_fen("4k3/8/8/8/3b4/8/8/4K3 b - - 0 1");
+        assert!(pos.legal_moves().contains(&Move::Normal {
+            role: Role::Bishop,
+            from: Square::D4,
+            capture: None,
+            to: Square::D3,
+            promotion: None,
+        }));
+        assert!(!pos.legal_moves().iter().any(|m| m.to() == Square::D5));
+
+        // Pawns promote on the sixth rank.
+        let pos: Makruk = setup_fen("4k3/8/8/P7/8/8/8/4K3 w - - 0 1");
+        assert!(pos.legal_moves().contains(&Move::Normal {
+            role: Role::Pawn,
+            from: Square::A5,
+            capture: None,
+            to: Square::A6,
+            promotion: Some(Role::Queen),
+        }));
+
+        // Counting against a bare king.
+        let pos: Makruk = setup_fen("4k3/8/8/8/8/8/8/R3K3 b - - 23 50");
+        assert_eq!(pos.counting_limit(), Some(12));
+        assert_eq!(pos.outcome(), Outcome::Unknown);
+        let pos: Makruk = setup_fen("4k3/8/8/8/8/8/8/R3K3 b - - 24 50");
+        assert_eq!(pos.outcome(), Outcome::Known(KnownOutcome::Draw));
+    }
+
     #[cfg(feature = "variant")]
     #[test]
     fn test_antichess_insufficient_material() {
@@ -3907,6 +7567,19 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_antichess_invalid_ep_square() {
+        use super::variant::Antichess;
+
+        let err = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - e6 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position::<Antichess>(CastlingMode::Standard)
+            .expect_err("invalid ep square");
+        assert_eq!(err.kinds(), PositionErrorKinds::INVALID_EP_SQUARE);
+    }
+
     #[test]
     fn test_aligned_checkers() {
         let res = "2Nq4/2K5/1b6/8/7R/3k4/7P/8 w - - 0 1"
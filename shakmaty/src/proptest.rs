@@ -0,0 +1,100 @@
+//! Strategies for property testing with
+//! [`proptest`](https://docs.rs/proptest/1).
+//!
+//! # Examples
+//!
+//! ```
+//! use proptest::{prelude::*, test_runner::TestRunner};
+//! use shakmaty::{Position, proptest::legal_move};
+//!
+//! TestRunner::default()
+//!     .run(&legal_move(40), |(pos, m)| {
+//!         prop_assert!(pos.is_legal(m));
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use ::proptest::{
+    collection,
+    prelude::*,
+    sample::{Index, select},
+};
+
+use crate::{Board, Chess, Color, Move, Piece, Position, Role, Square};
+
+/// Any square.
+pub fn square() -> impl Strategy<Value = Square> {
+    (0..64u32).prop_map(Square::new)
+}
+
+/// Any piece.
+pub fn piece() -> impl Strategy<Value = Piece> {
+    (select(&Color::ALL[..]), select(&Role::ALL[..]))
+        .prop_map(|(color, role)| Piece { color, role })
+}
+
+/// Boards with up to 32 arbitrary pieces. The boards are not necessarily
+/// valid positions.
+pub fn board() -> impl Strategy<Value = Board> {
+    collection::vec((square(), piece()), 0..=32).prop_map(|pieces| {
+        let mut board = Board::empty();
+        for (square, piece) in pieces {
+            board.set_piece_at(square, piece);
+        }
+        board
+    })
+}
+
+/// Legal positions, reached by random playouts of up to `max_plies` moves
+/// from the starting position. Playouts stop early when the game is over.
+///
+/// Shrinks towards shorter playouts.
+pub fn legal_position(max_plies: usize) -> impl Strategy<Value = Chess> {
+    collection::vec(any::<Index>(), 0..=max_plies).prop_map(|indices| {
+        let mut pos = Chess::default();
+        for index in indices {
+            let moves = pos.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            pos.play_unchecked(*index.get(&moves));
+        }
+        pos
+    })
+}
+
+/// Legal positions (see [`legal_position()`]) together with one of their
+/// legal moves. Positions where the game is over are skipped.
+pub fn legal_move(max_plies: usize) -> impl Strategy<Value = (Chess, Move)> {
+    (legal_position(max_plies), any::<Index>()).prop_filter_map("no legal moves", |(pos, index)| {
+        let moves = pos.legal_moves();
+        if moves.is_empty() {
+            None
+        } else {
+            let m = *index.get(&moves);
+            Some((pos, m))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+
+    use super::*;
+    use crate::san::SanPlus;
+
+    proptest! {
+        #[test]
+        fn test_san_roundtrip((pos, m) in legal_move(60)) {
+            let san = SanPlus::from_move(pos.clone(), m);
+            prop_assert_eq!(san.san.to_move(&pos), Ok(m));
+        }
+
+        #[test]
+        fn test_board_roundtrip(board in board()) {
+            prop_assert_eq!(board.to_string().parse::<Board>().ok(), Some(board));
+        }
+    }
+}
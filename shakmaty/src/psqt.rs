@@ -0,0 +1,354 @@
+//! Piece-square tables for simple evaluation functions.
+//!
+//! A [`Psqt`] assigns a value to each role on each square, from the point of
+//! view of White. Black pieces are looked up on the vertically mirrored
+//! square, and their values count negatively. Scores can be computed from
+//! scratch with [`Psqt::evaluate()`], or updated incrementally with
+//! [`Psqt::delta()`] as moves are played.
+//!
+//! Use [`Tapered`] values to interpolate between middlegame and endgame
+//! scores according to the [`game_phase()`].
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{Chess, Position, Role, psqt::{Psqt, Tapered, game_phase}};
+//!
+//! let psqt = Psqt::new_with(|role, sq| {
+//!     let material = match role {
+//!         Role::Pawn => 100,
+//!         Role::Knight | Role::Bishop => 300,
+//!         Role::Rook => 500,
+//!         Role::Queen => 900,
+//!         Role::King => 0,
+//!     };
+//!     // Prefer advanced pawns in the endgame.
+//!     let bonus = if role == Role::Pawn { 5 * i32::from(sq.rank()) } else { 0 };
+//!     Tapered::new(material, material + bonus)
+//! });
+//!
+//! let mut pos = Chess::default();
+//! let mut score = psqt.evaluate(pos.board());
+//! assert_eq!(score, Tapered::new(0, 0));
+//!
+//! for m in ["e2e4", "d7d5", "e4d5"] {
+//!     let m = m.parse::<shakmaty::uci::UciMove>()?.to_move(&pos)?;
+//!     score += psqt.delta(&pos, m);
+//!     pos.play_unchecked(m);
+//! }
+//!
+//! assert_eq!(score, psqt.evaluate(pos.board()));
+//! assert_eq!(score.interpolate(game_phase(pos.board())), 100);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+
+use crate::{Board, ByRole, Color, Move, Piece, Position, Role, Square};
+
+/// A value for each role on each square, from the point of view of White.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Psqt<T> {
+    table: ByRole<[T; 64]>,
+}
+
+impl<T> Psqt<T> {
+    /// Creates a table indexed by [`Square`], so that the first entry of
+    /// each role is for `a1`.
+    pub const fn new(table: ByRole<[T; 64]>) -> Psqt<T> {
+        Psqt { table }
+    }
+
+    /// Creates a table from the value for a White piece on each square.
+    pub fn new_with<F>(mut init: F) -> Psqt<T>
+    where
+        F: FnMut(Role, Square) -> T,
+    {
+        Psqt {
+            table: ByRole::new_with(|role| {
+                core::array::from_fn(|i| init(role, Square::new(i as u32)))
+            }),
+        }
+    }
+
+    /// Creates a table from tables in the order they are usually printed,
+    /// with White at the bottom, so that the first entry of each role is
+    /// for `a8`.
+    pub fn from_visual(table: ByRole<[T; 64]>) -> Psqt<T> {
+        let mut table = table;
+        table.as_mut().for_each(|squares| {
+            // Flip ranks, keeping the order of files.
+            squares.reverse();
+            for rank in squares.chunks_exact_mut(8) {
+                rank.reverse();
+            }
+        });
+        Psqt { table }
+    }
+
+    /// Gets the value of `piece` on `sq`, as seen from the side of
+    /// `piece`. Black pieces are looked up on the mirrored square.
+    #[inline]
+    pub fn get(&self, piece: Piece, sq: Square) -> &T {
+        &self.table.get(piece.role)[relative_square(piece.color, sq).to_usize()]
+    }
+
+    /// Gets a mutable reference to the value of a White piece with `role`
+    /// on `sq`.
+    #[inline]
+    pub fn get_mut(&mut self, role: Role, sq: Square) -> &mut T {
+        &mut self.table.get_mut(role)[sq.to_usize()]
+    }
+}
+
+impl<T> Psqt<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// Computes the score of `board` from scratch, from the point of view
+    /// of White.
+    pub fn evaluate(&self, board: &Board) -> T {
+        let mut white = T::default();
+        let mut black = T::default();
+        for (sq, piece) in board.iter() {
+            match piece.color {
+                Color::White => white = white + *self.get(piece, sq),
+                Color::Black => black = black + *self.get(piece, sq),
+            }
+        }
+        white - black
+    }
+
+    /// Computes the change of [`Psqt::evaluate()`] when `m` is played in
+    /// `pos`, from the point of view of White. Add it to an accumulated
+    /// score to update it incrementally.
+    ///
+    /// Only the squares that `m` moves pieces from and to are considered.
+    /// The result differs from evaluating the board again if the variant
+    /// changes other squares, for example with explosions in Atomic chess.
+    /// Pockets in Crazyhouse and check counters in Three-check are not part
+    /// of the board, so they do not matter.
+    pub fn delta<P: Position>(&self, pos: &P, m: Move) -> T {
+        let sum = |changes: &[(Square, Piece)]| {
+            changes
+                .iter()
+                .fold(T::default(), |acc, &(sq, piece)| match piece.color {
+                    Color::White => acc + *self.get(piece, sq),
+                    Color::Black => acc - *self.get(piece, sq),
+                })
+        };
+        let changes = m.board_changes(pos.turn());
+        sum(&changes.added) - sum(&changes.removed)
+    }
+}
+
+impl<T: Default> Default for Psqt<T> {
+    fn default() -> Psqt<T> {
+        Psqt::new_with(|_, _| T::default())
+    }
+}
+
+const fn relative_square(color: Color, sq: Square) -> Square {
+    match color {
+        Color::White => sq,
+        Color::Black => sq.flip_vertical(),
+    }
+}
+
+/// Maximum value of [`game_phase()`], for the starting position.
+pub const MAX_PHASE: i32 = 24;
+
+/// Computes the game phase from the remaining pieces, between `0` for
+/// endgames without pieces and [`MAX_PHASE`] for the starting material.
+///
+/// Knights and bishops count 1, rooks 2, and queens 4. The result is
+/// capped at [`MAX_PHASE`], so that promotions do not overflow it.
+pub fn game_phase(board: &Board) -> i32 {
+    let phase = board.knights().count()
+        + board.bishops().count()
+        + 2 * board.rooks().count()
+        + 4 * board.queens().count();
+    (phase as i32).min(MAX_PHASE)
+}
+
+/// A pair of middlegame and endgame values.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Tapered<T> {
+    pub mg: T,
+    pub eg: T,
+}
+
+impl<T> Tapered<T> {
+    pub const fn new(mg: T, eg: T) -> Tapered<T> {
+        Tapered { mg, eg }
+    }
+}
+
+impl Tapered<i32> {
+    /// Interpolates linearly between the endgame value at phase `0` and
+    /// the middlegame value at phase [`MAX_PHASE`]. See [`game_phase()`].
+    pub const fn interpolate(self, phase: i32) -> i32 {
+        let phase = if phase < 0 {
+            0
+        } else if phase > MAX_PHASE {
+            MAX_PHASE
+        } else {
+            phase
+        };
+        (self.mg * phase + self.eg * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+impl<T: Add<Output = T>> Add for Tapered<T> {
+    type Output = Tapered<T>;
+
+    fn add(self, other: Tapered<T>) -> Tapered<T> {
+        Tapered {
+            mg: self.mg + other.mg,
+            eg: self.eg + other.eg,
+        }
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Tapered<T> {
+    type Output = Tapered<T>;
+
+    fn sub(self, other: Tapered<T>) -> Tapered<T> {
+        Tapered {
+            mg: self.mg - other.mg,
+            eg: self.eg - other.eg,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Tapered<T> {
+    fn add_assign(&mut self, other: Tapered<T>) {
+        self.mg += other.mg;
+        self.eg += other.eg;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Tapered<T> {
+    fn sub_assign(&mut self, other: Tapered<T>) {
+        self.mg -= other.mg;
+        self.eg -= other.eg;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastlingMode, Chess, fen::Fen, uci::UciMove};
+
+    fn test_psqt() -> Psqt<Tapered<i32>> {
+        Psqt::new_with(|role, sq| {
+            Tapered::new(
+                100 * role as i32 + sq as i32,
+                10 * role as i32 - i32::from(sq.rank()),
+            )
+        })
+    }
+
+    #[test]
+    fn test_delta_accumulates() {
+        let psqt = test_psqt();
+        let mut pos: Chess = "r3k2r/1P6/8/3pP3/8/8/6p1/R3K2R w KQkq d6 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Standard)
+            .expect("legal position");
+        let mut score = psqt.evaluate(pos.board());
+
+        // En passant, castling on both sides, and capturing promotions.
+        for uci in ["e5d6", "e8g8", "e1c1", "g2h1q", "b7a8q"] {
+            let m = uci
+                .parse::<UciMove>()
+                .expect("valid uci")
+                .to_move(&pos)
+                .expect("legal move");
+            score += psqt.delta(&pos, m);
+            pos.play_unchecked(m);
+            assert_eq!(score, psqt.evaluate(pos.board()), "after {uci}");
+        }
+    }
+
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_delta_variants() {
+        use crate::variant::{Atomic, Crazyhouse};
+
+        let psqt = test_psqt();
+
+        // Drops are covered.
+        let pos: Crazyhouse = "4k3/8/8/8/8/8/8/4K3[Q] w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Standard)
+            .expect("legal position");
+        let m = "Q@d4"
+            .parse::<UciMove>()
+            .expect("valid uci")
+            .to_move(&pos)
+            .expect("legal drop");
+        let mut after = pos.clone();
+        after.play_unchecked(m);
+        assert_eq!(
+            psqt.evaluate(pos.board()) + psqt.delta(&pos, m),
+            psqt.evaluate(after.board())
+        );
+
+        // Explosions are not: the bishop on d5 is ignored.
+        let pos: Atomic = "4k3/8/8/3bp3/8/3N4/8/4K3 w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Standard)
+            .expect("legal position");
+        let m = "d3e5"
+            .parse::<UciMove>()
+            .expect("valid uci")
+            .to_move(&pos)
+            .expect("legal capture");
+        let mut after = pos.clone();
+        after.play_unchecked(m);
+        assert_eq!(
+            psqt.evaluate(pos.board())
+                + psqt.delta(&pos, m)
+                + *psqt.get(Color::Black.bishop(), Square::D5)
+                - *psqt.get(Color::White.knight(), Square::E5),
+            psqt.evaluate(after.board())
+        );
+    }
+
+    #[test]
+    fn test_mirrored() {
+        let psqt = Psqt::new_with(|_, sq| sq as i32);
+        assert_eq!(
+            *psqt.get(Color::White.pawn(), Square::E2),
+            Square::E2 as i32
+        );
+        assert_eq!(
+            *psqt.get(Color::Black.pawn(), Square::E7),
+            Square::E2 as i32
+        );
+        assert_eq!(psqt.evaluate(&Board::new()), 0);
+    }
+
+    #[test]
+    fn test_from_visual() {
+        let visual = Psqt::from_visual(ByRole::new_with(|_| core::array::from_fn(|i| i)));
+        assert_eq!(*visual.get(Color::White.rook(), Square::A8), 0);
+        assert_eq!(*visual.get(Color::White.rook(), Square::H8), 7);
+        assert_eq!(*visual.get(Color::White.rook(), Square::A1), 56);
+        assert_eq!(*visual.get(Color::White.rook(), Square::H1), 63);
+        assert_eq!(*visual.get(Color::White.rook(), Square::D5), 27);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let value = Tapered::new(100, 200);
+        assert_eq!(value.interpolate(MAX_PHASE), 100);
+        assert_eq!(value.interpolate(0), 200);
+        assert_eq!(value.interpolate(MAX_PHASE / 2), 150);
+        assert_eq!(game_phase(&Board::new()), MAX_PHASE);
+    }
+}
@@ -0,0 +1,436 @@
+//! Read puzzles from the [Lichess puzzle database](https://database.lichess.org/#puzzles).
+//!
+//! Each CSV record has the fields
+//! `PuzzleId,FEN,Moves,Rating,RatingDeviation,Popularity,NbPlays,Themes,GameUrl,OpeningTags`.
+//! The FEN is the position *before* the opponent's move, and the first of
+//! the UCI moves is the opponent's move that sets up the puzzle. The
+//! remaining moves are the solution.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{Position, puzzle::Puzzle, uci::UciMove};
+//!
+//! let puzzle: Puzzle = "00008,r6k/pp2r2p/4Rp1Q/3p4/8/1N1P2R1/PqP2bPP/7K b - - 0 24,\
+//!     f2g3 e6e7 b2b1 b3c1 b1c1 h6c1,1913,75,94,6230,\
+//!     crushing hangingPiece long middlegame,\
+//!     https://lichess.org/787zsVup/black#48,".parse()?;
+//!
+//! assert_eq!(puzzle.id, "00008");
+//! assert_eq!(puzzle.rating, 1913);
+//! assert!(puzzle.themes.iter().any(|theme| theme == "hangingPiece"));
+//!
+//! let mut pos = puzzle.puzzle_position();
+//! assert_eq!(UciMove::from_standard(puzzle.solution()[0]).to_string(), "e6e7");
+//! for &m in puzzle.solution() {
+//!     pos.play_unchecked(m);
+//! }
+//! # Ok::<_, shakmaty::puzzle::ParsePuzzleError>(())
+//! ```
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{error, fmt, str::FromStr};
+
+use crate::{
+    CastlingMode, Chess, Move, Position, PositionErrorKinds,
+    fen::{Fen, ParseFenError},
+    uci::UciMove,
+};
+
+/// Error when parsing an invalid puzzle record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParsePuzzleError {
+    /// A required field is missing or could not be parsed.
+    InvalidField(&'static str),
+    /// The FEN could not be parsed.
+    InvalidFen(ParseFenError),
+    /// The FEN does not describe a legal position.
+    IllegalPosition(PositionErrorKinds),
+    /// The move with the given index is not a valid UCI move.
+    InvalidMove { index: usize },
+    /// The move with the given index is not legal.
+    IllegalMove { index: usize },
+    /// The puzzle needs at least a setup move and one solution move.
+    TooFewMoves,
+}
+
+impl fmt::Display for ParsePuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePuzzleError::InvalidField(field) => write!(f, "invalid {field} in puzzle"),
+            ParsePuzzleError::InvalidFen(err) => write!(f, "{err} of puzzle"),
+            ParsePuzzleError::IllegalPosition(kinds) => {
+                write!(f, "illegal puzzle position: {kinds:?}")
+            }
+            ParsePuzzleError::InvalidMove { index } => {
+                write!(f, "invalid uci move at index {index} in puzzle")
+            }
+            ParsePuzzleError::IllegalMove { index } => {
+                write!(f, "illegal move at index {index} in puzzle")
+            }
+            ParsePuzzleError::TooFewMoves => f.write_str("too few moves in puzzle"),
+        }
+    }
+}
+
+impl error::Error for ParsePuzzleError {}
+
+impl From<ParseFenError> for ParsePuzzleError {
+    fn from(err: ParseFenError) -> ParsePuzzleError {
+        ParsePuzzleError::InvalidFen(err)
+    }
+}
+
+/// A validated puzzle.
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    /// Unique puzzle id, like `00008`.
+    pub id: String,
+    /// The position before the setup move.
+    pub position: Chess,
+    /// The setup move, followed by the solution. All moves are legal.
+    pub moves: Vec<Move>,
+    /// Glicko-2 rating of the puzzle.
+    pub rating: u32,
+    /// Glicko-2 rating deviation of the puzzle.
+    pub rating_deviation: u32,
+    /// Popularity from -100 (worst) to 100 (best).
+    pub popularity: i32,
+    /// Number of times the puzzle was played.
+    pub plays: u32,
+    /// Themes, like `mateIn2` or `endgame`.
+    pub themes: Vec<String>,
+    /// URL of the game and ply the puzzle was taken from.
+    pub game_url: String,
+    /// Opening tags, like `Sicilian_Defense`. Empty for puzzles not from
+    /// the opening phase, or in older database exports.
+    pub opening_tags: Vec<String>,
+}
+
+impl Puzzle {
+    /// The header line of the CSV database export.
+    pub const CSV_HEADER: &'static str =
+        "PuzzleId,FEN,Moves,Rating,RatingDeviation,Popularity,NbPlays,Themes,GameUrl,OpeningTags";
+
+    /// Parses and validates a single CSV record, without the trailing line
+    /// break.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParsePuzzleError`] if any of the fields is invalid, or if
+    /// any of the moves is illegal.
+    pub fn from_csv_record(record: &str) -> Result<Puzzle, ParsePuzzleError> {
+        let mut fields = CsvFields { rest: Some(record) };
+
+        let id = fields.next_or("PuzzleId")?;
+        let fen = Fen::from_ascii(fields.next_or("FEN")?.as_bytes())?;
+        let uci_moves = fields.next_or("Moves")?;
+        let rating = parse_field(fields.next_or("Rating")?, "Rating")?;
+        let rating_deviation = parse_field(fields.next_or("RatingDeviation")?, "RatingDeviation")?;
+        let popularity = parse_field(fields.next_or("Popularity")?, "Popularity")?;
+        let plays = parse_field(fields.next_or("NbPlays")?, "NbPlays")?;
+        let themes = split_words(&fields.next_or("Themes")?);
+        let game_url = fields.next_or("GameUrl")?;
+        let opening_tags = fields
+            .next()
+            .map_or_else(Vec::new, |tags| split_words(&tags));
+
+        let position: Chess = fen
+            .into_position(CastlingMode::Standard)
+            .map_err(|err| ParsePuzzleError::IllegalPosition(err.kinds()))?;
+
+        let mut pos = position.clone();
+        let mut moves = Vec::new();
+        for (index, uci) in uci_moves.split_ascii_whitespace().enumerate() {
+            let m = uci
+                .parse::<UciMove>()
+                .map_err(|_| ParsePuzzleError::InvalidMove { index })?
+                .to_move(&pos)
+                .map_err(|_| ParsePuzzleError::IllegalMove { index })?;
+            pos.play_unchecked(m);
+            moves.push(m);
+        }
+        if moves.len() < 2 {
+            return Err(ParsePuzzleError::TooFewMoves);
+        }
+
+        Ok(Puzzle {
+            id,
+            position,
+            moves,
+            rating,
+            rating_deviation,
+            popularity,
+            plays,
+            themes,
+            game_url,
+            opening_tags,
+        })
+    }
+
+    /// The opponent's move that sets up the puzzle.
+    pub fn setup_move(&self) -> Move {
+        self.moves[0]
+    }
+
+    /// The expected moves, starting with the first move of the solver.
+    pub fn solution(&self) -> &[Move] {
+        &self.moves[1..]
+    }
+
+    /// The position presented to the solver, i.e., after the setup move.
+    pub fn puzzle_position(&self) -> Chess {
+        let mut pos = self.position.clone();
+        pos.play_unchecked(self.setup_move());
+        pos
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = ParsePuzzleError;
+
+    fn from_str(s: &str) -> Result<Puzzle, ParsePuzzleError> {
+        Puzzle::from_csv_record(s)
+    }
+}
+
+fn parse_field<T: FromStr>(field: String, name: &'static str) -> Result<T, ParsePuzzleError> {
+    field
+        .parse()
+        .map_err(|_| ParsePuzzleError::InvalidField(name))
+}
+
+fn split_words(field: &str) -> Vec<String> {
+    field
+        .split_ascii_whitespace()
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Splits a CSV record into fields, handling double quotes.
+struct CsvFields<'a> {
+    rest: Option<&'a str>,
+}
+
+impl CsvFields<'_> {
+    fn next_or(&mut self, name: &'static str) -> Result<String, ParsePuzzleError> {
+        self.next().ok_or(ParsePuzzleError::InvalidField(name))
+    }
+}
+
+impl Iterator for CsvFields<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let rest = self.rest?;
+        let Some(quoted) = rest.strip_prefix('"') else {
+            return Some(match rest.split_once(',') {
+                Some((field, rest)) => {
+                    self.rest = Some(rest);
+                    field.to_string()
+                }
+                None => {
+                    self.rest = None;
+                    rest.to_string()
+                }
+            });
+        };
+
+        let mut field = String::new();
+        let mut chars = quoted.char_indices();
+        self.rest = None;
+        while let Some((i, ch)) = chars.next() {
+            if ch == '"' {
+                if quoted[i + 1..].starts_with('"') {
+                    chars.next();
+                } else {
+                    self.rest = quoted[i + 1..].strip_prefix(',');
+                    break;
+                }
+            }
+            field.push(ch);
+        }
+        Some(field)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::reader::{PuzzleReader, ReadPuzzleError};
+
+#[cfg(feature = "std")]
+mod reader {
+    use std::io::{self, BufRead};
+
+    use super::*;
+
+    /// Error when reading puzzles with [`PuzzleReader`].
+    #[derive(Debug)]
+    pub enum ReadPuzzleError {
+        /// I/O error from the underlying reader.
+        Io(io::Error),
+        /// Invalid record on the given line (1-based).
+        Parse {
+            line: usize,
+            error: ParsePuzzleError,
+        },
+    }
+
+    impl fmt::Display for ReadPuzzleError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ReadPuzzleError::Io(err) => err.fmt(f),
+                ReadPuzzleError::Parse { line, error } => write!(f, "line {line}: {error}"),
+            }
+        }
+    }
+
+    impl error::Error for ReadPuzzleError {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            match self {
+                ReadPuzzleError::Io(err) => Some(err),
+                ReadPuzzleError::Parse { error, .. } => Some(error),
+            }
+        }
+    }
+
+    impl From<io::Error> for ReadPuzzleError {
+        fn from(err: io::Error) -> ReadPuzzleError {
+            ReadPuzzleError::Io(err)
+        }
+    }
+
+    /// Iterates over the records of a CSV puzzle database.
+    ///
+    /// Skips the header line and empty lines. Iteration can continue after
+    /// encountering an invalid record.
+    ///
+    /// ```no_run
+    /// use std::{fs::File, io::BufReader};
+    /// use shakmaty::puzzle::PuzzleReader;
+    ///
+    /// let file = BufReader::new(File::open("lichess_db_puzzle.csv")?);
+    /// for puzzle in PuzzleReader::new(file) {
+    ///     let puzzle = puzzle?;
+    ///     println!("{}: {} moves", puzzle.id, puzzle.solution().len());
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    #[derive(Debug)]
+    pub struct PuzzleReader<R> {
+        reader: R,
+        line: usize,
+        buffer: String,
+    }
+
+    impl<R: BufRead> PuzzleReader<R> {
+        /// Creates a reader for the given CSV source.
+        pub fn new(reader: R) -> PuzzleReader<R> {
+            PuzzleReader {
+                reader,
+                line: 0,
+                buffer: String::new(),
+            }
+        }
+
+        /// Gets the underlying reader.
+        pub fn into_inner(self) -> R {
+            self.reader
+        }
+    }
+
+    impl<R: BufRead> Iterator for PuzzleReader<R> {
+        type Item = Result<Puzzle, ReadPuzzleError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                self.buffer.clear();
+                match self.reader.read_line(&mut self.buffer) {
+                    Ok(0) => return None,
+                    Ok(_) => self.line += 1,
+                    Err(err) => return Some(Err(err.into())),
+                }
+                let record = self.buffer.trim_end_matches(['\r', '\n']);
+                if record.is_empty() || (self.line == 1 && record.starts_with("PuzzleId,")) {
+                    continue;
+                }
+                return Some(Puzzle::from_csv_record(record).map_err(|error| {
+                    ReadPuzzleError::Parse {
+                        line: self.line,
+                        error,
+                    }
+                }));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+
+    const RECORD: &str = "000aY,r4rk1/pp3ppp/2n1b3/q1pp2B1/8/P1Q2NP1/1PP1PP1P/2KR3R w - - 0 15,\
+        g5e7 a5c3 b2c3 c6e7,1475,75,93,1237,\
+        advantage master middlegame short,\
+        https://lichess.org/iihZGl6t#29,";
+
+    #[test]
+    fn test_parse() {
+        let puzzle = Puzzle::from_csv_record(RECORD).expect("valid puzzle");
+        assert_eq!(puzzle.id, "000aY");
+        assert_eq!(puzzle.moves.len(), 4);
+        assert_eq!(puzzle.setup_move().from(), Some(Square::G5));
+        assert_eq!(puzzle.solution().len(), 3);
+        assert_eq!(puzzle.rating_deviation, 75);
+        assert_eq!(puzzle.popularity, 93);
+        assert_eq!(puzzle.plays, 1237);
+        assert_eq!(
+            puzzle.themes,
+            ["advantage", "master", "middlegame", "short"]
+        );
+        assert_eq!(puzzle.game_url, "https://lichess.org/iihZGl6t#29");
+        assert!(puzzle.opening_tags.is_empty());
+        assert_eq!(puzzle.puzzle_position().turn(), crate::Color::Black);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            Puzzle::from_csv_record(&RECORD.replace("a5c3", "a5h5")).unwrap_err(),
+            ParsePuzzleError::IllegalMove { index: 1 }
+        );
+        assert_eq!(
+            Puzzle::from_csv_record(&RECORD.replace(",1475,", ",x,")).unwrap_err(),
+            ParsePuzzleError::InvalidField("Rating")
+        );
+        assert_eq!(
+            Puzzle::from_csv_record("000aY").unwrap_err(),
+            ParsePuzzleError::InvalidField("FEN")
+        );
+    }
+
+    #[test]
+    fn test_quoted_fields() {
+        let fields: Vec<String> = CsvFields {
+            rest: Some("a,\"b,\"\"c\"\"\",,d"),
+        }
+        .collect();
+        assert_eq!(fields, ["a", "b,\"c\"", "", "d"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reader() {
+        let csv = alloc::format!("{}\n{RECORD}\r\n\n{}\n", Puzzle::CSV_HEADER, "bad");
+        let puzzles: Vec<_> = PuzzleReader::new(csv.as_bytes()).collect();
+        assert_eq!(puzzles.len(), 2);
+        assert!(puzzles[0].is_ok());
+        assert!(matches!(
+            puzzles[1],
+            Err(ReadPuzzleError::Parse { line: 4, .. })
+        ));
+    }
+}
@@ -0,0 +1,287 @@
+//! Python bindings via [`pyo3`](https://docs.rs/pyo3/0.29).
+//!
+//! Build a Python extension module from a `cdylib` crate that depends on
+//! `shakmaty` with the `python` feature and re-exports [`module`], for
+//! example using [maturin](https://www.maturin.rs/).
+//!
+//! # Examples
+//!
+//! ```python
+//! from shakmaty import Position
+//!
+//! pos = Position()
+//! pos.push_san("e4")
+//! pos.push_uci("e7e5")
+//! pos.fen()  # 'rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2'
+//! pos.board().occupied()  # 18441959068093444095
+//! ```
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    Board, CastlingMode, Chess, EnPassantMode, FromSetup, Piece, Position, Square,
+    fen::Fen,
+    san::{San, SanPlus},
+    uci::UciMove,
+};
+
+fn value_error<E: fmt::Display>(err: E) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// A piece placement. Bitboards are exposed as integers.
+#[pyclass(name = "Board", skip_from_py_object, frozen, eq, hash, str)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PyBoard(Board);
+
+#[pymethods]
+impl PyBoard {
+    /// Parses a board FEN, defaulting to the standard starting position.
+    #[new]
+    #[pyo3(signature = (board_fen = None))]
+    fn new(board_fen: Option<&str>) -> PyResult<PyBoard> {
+        Ok(PyBoard(match board_fen {
+            Some(board_fen) => board_fen.parse().map_err(value_error)?,
+            None => Board::new(),
+        }))
+    }
+
+    /// The board FEN.
+    fn board_fen(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// The piece symbol on a square given by its name, if any.
+    fn piece_at(&self, square: &str) -> PyResult<Option<char>> {
+        let square = square.parse::<Square>().map_err(value_error)?;
+        Ok(self.0.piece_at(square).map(Piece::char))
+    }
+
+    /// The set of occupied squares.
+    fn occupied(&self) -> u64 {
+        self.0.occupied().0
+    }
+
+    /// The set of squares occupied by the given piece symbol.
+    fn pieces(&self, symbol: char) -> PyResult<u64> {
+        let piece =
+            Piece::from_char(symbol).ok_or_else(|| PyValueError::new_err("invalid piece"))?;
+        Ok(self.0.by_piece(piece).0)
+    }
+}
+
+impl fmt::Display for PyBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// A parsed FEN.
+#[pyclass(name = "Fen", skip_from_py_object, frozen, str)]
+#[derive(Debug, Clone)]
+pub struct PyFen(Fen);
+
+#[pymethods]
+impl PyFen {
+    #[new]
+    fn new(fen: &str) -> PyResult<PyFen> {
+        fen.parse().map(PyFen).map_err(value_error)
+    }
+
+    /// The board of the FEN.
+    fn board(&self) -> PyBoard {
+        PyBoard(self.0.as_setup().board.clone())
+    }
+
+    /// Sets up the position, validating it.
+    fn position(&self) -> PyResult<PyPosition> {
+        let setup = self.0.as_setup().clone();
+        let mode = CastlingMode::detect(&setup);
+        Chess::from_setup(setup, mode)
+            .map(PyPosition)
+            .map_err(value_error)
+    }
+}
+
+impl fmt::Display for PyFen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// A move in Standard Algebraic Notation.
+#[pyclass(name = "San", skip_from_py_object, frozen, eq, hash, str)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PySan(San);
+
+#[pymethods]
+impl PySan {
+    #[new]
+    fn new(san: &str) -> PyResult<PySan> {
+        san.parse::<SanPlus>()
+            .map(|san_plus| PySan(san_plus.san))
+            .map_err(value_error)
+    }
+
+    /// Converts the move to UCI notation, in the context of a position.
+    fn to_uci(&self, pos: &PyPosition) -> PyResult<String> {
+        let m = self.0.to_move(&pos.0).map_err(value_error)?;
+        Ok(m.to_uci(pos.0.castles().mode()).to_string())
+    }
+}
+
+impl fmt::Display for PySan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// A legal position of standard chess or Chess960. Moves are given in UCI
+/// or SAN notation.
+#[pyclass(name = "Position", skip_from_py_object, eq, str)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PyPosition(Chess);
+
+#[pymethods]
+impl PyPosition {
+    /// Sets up a position from FEN, defaulting to the starting position.
+    #[new]
+    #[pyo3(signature = (fen = None))]
+    fn new(fen: Option<&str>) -> PyResult<PyPosition> {
+        match fen {
+            Some(fen) => PyFen::new(fen)?.position(),
+            None => Ok(PyPosition(Chess::new())),
+        }
+    }
+
+    /// The FEN of the position.
+    fn fen(&self) -> String {
+        self.to_string()
+    }
+
+    /// The board of the position.
+    fn board(&self) -> PyBoard {
+        PyBoard(self.0.board().clone())
+    }
+
+    /// The side to move, `True` for white.
+    #[getter]
+    fn turn(&self) -> bool {
+        self.0.turn().is_white()
+    }
+
+    /// The legal moves in UCI notation.
+    fn legal_moves(&self) -> Vec<String> {
+        let mode = self.0.castles().mode();
+        self.0
+            .legal_moves()
+            .iter()
+            .map(|m| m.to_uci(mode).to_string())
+            .collect()
+    }
+
+    /// Converts a move from UCI to SAN notation.
+    fn san(&self, uci: &str) -> PyResult<String> {
+        let m = uci
+            .parse::<UciMove>()
+            .map_err(value_error)?
+            .to_move(&self.0)
+            .map_err(value_error)?;
+        Ok(SanPlus::from_move(self.0.clone(), m).to_string())
+    }
+
+    /// Plays a move given in UCI notation.
+    fn push_uci(&mut self, uci: &str) -> PyResult<()> {
+        let m = uci
+            .parse::<UciMove>()
+            .map_err(value_error)?
+            .to_move(&self.0)
+            .map_err(value_error)?;
+        self.0.play_unchecked(m);
+        Ok(())
+    }
+
+    /// Plays a move given in SAN notation, and returns it in UCI notation.
+    fn push_san(&mut self, san: &str) -> PyResult<String> {
+        let uci = PySan::new(san)?.to_uci(self)?;
+        self.push_uci(&uci)?;
+        Ok(uci)
+    }
+
+    fn is_check(&self) -> bool {
+        self.0.is_check()
+    }
+
+    fn is_checkmate(&self) -> bool {
+        self.0.is_checkmate()
+    }
+
+    fn is_stalemate(&self) -> bool {
+        self.0.is_stalemate()
+    }
+
+    fn is_insufficient_material(&self) -> bool {
+        self.0.is_insufficient_material()
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.0.is_game_over()
+    }
+
+    /// The result of the game, `"1-0"`, `"0-1"`, `"1/2-1/2"`, or `"*"` if
+    /// the game is not over.
+    fn outcome(&self) -> &'static str {
+        self.0.outcome().as_str()
+    }
+
+    fn copy(&self) -> PyPosition {
+        self.clone()
+    }
+}
+
+impl fmt::Display for PyPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&Fen::from_position(&self.0, EnPassantMode::Legal), f)
+    }
+}
+
+/// The `shakmaty` Python module.
+#[pymodule(name = "shakmaty")]
+pub mod module {
+    #[pymodule_export]
+    use super::{PyBoard, PyFen, PyPosition, PySan};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        Python::initialize();
+        Python::attach(|py| {
+            let mut pos = PyPosition::new(None).unwrap();
+            assert_eq!(pos.legal_moves().len(), 20);
+            assert_eq!(pos.push_san("e4").unwrap(), "e2e4");
+            pos.push_uci("e7e5").unwrap();
+            assert_eq!(
+                pos.fen(),
+                "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2"
+            );
+            assert_eq!(pos.san("d1h5").unwrap(), "Qh5");
+            assert!(pos.push_uci("e1e3").is_err());
+            assert!(pos.board().piece_at("e4").unwrap() == Some('P'));
+            // Kept in sync with the module documentation.
+            assert_eq!(pos.board().occupied(), 18_441_959_068_093_444_095);
+
+            let err = PyPosition::new(Some("8/8/8/8/8/8/8/8 w - - 0 1")).unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+        });
+    }
+}
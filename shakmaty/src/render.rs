@@ -0,0 +1,422 @@
+//! Render board diagrams as SVG.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{Chess, Color, Square, render::Diagram};
+//!
+//! let mut diagram = Diagram::new();
+//! diagram.orientation = Color::Black;
+//! diagram.highlight(Square::E4, "#9bc70080");
+//! diagram.arrow(Square::G1, Square::F3, "#15781b80");
+//!
+//! let svg = diagram.render_position(&Chess::default());
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write as _};
+
+use crate::{Bitboard, Board, Color, File, Piece, Position, Rank, Role, Square};
+
+/// Writes SVG elements for pieces.
+///
+/// Implement this to embed a custom piece set, for example by referencing
+/// external images or inlining vector graphics.
+pub trait PieceSet {
+    /// Writes an SVG element for `piece`, filling the square with the
+    /// top-left corner at (`x`, `y`) and side length `size`.
+    ///
+    /// # Errors
+    ///
+    /// Errors only if `f` errors.
+    fn write_piece(
+        &self,
+        f: &mut dyn fmt::Write,
+        piece: Piece,
+        x: u32,
+        y: u32,
+        size: u32,
+    ) -> fmt::Result;
+}
+
+/// Draws pieces using Unicode chess symbols. Needs no embedded assets, but
+/// the appearance depends on the available fonts.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct UnicodePieces;
+
+impl PieceSet for UnicodePieces {
+    fn write_piece(
+        &self,
+        f: &mut dyn fmt::Write,
+        piece: Piece,
+        x: u32,
+        y: u32,
+        size: u32,
+    ) -> fmt::Result {
+        // Use the filled symbols for both colors, so that white pieces are
+        // not transparent.
+        let symbol = match piece.role {
+            Role::Pawn => '\u{265f}',
+            Role::Knight => '\u{265e}',
+            Role::Bishop => '\u{265d}',
+            Role::Rook => '\u{265c}',
+            Role::Queen => '\u{265b}',
+            Role::King => '\u{265a}',
+        };
+        write!(
+            f,
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\" stroke=\"#000\" stroke-width=\"{}\">{}\u{fe0e}</text>",
+            x + size / 2,
+            y + size / 2,
+            size * 4 / 5,
+            piece.color.fold_wb("#fff", "#000"),
+            (size / 30).max(1),
+            symbol,
+        )
+    }
+}
+
+/// A square highlight. See [`Diagram::highlight()`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Highlight {
+    pub square: Square,
+    pub color: String,
+}
+
+/// An arrow between two squares, or a circle if both squares are the same.
+/// See [`Diagram::arrow()`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Arrow {
+    pub orig: Square,
+    pub dest: Square,
+    pub color: String,
+}
+
+/// Configuration for rendering a board diagram.
+///
+/// Colors are written verbatim into SVG attributes (after escaping), so any
+/// CSS color is accepted.
+#[derive(Debug, Clone)]
+pub struct Diagram<P = UnicodePieces> {
+    /// The color at the bottom of the diagram.
+    pub orientation: Color,
+    /// Side length of each square in user units.
+    pub square_size: u32,
+    /// Whether to label files and ranks along the edge squares.
+    pub coordinates: bool,
+    /// Color of light squares.
+    pub light: String,
+    /// Color of dark squares.
+    pub dark: String,
+    /// Color of the highlight for a king in check.
+    pub check: String,
+    /// Square highlights, drawn below the pieces.
+    pub highlights: Vec<Highlight>,
+    /// Arrows, drawn above the pieces.
+    pub arrows: Vec<Arrow>,
+    /// Prefix for the ids of elements in the SVG. Set a distinct prefix for
+    /// each diagram that is embedded into the same HTML document, so that
+    /// their ids do not collide.
+    pub id_prefix: String,
+    /// The piece set.
+    pub pieces: P,
+}
+
+impl Default for Diagram {
+    fn default() -> Diagram {
+        Diagram::new()
+    }
+}
+
+impl Diagram {
+    /// Creates a diagram configuration with Unicode pieces and the default
+    /// colors of Lichess.
+    pub fn new() -> Diagram {
+        Diagram::with_pieces(UnicodePieces)
+    }
+}
+
+impl<P: PieceSet> Diagram<P> {
+    /// Creates a diagram configuration with a custom piece set.
+    pub fn with_pieces(pieces: P) -> Diagram<P> {
+        Diagram {
+            orientation: Color::White,
+            square_size: 45,
+            coordinates: true,
+            light: String::from("#f0d9b5"),
+            dark: String::from("#b58863"),
+            check: String::from("#ff000080"),
+            highlights: Vec::new(),
+            arrows: Vec::new(),
+            id_prefix: String::new(),
+            pieces,
+        }
+    }
+
+    /// Adds a square highlight.
+    pub fn highlight(&mut self, square: Square, color: impl Into<String>) -> &mut Diagram<P> {
+        self.highlights.push(Highlight {
+            square,
+            color: color.into(),
+        });
+        self
+    }
+
+    /// Adds an arrow from `orig` to `dest`, or a circle if both are the
+    /// same square.
+    pub fn arrow(
+        &mut self,
+        orig: Square,
+        dest: Square,
+        color: impl Into<String>,
+    ) -> &mut Diagram<P> {
+        self.arrows.push(Arrow {
+            orig,
+            dest,
+            color: color.into(),
+        });
+        self
+    }
+
+    /// Renders `board` as an SVG document.
+    pub fn render_board(&self, board: &Board) -> String {
+        let mut svg = String::new();
+        self.write(&mut svg, board, Bitboard::EMPTY)
+            .expect("writing to string");
+        svg
+    }
+
+    /// Renders the board of `pos` as an SVG document, highlighting the king
+    /// if it is in check.
+    pub fn render_position<S: Position>(&self, pos: &S) -> String {
+        let mut svg = String::new();
+        let checked = if pos.is_check() {
+            pos.our(Role::King)
+        } else {
+            Bitboard::EMPTY
+        };
+        self.write(&mut svg, pos.board(), checked)
+            .expect("writing to string");
+        svg
+    }
+
+    /// Writes `board` as an SVG document, highlighting the pieces on
+    /// `checked`.
+    ///
+    /// # Errors
+    ///
+    /// Errors only if `f` errors.
+    pub fn write(&self, f: &mut dyn fmt::Write, board: &Board, checked: Bitboard) -> fmt::Result {
+        let size = self.square_size;
+        let side = size * 8;
+        write!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {side} {side}\" width=\"{side}\" height=\"{side}\">"
+        )?;
+
+        for sq in Square::ALL {
+            let (x, y) = self.origin(sq);
+            let color = if sq.is_light() {
+                &self.light
+            } else {
+                &self.dark
+            };
+            write!(
+                f,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"/>",
+                Escape(color)
+            )?;
+        }
+
+        for highlight in &self.highlights {
+            let (x, y) = self.origin(highlight.square);
+            write!(
+                f,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"/>",
+                Escape(&highlight.color)
+            )?;
+        }
+
+        for sq in checked {
+            let (x, y) = self.origin(sq);
+            write!(
+                f,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                x + size / 2,
+                y + size / 2,
+                size / 2,
+                Escape(&self.check)
+            )?;
+        }
+
+        if self.coordinates {
+            self.write_coordinates(f)?;
+        }
+
+        for (sq, piece) in board {
+            let (x, y) = self.origin(sq);
+            self.pieces.write_piece(f, piece, x, y, size)?;
+        }
+
+        for (index, arrow) in self.arrows.iter().enumerate() {
+            self.write_arrow(f, index, arrow)?;
+        }
+
+        f.write_str("</svg>")
+    }
+
+    fn origin(&self, sq: Square) -> (u32, u32) {
+        let (file, rank) = match self.orientation {
+            Color::White => (u32::from(sq.file()), 7 - u32::from(sq.rank())),
+            Color::Black => (7 - u32::from(sq.file()), u32::from(sq.rank())),
+        };
+        (file * self.square_size, rank * self.square_size)
+    }
+
+    fn write_coordinates(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        let size = self.square_size;
+        let font_size = (size / 4).max(1);
+        let bottom = self.orientation.fold_wb(Rank::First, Rank::Eighth);
+        for file in File::ALL {
+            let sq = Square::from_coords(file, bottom);
+            let (x, y) = self.origin(sq);
+            write!(
+                f,
+                "<text x=\"{}\" y=\"{}\" font-size=\"{font_size}\" font-family=\"sans-serif\" text-anchor=\"end\" fill=\"{}\">{}</text>",
+                x + size - 2,
+                y + size - 3,
+                Escape(self.label_color(sq)),
+                file.char(),
+            )?;
+        }
+        let left = self.orientation.fold_wb(File::A, File::H);
+        for rank in Rank::ALL {
+            let sq = Square::from_coords(left, rank);
+            let (x, y) = self.origin(sq);
+            write!(
+                f,
+                "<text x=\"{}\" y=\"{}\" font-size=\"{font_size}\" font-family=\"sans-serif\" dominant-baseline=\"hanging\" fill=\"{}\">{}</text>",
+                x + 2,
+                y + 2,
+                Escape(self.label_color(sq)),
+                rank.char(),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn label_color(&self, sq: Square) -> &str {
+        if sq.is_light() {
+            &self.dark
+        } else {
+            &self.light
+        }
+    }
+
+    fn write_arrow(&self, f: &mut dyn fmt::Write, index: usize, arrow: &Arrow) -> fmt::Result {
+        let size = self.square_size;
+        let (x1, y1) = self.origin(arrow.orig);
+        let (x1, y1) = (x1 + size / 2, y1 + size / 2);
+        let width = (size / 6).max(1);
+
+        if arrow.orig == arrow.dest {
+            return write!(
+                f,
+                "<circle cx=\"{x1}\" cy=\"{y1}\" r=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                size / 2 - width / 2,
+                Escape(&arrow.color),
+                width / 2,
+            );
+        }
+
+        let (x2, y2) = self.origin(arrow.dest);
+        let (x2, y2) = (x2 + size / 2, y2 + size / 2);
+        // Marker units scale with the stroke width. The tip of the head ends
+        // in the center of the destination square.
+        write!(
+            f,
+            "<defs><marker id=\"{prefix}arrowhead-{index}\" markerWidth=\"3\" markerHeight=\"3\" refX=\"3\" refY=\"1.5\" orient=\"auto\"><path d=\"M0,0 V3 L3,1.5 Z\" fill=\"{color}\"/></marker></defs>",
+            prefix = Escape(&self.id_prefix),
+            color = Escape(&arrow.color),
+        )?;
+        write!(
+            f,
+            "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{}\" stroke-width=\"{width}\" marker-end=\"url(#{prefix}arrowhead-{index})\"/>",
+            Escape(&arrow.color),
+            prefix = Escape(&self.id_prefix),
+        )
+    }
+}
+
+struct Escape<'a>(&'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ch in self.0.chars() {
+            match ch {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                ch => f.write_char(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastlingMode, Chess, fen::Fen};
+
+    #[test]
+    fn test_render_board() {
+        let svg = Diagram::new().render_board(&Board::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 64);
+        assert_eq!(svg.matches("\u{265f}").count(), 16);
+        assert_eq!(svg.matches("\u{265a}").count(), 2);
+    }
+
+    #[test]
+    fn test_render_position() {
+        let pos: Chess = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"
+            .parse::<Fen>()
+            .unwrap()
+            .into_position(CastlingMode::Standard)
+            .unwrap();
+
+        let mut diagram = Diagram::new();
+        diagram.coordinates = false;
+        diagram.check = String::from("red");
+        diagram
+            .highlight(Square::H4, "\"yellow\"")
+            .arrow(Square::D8, Square::H4, "blue")
+            .arrow(Square::E1, Square::E1, "blue");
+        let svg = diagram.render_position(&pos);
+        assert!(svg.contains("<circle cx=\"202\" cy=\"337\" r=\"22\" fill=\"red\"/>"));
+        assert!(svg.contains("fill=\"&quot;yellow&quot;\""));
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert!(svg.contains("marker-end=\"url(#arrowhead-0)\""));
+        assert_eq!(svg.matches("stroke=\"blue\"").count(), 2);
+        assert!(!svg.contains("font-family"));
+
+        diagram.id_prefix = String::from("board-2-");
+        let svg = diagram.render_position(&pos);
+        assert!(svg.contains("<marker id=\"board-2-arrowhead-0\""));
+        assert!(svg.contains("marker-end=\"url(#board-2-arrowhead-0)\""));
+    }
+
+    #[test]
+    fn test_orientation() {
+        let mut diagram = Diagram::new();
+        assert_eq!(diagram.origin(Square::A1), (0, 315));
+        assert_eq!(diagram.origin(Square::H8), (315, 0));
+        diagram.orientation = Color::Black;
+        assert_eq!(diagram.origin(Square::A1), (315, 0));
+        assert_eq!(diagram.origin(Square::H8), (0, 315));
+    }
+}
@@ -118,6 +118,9 @@ impl Role {
 
 from_enum_as_int_impl! { Role, u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize }
 
+#[cfg(feature = "serde")]
+serde_char_impl! { Role, "piece type character" }
+
 macro_rules! nonzero_int_from_role_impl {
     ($($t:ty)+) => {
         $(impl From<Role> for $t {
@@ -164,6 +167,11 @@ try_role_from_int_impl! { u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize }
 /// Container with values for each [`Role`].
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug), archive_bounds(T::Archived: core::fmt::Debug))
+)]
 #[derive(Copy, Clone, Default, Eq, PartialEq, Debug, Hash)]
 #[repr(C)]
 pub struct ByRole<T> {
@@ -320,9 +328,9 @@ impl<T> ByRole<T> {
     }
 }
 
-#[cfg(feature = "variant")]
 impl ByRole<u8> {
-    pub(crate) fn count(self) -> usize {
+    /// Sums the counts of all roles.
+    pub fn count(&self) -> usize {
         self.iter().map(|c| usize::from(*c)).sum()
     }
 }
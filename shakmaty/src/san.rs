@@ -535,10 +535,19 @@ impl Suffix {
     }
 
     pub fn from_position<P: Position>(pos: &P) -> Option<Suffix> {
-        if matches!(pos.outcome(), Outcome::Known(KnownOutcome::Decisive { .. })) {
+        // Equivalent to checking for a decisive Position::outcome(), but
+        // generates legal moves only when in check.
+        if matches!(
+            pos.variant_outcome(),
+            Outcome::Known(KnownOutcome::Decisive { .. })
+        ) {
             Some(Suffix::Checkmate)
         } else if pos.checkers().any() {
-            Some(Suffix::Check)
+            Some(if !pos.has_legal_moves() {
+                Suffix::Checkmate
+            } else {
+                Suffix::Check
+            })
         } else {
             None
         }
@@ -614,6 +623,43 @@ impl SanPlus {
         }
     }
 
+    /// Converts a sequence of moves to SAN, for example to write all moves
+    /// of a game. The moves are played on `pos`, so that each position is
+    /// visited only once.
+    ///
+    /// It is the callers responsibility to ensure the moves are legal. See
+    /// [`SanPlus::from_move_and_play_unchecked()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Position, san::SanPlus, uci::UciMove};
+    ///
+    /// let mut pos = Chess::default();
+    /// let mut moves = Vec::new();
+    /// for uci in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+    ///     let m = uci.parse::<UciMove>()?.to_move(&pos)?;
+    ///     pos.play_unchecked(m);
+    ///     moves.push(m);
+    /// }
+    ///
+    /// let sans: Vec<String> = SanPlus::iter_from_moves(Chess::default(), moves)
+    ///     .map(|san| san.to_string())
+    ///     .collect();
+    /// assert_eq!(sans, ["f3", "e5", "g4", "Qh4#"]);
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn iter_from_moves<P, I>(pos: P, moves: I) -> SanPlusIter<P, I::IntoIter>
+    where
+        P: Position,
+        I: IntoIterator<Item = Move>,
+    {
+        SanPlusIter {
+            pos,
+            moves: moves.into_iter(),
+        }
+    }
+
     pub fn from_move<P: Position>(mut pos: P, m: Move) -> SanPlus {
         let moves = match m {
             Move::Normal { role, to, .. } | Move::Put { role, to } => pos.san_candidates(role, to),
@@ -653,6 +699,42 @@ impl SanPlus {
     }
 }
 
+/// Iterator that converts a sequence of moves to SAN, while playing them.
+/// See [`SanPlus::iter_from_moves()`].
+#[derive(Debug, Clone)]
+pub struct SanPlusIter<P, I> {
+    pos: P,
+    moves: I,
+}
+
+impl<P, I> SanPlusIter<P, I> {
+    /// The position after the moves converted so far.
+    pub const fn position(&self) -> &P {
+        &self.pos
+    }
+
+    /// Stops converting and returns the position after the moves converted
+    /// so far.
+    pub fn into_position(self) -> P {
+        self.pos
+    }
+}
+
+impl<P: Position, I: Iterator<Item = Move>> Iterator for SanPlusIter<P, I> {
+    type Item = SanPlus;
+
+    fn next(&mut self) -> Option<SanPlus> {
+        let m = self.moves.next()?;
+        Some(SanPlus::from_move_and_play_unchecked(&mut self.pos, m))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.moves.size_hint()
+    }
+}
+
+impl<P: Position, I: ExactSizeIterator<Item = Move>> ExactSizeIterator for SanPlusIter<P, I> {}
+
 struct Reader<'a> {
     bytes: &'a [u8],
 }
@@ -887,6 +969,63 @@ mod tests {
         assert!(mem::size_of::<SanPlus>() <= 8);
     }
 
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_suffix_from_position() {
+        use crate::variant::{Variant, VariantPosition};
+
+        fn assert_suffixes(pos: &VariantPosition, depth: u32) {
+            let expected = if matches!(pos.outcome(), Outcome::Known(KnownOutcome::Decisive { .. }))
+            {
+                Some(Suffix::Checkmate)
+            } else if pos.is_check() {
+                Some(Suffix::Check)
+            } else {
+                None
+            };
+            assert_eq!(Suffix::from_position(pos), expected);
+
+            if depth > 0 {
+                for m in pos.legal_moves() {
+                    let mut after = pos.clone();
+                    after.play_unchecked(m);
+                    assert_suffixes(&after, depth - 1);
+                }
+            }
+        }
+
+        for (variant, fen) in [
+            (
+                Variant::Chess,
+                "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+            ),
+            (Variant::Chess, "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"),
+            (Variant::Chess, "4k3/8/8/8/8/8/4q3/4K3 w - - 0 1"),
+            (Variant::Antichess, "8/8/8/8/8/p7/P7/8 w - - 0 1"),
+            (
+                Variant::Atomic,
+                "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2",
+            ),
+            (Variant::KingOfTheHill, "4k3/8/8/8/3K4/8/8/8 b - - 0 1"),
+            (
+                Variant::ThreeCheck,
+                "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2 +2+0",
+            ),
+            (
+                Variant::RacingKings,
+                "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1",
+            ),
+        ] {
+            let pos = VariantPosition::from_setup(
+                variant,
+                fen.parse::<Fen>().expect("valid fen").into_setup(),
+                CastlingMode::Standard,
+            )
+            .expect("legal position");
+            assert_suffixes(&pos, 2);
+        }
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn test_read_write() {
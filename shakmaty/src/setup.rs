@@ -11,7 +11,18 @@ use crate::{
 ///
 /// [`Hash`](core::hash::Hash), [`PartialEq`], and
 /// [`Eq`] are implemented in terms of structural equality.
+///
+/// # Hashing
+///
+/// Like for [`Board`], [`Hash`](core::hash::Hash) feeds only fixed-width
+/// integers to the hasher, and never variable-length data.
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(derive(Debug))
+)]
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Setup {
     /// Piece positions on the board.
@@ -108,6 +119,43 @@ impl Setup {
         }
     }
 
+    /// Tests for equality, ignoring which pieces are marked as
+    /// [`promoted`](Setup::promoted).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Setup, fen::Fen};
+    ///
+    /// let a = "4k3/8/8/8/8/8/8/2Q~1K3 w - - 0 1".parse::<Fen>()?.into_setup();
+    /// let b = "4k3/8/8/8/8/8/8/2Q1K3 w - - 0 1".parse::<Fen>()?.into_setup();
+    /// assert_ne!(a, b);
+    /// assert!(a.eq_ignore_promoted(&b));
+    /// # Ok::<_, shakmaty::fen::ParseFenError>(())
+    /// ```
+    pub fn eq_ignore_promoted(&self, other: &Setup) -> bool {
+        let Setup {
+            board,
+            promoted: _,
+            pockets,
+            turn,
+            castling_rights,
+            ep_square,
+            remaining_checks,
+            halfmoves,
+            fullmoves,
+        } = self;
+
+        *board == other.board
+            && *pockets == other.pockets
+            && *turn == other.turn
+            && *castling_rights == other.castling_rights
+            && *ep_square == other.ep_square
+            && *remaining_checks == other.remaining_checks
+            && *halfmoves == other.halfmoves
+            && *fullmoves == other.fullmoves
+    }
+
     /// Swap turns and discard en passant rights. This is sometimes called
     /// "playing a null move".
     pub const fn swap_turn(&mut self) {
@@ -161,7 +209,7 @@ impl Default for Setup {
 pub struct Castles {
     mask: Bitboard,
     rook: ByColor<ByCastlingSide<Option<Square>>>,
-    path: ByColor<ByCastlingSide<Bitboard>>,
+    king: ByColor<Option<Square>>,
     mode: CastlingMode,
 }
 
@@ -180,15 +228,9 @@ impl Castles {
                     queen_side: Some(Square::A1),
                 },
             },
-            path: ByColor {
-                black: ByCastlingSide {
-                    king_side: Bitboard(0x6000_0000_0000_0000),
-                    queen_side: Bitboard(0x0e00_0000_0000_0000),
-                },
-                white: ByCastlingSide {
-                    king_side: Bitboard(0x0000_0000_0000_0060),
-                    queen_side: Bitboard(0x0000_0000_0000_000e),
-                },
+            king: ByColor {
+                black: Some(Square::E8),
+                white: Some(Square::E1),
             },
         }
     }
@@ -223,15 +265,9 @@ impl Castles {
                     queen_side: None,
                 },
             },
-            path: ByColor {
-                black: ByCastlingSide {
-                    king_side: Bitboard::EMPTY,
-                    queen_side: Bitboard::EMPTY,
-                },
-                white: ByCastlingSide {
-                    king_side: Bitboard::EMPTY,
-                    queen_side: Bitboard::EMPTY,
-                },
+            king: ByColor {
+                black: None,
+                white: None,
             },
         }
     }
@@ -249,32 +285,20 @@ impl Castles {
                 let side = rooks & setup.board.by_color(color) & color.backrank();
 
                 if let Some(a_side) = side.first().filter(|rook| rook.file() < king.file()) {
-                    let rook_to = CastlingSide::QueenSide.rook_to(color);
-                    let king_to = CastlingSide::QueenSide.king_to(color);
                     let chess960 = king.file() != File::E || a_side.file() != File::A;
                     if !chess960 || mode.is_chess960() {
                         castles.mask.add(a_side);
                         castles.rook.get_mut(color).queen_side = Some(a_side);
-                        castles.path.get_mut(color).queen_side =
-                            (attacks::between(a_side, rook_to).with(rook_to)
-                                | attacks::between(king, king_to).with(king_to))
-                            .without(king)
-                            .without(a_side);
+                        *castles.king.get_mut(color) = Some(king);
                     }
                 }
 
                 if let Some(h_side) = side.last().filter(|rook| king.file() < rook.file()) {
-                    let rook_to = CastlingSide::KingSide.rook_to(color);
-                    let king_to = CastlingSide::KingSide.king_to(color);
                     let chess960 = king.file() != File::E || h_side.file() != File::H;
                     if !chess960 || mode.is_chess960() {
                         castles.mask.add(h_side);
                         castles.rook.get_mut(color).king_side = Some(h_side);
-                        castles.path.get_mut(color).king_side = (attacks::between(h_side, rook_to)
-                            .with(rook_to)
-                            | attacks::between(king, king_to).with(king_to))
-                        .without(king)
-                        .without(h_side);
+                        *castles.king.get_mut(color) = Some(king);
                     }
                 }
             }
@@ -360,7 +384,20 @@ impl Castles {
     /// ```
     #[inline]
     pub const fn path(&self, color: Color, side: CastlingSide) -> Bitboard {
-        *self.path.get(color).get(side)
+        // Computed on demand to keep positions small.
+        match (*self.king.get(color), self.rook(color, side)) {
+            (Some(king), Some(rook)) => {
+                let king_to = side.king_to(color);
+                let rook_to = side.rook_to(color);
+                attacks::between(rook, rook_to)
+                    .with_const(Bitboard::from_square(rook_to))
+                    .with_const(attacks::between(king, king_to))
+                    .with_const(Bitboard::from_square(king_to))
+                    .without_const(Bitboard::from_square(king))
+                    .without_const(Bitboard::from_square(rook))
+            }
+            _ => Bitboard::EMPTY,
+        }
     }
 
     /// Castling rigths in terms of corresponding rook positions.
@@ -186,6 +186,9 @@ impl fmt::Display for File {
 from_enum_as_int_impl! { File, u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize }
 try_from_int_impl! { File, 0, 8, u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize }
 
+#[cfg(feature = "serde")]
+serde_char_impl! { File, "file character" }
+
 /// A rank of the chessboard.
 #[allow(missing_docs)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -331,6 +334,9 @@ impl fmt::Display for Rank {
 from_enum_as_int_impl! { Rank, u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize }
 try_from_int_impl! { Rank, 0, 8, u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize }
 
+#[cfg(feature = "serde")]
+serde_char_impl! { Rank, "rank character" }
+
 /// Error when parsing an invalid square name.
 #[derive(Clone, Debug)]
 pub struct ParseSquareError;
@@ -348,6 +354,11 @@ impl error::Error for ParseSquareError {}
 #[allow(missing_docs)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize),
+    rkyv(compare(PartialEq), derive(Debug))
+)]
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[repr(u8)]
 pub enum Square {
@@ -752,6 +763,45 @@ impl fmt::Display for Square {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Square {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = arrayvec::ArrayString::<2>::new();
+        let _ = self.append_to(&mut s);
+        serializer.serialize_str(&s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Square {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SquareVisitor;
+
+        impl serde::de::Visitor<'_> for SquareVisitor {
+            type Value = Square;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("square name")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Square, E>
+            where
+                E: serde::de::Error,
+            {
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_str(SquareVisitor)
+    }
+}
+
 impl fmt::Debug for Square {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_char(self.file().upper_char())?;
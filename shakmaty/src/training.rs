@@ -0,0 +1,1330 @@
+//! Read and write training data in the formats of the Stockfish trainers.
+//!
+//! In the `.bin` format, each record is a fixed-size [`PackedSfenValue`] of
+//! 40 bytes: a Huffman-coded position ([`PackedSfen`]), followed by the
+//! score, move, game ply and game result.
+//!
+//! The `.binpack` format is read with [`BinpackReader`] and written with
+//! [`BinpackWriter`]. It stores chains of consecutive entries: The first
+//! entry of each chain is stored in full, and each following entry only as
+//! its move and the score difference.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{CastlingMode, Chess, EnPassantMode, FromSetup, Position};
+//! use shakmaty::training::{PackedSfen, PackedSfenValue, SfenMove};
+//! use shakmaty::uci::UciMove;
+//!
+//! let pos = Chess::default();
+//! let m = UciMove::from_ascii(b"e2e4")?.to_move(&pos)?;
+//!
+//! let record = PackedSfenValue {
+//!     sfen: PackedSfen::pack(&pos.to_setup(EnPassantMode::Legal))?,
+//!     score: 25,
+//!     m: SfenMove::from_move(m).expect("representable"),
+//!     game_ply: 0,
+//!     game_result: 0,
+//! };
+//!
+//! let record = PackedSfenValue::from_bytes(record.to_bytes());
+//! let pos = Chess::from_setup(record.sfen.unpack()?, CastlingMode::Standard)?;
+//! assert_eq!(record.m.to_uci().to_move(&pos)?, m);
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use core::{error, fmt, num::NonZeroU32};
+#[cfg(feature = "std")]
+use std::{io, vec::Vec};
+
+use crate::{
+    Bitboard, Board, ByColor, CastlingSide, Color, File, Move, Piece, Rank, Role, Setup, Square,
+    uci::UciMove,
+};
+#[cfg(feature = "std")]
+use crate::{CastlingMode, Chess, EnPassantMode, FromSetup, Position, attacks};
+
+/// Error when packing a setup that is not representable as a
+/// [`PackedSfen`].
+#[derive(Debug, Clone)]
+pub enum PackSfenError {
+    /// There is not exactly one king of each color, or there are too many
+    /// pieces.
+    Board,
+    /// The setup has variant specific state, like pockets, promoted pieces
+    /// or remaining checks.
+    Variant,
+    /// The castling rights are not representable as standard castling
+    /// rights with outermost rooks.
+    CastlingRights,
+    /// The halfmove clock or fullmove number are too large.
+    Counters,
+}
+
+impl fmt::Display for PackSfenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match *self {
+            PackSfenError::Board => "unrepresentable board",
+            PackSfenError::Variant => "unrepresentable variant state",
+            PackSfenError::CastlingRights => "unrepresentable castling rights",
+            PackSfenError::Counters => "unrepresentable move counters",
+        })
+    }
+}
+
+impl error::Error for PackSfenError {}
+
+/// Error when unpacking an invalid [`PackedSfen`].
+#[derive(Debug, Clone)]
+pub struct UnpackSfenError {
+    _priv: (),
+}
+
+impl fmt::Display for UnpackSfenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid packed sfen")
+    }
+}
+
+impl error::Error for UnpackSfenError {}
+
+struct BitWriter {
+    inner: [u8; 32],
+    cursor: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: usize) -> Result<(), PackSfenError> {
+        if self.cursor + bits > 256 {
+            return Err(PackSfenError::Board);
+        }
+        for i in 0..bits {
+            if value & (1 << i) != 0 {
+                self.inner[self.cursor / 8] |= 1 << (self.cursor % 8);
+            }
+            self.cursor += 1;
+        }
+        Ok(())
+    }
+}
+
+struct BitReader<'a> {
+    inner: &'a [u8; 32],
+    cursor: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, bits: usize) -> Result<u32, UnpackSfenError> {
+        if self.cursor + bits > 256 {
+            return Err(UnpackSfenError { _priv: () });
+        }
+        let mut value = 0;
+        for i in 0..bits {
+            if self.inner[self.cursor / 8] & (1 << (self.cursor % 8)) != 0 {
+                value |= 1 << i;
+            }
+            self.cursor += 1;
+        }
+        Ok(value)
+    }
+}
+
+/// Squares in the order of the encoding, from a8 to h1.
+fn sfen_squares() -> impl Iterator<Item = Square> {
+    Rank::ALL.into_iter().rev().flat_map(|rank| {
+        File::ALL
+            .into_iter()
+            .map(move |file| Square::from_coords(file, rank))
+    })
+}
+
+/// Finds the outermost rook on the given side, as implied by standard
+/// castling rights.
+fn castling_rook(board: &Board, color: Color, side: CastlingSide) -> Option<Square> {
+    let backrank = board.rooks() & board.by_color(color) & Bitboard::from_rank(color.backrank());
+    match side {
+        CastlingSide::KingSide => backrank.last(),
+        CastlingSide::QueenSide => backrank.first(),
+    }
+}
+
+/// A position Huffman-coded in 32 bytes, as used by the Stockfish trainers.
+///
+/// Supports standard chess positions with standard castling rights.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedSfen {
+    inner: [u8; 32],
+}
+
+impl PackedSfen {
+    /// Packs a standard chess setup.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the setup is not representable, see [`PackSfenError`].
+    pub fn pack(setup: &Setup) -> Result<PackedSfen, PackSfenError> {
+        if setup.pockets.is_some() || setup.promoted.any() || setup.remaining_checks.is_some() {
+            return Err(PackSfenError::Variant);
+        }
+        if setup.halfmoves > 127 || setup.fullmoves.get() > 0xffff {
+            return Err(PackSfenError::Counters);
+        }
+
+        let board = &setup.board;
+        let kings =
+            ByColor::new_with(|color| (board.kings() & board.by_color(color)).single_square());
+        let (Some(white_king), Some(black_king)) = (kings.white, kings.black) else {
+            return Err(PackSfenError::Board);
+        };
+
+        let mut castling = 0;
+        let mut remaining = setup.castling_rights;
+        for (i, (color, side)) in [
+            (Color::White, CastlingSide::KingSide),
+            (Color::White, CastlingSide::QueenSide),
+            (Color::Black, CastlingSide::KingSide),
+            (Color::Black, CastlingSide::QueenSide),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if let Some(rook) = castling_rook(board, color, side) {
+                let king = kings.get(color).expect("king checked");
+                if setup.castling_rights.contains(rook)
+                    && king.rank() == color.backrank()
+                    && (king < rook) == side.is_king_side()
+                {
+                    castling |= 1 << i;
+                    remaining.discard(rook);
+                }
+            }
+        }
+        if remaining.any() {
+            return Err(PackSfenError::CastlingRights);
+        }
+
+        let mut writer = BitWriter {
+            inner: [0; 32],
+            cursor: 0,
+        };
+        writer.write(u32::from(setup.turn.is_black()), 1)?;
+        writer.write(white_king.to_u32(), 6)?;
+        writer.write(black_king.to_u32(), 6)?;
+        for sq in sfen_squares() {
+            match board.piece_at(sq) {
+                Some(piece) if piece.role == Role::King => (),
+                Some(piece) => {
+                    let code = match piece.role {
+                        Role::Pawn => 0b0001,
+                        Role::Knight => 0b0011,
+                        Role::Bishop => 0b0101,
+                        Role::Rook => 0b0111,
+                        Role::Queen => 0b1001,
+                        Role::King => unreachable!(),
+                    };
+                    writer.write(code, 4)?;
+                    writer.write(u32::from(piece.color.is_black()), 1)?;
+                }
+                None => writer.write(0, 1)?,
+            }
+        }
+        writer.write(castling, 4)?;
+        match setup.ep_square {
+            Some(ep_square) => {
+                writer.write(1, 1)?;
+                writer.write(ep_square.to_u32(), 6)?;
+            }
+            None => writer.write(0, 1)?,
+        }
+        writer.write(setup.halfmoves, 6)?;
+        writer.write(setup.fullmoves.get(), 16)?;
+        writer.write(setup.halfmoves >> 6, 1)?;
+
+        Ok(PackedSfen {
+            inner: writer.inner,
+        })
+    }
+
+    /// Unpacks the setup.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the encoding is invalid. The resulting setup is not
+    /// validated.
+    pub fn unpack(&self) -> Result<Setup, UnpackSfenError> {
+        let mut reader = BitReader {
+            inner: &self.inner,
+            cursor: 0,
+        };
+        let mut setup = Setup::empty();
+        setup.turn = Color::from_white(reader.read(1)? == 0);
+        let white_king = Square::new(reader.read(6)?);
+        let black_king = Square::new(reader.read(6)?);
+        setup.board.set_piece_at(white_king, Color::White.king());
+        setup.board.set_piece_at(black_king, Color::Black.king());
+        for sq in sfen_squares() {
+            if sq == white_king || sq == black_king {
+                continue;
+            }
+            if reader.read(1)? == 0 {
+                continue;
+            }
+            let role = match reader.read(3)? {
+                0b000 => Role::Pawn,
+                0b001 => Role::Knight,
+                0b010 => Role::Bishop,
+                0b011 => Role::Rook,
+                0b100 => Role::Queen,
+                _ => return Err(UnpackSfenError { _priv: () }),
+            };
+            let color = Color::from_white(reader.read(1)? == 0);
+            setup.board.set_piece_at(sq, Piece { color, role });
+        }
+        for (color, side) in [
+            (Color::White, CastlingSide::KingSide),
+            (Color::White, CastlingSide::QueenSide),
+            (Color::Black, CastlingSide::KingSide),
+            (Color::Black, CastlingSide::QueenSide),
+        ] {
+            if reader.read(1)? != 0 {
+                let rook = castling_rook(&setup.board, color, side)
+                    .ok_or(UnpackSfenError { _priv: () })?;
+                setup.castling_rights.add(rook);
+            }
+        }
+        if reader.read(1)? != 0 {
+            setup.ep_square = Some(Square::new(reader.read(6)?));
+        }
+        setup.halfmoves = reader.read(6)?;
+        setup.fullmoves = NonZeroU32::new(reader.read(16)?).unwrap_or(NonZeroU32::MIN);
+        // Older writers may not have written the high bit of the halfmove
+        // clock, but the remaining bits are zero in that case.
+        setup.halfmoves |= reader.read(1)? << 6;
+        Ok(setup)
+    }
+
+    /// Wraps a given byte representation.
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; 32]) -> PackedSfen {
+        PackedSfen { inner: bytes }
+    }
+
+    /// Unwraps the packed byte representation.
+    #[inline]
+    pub const fn to_bytes(&self) -> [u8; 32] {
+        self.inner
+    }
+}
+
+/// A move in the 16-bit encoding of Stockfish.
+///
+/// Castling moves are encoded as the king moving to the rook.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SfenMove(pub u16);
+
+impl SfenMove {
+    const PROMOTION: u16 = 1 << 14;
+    const EN_PASSANT: u16 = 2 << 14;
+    const CASTLING: u16 = 3 << 14;
+
+    /// Encodes a move. Returns `None` for moves that are not representable,
+    /// like piece drops or promotions to a king.
+    pub fn from_move(m: Move) -> Option<SfenMove> {
+        let (from, to, flags) = match m {
+            Move::Normal {
+                from,
+                to,
+                promotion: None,
+                ..
+            } => (from, to, 0),
+            Move::Normal {
+                from,
+                to,
+                promotion: Some(promotion),
+                ..
+            } => {
+                let promotion = match promotion {
+                    Role::Knight => 0,
+                    Role::Bishop => 1,
+                    Role::Rook => 2,
+                    Role::Queen => 3,
+                    Role::Pawn | Role::King => return None,
+                };
+                (from, to, SfenMove::PROMOTION | (promotion << 12))
+            }
+            Move::EnPassant { from, to } => (from, to, SfenMove::EN_PASSANT),
+            Move::Castle { king, rook } => (king, rook, SfenMove::CASTLING),
+            Move::Put { .. } => return None,
+        };
+        Some(SfenMove((u16::from(from) << 6) | u16::from(to) | flags))
+    }
+
+    /// Decodes the move in UCI notation, using the king-to-rook notation for
+    /// castling moves.
+    ///
+    /// Use [`UciMove::to_move()`] to get a legal move in the context of a
+    /// position.
+    pub fn to_uci(self) -> UciMove {
+        let from = Square::try_from((self.0 >> 6) & 0x3f).expect("masked");
+        let to = Square::try_from(self.0 & 0x3f).expect("masked");
+        let promotion = (self.0 & SfenMove::CASTLING == SfenMove::PROMOTION).then_some(
+            match (self.0 >> 12) & 0x3 {
+                0 => Role::Knight,
+                1 => Role::Bishop,
+                2 => Role::Rook,
+                _ => Role::Queen,
+            },
+        );
+        UciMove::Normal {
+            from,
+            to,
+            promotion,
+        }
+    }
+}
+
+/// A training data record, as used by the Stockfish trainers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedSfenValue {
+    /// The position.
+    pub sfen: PackedSfen,
+    /// The score from the point of view of the side to move.
+    pub score: i16,
+    /// The move played or searched in the position.
+    pub m: SfenMove,
+    /// The number of plies since the start of the game.
+    pub game_ply: u16,
+    /// The result from the point of view of the side to move: `1` for a
+    /// win, `0` for a draw, and `-1` for a loss.
+    pub game_result: i8,
+}
+
+impl PackedSfenValue {
+    /// The size of an encoded record.
+    pub const BYTES: usize = 40;
+
+    /// Decodes a record.
+    pub fn from_bytes(bytes: [u8; PackedSfenValue::BYTES]) -> PackedSfenValue {
+        let (sfen, rest) = bytes.split_first_chunk::<32>().expect("size");
+        PackedSfenValue {
+            sfen: PackedSfen::from_bytes(*sfen),
+            score: i16::from_le_bytes([rest[0], rest[1]]),
+            m: SfenMove(u16::from_le_bytes([rest[2], rest[3]])),
+            game_ply: u16::from_le_bytes([rest[4], rest[5]]),
+            game_result: i8::from_le_bytes([rest[6]]),
+        }
+    }
+
+    /// Encodes the record. The trailing padding byte is zero.
+    pub fn to_bytes(&self) -> [u8; PackedSfenValue::BYTES] {
+        let mut bytes = [0; PackedSfenValue::BYTES];
+        bytes[..32].copy_from_slice(&self.sfen.inner);
+        bytes[32..34].copy_from_slice(&self.score.to_le_bytes());
+        bytes[34..36].copy_from_slice(&self.m.0.to_le_bytes());
+        bytes[36..38].copy_from_slice(&self.game_ply.to_le_bytes());
+        bytes[38..39].copy_from_slice(&self.game_result.to_le_bytes());
+        bytes
+    }
+
+    /// Reads the next record from a stream. Returns `None` at the end of
+    /// the stream.
+    ///
+    /// # Errors
+    ///
+    /// Errors if reading fails, or if the stream ends within a record.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: io::Read>(mut reader: R) -> io::Result<Option<PackedSfenValue>> {
+        let mut bytes = [0; PackedSfenValue::BYTES];
+        let mut filled = 0;
+        while filled < bytes.len() {
+            match reader.read(&mut bytes[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(Some(PackedSfenValue::from_bytes(bytes)))
+    }
+
+    /// Writes the record to a stream.
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing fails.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
+
+/// Converts a signed value to the zigzag-like encoding of the `.binpack`
+/// format.
+#[cfg(feature = "std")]
+fn signed_to_unsigned(value: i16) -> u16 {
+    let mut r = value as u16;
+    if r & 0x8000 != 0 {
+        r ^= 0x7fff;
+    }
+    r.rotate_left(1)
+}
+
+#[cfg(feature = "std")]
+fn unsigned_to_signed(value: u16) -> i16 {
+    let mut r = value.rotate_right(1);
+    if r & 0x8000 != 0 {
+        r ^= 0x7fff;
+    }
+    r as i16
+}
+
+/// Number of bits needed to encode a choice between `n` alternatives.
+#[cfg(feature = "std")]
+fn used_bits(n: usize) -> usize {
+    if n <= 1 {
+        0
+    } else {
+        (usize::BITS - (n - 1).leading_zeros()) as usize
+    }
+}
+
+/// Index of `sq` among the squares of `bitboard`.
+#[cfg(feature = "std")]
+fn index_of(bitboard: Bitboard, sq: Square) -> usize {
+    (bitboard & Bitboard((1 << sq.to_u32()) - 1)).count()
+}
+
+#[cfg(feature = "std")]
+fn invalid_data() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, UnpackSfenError { _priv: () })
+}
+
+/// Compresses a position into the 24 bytes used in the stems of the
+/// `.binpack` format: the occupied squares, followed by a nibble for each
+/// piece.
+#[cfg(feature = "std")]
+fn compress_position(pos: &Chess) -> Result<[u8; 24], PackSfenError> {
+    let board = pos.board();
+    if board.occupied().count() > 32 {
+        return Err(PackSfenError::Board);
+    }
+
+    let castling_rights = pos.castles().castling_rights();
+    for color in Color::ALL {
+        let rooks = castling_rights & Bitboard::from_rank(color.backrank());
+        if rooks.any()
+            && (!rooks.is_subset(Bitboard::CORNERS)
+                || board.king_of(color) != Some(Square::from_coords(File::E, color.backrank())))
+        {
+            return Err(PackSfenError::CastlingRights);
+        }
+    }
+
+    let ep_pawn = pos
+        .ep_square(EnPassantMode::Legal)
+        .map(|ep| Square::from_coords(ep.file(), pos.turn().relative_rank(Rank::Fifth)));
+
+    let mut bytes = [0; 24];
+    bytes[..8].copy_from_slice(&board.occupied().0.to_be_bytes());
+    for (i, sq) in board.occupied().into_iter().enumerate() {
+        let piece = board.piece_at(sq).expect("occupied");
+        let nibble = if Some(sq) == ep_pawn {
+            12
+        } else if piece.role == Role::Rook && castling_rights.contains(sq) {
+            piece.color.fold_wb(13, 14)
+        } else if piece == Color::Black.king() && pos.turn().is_black() {
+            15
+        } else {
+            ((u8::from(piece.role) - 1) << 1) | u8::from(piece.color.is_black())
+        };
+        bytes[8 + i / 2] |= nibble << (4 * (i % 2));
+    }
+    Ok(bytes)
+}
+
+#[cfg(feature = "std")]
+fn decompress_position(bytes: &[u8; 24]) -> Result<Setup, UnpackSfenError> {
+    let mut setup = Setup::empty();
+    let occupied = Bitboard(u64::from_be_bytes(bytes[..8].try_into().expect("size")));
+    for (i, sq) in occupied.into_iter().enumerate() {
+        let nibble = (bytes[8 + i / 2] >> (4 * (i % 2))) & 0xf;
+        let piece = match nibble {
+            12 if sq.rank() == Rank::Fourth => {
+                setup.ep_square = sq.offset(-8);
+                Color::White.pawn()
+            }
+            12 if sq.rank() == Rank::Fifth => {
+                setup.ep_square = sq.offset(8);
+                Color::Black.pawn()
+            }
+            12 => return Err(UnpackSfenError { _priv: () }),
+            13 | 14 => {
+                setup.castling_rights.add(sq);
+                Color::from_white(nibble == 13).rook()
+            }
+            15 => {
+                setup.turn = Color::Black;
+                Color::Black.king()
+            }
+            _ => Piece {
+                color: Color::from_white(nibble & 1 == 0),
+                role: Role::try_from((nibble >> 1) + 1).expect("nibble in range"),
+            },
+        };
+        setup.board.set_piece_at(sq, piece);
+    }
+    Ok(setup)
+}
+
+/// Encodes a move in the 16-bit encoding of the `.binpack` stems, which
+/// differs from [`SfenMove`].
+#[cfg(feature = "std")]
+fn compress_move(m: Move) -> Option<u16> {
+    let (from, to, kind, promotion) = match m {
+        Move::Normal {
+            from,
+            to,
+            promotion: None,
+            ..
+        } => (from, to, 0, 0),
+        Move::Normal {
+            from,
+            to,
+            promotion: Some(promotion),
+            ..
+        } => (from, to, 1, u16::from(promotion) - u16::from(Role::Knight)),
+        Move::Castle { king, rook } => (king, rook, 2, 0),
+        Move::EnPassant { from, to } => (from, to, 3, 0),
+        Move::Put { .. } => return None,
+    };
+    Some((kind << 14) | (u16::from(from) << 8) | (u16::from(to) << 2) | promotion)
+}
+
+#[cfg(feature = "std")]
+fn decompress_move(value: u16) -> UciMove {
+    UciMove::Normal {
+        from: Square::try_from((value >> 8) & 0x3f).expect("masked"),
+        to: Square::try_from((value >> 2) & 0x3f).expect("masked"),
+        promotion: (value >> 14 == 1)
+            .then(|| Role::try_from((value & 0x3) + u16::from(Role::Knight)).expect("masked")),
+    }
+}
+
+/// Candidate target squares of the piece on `from`, in the order of the
+/// movetext encoding of the `.binpack` format, and the number of additional
+/// castling moves.
+#[cfg(feature = "std")]
+fn move_targets(pos: &Chess, from: Square, role: Role) -> (Bitboard, usize) {
+    let board = pos.board();
+    let turn = pos.turn();
+    let ours = board.by_color(turn);
+    match role {
+        Role::Pawn => {
+            let mut targets = attacks::pawn_attacks(turn, from)
+                & (board.by_color(!turn)
+                    | Bitboard::from_iter(pos.ep_square(EnPassantMode::Legal)));
+            if let Some(single) = from.offset(turn.fold_wb(8, -8)) {
+                if !board.occupied().contains(single) {
+                    targets.add(single);
+                    if let Some(double) = single.offset(turn.fold_wb(8, -8)) {
+                        if from.rank() == turn.relative_rank(Rank::Second)
+                            && !board.occupied().contains(double)
+                        {
+                            targets.add(double);
+                        }
+                    }
+                }
+            }
+            (targets, 0)
+        }
+        Role::King => {
+            let castlings = CastlingSide::ALL
+                .into_iter()
+                .filter(|&side| pos.castles().has(turn, side))
+                .count();
+            (attacks::king_attacks(from) & !ours, castlings)
+        }
+        _ => (
+            attacks::attacks(from, role.of(turn), board.occupied()) & !ours,
+            0,
+        ),
+    }
+}
+
+/// Bits of the movetext in the `.binpack` format, most significant bit
+/// first.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct MovetextWriter {
+    bytes: Vec<u8>,
+    bits: usize,
+}
+
+#[cfg(feature = "std")]
+impl MovetextWriter {
+    fn write(&mut self, value: usize, bits: usize) {
+        for i in (0..bits).rev() {
+            if self.bits % 8 == 0 {
+                self.bytes.push(0);
+            }
+            if value & (1 << i) != 0 {
+                *self.bytes.last_mut().expect("pushed") |= 0x80 >> (self.bits % 8);
+            }
+            self.bits += 1;
+        }
+    }
+
+    fn write_score(&mut self, mut value: u16) {
+        loop {
+            self.write(
+                usize::from(value & 0xf) | (usize::from(value > 0xf) << 4),
+                5,
+            );
+            value >>= 4;
+            if value == 0 {
+                break;
+            }
+        }
+    }
+
+    fn write_move(&mut self, pos: &Chess, m: Move) {
+        let from = m.from().expect("no drops in chess");
+        let ours = pos.us();
+        self.write(index_of(ours, from), used_bits(ours.count()));
+
+        let (targets, castlings) = move_targets(pos, from, m.role());
+        let (id, choices) = match m {
+            Move::Normal {
+                to,
+                promotion: Some(promotion),
+                ..
+            } => (
+                index_of(targets, to) * 4 + usize::from(promotion) - usize::from(Role::Knight),
+                targets.count() * 4,
+            ),
+            Move::Castle { .. } => {
+                let side = m.castling_side().expect("castling move");
+                let id = if side.is_queen_side() {
+                    0
+                } else {
+                    usize::from(pos.castles().has(pos.turn(), CastlingSide::QueenSide))
+                };
+                (targets.count() + id, targets.count() + castlings)
+            }
+            _ => (index_of(targets, m.to()), targets.count() + castlings),
+        };
+        self.write(id, used_bits(choices));
+    }
+}
+
+#[cfg(feature = "std")]
+struct MovetextReader<'a> {
+    bytes: &'a [u8],
+    bits: usize,
+}
+
+#[cfg(feature = "std")]
+impl MovetextReader<'_> {
+    fn read(&mut self, bits: usize) -> io::Result<usize> {
+        let mut value = 0;
+        for _ in 0..bits {
+            let byte = self.bytes.get(self.bits / 8).ok_or_else(invalid_data)?;
+            value = (value << 1) | usize::from(byte & (0x80 >> (self.bits % 8)) != 0);
+            self.bits += 1;
+        }
+        Ok(value)
+    }
+
+    fn read_score(&mut self) -> io::Result<u16> {
+        let mut value = 0;
+        for shift in (0..16).step_by(4) {
+            let block = self.read(5)?;
+            value |= ((block & 0xf) as u16) << shift;
+            if block & 0x10 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid_data())
+    }
+
+    fn read_move(&mut self, pos: &Chess) -> io::Result<Move> {
+        let ours = pos.us();
+        let from = ours
+            .into_iter()
+            .nth(self.read(used_bits(ours.count()))?)
+            .ok_or_else(invalid_data)?;
+        let role = pos.board().role_at(from).expect("our piece");
+
+        let (targets, castlings) = move_targets(pos, from, role);
+        let promoting =
+            role == Role::Pawn && from.rank() == pos.turn().relative_rank(Rank::Seventh);
+        let choices = if promoting {
+            targets.count() * 4
+        } else {
+            targets.count() + castlings
+        };
+        let id = self.read(used_bits(choices))?;
+
+        let (to, promotion) = if promoting {
+            let promotion = Role::try_from(id % 4 + usize::from(Role::Knight)).expect("in range");
+            (targets.into_iter().nth(id / 4), Some(promotion))
+        } else if id >= targets.count() {
+            let side = if id == targets.count()
+                && pos.castles().has(pos.turn(), CastlingSide::QueenSide)
+            {
+                CastlingSide::QueenSide
+            } else {
+                CastlingSide::KingSide
+            };
+            (pos.castles().rook(pos.turn(), side), None)
+        } else {
+            (targets.into_iter().nth(id), None)
+        };
+        let to = to.ok_or_else(invalid_data)?;
+
+        UciMove::Normal {
+            from,
+            to,
+            promotion,
+        }
+        .to_move(pos)
+        .map_err(|_| invalid_data())
+    }
+}
+
+/// A training data entry, as stored in the `.binpack` format.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct TrainingDataEntry {
+    /// The position.
+    pub pos: Chess,
+    /// The move played or searched in the position. Must be legal.
+    pub m: Move,
+    /// The score from the point of view of the side to move.
+    pub score: i16,
+    /// The number of plies since the start of the game, at most `0x3fff`.
+    pub game_ply: u16,
+    /// The result from the point of view of the side to move: `1` for a
+    /// win, `0` for a draw, and `-1` for a loss.
+    pub game_result: i8,
+}
+
+#[cfg(feature = "std")]
+impl TrainingDataEntry {
+    /// Tests if `next` can be stored as a continuation of this entry,
+    /// i.e., it follows by playing the move.
+    fn is_continued_by(&self, next: &TrainingDataEntry) -> bool {
+        let mut after = self.pos.clone();
+        after.play_unchecked(self.m);
+        let mut setup = after.to_setup(EnPassantMode::Legal);
+        let next_setup = next.pos.to_setup(EnPassantMode::Legal);
+        setup.fullmoves = next_setup.fullmoves;
+        next.game_ply == self.game_ply.wrapping_add(1)
+            && next.game_result == self.game_result.wrapping_neg()
+            && setup == next_setup
+    }
+
+    fn pack(&self) -> Result<[u8; 32], PackSfenError> {
+        let halfmoves = u16::try_from(self.pos.halfmoves()).map_err(|_| PackSfenError::Counters)?;
+        if self.game_ply > 0x3fff || !(-1..=1).contains(&self.game_result) {
+            return Err(PackSfenError::Counters);
+        }
+        let m = compress_move(self.m).ok_or(PackSfenError::Variant)?;
+        let ply_and_result =
+            self.game_ply | (signed_to_unsigned(i16::from(self.game_result)) << 14);
+
+        let mut bytes = [0; 32];
+        bytes[..24].copy_from_slice(&compress_position(&self.pos)?);
+        bytes[24..26].copy_from_slice(&m.to_be_bytes());
+        bytes[26..28].copy_from_slice(&signed_to_unsigned(self.score).to_be_bytes());
+        bytes[28..30].copy_from_slice(&ply_and_result.to_be_bytes());
+        bytes[30..32].copy_from_slice(&halfmoves.to_be_bytes());
+        Ok(bytes)
+    }
+
+    fn unpack(bytes: &[u8; 32]) -> io::Result<TrainingDataEntry> {
+        let word = |i: usize| u16::from_be_bytes([bytes[i], bytes[i + 1]]);
+        let game_ply = word(28) & 0x3fff;
+
+        let mut setup = decompress_position(bytes[..24].try_into().expect("size"))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        setup.halfmoves = u32::from(word(30));
+        setup.fullmoves = NonZeroU32::MIN.saturating_add(u32::from(game_ply / 2));
+        let pos = Chess::from_setup(setup, CastlingMode::Standard)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let m = decompress_move(word(24))
+            .to_move(&pos)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(TrainingDataEntry {
+            pos,
+            m,
+            score: unsigned_to_signed(word(26)),
+            game_ply,
+            game_result: unsigned_to_signed(word(28) >> 14) as i8,
+        })
+    }
+}
+
+/// Magic bytes at the start of each chunk of a `.binpack` file.
+#[cfg(feature = "std")]
+const BINPACK_MAGIC: [u8; 4] = *b"BINP";
+
+/// Chunks are written once they exceed this size.
+#[cfg(feature = "std")]
+const BINPACK_CHUNK_SIZE: usize = 1 << 20;
+
+/// Larger chunks are rejected when reading.
+#[cfg(feature = "std")]
+const BINPACK_MAX_CHUNK_SIZE: usize = 100 << 20;
+
+/// Writes training data in the `.binpack` format.
+///
+/// Entries are buffered in chunks, so call [`BinpackWriter::finish()`]
+/// after the last entry.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct BinpackWriter<W> {
+    inner: W,
+    chunk: Vec<u8>,
+    last: Option<TrainingDataEntry>,
+    last_score: i16,
+    plies: u16,
+    movetext: MovetextWriter,
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> BinpackWriter<W> {
+    /// Creates a writer.
+    pub fn new(inner: W) -> BinpackWriter<W> {
+        BinpackWriter {
+            inner,
+            chunk: Vec::new(),
+            last: None,
+            last_score: 0,
+            plies: 0,
+            movetext: MovetextWriter::default(),
+        }
+    }
+
+    /// Adds an entry. If it follows from the previous entry by playing its
+    /// move, it is stored compactly as a continuation.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`io::ErrorKind::InvalidInput`] if the entry is not
+    /// representable (see [`PackSfenError`]) or the move is not legal, or
+    /// if writing a completed chunk fails.
+    pub fn write(&mut self, entry: &TrainingDataEntry) -> io::Result<()> {
+        if !entry.pos.is_legal(entry.m) {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+
+        match self.last {
+            Some(ref last) if self.plies < u16::MAX && last.is_continued_by(entry) => {
+                self.movetext.write_move(&entry.pos, entry.m);
+                self.movetext.write_score(signed_to_unsigned(
+                    entry.score.wrapping_sub(self.last_score),
+                ));
+                self.plies += 1;
+            }
+            _ => {
+                let stem = entry
+                    .pack()
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+                self.finish_chain();
+                if self.chunk.len() >= BINPACK_CHUNK_SIZE {
+                    self.flush_chunk()?;
+                }
+                self.chunk.extend_from_slice(&stem);
+            }
+        }
+
+        self.last_score = entry.score.wrapping_neg();
+        self.last = Some(entry.clone());
+        Ok(())
+    }
+
+    fn finish_chain(&mut self) {
+        if self.last.take().is_some() {
+            self.chunk.extend_from_slice(&self.plies.to_be_bytes());
+            self.chunk.append(&mut self.movetext.bytes);
+            self.movetext.bits = 0;
+            self.plies = 0;
+        }
+    }
+
+    fn flush_chunk(&mut self) -> io::Result<()> {
+        if !self.chunk.is_empty() {
+            let size = u32::try_from(self.chunk.len()).expect("chunk size");
+            self.inner.write_all(&BINPACK_MAGIC)?;
+            self.inner.write_all(&size.to_le_bytes())?;
+            self.inner.write_all(&self.chunk)?;
+            self.chunk.clear();
+        }
+        Ok(())
+    }
+
+    /// Writes all remaining entries, and returns the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Errors if writing fails.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.finish_chain();
+        self.flush_chunk()?;
+        Ok(self.inner)
+    }
+}
+
+/// Position in a chain of entries.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct BinpackChain {
+    entry: TrainingDataEntry,
+    last_score: i16,
+    remaining: u16,
+    bits: usize,
+}
+
+/// Reads training data in the `.binpack` format.
+///
+/// Iterates over all entries, including continuations.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct BinpackReader<R> {
+    inner: R,
+    chunk: Vec<u8>,
+    offset: usize,
+    chain: Option<BinpackChain>,
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> BinpackReader<R> {
+    /// Creates a reader.
+    pub fn new(inner: R) -> BinpackReader<R> {
+        BinpackReader {
+            inner,
+            chunk: Vec::new(),
+            offset: 0,
+            chain: None,
+        }
+    }
+
+    /// Reads the next entry. Returns `None` at the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Errors if reading fails, or with [`io::ErrorKind::InvalidData`] if
+    /// the data is invalid.
+    pub fn read_entry(&mut self) -> io::Result<Option<TrainingDataEntry>> {
+        if let Some(chain) = self.chain.as_mut() {
+            let mut pos = chain.entry.pos.clone();
+            pos.play_unchecked(chain.entry.m);
+
+            let mut reader = MovetextReader {
+                bytes: &self.chunk,
+                bits: chain.bits,
+            };
+            let m = reader.read_move(&pos)?;
+            let score = chain
+                .last_score
+                .wrapping_add(unsigned_to_signed(reader.read_score()?));
+
+            chain.entry = TrainingDataEntry {
+                pos,
+                m,
+                score,
+                game_ply: chain.entry.game_ply.wrapping_add(1),
+                game_result: chain.entry.game_result.wrapping_neg(),
+            };
+            chain.last_score = score.wrapping_neg();
+            chain.remaining -= 1;
+            chain.bits = reader.bits;
+
+            let entry = chain.entry.clone();
+            if chain.remaining == 0 {
+                self.offset = chain.bits.div_ceil(8);
+                self.chain = None;
+            }
+            return Ok(Some(entry));
+        }
+
+        if self.offset >= self.chunk.len() && !self.read_chunk()? {
+            return Ok(None);
+        }
+
+        let stem = self
+            .chunk
+            .get(self.offset..self.offset + 34)
+            .ok_or_else(invalid_data)?;
+        let entry = TrainingDataEntry::unpack(stem[..32].try_into().expect("size"))?;
+        let plies = u16::from_be_bytes([stem[32], stem[33]]);
+        self.offset += 34;
+        if plies > 0 {
+            self.chain = Some(BinpackChain {
+                entry: entry.clone(),
+                last_score: entry.score.wrapping_neg(),
+                remaining: plies,
+                bits: self.offset * 8,
+            });
+        }
+        Ok(Some(entry))
+    }
+
+    fn read_chunk(&mut self) -> io::Result<bool> {
+        let mut header = [0; 8];
+        let mut filled = 0;
+        while filled < header.len() {
+            match self.inner.read(&mut header[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) => return Err(err),
+            }
+        }
+
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if header[..4] != BINPACK_MAGIC || size > BINPACK_MAX_CHUNK_SIZE {
+            return Err(invalid_data());
+        }
+        self.chunk.resize(size, 0);
+        self.inner.read_exact(&mut self.chunk)?;
+        self.offset = 0;
+        Ok(true)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> Iterator for BinpackReader<R> {
+    type Item = io::Result<TrainingDataEntry>;
+
+    fn next(&mut self) -> Option<io::Result<TrainingDataEntry>> {
+        self.read_entry().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CastlingMode, Chess, Position, fen::Fen};
+
+    #[test]
+    fn test_packed_sfen_roundtrip() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 100 300",
+            "4k3/8/8/8/8/8/8/R3K2R b Q - 0 42",
+            "4k3/8/8/8/8/8/8/R3K2R b Q - 0 70000",
+        ] {
+            let setup = fen.parse::<Fen>().unwrap().into_setup();
+            let result = PackedSfen::pack(&setup);
+            if setup.fullmoves.get() > 0xffff {
+                assert!(matches!(result, Err(PackSfenError::Counters)));
+                continue;
+            }
+            assert_eq!(result.unwrap().unpack().unwrap(), setup, "{fen}");
+        }
+
+        assert_eq!(
+            PackedSfen::pack(&Setup::default()).unwrap().to_bytes()[..2],
+            [0x08, 0xfe]
+        );
+    }
+
+    #[test]
+    fn test_packed_sfen_unrepresentable() {
+        let setup = "4k3/8/8/8/8/8/8/RR2K3 w B - 0 1"
+            .parse::<Fen>()
+            .unwrap()
+            .into_setup();
+        assert!(matches!(
+            PackedSfen::pack(&setup),
+            Err(PackSfenError::CastlingRights)
+        ));
+        assert!(matches!(
+            PackedSfen::pack(&Setup::empty()),
+            Err(PackSfenError::Board)
+        ));
+    }
+
+    #[test]
+    fn test_sfen_move() {
+        for fen in [
+            "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ] {
+            let pos: Chess = fen
+                .parse::<Fen>()
+                .unwrap()
+                .into_position(CastlingMode::Standard)
+                .unwrap();
+            for m in pos.legal_moves() {
+                let sfen_move = SfenMove::from_move(m).unwrap();
+                assert_eq!(sfen_move.to_uci().to_move(&pos).ok(), Some(m));
+            }
+        }
+        let m = SfenMove::from_move(Move::Normal {
+            role: Role::Pawn,
+            from: Square::B2,
+            capture: Some(Role::Rook),
+            to: Square::A1,
+            promotion: Some(Role::Queen),
+        })
+        .unwrap();
+        assert_eq!(m.0, (1 << 14) | (3 << 12) | (9 << 6));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_packed_sfen_value_stream() {
+        use alloc::vec::Vec;
+
+        use crate::EnPassantMode;
+
+        let pos = Chess::default();
+        let records: [PackedSfenValue; 2] = [
+            PackedSfenValue {
+                sfen: PackedSfen::pack(&pos.to_setup(EnPassantMode::Legal)).unwrap(),
+                score: -123,
+                m: SfenMove(0x1234),
+                game_ply: 0,
+                game_result: -1,
+            },
+            PackedSfenValue {
+                sfen: PackedSfen::from_bytes([0xaa; 32]),
+                score: i16::MAX,
+                m: SfenMove(0),
+                game_ply: 4321,
+                game_result: 1,
+            },
+        ];
+
+        let mut buf = Vec::new();
+        for record in &records {
+            record.write_to(&mut buf).unwrap();
+        }
+        assert_eq!(buf.len(), 2 * PackedSfenValue::BYTES);
+
+        let mut reader = &buf[..];
+        for record in &records {
+            assert_eq!(
+                PackedSfenValue::read_from(&mut reader).unwrap().as_ref(),
+                Some(record)
+            );
+        }
+        assert!(PackedSfenValue::read_from(&mut reader).unwrap().is_none());
+        assert!(PackedSfenValue::read_from(&buf[..10]).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_binpack_roundtrip() {
+        use alloc::vec::Vec;
+
+        use crate::san::San;
+
+        let mut entries = Vec::new();
+        for (fen, sans) in [
+            (
+                "r3k2r/1P4pp/8/8/4p3/8/3P2PP/R3K2R w KQkq - 3 20",
+                &["d4", "exd3", "bxa8=N", "O-O", "O-O-O", "Rf1", "Nb6"][..],
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                &["e4", "c5", "Nf3", "d6", "Bb5+", "Bd7"][..],
+            ),
+        ] {
+            let mut pos: Chess = fen
+                .parse::<Fen>()
+                .unwrap()
+                .into_position(CastlingMode::Standard)
+                .unwrap();
+            let mut game_result = 1;
+            for (i, san) in sans.iter().enumerate() {
+                let m = san.parse::<San>().unwrap().to_move(&pos).unwrap();
+                entries.push(TrainingDataEntry {
+                    pos: pos.clone(),
+                    m,
+                    score: [-32000, 5, 300, -17, 4000, 0, 1][i],
+                    game_ply: 38 + i as u16,
+                    game_result,
+                });
+                pos.play_unchecked(m);
+                game_result = -game_result;
+            }
+        }
+        entries.swap(5, 6); // Break a chain
+
+        let mut writer = BinpackWriter::new(Vec::new());
+        for entry in &entries {
+            writer.write(entry).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes[..4], *b"BINP");
+        assert!(bytes.len() < 8 + 5 * 34); // 4 chains with continuations
+
+        let read = BinpackReader::new(&bytes[..])
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(read.len(), entries.len());
+        for (a, b) in entries.iter().zip(&read) {
+            assert_eq!(
+                a.pos.to_setup(EnPassantMode::Legal).board,
+                b.pos.to_setup(EnPassantMode::Legal).board
+            );
+            assert_eq!(a.pos.turn(), b.pos.turn());
+            assert_eq!(
+                a.pos.castles().castling_rights(),
+                b.pos.castles().castling_rights()
+            );
+            assert_eq!(
+                a.pos.ep_square(EnPassantMode::Legal),
+                b.pos.ep_square(EnPassantMode::Legal)
+            );
+            assert_eq!(a.pos.halfmoves(), b.pos.halfmoves());
+            assert_eq!(a.m, b.m);
+            assert_eq!(a.score, b.score);
+            assert_eq!(a.game_ply, b.game_ply);
+            assert_eq!(a.game_result, b.game_result);
+        }
+
+        assert!(BinpackReader::new(&bytes[..bytes.len() - 1]).any(|entry| entry.is_err()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_binpack_stem() {
+        let entry = TrainingDataEntry {
+            pos: Chess::default(),
+            m: Move::Normal {
+                role: Role::Pawn,
+                from: Square::E2,
+                capture: None,
+                to: Square::E4,
+                promotion: None,
+            },
+            score: -1,
+            game_ply: 0,
+            game_result: -1,
+        };
+        let bytes = entry.pack().unwrap();
+        assert_eq!(bytes[..8], 0xffff_0000_0000_ffff_u64.to_be_bytes());
+        assert_eq!(bytes[8..12], [0x2d, 0x84, 0x4a, 0xd2]);
+        assert_eq!(
+            bytes[24..],
+            [0x0c, 0x70, 0x00, 0x01, 0x40, 0x00, 0x00, 0x00]
+        );
+
+        assert_eq!(signed_to_unsigned(0), 0);
+        assert_eq!(signed_to_unsigned(1), 2);
+        assert_eq!(signed_to_unsigned(-1), 1);
+        for value in [0, 1, -1, 1234, i16::MIN, i16::MAX] {
+            assert_eq!(unsigned_to_signed(signed_to_unsigned(value)), value);
+        }
+    }
+}
@@ -85,6 +85,9 @@ impl<Config> bincode::Decode<Config> for Piece {
 #[cfg(feature = "bincode")]
 bincode::impl_borrow_decode!(Piece);
 
+#[cfg(feature = "serde")]
+serde_char_impl! { Piece, "piece character" }
+
 /// `Standard` or `Chess960`.
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub enum CastlingMode {
@@ -168,7 +171,9 @@ mod tests {
 }
 
 /// The number of checks the respective side needs to give in order to win
-/// (in a game of Three-Check).
+/// (in a game of Three-Check, or more generally N-Check).
+///
+/// At most [`RemainingChecks::MAX`] checks are supported.
 ///
 /// # Examples
 ///
@@ -185,6 +190,11 @@ mod tests {
 ///
 /// assert!(remaining_checks.white.is_zero());
 /// ```
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize),
+    rkyv(derive(Debug))
+)]
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
 pub struct RemainingChecks(u32);
 
@@ -195,14 +205,17 @@ impl Default for RemainingChecks {
 }
 
 impl RemainingChecks {
+    /// The maximum number of remaining checks, for example in Five-Check.
+    pub const MAX: RemainingChecks = RemainingChecks(15);
+
     /// Constructs a new [`RemainingChecks`] value.
     ///
     /// # Panics
     ///
-    /// Panics if `n > 3`.
+    /// Panics if `n > 15`.
     #[track_caller]
     pub const fn new(n: u32) -> RemainingChecks {
-        assert!(n <= 3);
+        assert!(n <= RemainingChecks::MAX.0);
         RemainingChecks(n)
     }
 
@@ -219,7 +232,7 @@ impl RemainingChecks {
 #[cfg(feature = "arbitrary")]
 impl arbitrary::Arbitrary<'_> for RemainingChecks {
     fn arbitrary(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<RemainingChecks> {
-        u.int_in_range::<u8>(0..=3)
+        u.int_in_range::<u8>(0..=15)
             .map(|n| RemainingChecks(u32::from(n)))
     }
 
@@ -250,7 +263,7 @@ macro_rules! try_remaining_checks_from_int_impl {
             #[inline]
             fn try_from(value: $t) -> Result<RemainingChecks, Self::Error> {
                 let n = u32::try_from(value)?;
-                if n <= 3 {
+                if n <= RemainingChecks::MAX.0 {
                     Ok(RemainingChecks::new(n))
                 } else {
                     Err(out_of_range_error())
@@ -298,3 +311,16 @@ impl<Config> bincode::Decode<Config> for RemainingChecks {
 
 #[cfg(feature = "bincode")]
 bincode::impl_borrow_decode!(RemainingChecks);
+
+#[cfg(feature = "rkyv")]
+impl<D> rkyv::Deserialize<RemainingChecks, D> for ArchivedRemainingChecks
+where
+    D: rkyv::rancor::Fallible + ?Sized,
+    D::Error: rkyv::rancor::Source,
+{
+    fn deserialize(&self, _deserializer: &mut D) -> Result<RemainingChecks, D::Error> {
+        use rkyv::rancor::Source as _;
+
+        RemainingChecks::try_from(self.0.to_native()).map_err(D::Error::new)
+    }
+}
@@ -1,4 +1,5 @@
-//! Parse and write moves in Universal Chess Interface representation.
+//! Parse and write moves in Universal Chess Interface representation, and
+//! parse engine options (see [`EngineOption`], requires `alloc`).
 //!
 //! # Examples
 //!
@@ -64,9 +65,14 @@
 //!
 //! [`Move`]: super::Move
 
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
 use core::{error, fmt, str::FromStr};
 
-use crate::{CastlingMode, CastlingSide, Move, Position, Rank, Role, Square, util::AppendAscii};
+use crate::{
+    CastlingMode, CastlingSide, Move, Position, Rank, Role, Square,
+    util::{AppendAscii, ErrorPayload},
+};
 
 /// Error when parsing an invalid UCI move.
 #[derive(Clone, Debug)]
@@ -92,6 +98,137 @@ impl fmt::Display for IllegalUciMoveError {
 
 impl error::Error for IllegalUciMoveError {}
 
+/// Reason for rejecting a UCI move.
+#[derive(Clone, Debug)]
+pub enum UciMoveError {
+    /// The move is syntactically invalid.
+    Parse(ParseUciMoveError),
+    /// The move is not legal in the position.
+    Illegal(IllegalUciMoveError),
+}
+
+impl fmt::Display for UciMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UciMoveError::Parse(err) => err.fmt(f),
+            UciMoveError::Illegal(err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for UciMoveError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            UciMoveError::Parse(err) => Some(err),
+            UciMoveError::Illegal(err) => Some(err),
+        }
+    }
+}
+
+impl From<ParseUciMoveError> for UciMoveError {
+    fn from(err: ParseUciMoveError) -> UciMoveError {
+        UciMoveError::Parse(err)
+    }
+}
+
+impl From<IllegalUciMoveError> for UciMoveError {
+    fn from(err: IllegalUciMoveError) -> UciMoveError {
+        UciMoveError::Illegal(err)
+    }
+}
+
+/// Error when a move in a sequence is rejected by [`play_uci_moves()`].
+///
+/// The position is boxed if the `alloc` feature is enabled, so that
+/// `Result<P, PlayUciMovesError<P>>` is not much larger than `P`.
+#[derive(Debug)]
+pub struct PlayUciMovesError<P> {
+    index: usize,
+    error: UciMoveError,
+    position: ErrorPayload<P>,
+}
+
+impl<P> PlayUciMovesError<P> {
+    /// The zero-based index of the rejected move in the sequence.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The reason for rejecting the move.
+    pub fn error(&self) -> &UciMoveError {
+        &self.error
+    }
+
+    /// The position before the rejected move, after all previous moves.
+    pub fn position(&self) -> &P {
+        self.position.get()
+    }
+
+    /// Returns the position before the rejected move.
+    pub fn into_position(self) -> P {
+        self.position.into_inner()
+    }
+}
+
+impl<P> fmt::Display for PlayUciMovesError<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at index {}", self.error, self.index)
+    }
+}
+
+impl<P: fmt::Debug> error::Error for PlayUciMovesError<P> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Parses, validates and plays a sequence of UCI moves, starting from
+/// `pos`.
+///
+/// # Errors
+///
+/// Returns [`PlayUciMovesError`] with the index of the first invalid or
+/// illegal move, the reason, and the position before that move.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Chess, Position, uci::{UciMoveError, play_uci_moves}};
+///
+/// let pos = play_uci_moves(Chess::default(), ["e2e4", "e7e5", "g1f3"])?;
+/// assert_eq!(pos.fullmoves().get(), 2);
+///
+/// let err = play_uci_moves(Chess::default(), ["e2e4", "e2e4"]).unwrap_err();
+/// assert_eq!(err.index(), 1);
+/// assert!(matches!(err.error(), UciMoveError::Illegal(_)));
+/// assert_eq!(err.position().fullmoves().get(), 1);
+/// # Ok::<_, shakmaty::uci::PlayUciMovesError<Chess>>(())
+/// ```
+#[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
+pub fn play_uci_moves<P, I>(mut pos: P, moves: I) -> Result<P, PlayUciMovesError<P>>
+where
+    P: Position,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    for (index, uci) in moves.into_iter().enumerate() {
+        match UciMove::from_ascii(uci.as_ref())
+            .map_err(UciMoveError::from)
+            .and_then(|uci| uci.to_move(&pos).map_err(UciMoveError::from))
+        {
+            Ok(m) => pos.play_unchecked(m),
+            Err(error) => {
+                return Err(PlayUciMovesError {
+                    index,
+                    error,
+                    position: ErrorPayload::new(pos),
+                });
+            }
+        }
+    }
+    Ok(pos)
+}
+
 /// A move as represented in the UCI protocol.
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
@@ -471,10 +608,213 @@ impl Move {
     }
 }
 
+/// Error when parsing an invalid `option` line.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseEngineOptionError;
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for ParseEngineOptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid option line")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl error::Error for ParseEngineOptionError {}
+
+/// Error when setting an option to a value that is not accepted by its
+/// [`OptionKind`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidOptionValueError;
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for InvalidOptionValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid option value")
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl error::Error for InvalidOptionValueError {}
+
+/// Type, default value and constraints of an engine option.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OptionKind {
+    /// A boolean option (`type check`).
+    Check { default: bool },
+    /// An integer option in an inclusive range (`type spin`).
+    Spin { default: i64, min: i64, max: i64 },
+    /// A choice of predefined values (`type combo`).
+    Combo { default: String, vars: Vec<String> },
+    /// An action without value (`type button`).
+    Button,
+    /// A text option (`type string`). The special value `<empty>` is
+    /// parsed as an empty string.
+    String { default: String },
+}
+
+#[cfg(feature = "alloc")]
+impl OptionKind {
+    /// Validates a value for a `setoption` command.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidOptionValueError`] if `value` is not `true` or
+    /// `false` for check options, not an integer in range for spin options,
+    /// not one of the predefined values (ignoring ASCII case) for combo
+    /// options, or not empty for buttons.
+    pub fn validate(&self, value: &str) -> Result<(), InvalidOptionValueError> {
+        let valid = match self {
+            OptionKind::Check { .. } => value == "true" || value == "false",
+            OptionKind::Spin { min, max, .. } => value
+                .parse::<i64>()
+                .is_ok_and(|value| *min <= value && value <= *max),
+            OptionKind::Combo { vars, .. } => {
+                vars.iter().any(|var| var.eq_ignore_ascii_case(value))
+            }
+            OptionKind::Button => value.is_empty(),
+            OptionKind::String { .. } => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(InvalidOptionValueError)
+        }
+    }
+}
+
+/// An engine option, as announced by an `option` line during the handshake.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EngineOption {
+    pub name: String,
+    pub kind: OptionKind,
+}
+
+#[cfg(feature = "alloc")]
+impl EngineOption {
+    /// Parses an `option` line.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseEngineOptionError`] if the line does not start with
+    /// `option`, if the name or type are missing, or if a default value or
+    /// bound is missing or invalid for the type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::uci::{EngineOption, OptionKind};
+    ///
+    /// let option: EngineOption = "option name Hash type spin default 16 min 1 max 33554432".parse()?;
+    /// assert_eq!(option.name, "Hash");
+    /// assert_eq!(option.kind, OptionKind::Spin { default: 16, min: 1, max: 33554432 });
+    /// assert!(option.kind.validate("64").is_ok());
+    /// assert!(option.kind.validate("0").is_err());
+    /// # Ok::<_, shakmaty::uci::ParseEngineOptionError>(())
+    /// ```
+    pub fn from_line(line: &str) -> Result<EngineOption, ParseEngineOptionError> {
+        let mut tokens = line.split_ascii_whitespace();
+        if tokens.next() != Some("option") {
+            return Err(ParseEngineOptionError);
+        }
+
+        let mut name = None;
+        let mut kind = None;
+        let mut default = None;
+        let mut min = None;
+        let mut max = None;
+        let mut vars = Vec::new();
+
+        let mut key = None;
+        let mut value = Vec::new();
+        for token in tokens.map(Some).chain([None]) {
+            if let Some(next) = token {
+                let is_key = match next {
+                    "name" => key.is_none(),
+                    "type" | "default" | "min" | "max" | "var" => key.is_some(),
+                    _ => false,
+                };
+                if !is_key {
+                    value.push(next);
+                    continue;
+                }
+            }
+            if let Some(key) = key {
+                let joined = value.join(" ");
+                match key {
+                    "name" => name = Some(joined),
+                    "type" => kind = Some(joined),
+                    "default" => default = Some(joined),
+                    "min" => min = Some(joined),
+                    "max" => max = Some(joined),
+                    _ => vars.push(joined),
+                }
+            }
+            key = token;
+            value.clear();
+        }
+
+        let name = name
+            .filter(|name| !name.is_empty())
+            .ok_or(ParseEngineOptionError)?;
+        let default = default.ok_or(ParseEngineOptionError);
+        let kind = match kind.as_deref() {
+            Some("check") => OptionKind::Check {
+                default: match default?.as_str() {
+                    "true" => true,
+                    "false" => false,
+                    _ => return Err(ParseEngineOptionError),
+                },
+            },
+            Some("spin") => {
+                let parse = |value: Option<String>| {
+                    value
+                        .and_then(|value| value.parse().ok())
+                        .ok_or(ParseEngineOptionError)
+                };
+                OptionKind::Spin {
+                    default: parse(default.ok())?,
+                    min: parse(min)?,
+                    max: parse(max)?,
+                }
+            }
+            Some("combo") => OptionKind::Combo {
+                default: default?,
+                vars,
+            },
+            Some("button") => OptionKind::Button,
+            Some("string") => OptionKind::String {
+                default: default.map(|default| {
+                    if default == "<empty>" {
+                        String::new()
+                    } else {
+                        default
+                    }
+                })?,
+            },
+            _ => return Err(ParseEngineOptionError),
+        };
+        Ok(EngineOption { name, kind })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for EngineOption {
+    type Err = ParseEngineOptionError;
+
+    fn from_str(line: &str) -> Result<EngineOption, ParseEngineOptionError> {
+        EngineOption::from_line(line)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Chess, fen::Fen};
+    use crate::{Chess, Color, fen::Fen};
 
     #[cfg(feature = "alloc")]
     #[test]
@@ -635,4 +975,84 @@ mod tests {
             "nbkr1nbr/ppp1pppp/3p4/8/5Pq1/6N1/PPPPPBPP/NBQR1RK1 b - - 5 4"
         );
     }
+
+    #[test]
+    fn test_play_uci_moves() {
+        let pos = play_uci_moves(Chess::default(), ["e2e4", "e7e5", "e1e2"]).expect("legal");
+        assert_eq!(pos.board().king_of(Color::White), Some(Square::E2));
+
+        let err =
+            play_uci_moves(Chess::default(), ["e2e4", "e7e5", "xyz", "e1e2"]).expect_err("invalid");
+        assert_eq!(err.index(), 2);
+        assert!(matches!(err.error(), UciMoveError::Parse(_)));
+        assert_eq!(err.position().turn(), Color::White);
+
+        let err =
+            play_uci_moves(Chess::default(), [b"e2e4".as_slice(), b"e1e2"]).expect_err("illegal");
+        assert_eq!(err.index(), 1);
+        assert!(matches!(err.error(), UciMoveError::Illegal(_)));
+        assert_eq!(
+            err.into_position().board().piece_at(Square::E4),
+            Some(Color::White.pawn())
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_parse_option() {
+        use alloc::{
+            string::{String, ToString as _},
+            vec,
+        };
+
+        let option = EngineOption::from_line("option name Clear Hash type button").expect("valid");
+        assert_eq!(option.name, "Clear Hash");
+        assert_eq!(option.kind, OptionKind::Button);
+        assert!(option.kind.validate("").is_ok());
+
+        let option =
+            EngineOption::from_line("option name Ponder type check default false").expect("valid");
+        assert_eq!(option.kind, OptionKind::Check { default: false });
+        assert!(option.kind.validate("true").is_ok());
+        assert!(option.kind.validate("yes").is_err());
+
+        let option = EngineOption::from_line(
+            "option name Style type combo default Normal var Solid var Normal var Risky Play",
+        )
+        .expect("valid");
+        assert_eq!(
+            option.kind,
+            OptionKind::Combo {
+                default: "Normal".to_string(),
+                vars: vec![
+                    "Solid".to_string(),
+                    "Normal".to_string(),
+                    "Risky Play".to_string()
+                ],
+            }
+        );
+        assert!(option.kind.validate("risky play").is_ok());
+        assert!(option.kind.validate("Aggressive").is_err());
+
+        let option = EngineOption::from_line("option name SyzygyPath type string default <empty>")
+            .expect("valid");
+        assert_eq!(option.name, "SyzygyPath");
+        assert_eq!(
+            option.kind,
+            OptionKind::String {
+                default: String::new()
+            }
+        );
+
+        let option =
+            EngineOption::from_line("option name Threads type spin default 1 min 1 max 1024")
+                .expect("valid");
+        assert!(option.kind.validate("1024").is_ok());
+        assert!(option.kind.validate("1025").is_err());
+        assert!(option.kind.validate("x").is_err());
+
+        assert!(EngineOption::from_line("option name Threads type spin default 1").is_err());
+        assert!(EngineOption::from_line("option name Foo type unknown").is_err());
+        assert!(EngineOption::from_line("option type check default true").is_err());
+    }
 }
@@ -25,6 +25,55 @@ pub(crate) fn try_from_slice_error() -> TryFromSliceError {
     <[u8; 0]>::try_from(&[0u8][..]).unwrap_err()
 }
 
+/// Payload of an error, boxed if `alloc` is available, so that the error
+/// path does not inflate the size of commonly returned `Result`s.
+#[derive(Clone)]
+pub(crate) struct ErrorPayload<T> {
+    #[cfg(feature = "alloc")]
+    inner: alloc::boxed::Box<T>,
+    #[cfg(not(feature = "alloc"))]
+    inner: T,
+}
+
+impl<T> ErrorPayload<T> {
+    #[cfg(feature = "alloc")]
+    #[cold]
+    pub(crate) fn new(value: T) -> ErrorPayload<T> {
+        ErrorPayload {
+            inner: alloc::boxed::Box::new(value),
+        }
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    pub(crate) fn new(value: T) -> ErrorPayload<T> {
+        ErrorPayload { inner: value }
+    }
+
+    pub(crate) fn get(&self) -> &T {
+        &self.inner
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn into_inner(self) -> T {
+        *self.inner
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    pub(crate) fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub(crate) fn map<U, F: FnOnce(T) -> U>(self, f: F) -> ErrorPayload<U> {
+        ErrorPayload::new(f(self.into_inner()))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ErrorPayload<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
 macro_rules! from_enum_as_int_impl {
     ($from:ty, $($t:ident)+) => {
         $(impl From<$from> for $t {
@@ -36,6 +85,95 @@ macro_rules! from_enum_as_int_impl {
     }
 }
 
+/// Implements `serde` (de)serialization as a single character string, using
+/// the `char()` and `from_char()` methods of the type.
+#[cfg(feature = "serde")]
+macro_rules! serde_char_impl {
+    ($type:ident, $expecting:literal) => {
+        impl serde::Serialize for $type {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.char().encode_utf8(&mut [0; 4]))
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct CharVisitor;
+
+                impl serde::de::Visitor<'_> for CharVisitor {
+                    type Value = $type;
+
+                    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        formatter.write_str($expecting)
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> Result<$type, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        let mut chars = value.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(ch), None) => $type::from_char(ch),
+                            _ => None,
+                        }
+                        .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+                    }
+                }
+
+                deserializer.deserialize_str(CharVisitor)
+            }
+        }
+    };
+}
+
+/// Implements `serde` (de)serialization as a string, using the given
+/// `&'static str` method of the type and its `FromStr` implementation.
+#[cfg(feature = "serde")]
+macro_rules! serde_static_str_impl {
+    ($type:ident, $as_str:ident, $expecting:literal) => {
+        impl serde::Serialize for $type {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.$as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct StrVisitor;
+
+                impl serde::de::Visitor<'_> for StrVisitor {
+                    type Value = $type;
+
+                    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        formatter.write_str($expecting)
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> Result<$type, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        value.parse().map_err(serde::de::Error::custom)
+                    }
+                }
+
+                deserializer.deserialize_str(StrVisitor)
+            }
+        }
+    };
+}
+
 pub(crate) trait AppendAscii {
     type Error;
 
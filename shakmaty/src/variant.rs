@@ -3,16 +3,24 @@
 //! These are games played with normal chess pieces but special rules.
 //! Every chess variant implements [`FromSetup`] and [`Position`].
 
-use core::{error, fmt, num::NonZeroU32, str, str::FromStr};
+use core::{error, fmt, mem::MaybeUninit, num::NonZeroU32, str, str::FromStr};
 
+#[cfg(feature = "bincode")]
+use crate::packed::PackedSetup;
+pub use crate::perft::{PERFT_VECTORS, PerftMismatch, PerftVector, perft_self_test};
 pub use crate::position::{
     Chess,
-    variant::{Antichess, Atomic, Crazyhouse, Horde, KingOfTheHill, RacingKings, ThreeCheck},
+    variant::{
+        Antichess, Atomic, Bughouse, BughouseBoard, CaptureAll, Crazyhouse, Custom, FogOfWar,
+        Horde, KingOfTheHill, Makruk, NoCastling, RacingKings, ReachSquare, Rules, Shatranj,
+        ThreeCheck, WinCondition, WithWinCondition,
+    },
 };
 use crate::{
     Bitboard, Board, ByColor, ByRole, Castles, CastlingMode, CastlingSide, Color, EnPassantMode,
-    FromSetup, Move, MoveList, Outcome, Position, PositionError, RemainingChecks, Role, Setup,
-    Square,
+    FromSetup, InsufficientMaterialRule, KnownOutcome, LegalMovesIter, Move, MoveList, Outcome,
+    Position, PositionError, RemainingChecks, Role, Setup, Square,
+    fen::{Fen, ParsePositionError},
 };
 
 /// Discriminant of [`VariantPosition`].
@@ -37,6 +45,14 @@ pub enum Variant {
     RacingKings,
     /// See [`Horde`].
     Horde,
+    /// See [`FogOfWar`].
+    FogOfWar,
+    /// See [`Shatranj`].
+    Shatranj,
+    /// See [`Makruk`].
+    Makruk,
+    /// See [`NoCastling`].
+    NoCastling,
 }
 
 impl Variant {
@@ -52,6 +68,10 @@ impl Variant {
             Variant::Crazyhouse => "crazyhouse",
             Variant::RacingKings => "racingkings",
             Variant::Horde => "horde",
+            Variant::FogOfWar => "fogofwar",
+            Variant::Shatranj => "shatranj",
+            Variant::Makruk => "makruk",
+            Variant::NoCastling => "nocastle",
         }
     }
 
@@ -67,22 +87,83 @@ impl Variant {
             "crazyhouse" => Variant::Crazyhouse,
             "racingkings" => Variant::RacingKings,
             "horde" => Variant::Horde,
+            "fogofwar" => Variant::FogOfWar,
+            "shatranj" => Variant::Shatranj,
+            "makruk" => Variant::Makruk,
+            "nocastle" => Variant::NoCastling,
             _ => return Err(ParseVariantError),
         })
     }
 
+    /// Selects a variant based on the value of the `UCI_Variant` option,
+    /// also accepting common aliases used by engines, like `giveaway` for
+    /// Antichess.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::variant::Variant;
+    ///
+    /// assert_eq!(Variant::from_uci_variant_str("3check")?, Variant::ThreeCheck);
+    /// assert_eq!(Variant::from_uci_variant_str("giveaway")?, Variant::Antichess);
+    /// # Ok::<_, shakmaty::variant::ParseVariantError>(())
+    /// ```
+    pub fn from_uci_variant_str(s: &str) -> Result<Variant, ParseVariantError> {
+        match s {
+            "standard" | "chess960" | "fischerandom" => Ok(Variant::Chess),
+            "giveaway" | "suicide" => Ok(Variant::Antichess),
+            "threecheck" => Ok(Variant::ThreeCheck),
+            "koth" => Ok(Variant::KingOfTheHill),
+            "zh" => Ok(Variant::Crazyhouse),
+            _ => Variant::from_uci(s),
+        }
+    }
+
+    /// Gets the key of the variant in the Lichess API, or `None` if the
+    /// variant is not played on Lichess.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::variant::Variant;
+    ///
+    /// assert_eq!(Variant::ThreeCheck.to_lichess_key(), Some("threeCheck"));
+    /// assert_eq!(Variant::Makruk.to_lichess_key(), None);
+    /// ```
+    pub const fn to_lichess_key(self) -> Option<&'static str> {
+        Some(match self {
+            Variant::Chess => "standard",
+            Variant::Atomic => "atomic",
+            Variant::Antichess => "antichess",
+            Variant::KingOfTheHill => "kingOfTheHill",
+            Variant::ThreeCheck => "threeCheck",
+            Variant::Crazyhouse => "crazyhouse",
+            Variant::RacingKings => "racingKings",
+            Variant::Horde => "horde",
+            Variant::FogOfWar | Variant::Shatranj | Variant::Makruk | Variant::NoCastling => {
+                return None;
+            }
+        })
+    }
+
     /// Selects a variant based on its name or known alias.
     pub fn from_ascii(s: &[u8]) -> Result<Variant, ParseVariantError> {
         Ok(match s {
             b"chess" | b"standard" | b"chess960" | b"fromPosition" | b"Standard" | b"Chess960"
             | b"From Position" => Variant::Chess,
             b"atomic" | b"Atomic" => Variant::Atomic,
-            b"antichess" | b"Antichess" => Variant::Antichess,
+            b"antichess" | b"Antichess" | b"giveaway" | b"Giveaway" => Variant::Antichess,
             b"kingofthehill" | b"kingOfTheHill" | b"King of the Hill" => Variant::KingOfTheHill,
             b"3check" | b"threeCheck" | b"Three-check" => Variant::ThreeCheck,
             b"crazyhouse" | b"Crazyhouse" => Variant::Crazyhouse,
             b"racingkings" | b"racingKings" | b"Racing Kings" => Variant::RacingKings,
             b"horde" | b"Horde" => Variant::Horde,
+            b"fogofwar" | b"fogOfWar" | b"Fog of War" | b"darkchess" | b"Dark Chess" => {
+                Variant::FogOfWar
+            }
+            b"shatranj" | b"Shatranj" => Variant::Shatranj,
+            b"makruk" | b"Makruk" | b"Thai chess" => Variant::Makruk,
+            b"nocastle" | b"noCastling" | b"No castling" => Variant::NoCastling,
             _ => return Err(ParseVariantError),
         })
     }
@@ -91,7 +172,20 @@ impl Variant {
         matches!(self, Variant::Crazyhouse)
     }
 
-    pub const ALL: [Variant; 8] = [
+    /// Tests if the variant has castling, so that the [`CastlingMode`] is
+    /// relevant.
+    pub const fn has_castling(self) -> bool {
+        !matches!(
+            self,
+            Variant::Antichess
+                | Variant::RacingKings
+                | Variant::Shatranj
+                | Variant::Makruk
+                | Variant::NoCastling
+        )
+    }
+
+    pub const ALL: [Variant; 12] = [
         Variant::Chess,
         Variant::Atomic,
         Variant::Antichess,
@@ -100,6 +194,10 @@ impl Variant {
         Variant::Crazyhouse,
         Variant::RacingKings,
         Variant::Horde,
+        Variant::FogOfWar,
+        Variant::Shatranj,
+        Variant::Makruk,
+        Variant::NoCastling,
     ];
 }
 
@@ -132,6 +230,166 @@ impl FromStr for Variant {
 #[cfg(feature = "nohash-hasher")]
 impl nohash_hasher::IsEnabled for Variant {}
 
+/// How a game ended, including the variant-specific ways to win or draw.
+///
+/// Converts to a [`KnownOutcome`] or [`Outcome`], e.g., for PGN result tags.
+/// See [`VariantPosition::detailed_outcome()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum VariantOutcome {
+    /// The side to move is checkmated.
+    Checkmate { winner: Color },
+    /// The side to move has no legal moves, but is not in check. A draw,
+    /// except in Antichess and Shatranj.
+    Stalemate { winner: Option<Color> },
+    /// Neither side has sufficient winning material.
+    InsufficientMaterial,
+    /// A king exploded (Atomic).
+    KingExploded { winner: Color },
+    /// A king reached the center (King of the Hill).
+    ReachedHill { winner: Color },
+    /// All remaining checks were delivered (Three-Check).
+    ChecksDelivered { winner: Color },
+    /// A side has no pieces left (Antichess, where that side wins, and
+    /// Horde).
+    AllPiecesLost { winner: Color },
+    /// A king reached the eighth rank, or both did (Racing Kings).
+    RaceFinished { winner: Option<Color> },
+    /// A king was captured (Fog of War).
+    KingCaptured { winner: Color },
+    /// A side was left with a bare king, or both were (Shatranj).
+    BareKing { winner: Option<Color> },
+    /// The counting limit was reached (Makruk).
+    CountingLimit,
+    /// Any other variant-specific ending.
+    Other(KnownOutcome),
+}
+
+impl VariantOutcome {
+    pub const fn winner(self) -> Option<Color> {
+        match self {
+            VariantOutcome::Checkmate { winner }
+            | VariantOutcome::KingExploded { winner }
+            | VariantOutcome::ReachedHill { winner }
+            | VariantOutcome::ChecksDelivered { winner }
+            | VariantOutcome::AllPiecesLost { winner }
+            | VariantOutcome::KingCaptured { winner } => Some(winner),
+            VariantOutcome::Stalemate { winner }
+            | VariantOutcome::RaceFinished { winner }
+            | VariantOutcome::BareKing { winner } => winner,
+            VariantOutcome::InsufficientMaterial | VariantOutcome::CountingLimit => None,
+            VariantOutcome::Other(outcome) => outcome.winner(),
+        }
+    }
+
+    pub const fn known(self) -> KnownOutcome {
+        KnownOutcome::from_winner(self.winner())
+    }
+}
+
+impl From<VariantOutcome> for KnownOutcome {
+    fn from(outcome: VariantOutcome) -> KnownOutcome {
+        outcome.known()
+    }
+}
+
+impl From<VariantOutcome> for Outcome {
+    fn from(outcome: VariantOutcome) -> Outcome {
+        Outcome::Known(outcome.known())
+    }
+}
+
+/// Error when pairing a [`Variant`] with an unsupported [`CastlingMode`].
+#[derive(Clone, Debug)]
+pub struct UnsupportedCastlingModeError;
+
+impl fmt::Display for UnsupportedCastlingModeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("castling mode not supported by variant")
+    }
+}
+
+impl error::Error for UnsupportedCastlingModeError {}
+
+/// A [`Variant`] paired with a supported [`CastlingMode`].
+///
+/// Variants without castling support only [`CastlingMode::Standard`].
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{CastlingMode, variant::{Variant, VariantMode}};
+///
+/// let mode = VariantMode::new(Variant::Crazyhouse, CastlingMode::Chess960)?;
+/// assert_eq!(mode.variant(), Variant::Crazyhouse);
+///
+/// assert!(VariantMode::new(Variant::RacingKings, CastlingMode::Chess960).is_err());
+/// # Ok::<_, shakmaty::variant::UnsupportedCastlingModeError>(())
+/// ```
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct VariantMode {
+    variant: Variant,
+    mode: CastlingMode,
+}
+
+impl VariantMode {
+    /// Pairs `variant` with `mode`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`UnsupportedCastlingModeError`] for
+    /// [`CastlingMode::Chess960`] with a variant that has no castling.
+    pub const fn new(
+        variant: Variant,
+        mode: CastlingMode,
+    ) -> Result<VariantMode, UnsupportedCastlingModeError> {
+        if mode.is_chess960() && !variant.has_castling() {
+            Err(UnsupportedCastlingModeError)
+        } else {
+            Ok(VariantMode { variant, mode })
+        }
+    }
+
+    /// Pairs `variant` with [`CastlingMode::Standard`], which is supported by
+    /// all variants.
+    pub const fn standard(variant: Variant) -> VariantMode {
+        VariantMode {
+            variant,
+            mode: CastlingMode::Standard,
+        }
+    }
+
+    pub const fn variant(self) -> Variant {
+        self.variant
+    }
+
+    pub const fn mode(self) -> CastlingMode {
+        self.mode
+    }
+
+    /// Validates a setup for the paired variant and castling mode.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the setup is not legal. See [`FromSetup`].
+    #[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
+    pub fn position(self, setup: Setup) -> Result<VariantPosition, PositionError<VariantPosition>> {
+        VariantPosition::from_setup(self.variant, setup, self.mode)
+    }
+}
+
+impl Default for VariantMode {
+    fn default() -> VariantMode {
+        VariantMode::standard(Variant::default())
+    }
+}
+
+impl From<Variant> for VariantMode {
+    fn from(variant: Variant) -> VariantMode {
+        VariantMode::standard(variant)
+    }
+}
+
 /// Dynamically dispatched chess variant [`Position`].
 #[allow(missing_docs)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
@@ -145,6 +403,10 @@ pub enum VariantPosition {
     Crazyhouse(Crazyhouse),
     RacingKings(RacingKings),
     Horde(Horde),
+    FogOfWar(FogOfWar),
+    Shatranj(Shatranj),
+    Makruk(Makruk),
+    NoCastling(NoCastling),
 }
 
 impl Default for VariantPosition {
@@ -201,6 +463,30 @@ impl From<Horde> for VariantPosition {
     }
 }
 
+impl From<FogOfWar> for VariantPosition {
+    fn from(pos: FogOfWar) -> VariantPosition {
+        VariantPosition::FogOfWar(pos)
+    }
+}
+
+impl From<Shatranj> for VariantPosition {
+    fn from(pos: Shatranj) -> VariantPosition {
+        VariantPosition::Shatranj(pos)
+    }
+}
+
+impl From<Makruk> for VariantPosition {
+    fn from(pos: Makruk) -> VariantPosition {
+        VariantPosition::Makruk(pos)
+    }
+}
+
+impl From<NoCastling> for VariantPosition {
+    fn from(pos: NoCastling) -> VariantPosition {
+        VariantPosition::NoCastling(pos)
+    }
+}
+
 impl VariantPosition {
     pub fn new(variant: Variant) -> VariantPosition {
         match variant {
@@ -212,10 +498,14 @@ impl VariantPosition {
             Variant::Crazyhouse => Crazyhouse::default().into(),
             Variant::RacingKings => RacingKings::default().into(),
             Variant::Horde => Horde::default().into(),
+            Variant::FogOfWar => FogOfWar::default().into(),
+            Variant::Shatranj => Shatranj::default().into(),
+            Variant::Makruk => Makruk::default().into(),
+            Variant::NoCastling => NoCastling::default().into(),
         }
     }
 
-    #[allow(clippy::result_large_err)] // Ok variant is also large
+    #[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
     pub fn from_setup(
         variant: Variant,
         setup: Setup,
@@ -229,7 +519,7 @@ impl VariantPosition {
                 Ok(p) => Ok(f(p)),
                 Err(PositionError { errors, pos }) => Err(PositionError {
                     errors,
-                    pos: f(pos),
+                    pos: pos.map(f),
                 }),
             }
         }
@@ -258,10 +548,17 @@ impl VariantPosition {
                 VariantPosition::RacingKings,
             ),
             Variant::Horde => wrap(Horde::from_setup(setup, mode), VariantPosition::Horde),
+            Variant::FogOfWar => wrap(FogOfWar::from_setup(setup, mode), VariantPosition::FogOfWar),
+            Variant::Shatranj => wrap(Shatranj::from_setup(setup, mode), VariantPosition::Shatranj),
+            Variant::Makruk => wrap(Makruk::from_setup(setup, mode), VariantPosition::Makruk),
+            Variant::NoCastling => wrap(
+                NoCastling::from_setup(setup, mode),
+                VariantPosition::NoCastling,
+            ),
         }
     }
 
-    #[allow(clippy::result_large_err)] // Ok variant is also large
+    #[cfg_attr(not(feature = "alloc"), allow(clippy::result_large_err))] // Error is boxed with alloc
     pub fn swap_turn(self) -> Result<VariantPosition, PositionError<VariantPosition>> {
         let mode = self.castles().mode();
         let variant = self.variant();
@@ -280,6 +577,85 @@ impl VariantPosition {
             VariantPosition::Crazyhouse(_) => Variant::Crazyhouse,
             VariantPosition::RacingKings(_) => Variant::RacingKings,
             VariantPosition::Horde(_) => Variant::Horde,
+            VariantPosition::FogOfWar(_) => Variant::FogOfWar,
+            VariantPosition::Shatranj(_) => Variant::Shatranj,
+            VariantPosition::Makruk(_) => Variant::Makruk,
+            VariantPosition::NoCastling(_) => Variant::NoCastling,
+        }
+    }
+
+    /// The outcome of the game, like [`Position::outcome()`], but with the
+    /// reason the game ended. `None` if the game is not over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{CastlingMode, Color, KnownOutcome, fen::Fen};
+    /// use shakmaty::variant::{Variant, VariantOutcome, VariantPosition};
+    ///
+    /// let setup = "8/8/8/3K4/8/8/8/7k b - - 0 1".parse::<Fen>()?.into_setup();
+    /// let pos = VariantPosition::from_setup(Variant::KingOfTheHill, setup, CastlingMode::Standard)?;
+    ///
+    /// let outcome = pos.detailed_outcome();
+    /// assert_eq!(outcome, Some(VariantOutcome::ReachedHill { winner: Color::White }));
+    /// assert_eq!(outcome.map(KnownOutcome::from), Some(KnownOutcome::Decisive { winner: Color::White }));
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn detailed_outcome(&self) -> Option<VariantOutcome> {
+        if let Outcome::Known(outcome) = self.variant_outcome() {
+            return Some(match (self, outcome.winner()) {
+                (VariantPosition::Atomic(_), Some(winner)) => {
+                    VariantOutcome::KingExploded { winner }
+                }
+                (VariantPosition::KingOfTheHill(_), Some(winner)) => {
+                    VariantOutcome::ReachedHill { winner }
+                }
+                (VariantPosition::ThreeCheck(_), Some(winner)) => {
+                    VariantOutcome::ChecksDelivered { winner }
+                }
+                (VariantPosition::Antichess(pos), Some(winner)) if pos.us().is_empty() => {
+                    VariantOutcome::AllPiecesLost { winner }
+                }
+                (VariantPosition::Antichess(_) | VariantPosition::Shatranj(_), winner)
+                    if self.is_stalemate() =>
+                {
+                    VariantOutcome::Stalemate { winner }
+                }
+                (VariantPosition::Horde(_), Some(winner)) => {
+                    VariantOutcome::AllPiecesLost { winner }
+                }
+                (VariantPosition::RacingKings(_), winner) => {
+                    VariantOutcome::RaceFinished { winner }
+                }
+                (VariantPosition::FogOfWar(_), Some(winner)) => {
+                    VariantOutcome::KingCaptured { winner }
+                }
+                (VariantPosition::Shatranj(_), winner) => VariantOutcome::BareKing { winner },
+                (VariantPosition::Makruk(_), None) => VariantOutcome::CountingLimit,
+                _ => VariantOutcome::Other(outcome),
+            });
+        }
+
+        if !self.has_legal_moves() {
+            Some(if self.is_check() {
+                VariantOutcome::Checkmate {
+                    winner: !self.turn(),
+                }
+            } else {
+                VariantOutcome::Stalemate { winner: None }
+            })
+        } else if self.is_insufficient_material() {
+            Some(VariantOutcome::InsufficientMaterial)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the variant together with the castling mode of the position.
+    pub fn variant_mode(&self) -> VariantMode {
+        VariantMode {
+            variant: self.variant(),
+            mode: self.castles().mode(),
         }
     }
 
@@ -293,6 +669,10 @@ impl VariantPosition {
             VariantPosition::Crazyhouse(ref pos) => pos,
             VariantPosition::RacingKings(ref pos) => pos,
             VariantPosition::Horde(ref pos) => pos,
+            VariantPosition::FogOfWar(ref pos) => pos,
+            VariantPosition::Shatranj(ref pos) => pos,
+            VariantPosition::Makruk(ref pos) => pos,
+            VariantPosition::NoCastling(ref pos) => pos,
         }
     }
 
@@ -306,10 +686,37 @@ impl VariantPosition {
             VariantPosition::Crazyhouse(ref mut pos) => pos,
             VariantPosition::RacingKings(ref mut pos) => pos,
             VariantPosition::Horde(ref mut pos) => pos,
+            VariantPosition::FogOfWar(ref mut pos) => pos,
+            VariantPosition::Shatranj(ref mut pos) => pos,
+            VariantPosition::Makruk(ref mut pos) => pos,
+            VariantPosition::NoCastling(ref mut pos) => pos,
         }
     }
 }
 
+/// Parses a FEN and sets it up as standard [`Chess`], with the castling mode
+/// detected by [`CastlingMode::detect()`]. The variant can not be determined
+/// from a FEN, so use [`VariantPosition::from_setup()`] for other variants.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::variant::{Variant, VariantPosition};
+///
+/// let pos: VariantPosition = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".parse()?;
+/// assert_eq!(pos.variant(), Variant::Chess);
+/// # Ok::<_, shakmaty::fen::ParsePositionError<_>>(())
+/// ```
+impl FromStr for VariantPosition {
+    type Err = ParsePositionError<VariantPosition>;
+
+    fn from_str(s: &str) -> Result<VariantPosition, ParsePositionError<VariantPosition>> {
+        let setup = Fen::from_ascii(s.as_bytes())?.into_setup();
+        let mode = CastlingMode::detect(&setup);
+        Ok(VariantPosition::from_setup(Variant::Chess, setup, mode)?)
+    }
+}
+
 impl Position for VariantPosition {
     fn board(&self) -> &Board {
         self.borrow().board()
@@ -355,6 +762,34 @@ impl Position for VariantPosition {
         self.borrow().legal_moves()
     }
 
+    #[cfg(feature = "alloc")]
+    fn legal_moves_into(&self, moves: &mut alloc::vec::Vec<Move>) {
+        self.borrow().legal_moves_into(moves);
+    }
+
+    fn legal_moves_into_uninit<'a>(&self, buffer: &'a mut [MaybeUninit<Move>]) -> &'a mut [Move] {
+        self.borrow().legal_moves_into_uninit(buffer)
+    }
+
+    fn legal_moves_iter(&self) -> LegalMovesIter<'_, VariantPosition> {
+        match *self {
+            VariantPosition::Chess(_)
+            | VariantPosition::KingOfTheHill(_)
+            | VariantPosition::ThreeCheck(_)
+            | VariantPosition::Crazyhouse(_)
+            | VariantPosition::NoCastling(_) => LegalMovesIter::lazy(self),
+            _ => LegalMovesIter::buffered(self),
+        }
+    }
+
+    fn legal_moves_from(&self, from: Square) -> MoveList {
+        self.borrow().legal_moves_from(from)
+    }
+
+    fn has_legal_moves(&self) -> bool {
+        self.borrow().has_legal_moves()
+    }
+
     fn san_candidates(&self, role: Role, to: Square) -> MoveList {
         self.borrow().san_candidates(role, to)
     }
@@ -375,6 +810,10 @@ impl Position for VariantPosition {
         self.borrow().promotion_moves()
     }
 
+    fn legal_moves_captures_first(&self) -> MoveList {
+        self.borrow().legal_moves_captures_first()
+    }
+
     fn is_irreversible(&self, m: Move) -> bool {
         self.borrow().is_irreversible(m)
     }
@@ -383,6 +822,10 @@ impl Position for VariantPosition {
         self.borrow().king_attackers(square, attacker, occupied)
     }
 
+    fn checkers(&self) -> Bitboard {
+        self.borrow().checkers()
+    }
+
     fn is_variant_end(&self) -> bool {
         self.borrow().is_variant_end()
     }
@@ -391,6 +834,10 @@ impl Position for VariantPosition {
         self.borrow().has_insufficient_material(color)
     }
 
+    fn insufficient_material_rule(&self, color: Color) -> Option<InsufficientMaterialRule> {
+        self.borrow().insufficient_material_rule(color)
+    }
+
     fn variant_outcome(&self) -> Outcome {
         self.borrow().variant_outcome()
     }
@@ -400,10 +847,200 @@ impl Position for VariantPosition {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Variant {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.uci())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Variant {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct VariantVisitor;
+
+        impl serde::de::Visitor<'_> for VariantVisitor {
+            type Value = Variant;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("variant name")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_str(VariantVisitor)
+    }
+}
+
+/// Serializes as a struct with the `variant` name, the `fen` including
+/// pockets and remaining checks, and a `chess960` flag for the castling
+/// mode.
+#[cfg(all(feature = "serde", feature = "alloc"))]
+impl serde::Serialize for VariantPosition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct as _;
+
+        let mut state = serializer.serialize_struct("VariantPosition", 3)?;
+        state.serialize_field("variant", &self.variant())?;
+        state.serialize_field("fen", &Fen::from_position(self, EnPassantMode::Legal))?;
+        state.serialize_field("chess960", &self.castles().mode().is_chess960())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VariantPosition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &["variant", "fen", "chess960"];
+
+        struct VariantPositionVisitor;
+
+        fn build<E: serde::de::Error>(
+            variant: Variant,
+            fen: Fen,
+            chess960: bool,
+        ) -> Result<VariantPosition, E> {
+            VariantPosition::from_setup(
+                variant,
+                fen.into_setup(),
+                CastlingMode::from_chess960(chess960),
+            )
+            .map_err(serde::de::Error::custom)
+        }
+
+        impl<'de> serde::de::Visitor<'de> for VariantPositionVisitor {
+            type Value = VariantPosition;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("struct VariantPosition")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let variant = seq
+                    .next_element()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+                let fen = seq
+                    .next_element()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
+                let chess960 = seq.next_element()?.unwrap_or(false);
+                build(variant, fen, chess960)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut variant = None;
+                let mut fen = None;
+                let mut chess960 = None;
+                while let Some(key) = map.next_key::<&str>()? {
+                    match key {
+                        "variant" => variant = Some(map.next_value()?),
+                        "fen" => fen = Some(map.next_value()?),
+                        "chess960" => chess960 = Some(map.next_value()?),
+                        _ => return Err(serde::de::Error::unknown_field(key, FIELDS)),
+                    }
+                }
+                build(
+                    variant.ok_or_else(|| serde::de::Error::missing_field("variant"))?,
+                    fen.ok_or_else(|| serde::de::Error::missing_field("fen"))?,
+                    chess960.unwrap_or(false),
+                )
+            }
+        }
+
+        deserializer.deserialize_struct("VariantPosition", FIELDS, VariantPositionVisitor)
+    }
+}
+
+/// Encodes the castling mode followed by a [`PackedSetup`], which includes
+/// the variant, pockets and remaining checks.
+#[cfg(feature = "bincode")]
+impl bincode::Encode for VariantPosition {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        let packed =
+            PackedSetup::pack_variant(&self.to_setup(EnPassantMode::Legal), self.variant())
+                .map_err(|_| {
+                    bincode::error::EncodeError::Other("unrepresentable VariantPosition")
+                })?;
+        self.castles().mode().is_chess960().encode(encoder)?;
+        packed.encode(encoder)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<Config> bincode::Decode<Config> for VariantPosition {
+    fn decode<D: bincode::de::Decoder>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let mode = CastlingMode::from_chess960(bool::decode(decoder)?);
+        let (setup, variant) = PackedSetup::decode(decoder)?
+            .unpack_variant()
+            .map_err(|_| bincode::error::DecodeError::Other("invalid VariantPosition"))?;
+        VariantPosition::from_setup(variant, setup, mode)
+            .map_err(|_| bincode::error::DecodeError::Other("illegal VariantPosition"))
+    }
+}
+
+#[cfg(feature = "bincode")]
+bincode::impl_borrow_decode!(VariantPosition);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_variant_position_from_str() {
+        let pos: VariantPosition = "8/8/8/8/8/8/6k1/4K1N1 b - - 0 1"
+            .parse()
+            .expect("valid chess position");
+        assert_eq!(pos.variant(), Variant::Chess);
+        assert_eq!(pos.castles().mode(), CastlingMode::Standard);
+        let pos: VariantPosition =
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9"
+                .parse()
+                .expect("valid chess960 position");
+        assert_eq!(pos.castles().mode(), CastlingMode::Chess960);
+        assert!(pos.castles().has(Color::White, CastlingSide::QueenSide));
+        assert!(matches!(
+            "8/8/8/8/8/8/8/6N1 b - - 0 1".parse::<VariantPosition>(),
+            Err(ParsePositionError::Position(_))
+        ));
+        assert!(matches!(
+            "antichess 8/8/8/8/8/8/8/6N1 b - - 0 1".parse::<VariantPosition>(),
+            Err(ParsePositionError::Fen(_))
+        ));
+        assert!(matches!(
+            "atomc rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+                .parse::<VariantPosition>(),
+            Err(ParsePositionError::Fen(_))
+        ));
+    }
+
     #[test]
     fn test_variant_position_play() {
         let pos = VariantPosition::new(Variant::Chess);
@@ -418,4 +1055,148 @@ mod tests {
             .expect("legal move");
         assert_eq!(pos.variant(), Variant::Chess);
     }
+
+    #[test]
+    fn test_detailed_outcome() {
+        use crate::fen::Fen;
+
+        for (variant, fen, expected) in [
+            (
+                Variant::Chess,
+                "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+                Some(VariantOutcome::Checkmate {
+                    winner: Color::Black,
+                }),
+            ),
+            (
+                Variant::Chess,
+                "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",
+                Some(VariantOutcome::Stalemate { winner: None }),
+            ),
+            (
+                Variant::Chess,
+                "8/8/8/4k3/8/8/8/4K3 w - - 0 1",
+                Some(VariantOutcome::InsufficientMaterial),
+            ),
+            (
+                Variant::Atomic,
+                "8/8/8/8/8/8/8/4K3 b - - 0 1",
+                Some(VariantOutcome::KingExploded {
+                    winner: Color::White,
+                }),
+            ),
+            (
+                Variant::Antichess,
+                "8/8/8/8/8/8/8/4K3 b - - 0 1",
+                Some(VariantOutcome::AllPiecesLost {
+                    winner: Color::Black,
+                }),
+            ),
+            (
+                Variant::RacingKings,
+                "4K3/8/8/8/8/8/8/k7 w - - 0 1",
+                Some(VariantOutcome::RaceFinished {
+                    winner: Some(Color::White),
+                }),
+            ),
+            (
+                Variant::Shatranj,
+                "8/8/8/4k3/8/8/3RK3/8 b - - 0 1",
+                Some(VariantOutcome::BareKing {
+                    winner: Some(Color::White),
+                }),
+            ),
+            (
+                Variant::Horde,
+                "4k3/8/8/8/8/8/8/8 w - - 0 1",
+                Some(VariantOutcome::AllPiecesLost {
+                    winner: Color::Black,
+                }),
+            ),
+            (
+                Variant::Crazyhouse,
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1",
+                None,
+            ),
+        ] {
+            let setup = fen.parse::<Fen>().expect("valid fen").into_setup();
+            let pos = VariantPosition::from_setup(variant, setup, CastlingMode::Standard)
+                .expect("legal position");
+            let outcome = pos.detailed_outcome();
+            assert_eq!(outcome, expected, "{fen}");
+            assert_eq!(
+                outcome.map(KnownOutcome::from),
+                pos.outcome().known(),
+                "{fen}"
+            );
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode() {
+        use crate::fen::Fen;
+
+        for (variant, fen, mode) in [
+            (
+                Variant::Crazyhouse,
+                "r1bqk2r/pppp1ppp/2n5/4p3/1bB5/5Q~2/PPPP1PPP/R1B1K1NR[Nn] w KQkq - 0 6",
+                CastlingMode::Standard,
+            ),
+            (
+                Variant::ThreeCheck,
+                "rnbqkbnr/ppp2ppp/8/3pp3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 2+3 0 3",
+                CastlingMode::Standard,
+            ),
+            (
+                Variant::Chess,
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                CastlingMode::Chess960,
+            ),
+        ] {
+            let setup = fen.parse::<Fen>().expect("valid fen").into_setup();
+            let pos = VariantPosition::from_setup(variant, setup, mode).expect("legal position");
+
+            let mut buffer = [0; 64];
+            let config = bincode::config::standard();
+            let n = bincode::encode_into_slice(&pos, &mut buffer, config).expect("encode");
+            let (decoded, m): (VariantPosition, usize) =
+                bincode::decode_from_slice(&buffer[..n], config).expect("decode");
+            assert_eq!(n, m);
+            assert_eq!(decoded, pos);
+            assert_eq!(decoded.castles().mode(), mode);
+            assert_eq!(decoded.promoted(), pos.promoted());
+        }
+    }
+
+    #[test]
+    fn test_variant_names() {
+        for variant in Variant::ALL {
+            assert_eq!(Variant::from_uci(variant.uci()).ok(), Some(variant));
+            assert_eq!(
+                Variant::from_uci_variant_str(variant.uci()).ok(),
+                Some(variant)
+            );
+            if let Some(key) = variant.to_lichess_key() {
+                assert_eq!(key.parse::<Variant>().ok(), Some(variant));
+            }
+        }
+    }
+
+    #[test]
+    fn test_variant_mode() {
+        for variant in Variant::ALL {
+            for mode in [CastlingMode::Standard, CastlingMode::Chess960] {
+                let setup = VariantPosition::new(variant).to_setup(EnPassantMode::Legal);
+                let pos = VariantPosition::from_setup(variant, setup, mode)
+                    .expect("legal starting position");
+                assert_eq!(pos.castles().mode(), mode, "{variant} {mode:?}");
+
+                match VariantMode::new(variant, mode) {
+                    Ok(variant_mode) => assert_eq!(pos.variant_mode(), variant_mode),
+                    Err(_) => assert!(mode.is_chess960() && !pos.castles().any()),
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,145 @@
+//! JavaScript bindings via [`wasm_bindgen`](https://docs.rs/wasm-bindgen/0.2),
+//! so that web frontends can use the same rules as the backend.
+//!
+//! # Examples
+//!
+//! ```js
+//! import { Game } from "shakmaty";
+//!
+//! const game = Game.fromFen("7k/8/6Q1/8/8/8/8/K7 w - - 0 1");
+//! game.legalMoves(); // ["g6g1", ...]
+//! game.play("g6g7");
+//! game.isGameOver(); // true
+//! game.outcome(); // "1-0"
+//! ```
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{CastlingMode, Chess, EnPassantMode, FromSetup, Position, fen::Fen, uci::UciMove};
+
+/// A game of standard chess or Chess960.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default)]
+pub struct Game {
+    pos: Chess,
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// The starting position.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Game {
+        Game::default()
+    }
+
+    /// Sets up a position from FEN. Chess960 castling rights are detected
+    /// automatically.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the FEN is invalid or the position is illegal.
+    #[wasm_bindgen(js_name = fromFen)]
+    pub fn from_fen(fen: &str) -> Result<Game, JsError> {
+        let setup = fen.parse::<Fen>()?.into_setup();
+        let mode = CastlingMode::detect(&setup);
+        Ok(Game {
+            pos: Chess::from_setup(setup, mode).map_err(|err| JsError::new(&err.to_string()))?,
+        })
+    }
+
+    /// The FEN of the current position.
+    pub fn fen(&self) -> String {
+        Fen::from_position(&self.pos, EnPassantMode::Legal).to_string()
+    }
+
+    /// The side to move, `"white"` or `"black"`.
+    pub fn turn(&self) -> String {
+        self.pos.turn().to_string()
+    }
+
+    /// The legal moves in UCI notation.
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<String> {
+        let mode = self.pos.castles().mode();
+        self.pos
+            .legal_moves()
+            .iter()
+            .map(|m| m.to_uci(mode).to_string())
+            .collect()
+    }
+
+    /// Plays a move given in UCI notation.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the move is invalid or illegal in the current position.
+    pub fn play(&mut self, uci: &str) -> Result<(), JsError> {
+        let m = uci.parse::<UciMove>()?.to_move(&self.pos)?;
+        self.pos.play_unchecked(m);
+        Ok(())
+    }
+
+    /// Tests if the side to move is in check.
+    #[wasm_bindgen(js_name = isCheck)]
+    pub fn is_check(&self) -> bool {
+        self.pos.is_check()
+    }
+
+    /// Tests if the game is over, due to checkmate, stalemate, or
+    /// insufficient material.
+    #[wasm_bindgen(js_name = isGameOver)]
+    pub fn is_game_over(&self) -> bool {
+        self.pos.is_game_over()
+    }
+
+    /// The result of the game, `"1-0"`, `"0-1"`, `"1/2-1/2"`, or `"*"`
+    /// if the game is not over.
+    pub fn outcome(&self) -> String {
+        self.pos.outcome().to_string()
+    }
+}
+
+impl From<Chess> for Game {
+    fn from(pos: Chess) -> Game {
+        Game { pos }
+    }
+}
+
+impl From<Game> for Chess {
+    fn from(game: Game) -> Chess {
+        game.pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game() {
+        let mut game = Game::new();
+        assert_eq!(game.legal_moves().len(), 20);
+        assert_eq!(game.turn(), "white");
+
+        // Fool's mate. Errors can not be tested outside of wasm.
+        for uci in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            assert!(!game.is_game_over());
+            assert!(game.play(uci).is_ok());
+        }
+        assert!(game.is_check());
+        assert!(game.is_game_over());
+        assert_eq!(game.outcome(), "0-1");
+        assert!(game.legal_moves().is_empty());
+
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert!(Game::from_fen(fen).is_ok_and(|game| game.fen() == fen));
+
+        let chess960 = Game::from_fen("4k3/8/8/8/8/8/8/1R2K1R1 w GB - 0 1").expect("legal");
+        assert!(chess960.legal_moves().iter().any(|uci| uci == "e1g1"));
+    }
+}
@@ -6,6 +6,12 @@
 //! hash values is considered a semver breaking change and will be noted in the
 //! changelog.
 //!
+//! The same guarantee applies to the individual keys provided by
+//! [`ZobristValue`], so that engines can maintain their own incremental hash
+//! and stay consistent with [`ZobristHash::zobrist_hash()`]. For standard
+//! chess, the 64 bit keys are identical to the
+//! [Polyglot](http://hgm.nubati.net/book_format.html) opening book format.
+//!
 //! # Warning: Forged collisions
 //!
 //! Zobrist hashes have excellent collision resistance, but can be
@@ -19,8 +25,30 @@
 //! let pos = Chess::default();
 //! assert_eq!(pos.zobrist_hash::<Zobrist64>(EnPassantMode::Legal), Zobrist64(0x463b96181691fc9c));
 //! ```
+//!
+//! Update a hash incrementally:
+//!
+//! ```
+//! use shakmaty::{Chess, Color, EnPassantMode, Position, Square, uci::UciMove};
+//! use shakmaty::zobrist::{Zobrist64, ZobristHash, ZobristValue};
+//!
+//! let pos = Chess::default();
+//! let mut hash: Zobrist64 = pos.zobrist_hash(EnPassantMode::Legal);
+//!
+//! // 1. Nf3
+//! let m = UciMove::from_ascii(b"g1f3")?.to_move(&pos)?;
+//! let knight = Color::White.knight();
+//! hash ^= Zobrist64::zobrist_for_piece(Square::G1, knight);
+//! hash ^= Zobrist64::zobrist_for_piece(Square::F3, knight);
+//! hash ^= Zobrist64::zobrist_for_white_turn();
+//!
+//! let pos = pos.play(m)?;
+//! assert_eq!(hash, pos.zobrist_hash(EnPassantMode::Legal));
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
 
 use core::{
+    cmp::Ordering,
     fmt,
     hash::{Hash, Hasher},
     ops::{BitXor, BitXorAssign},
@@ -32,13 +60,88 @@ use crate::{
 };
 
 /// Integer type that can be returned as a Zobrist hash.
+///
+/// The hash of a position is the XOR of the keys provided by this trait, as
+/// described for each method. The keys are stable, see the
+/// [module documentation](crate::zobrist#stability).
+///
+/// Implementations with 8, 16, 32, 64 and 128 bits are provided. Smaller
+/// keys save memory, for example in transposition tables, while larger keys
+/// reduce the probability of collisions, for example in large databases.
+/// The smaller keys are truncations of [`Zobrist128`].
+///
+/// # Custom keys
+///
+/// Implement this trait to use custom key tables. Each method should map
+/// to a distinct slot of the table, for example:
+///
+/// ```
+/// use core::ops::BitXorAssign;
+///
+/// use shakmaty::{
+///     CastlingSide, Chess, Color, EnPassantMode, File, Piece, RemainingChecks, Role, Square,
+///     zobrist::{ZobristHash, ZobristValue},
+/// };
+///
+/// #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+/// struct Key(u64);
+///
+/// impl BitXorAssign for Key {
+///     fn bitxor_assign(&mut self, rhs: Key) {
+///         self.0 ^= rhs.0;
+///     }
+/// }
+///
+/// fn key(slot: u64) -> Key {
+///     Key((slot + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15)) // or a table lookup
+/// }
+///
+/// impl ZobristValue for Key {
+///     fn zobrist_for_piece(square: Square, piece: Piece) -> Key {
+///         let piece_index = piece.color as u64 * 6 + (piece.role as u64 - 1);
+///         key(piece_index * 64 + u64::from(square)) // 0..768
+///     }
+///     fn zobrist_for_white_turn() -> Key {
+///         key(768)
+///     }
+///     fn zobrist_for_castling_right(color: Color, side: CastlingSide) -> Key {
+///         key(769 + color as u64 * 2 + side as u64) // 769..773
+///     }
+///     fn zobrist_for_en_passant_file(file: File) -> Key {
+///         key(773 + u64::from(file)) // 773..781
+///     }
+///     // Not needed for standard chess.
+///     fn zobrist_for_remaining_checks(_: Color, _: RemainingChecks) -> Key {
+///         Key::default()
+///     }
+///     fn zobrist_for_promoted(_: Square) -> Key {
+///         Key::default()
+///     }
+///     fn zobrist_for_pocket(_: Color, _: Role, _: u8) -> Key {
+///         Key::default()
+///     }
+/// }
+///
+/// let hash: Key = Chess::default().zobrist_hash(EnPassantMode::Legal);
+/// assert_ne!(hash, Key::default());
+/// ```
 pub trait ZobristValue: BitXorAssign + Default + Copy {
+    /// Key for `piece` on `square`. Included for each piece on the board.
     fn zobrist_for_piece(square: Square, piece: Piece) -> Self;
+    /// Key included if White is to move.
     fn zobrist_for_white_turn() -> Self;
+    /// Key included for each castling right.
     fn zobrist_for_castling_right(color: Color, side: CastlingSide) -> Self;
+    /// Key included for the file of the en passant square, if any,
+    /// according to the requested [`EnPassantMode`].
     fn zobrist_for_en_passant_file(file: File) -> Self;
+    /// Key included for the remaining checks of each color. Used only for
+    /// Three-Check. The key for the default of 3 remaining checks is zero.
     fn zobrist_for_remaining_checks(color: Color, remaining: RemainingChecks) -> Self;
+    /// Key for a promoted piece on `square`. Used only for Crazyhouse.
     fn zobrist_for_promoted(square: Square) -> Self;
+    /// Key for the number of `pieces` of `role` in the pocket of `color`.
+    /// Used only for Crazyhouse. The key for empty pockets is zero.
     fn zobrist_for_pocket(color: Color, role: Role, pieces: u8) -> Self;
 }
 
@@ -57,6 +160,20 @@ macro_rules! zobrist_value_impl {
             }
         }
 
+        impl PartialOrd for $t {
+            #[inline]
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $t {
+            #[inline]
+            fn cmp(&self, other: &$t) -> Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
         impl From<$proxy> for $t {
             #[inline]
             fn from(value: $proxy) -> $t {
@@ -986,21 +1103,30 @@ macro_rules! zobrist_value_impl {
             #[inline]
             fn zobrist_for_remaining_checks(color: Color, remaining: RemainingChecks) -> $t {
                 #[allow(overflowing_literals)]
-                static REMAINING_CHECKS_MASKS: ByColor<[$proxy; 2]> = ByColor {
+                static REMAINING_CHECKS_MASKS: ByColor<[$proxy; 4]> = ByColor {
                     black: [
                         0x6a2a_d922_a69a_13e9_1d6d_c0ee_61ce_803e,
                         0x49b5_72c7_9420_27d5_c628_4b65_3d38_e96a,
+                        0xd9ca_a393_af18_d6cc_1ab6_8265_decf_f1a3,
+                        0x90d1_82fb_3848_b05d_b0ad_d6b7_12be_1732,
                     ],
                     white: [
                         0x08c2_e927_1dc9_1e69_803f_5fb0_d2f9_7fae,
                         0x088d_fad9_83bb_7913_b183_ccc9_e73d_f9ed,
+                        0xe7bd_4c17_f0ac_bb21_72de_3fea_fff8_6ca8,
+                        0x270e_7777_2917_450c_3603_63dc_9f8b_0b6c,
                     ],
                 };
 
                 let masks = REMAINING_CHECKS_MASKS.get(color);
-                let n = !<$proxy>::from(remaining);
+                let n = <$proxy>::from(remaining);
 
-                $t(((n & 1) * masks[0]) ^ (((n >> 1) & 1) * masks[1]))
+                // Low bits are inverted, so that the default of 3 remaining
+                // checks contributes nothing.
+                $t((((!n) & 1) * masks[0])
+                    ^ (((!n >> 1) & 1) * masks[1])
+                    ^ (((n >> 2) & 1) * masks[2])
+                    ^ (((n >> 3) & 1) * masks[3]))
             }
 
             #[inline]
@@ -1317,7 +1443,13 @@ impl From<Zobrist16> for Zobrist8 {
 pub trait ZobristHash {
     /// Computes the Zobrist hash of the position from scratch. The hash
     /// includes the position, except halfmove clock and fullmove number.
+    ///
+    /// See [`ZobristValue`] for the individual keys.
     fn zobrist_hash<V: ZobristValue>(&self, mode: EnPassantMode) -> V;
+
+    /// Like [`ZobristHash::zobrist_hash()`], but does not distinguish
+    /// promoted pieces from original pieces. Only relevant for Crazyhouse.
+    fn zobrist_hash_ignore_promoted<V: ZobristValue>(&self, mode: EnPassantMode) -> V;
 }
 
 impl<P: Position> ZobristHash for P {
@@ -1361,8 +1493,90 @@ impl<P: Position> ZobristHash for P {
 
         zobrist
     }
+
+    fn zobrist_hash_ignore_promoted<V: ZobristValue>(&self, mode: EnPassantMode) -> V {
+        let mut zobrist = self.zobrist_hash(mode);
+        for sq in self.promoted() {
+            zobrist ^= V::zobrist_for_promoted(sq);
+        }
+        zobrist
+    }
+}
+
+/// A position together with its precomputed Zobrist hash.
+///
+/// [`Hash`] feeds only the precomputed key to the hasher, so that
+/// position-keyed hash maps do not rehash the entire position on every
+/// lookup. With the `nohash-hasher` feature,
+/// [`nohash_hasher::IsEnabled`](https://docs.rs/nohash-hasher/0.2/nohash_hasher/trait.IsEnabled.html)
+/// is implemented, so that the key can be used as is.
+///
+/// [`PartialEq`] and [`Eq`] compare the keys first, and then the positions,
+/// so that hash collisions are handled correctly. The key is always
+/// computed with [`EnPassantMode::Legal`], to be consistent with
+/// equality of positions.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use shakmaty::{Chess, zobrist::Prehashed};
+///
+/// let mut seen = HashSet::new();
+/// assert!(seen.insert(Prehashed::<_>::new(Chess::default())));
+/// assert!(!seen.insert(Prehashed::<_>::new(Chess::default())));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Prehashed<P, V = Zobrist64> {
+    key: V,
+    pos: P,
 }
 
+impl<P: Position, V: ZobristValue> Prehashed<P, V> {
+    /// Computes the Zobrist hash of `pos`.
+    pub fn new(pos: P) -> Prehashed<P, V> {
+        Prehashed {
+            key: pos.zobrist_hash(EnPassantMode::Legal),
+            pos,
+        }
+    }
+}
+
+impl<P, V: Copy> Prehashed<P, V> {
+    /// Gets the precomputed Zobrist hash.
+    pub fn key(&self) -> V {
+        self.key
+    }
+
+    /// Gets the position.
+    pub fn get(&self) -> &P {
+        &self.pos
+    }
+
+    /// Gets the position, discarding the key.
+    pub fn into_inner(self) -> P {
+        self.pos
+    }
+}
+
+impl<P: PartialEq, V: PartialEq> PartialEq for Prehashed<P, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && self.pos == other.pos
+    }
+}
+
+impl<P: Eq, V: Eq> Eq for Prehashed<P, V> {}
+
+impl<P, V: Hash> Hash for Prehashed<P, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+#[cfg(feature = "nohash-hasher")]
+impl<P, V: nohash_hasher::IsEnabled> nohash_hasher::IsEnabled for Prehashed<P, V> {}
+
 fn hash_board<V: ZobristValue>(board: &Board) -> V {
     // Order optimized for cache efficiency.
     let mut zobrist = V::default();
@@ -1380,7 +1594,7 @@ fn hash_board<V: ZobristValue>(board: &Board) -> V {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{CastlingMode, Chess, fen::Fen};
+    use crate::{CastlingMode, Chess, Move, fen::Fen};
 
     #[test]
     fn test_polyglot() {
@@ -1438,6 +1652,84 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_incremental() {
+        fn update(pos: &Chess, m: Move) -> Zobrist64 {
+            let mut hash: Zobrist64 = pos.zobrist_hash(EnPassantMode::Legal);
+            let turn = pos.turn();
+            if let Some(sq) = pos.ep_square(EnPassantMode::Legal) {
+                hash ^= Zobrist64::zobrist_for_en_passant_file(sq.file());
+            }
+            match m {
+                Move::Normal {
+                    role,
+                    from,
+                    capture,
+                    to,
+                    promotion,
+                } => {
+                    hash ^= Zobrist64::zobrist_for_piece(from, role.of(turn));
+                    if let Some(capture) = capture {
+                        hash ^= Zobrist64::zobrist_for_piece(to, capture.of(!turn));
+                    }
+                    hash ^= Zobrist64::zobrist_for_piece(to, promotion.unwrap_or(role).of(turn));
+                }
+                Move::EnPassant { from, to } => {
+                    hash ^= Zobrist64::zobrist_for_piece(from, turn.pawn());
+                    hash ^= Zobrist64::zobrist_for_piece(to, turn.pawn());
+                    hash ^= Zobrist64::zobrist_for_piece(
+                        Square::from_coords(to.file(), from.rank()),
+                        (!turn).pawn(),
+                    );
+                }
+                Move::Castle { king, rook } => {
+                    let side = CastlingSide::from_king_side(king < rook);
+                    hash ^= Zobrist64::zobrist_for_piece(king, turn.king());
+                    hash ^= Zobrist64::zobrist_for_piece(rook, turn.rook());
+                    hash ^= Zobrist64::zobrist_for_piece(side.king_to(turn), turn.king());
+                    hash ^= Zobrist64::zobrist_for_piece(side.rook_to(turn), turn.rook());
+                }
+                Move::Put { .. } => unreachable!(),
+            }
+            hash ^= Zobrist64::zobrist_for_white_turn();
+
+            let mut after = pos.clone();
+            after.play_unchecked(m);
+            for color in Color::ALL {
+                for side in CastlingSide::ALL {
+                    if pos.castles().has(color, side) != after.castles().has(color, side) {
+                        hash ^= Zobrist64::zobrist_for_castling_right(color, side);
+                    }
+                }
+            }
+            if let Some(sq) = after.ep_square(EnPassantMode::Legal) {
+                hash ^= Zobrist64::zobrist_for_en_passant_file(sq.file());
+            }
+            hash
+        }
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ] {
+            let pos: Chess = fen
+                .parse::<Fen>()
+                .expect("valid fen")
+                .into_position(CastlingMode::Standard)
+                .expect("legal position");
+            for m in pos.legal_moves() {
+                let mut after = pos.clone();
+                after.play_unchecked(m);
+                assert_eq!(
+                    update(&pos, m),
+                    after.zobrist_hash(EnPassantMode::Legal),
+                    "{fen} {m:?}"
+                );
+            }
+        }
+    }
+
     #[cfg(feature = "variant")]
     #[test]
     fn test_variants_not_distinguished() {
@@ -1452,6 +1744,114 @@ mod tests {
         assert_eq!(chess, king_of_the_hill);
     }
 
+    #[cfg(feature = "variant")]
+    #[test]
+    fn test_ignore_promoted() {
+        use crate::variant::Crazyhouse;
+
+        let promoted: Crazyhouse = "4k3/8/8/8/8/8/8/2Q~1K3[] w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Standard)
+            .expect("legal position");
+        let original: Crazyhouse = "4k3/8/8/8/8/8/8/2Q1K3[] w - - 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_position(CastlingMode::Standard)
+            .expect("legal position");
+
+        assert_ne!(promoted, original);
+        assert!(promoted.eq_ignore_promoted(&original));
+        assert_ne!(
+            promoted.zobrist_hash::<Zobrist64>(EnPassantMode::Legal),
+            original.zobrist_hash::<Zobrist64>(EnPassantMode::Legal)
+        );
+        assert_eq!(
+            promoted.zobrist_hash_ignore_promoted::<Zobrist64>(EnPassantMode::Legal),
+            original.zobrist_hash::<Zobrist64>(EnPassantMode::Legal)
+        );
+    }
+
+    #[test]
+    fn test_hash_fixed_width() {
+        use crate::{Setup, board::MailboxBoard};
+
+        /// Counts words, and rejects variable-length data.
+        #[derive(Default)]
+        struct WordHasher(usize);
+
+        impl Hasher for WordHasher {
+            fn finish(&self) -> u64 {
+                self.0 as u64
+            }
+            fn write(&mut self, _bytes: &[u8]) {
+                panic!("variable-length data");
+            }
+            fn write_u8(&mut self, _: u8) {
+                self.0 += 1;
+            }
+            fn write_u16(&mut self, _: u16) {
+                self.0 += 1;
+            }
+            fn write_u32(&mut self, _: u32) {
+                self.0 += 1;
+            }
+            fn write_u64(&mut self, _: u64) {
+                self.0 += 1;
+            }
+            fn write_usize(&mut self, _: usize) {
+                self.0 += 1;
+            }
+            fn write_isize(&mut self, _: isize) {
+                self.0 += 1;
+            }
+        }
+
+        fn words<T: Hash>(value: &T) -> u64 {
+            let mut hasher = WordHasher::default();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        assert_eq!(words(&Board::new()), 7);
+        assert_eq!(words(&MailboxBoard::new()), 7);
+        assert!(words(&Setup::initial()) > 7);
+        assert_eq!(words(&Prehashed::<_, Zobrist64>::new(Chess::default())), 1);
+
+        let setup = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 3+3 0 1"
+            .parse::<Fen>()
+            .expect("valid fen")
+            .into_setup();
+        words(&setup);
+    }
+
+    #[test]
+    fn test_prehashed() {
+        let a = Prehashed::<_, Zobrist64>::new(Chess::default());
+        let b = Prehashed::<_, Zobrist64>::new(Chess::default());
+        assert_eq!(a, b);
+        assert_eq!(a.key(), Chess::default().zobrist_hash(EnPassantMode::Legal));
+        assert_eq!(a.into_inner(), Chess::default());
+    }
+
+    #[test]
+    fn test_remaining_checks() {
+        for color in Color::ALL {
+            for a in 0..=15 {
+                for b in 0..a {
+                    assert_ne!(
+                        Zobrist64::zobrist_for_remaining_checks(color, RemainingChecks::new(a)),
+                        Zobrist64::zobrist_for_remaining_checks(color, RemainingChecks::new(b))
+                    );
+                }
+            }
+            assert_eq!(
+                Zobrist64::zobrist_for_remaining_checks(color, RemainingChecks::default()),
+                Zobrist64(0)
+            );
+        }
+    }
+
     #[test]
     fn test_full_pockets() {
         // 8/8/8/7k/8/8/3K4/8[ppppppppppppppppnnnnbbbbrrrrqq] w - - 0 54
@@ -1467,6 +1867,88 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_custom_keys() {
+        #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        struct Key(u64);
+
+        impl BitXorAssign for Key {
+            fn bitxor_assign(&mut self, rhs: Key) {
+                self.0 ^= rhs.0;
+            }
+        }
+
+        fn key(slot: u64) -> Key {
+            Key((slot + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+        }
+
+        fn piece_index(color: Color, role: Role) -> u64 {
+            color as u64 * 6 + (role as u64 - 1)
+        }
+
+        // Full layout extending the example in the ZobristValue docs.
+        impl ZobristValue for Key {
+            fn zobrist_for_piece(square: Square, piece: Piece) -> Key {
+                key(piece_index(piece.color, piece.role) * 64 + u64::from(square))
+            }
+            fn zobrist_for_white_turn() -> Key {
+                key(768)
+            }
+            fn zobrist_for_castling_right(color: Color, side: CastlingSide) -> Key {
+                key(769 + color as u64 * 2 + side as u64)
+            }
+            fn zobrist_for_en_passant_file(file: File) -> Key {
+                key(773 + u64::from(file))
+            }
+            fn zobrist_for_remaining_checks(color: Color, remaining: RemainingChecks) -> Key {
+                match u32::from(remaining) {
+                    3 => Key::default(),
+                    remaining => key(781 + color as u64 * 3 + u64::from(remaining)),
+                }
+            }
+            fn zobrist_for_promoted(square: Square) -> Key {
+                key(787 + u64::from(square))
+            }
+            fn zobrist_for_pocket(color: Color, role: Role, pieces: u8) -> Key {
+                match pieces {
+                    0 => Key::default(),
+                    pieces => key(851 + piece_index(color, role) * 255 + u64::from(pieces - 1)),
+                }
+            }
+        }
+
+        let mut keys = alloc::collections::BTreeSet::new();
+        for color in Color::ALL {
+            for role in Role::ALL {
+                for square in Square::ALL {
+                    assert!(keys.insert(Key::zobrist_for_piece(square, role.of(color))));
+                }
+                for pieces in 1..=u8::MAX {
+                    assert!(keys.insert(Key::zobrist_for_pocket(color, role, pieces)));
+                }
+            }
+            for side in CastlingSide::ALL {
+                assert!(keys.insert(Key::zobrist_for_castling_right(color, side)));
+            }
+            for remaining in 0..3 {
+                let remaining = RemainingChecks::new(remaining);
+                assert!(keys.insert(Key::zobrist_for_remaining_checks(color, remaining)));
+            }
+        }
+        for file in File::ALL {
+            assert!(keys.insert(Key::zobrist_for_en_passant_file(file)));
+        }
+        for square in Square::ALL {
+            assert!(keys.insert(Key::zobrist_for_promoted(square)));
+        }
+        assert!(keys.insert(Key::zobrist_for_white_turn()));
+        assert!(!keys.contains(&Key::default()));
+
+        let hash: Key = Chess::default().zobrist_hash(EnPassantMode::Legal);
+        assert_ne!(hash, Key::default());
+    }
+
     #[cfg(feature = "nohash-hasher")]
     #[test]
     fn test_nohash_hasher() {
@@ -1478,6 +1960,11 @@ mod tests {
         Zobrist64(64).hash(&mut hasher);
         assert_eq!(hasher.finish(), 64);
 
+        let prehashed = Prehashed::<_, Zobrist64>::new(Chess::default());
+        let mut hasher = nohash_hasher::NoHashHasher::<Prehashed<Chess>>::default();
+        prehashed.hash(&mut hasher);
+        assert_eq!(hasher.finish(), prehashed.key().0);
+
         let mut hasher = nohash_hasher::NoHashHasher::<Zobrist32>::default();
         Zobrist32(32).hash(&mut hasher);
         assert_eq!(hasher.finish(), 32);
@@ -40,7 +40,7 @@ where
                     .parse()
                     .expect("depth not an integer");
 
-                let nodes = params
+                let nodes: u64 = params
                     .next()
                     .expect("missing perft nodes")
                     .parse()
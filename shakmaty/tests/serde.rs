@@ -0,0 +1,97 @@
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_roundtrip() {
+    use serde::{Deserialize, Serialize};
+    use shakmaty::{
+        Color, File, KnownOutcome, Outcome, Piece, Rank, Role, Square,
+        fen::Fen,
+        san::{San, SanPlus},
+        uci::UciMove,
+    };
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Record {
+        square: Square,
+        file: File,
+        rank: Rank,
+        color: Color,
+        role: Role,
+        piece: Piece,
+        fen: Fen,
+        san: San,
+        san_plus: SanPlus,
+        uci: UciMove,
+        outcome: Outcome,
+        known_outcome: KnownOutcome,
+    }
+
+    let csv = "\
+square,file,rank,color,role,piece,fen,san,san_plus,uci,outcome,known_outcome
+e4,a,8,white,n,Q,rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1,O-O,Qxf7#,e7e8q,*,1/2-1/2
+";
+
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let record: Record = reader
+        .deserialize()
+        .next()
+        .expect("record")
+        .expect("valid record");
+    assert_eq!(record.square, Square::E4);
+    assert_eq!(record.file, File::A);
+    assert_eq!(record.rank, Rank::Eighth);
+    assert_eq!(record.color, Color::White);
+    assert_eq!(record.role, Role::Knight);
+    assert_eq!(record.piece, Color::White.queen());
+    assert_eq!(record.outcome, Outcome::Unknown);
+    assert_eq!(record.known_outcome, KnownOutcome::Draw);
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.serialize(&record).expect("serialize");
+    let written = String::from_utf8(writer.into_inner().expect("flush")).expect("utf-8");
+    assert_eq!(written, csv);
+
+    let invalid = "\
+square,file,rank,color,role,piece,fen,san,san_plus,uci,outcome,known_outcome
+e4,ab,8,white,n,Q,8/8/8/8/8/8/8/8 w - - 0 1,O-O,Qxf7#,e7e8q,*,1/2-1/2
+";
+    let mut reader = csv::Reader::from_reader(invalid.as_bytes());
+    assert!(
+        reader
+            .deserialize::<Record>()
+            .next()
+            .expect("record")
+            .is_err()
+    );
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[test]
+fn test_serde_analysis_line() {
+    use shakmaty::analysis::{AnalysisLine, Depth, Score};
+
+    let csv = "\
+multipv,depth,score,pv
+2,24,cp -17,g1f3 d7d5
+1,,mate 3,
+";
+
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let lines = reader
+        .deserialize()
+        .collect::<Result<Vec<AnalysisLine>, _>>()
+        .expect("valid records");
+    assert_eq!(lines[0].multipv, 2);
+    assert_eq!(lines[0].depth, Some(Depth(24)));
+    assert_eq!(lines[0].score, Score::Cp(-17));
+    assert_eq!(lines[0].pv.0.len(), 2);
+    assert_eq!(lines[1].depth, None);
+    assert_eq!(lines[1].score, Score::Mate(3));
+    assert!(lines[1].pv.0.is_empty());
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for line in &lines {
+        writer.serialize(line).expect("serialize");
+    }
+    let written = String::from_utf8(writer.into_inner().expect("flush")).expect("utf-8");
+    assert_eq!(written, csv);
+}